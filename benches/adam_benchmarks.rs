@@ -3,10 +3,11 @@ use nalgebra::SMatrix;
 use rand::random;
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::{Config, OptConf, AlgConf, AdamConf};
+use non_convex_opt::benchmarks::Rosenbrock;
+use non_convex_opt::utils::config::{Config, OptConf, AlgConf, AdamConf, BoundHandling, BoundsConf};
 
 mod common;
-use common::fcns::{RosenbrockFunction, RosenbrockConstraints};
+use common::fcns::RosenbrockConstraints;
 
 fn bench_adam_unconstrained(c: &mut Criterion) {
     let config = Config {
@@ -14,13 +15,15 @@ fn bench_adam_unconstrained(c: &mut Criterion) {
             max_iter: 10,
             rtol: -1e8,
             atol: -1e8,
-            rtol_max_iter_fraction: 1.0,
+            bounds: Some(BoundsConf { lower: vec![f64::NEG_INFINITY], upper: vec![f64::INFINITY] }), // Unbounded, not the benchmark's [-5, 10]
+            ..Default::default()
         },
         alg_conf: AlgConf::Adam(AdamConf {
             learning_rate: 0.05,
             beta1: 0.9,
             beta2: 0.999,
             epsilon: 1e-8,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
@@ -30,10 +33,10 @@ fn bench_adam_unconstrained(c: &mut Criterion) {
             let mut opt = NonConvexOpt::new(
                 config.clone(),
                 black_box(init_pop),
-                RosenbrockFunction,
+                Rosenbrock,
                 None::<RosenbrockConstraints>,
-            );
-            let _st = opt.run();
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -44,13 +47,15 @@ fn bench_adam_constrained(c: &mut Criterion) {
             max_iter: 10,
             rtol: -1e8,
             atol: -1e8,
-            rtol_max_iter_fraction: 1.0,
+            bounds: Some(BoundsConf { lower: vec![f64::NEG_INFINITY], upper: vec![f64::INFINITY] }), // Unbounded, not the benchmark's [-5, 10]
+            ..Default::default()
         },
         alg_conf: AlgConf::Adam(AdamConf {
             learning_rate: 0.05,
             beta1: 0.9,
             beta2: 0.999,
             epsilon: 1e-8,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
@@ -60,10 +65,10 @@ fn bench_adam_constrained(c: &mut Criterion) {
             let mut opt = NonConvexOpt::new(
                 config.clone(),
                 black_box(init_pop),
-                RosenbrockFunction,
+                Rosenbrock,
                 Some(RosenbrockConstraints)
-            );
-            let _st = opt.run();
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -5,14 +5,13 @@ use serde_json;
 use std::sync::LazyLock;
 
 use non_convex_opt::NonConvexOpt;
+use non_convex_opt::benchmarks::KBF;
 use non_convex_opt::utils::config::Config;
 
-mod common;
-use common::fcns::{KBF, KBFConstraints};
-
 static CONFIG_JSON: &str = r#"
 {
     "opt_conf": {
+        "objective_sense": "Minimize",
         "max_iter": 10,
         "rtol": "1e-8",
         "atol": "1e-8",
@@ -52,13 +51,13 @@ fn bench_cga_unconstrained(c: &mut Criterion) {
     c.bench_function("cga_unconstrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 100, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                None::<KBFConstraints>,
-            );
-            let _st = opt.run();
+                None::<KBF>,
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -68,13 +67,13 @@ fn bench_cga_constrained(c: &mut Criterion) {
     c.bench_function("cga_constrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 100, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                Some(KBFConstraints)
-            );
-            let _st = opt.run();
+                Some(KBF)
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
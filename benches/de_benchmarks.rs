@@ -4,14 +4,13 @@ use rand::random;
 use std::sync::LazyLock;
 
 use non_convex_opt::NonConvexOpt;
+use non_convex_opt::benchmarks::KBF;
 use non_convex_opt::utils::config::Config;
 
-mod common;
-use common::fcns::{KBF, KBFConstraints};
-
 static CONFIG_JSON: &str = r#"
 {
     "opt_conf": {
+        "objective_sense": "Minimize",
         "max_iter": 10,
         "rtol": "1e-8",
         "atol": "1e-8",
@@ -44,13 +43,13 @@ fn bench_de_unconstrained(c: &mut Criterion) {
     c.bench_function("de_unconstrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 100, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                None::<KBFConstraints>
-            );
-            let _st = opt.run();
+                None::<KBF>
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -59,13 +58,13 @@ fn bench_de_constrained(c: &mut Criterion) {
     c.bench_function("de_constrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 100, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                Some(KBFConstraints)
-            );
-            let _st = opt.run();
+                Some(KBF)
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
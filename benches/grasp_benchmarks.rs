@@ -3,10 +3,8 @@ use nalgebra::SMatrix;
 use rand::random;
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::{Config, OptConf, AlgConf, GRASPConf};
-
-mod common;
-use common::fcns::{KBF, KBFConstraints};
+use non_convex_opt::benchmarks::KBF;
+use non_convex_opt::utils::config::{Config, OptConf, AlgConf, ObjectiveSense, GRASPConf, BoundHandling};
 
 fn bench_grasp_unconstrained(c: &mut Criterion) {
     let config = Config {
@@ -14,7 +12,8 @@ fn bench_grasp_unconstrained(c: &mut Criterion) {
             max_iter: 10,
             rtol: -1e8,
             atol: -1e8,
-            rtol_max_iter_fraction: 1.0,
+            objective_sense: ObjectiveSense::Minimize,
+            ..Default::default()
         },
         alg_conf: AlgConf::GRASP(GRASPConf {
             num_candidates: 50,
@@ -22,19 +21,20 @@ fn bench_grasp_unconstrained(c: &mut Criterion) {
             num_neighbors: 20,
             step_size: 0.1,
             perturbation_prob: 0.3,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
     c.bench_function("grasp_unconstrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1,2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 config.clone(),
                 black_box(init_pop),
                 KBF,
-                None::<KBFConstraints>
-            );
-            let _st = opt.run();
+                None::<KBF>
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -45,7 +45,8 @@ fn bench_grasp_constrained(c: &mut Criterion) {
             max_iter: 10,
             rtol: -1e8,
             atol: -1e8,
-            rtol_max_iter_fraction: 1.0,
+            objective_sense: ObjectiveSense::Minimize,
+            ..Default::default()
         },
         alg_conf: AlgConf::GRASP(GRASPConf {
             num_candidates: 50,
@@ -53,19 +54,20 @@ fn bench_grasp_constrained(c: &mut Criterion) {
             num_neighbors: 20,
             step_size: 0.1,
             perturbation_prob: 0.3,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
     c.bench_function("grasp_constrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 config.clone(),
                 black_box(init_pop),
                 KBF,
-                Some(KBFConstraints)
-            );
-            let _st = opt.run();
+                Some(KBF)
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -4,15 +4,17 @@ use std::sync::LazyLock;
 use nalgebra::SMatrix;
 
 use non_convex_opt::NonConvexOpt;
+use non_convex_opt::benchmarks::Rosenbrock;
 use non_convex_opt::utils::config::Config;
 
 mod common;
-use common::fcns::{RosenbrockFunction, RosenbrockConstraints};
+use common::fcns::RosenbrockConstraints;
 
 static BACKTRACKING_JSON: &str = r#"
 {
     "opt_conf": {
         "max_iter": 10,
+        "bounds": { "lower": ["-inf"], "upper": ["inf"] },
         "rtol": "1e-8",
         "atol": "1e-8",
         "rtol_max_iter_fraction": 1.0
@@ -38,6 +40,7 @@ static HAGER_ZHANG_JSON: &str = r#"
 {
     "opt_conf": {
         "max_iter": 10,
+        "bounds": { "lower": ["-inf"], "upper": ["inf"] },
         "rtol": "1e-8",
         "atol": "1e-8",
         "rtol_max_iter_fraction": 1.0
@@ -65,6 +68,7 @@ static MORE_THUENTE_JSON: &str = r#"
 {
     "opt_conf": {
         "max_iter": 10,
+        "bounds": { "lower": ["-inf"], "upper": ["inf"] },
         "rtol": "1e-8",
         "atol": "1e-8",
         "rtol_max_iter_fraction": 1.0
@@ -90,6 +94,7 @@ static GOLDEN_SECTION_JSON: &str = r#"
 {
     "opt_conf": {
         "max_iter": 10,
+        "bounds": { "lower": ["-inf"], "upper": ["inf"] },
         "rtol": "1e-8",
         "atol": "1e-8",
         "rtol_max_iter_fraction": 1.0
@@ -115,6 +120,7 @@ static STRONG_WOLFE_JSON: &str = r#"
 {
     "opt_conf": {
         "max_iter": 10,
+        "bounds": { "lower": ["-inf"], "upper": ["inf"] },
         "rtol": "1e-8",
         "atol": "1e-8",
         "rtol_max_iter_fraction": 1.0
@@ -161,8 +167,8 @@ fn bench_lbfgs_backtracking(c: &mut Criterion) {
     c.bench_function("lbfgs_backtracking", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(BACKTRACKING_CONFIG.clone(), black_box(init_pop), RosenbrockFunction, None::<RosenbrockConstraints>);
-            let _st = opt.run();
+            let mut opt = NonConvexOpt::new(BACKTRACKING_CONFIG.clone(), black_box(init_pop), Rosenbrock, None::<RosenbrockConstraints>).unwrap();
+            let _st = opt.run().unwrap();
         })
     }); 
 }
@@ -171,8 +177,8 @@ fn bench_lbfgs_hager_zhang(c: &mut Criterion) {
     c.bench_function("lbfgs_hager_zhang", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(HAGER_ZHANG_CONFIG.clone(), black_box(init_pop), RosenbrockFunction, None::<RosenbrockConstraints>);
-            let _st = opt.run();
+            let mut opt = NonConvexOpt::new(HAGER_ZHANG_CONFIG.clone(), black_box(init_pop), Rosenbrock, None::<RosenbrockConstraints>).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }   
@@ -181,8 +187,8 @@ fn bench_lbfgs_more_thuente(c: &mut Criterion) {
     c.bench_function("lbfgs_more_thuente", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(MORE_THUENTE_CONFIG.clone(), black_box(init_pop), RosenbrockFunction, None::<RosenbrockConstraints>);
-            let _st = opt.run();
+            let mut opt = NonConvexOpt::new(MORE_THUENTE_CONFIG.clone(), black_box(init_pop), Rosenbrock, None::<RosenbrockConstraints>).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -191,8 +197,8 @@ fn bench_lbfgs_golden_section(c: &mut Criterion) {
     c.bench_function("lbfgs_golden_section", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(GOLDEN_SECTION_CONFIG.clone(), black_box(init_pop), RosenbrockFunction, None::<RosenbrockConstraints>);
-            let _st = opt.run();
+            let mut opt = NonConvexOpt::new(GOLDEN_SECTION_CONFIG.clone(), black_box(init_pop), Rosenbrock, None::<RosenbrockConstraints>).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }   
@@ -201,8 +207,8 @@ fn bench_lbfgs_strong_wolfe(c: &mut Criterion) {
     c.bench_function("lbfgs_strong_wolfe", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(STRONG_WOLFE_CONFIG.clone(), black_box(init_pop), RosenbrockFunction, None::<RosenbrockConstraints>);
-            let _st = opt.run();
+            let mut opt = NonConvexOpt::new(STRONG_WOLFE_CONFIG.clone(), black_box(init_pop), Rosenbrock, None::<RosenbrockConstraints>).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
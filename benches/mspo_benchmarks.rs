@@ -5,14 +5,13 @@ use rand::random;
 use std::sync::LazyLock;
 
 use non_convex_opt::NonConvexOpt;
+use non_convex_opt::benchmarks::KBF;
 use non_convex_opt::utils::config::Config;
 
-mod common;
-use common::fcns::{KBF, KBFConstraints};
-
 static CONFIG_JSON: &str = r#"
 {
     "opt_conf": {
+        "objective_sense": "Minimize",
         "max_iter": 10,
         "rtol": "1e-8",
         "atol": "1e-8",
@@ -25,8 +24,6 @@ static CONFIG_JSON: &str = r#"
             "w": 0.729,
             "c1": 1.5,
             "c2": 1.5,
-            "x_min": 0.0,
-            "x_max": 10.0,
             "exchange_interval": 20,
             "exchange_ratio": 0.05
         }
@@ -42,13 +39,13 @@ fn bench_mspo_unconstrained(c: &mut Criterion) {
     c.bench_function("mspo_unconstrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 100, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                None::<KBFConstraints>,
-            );
-            let _st = opt.run();
+                None::<KBF>,
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -58,13 +55,13 @@ fn bench_mspo_constrained(c: &mut Criterion) {
     c.bench_function("mspo_constrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 100, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                Some(KBFConstraints)
-            );
-            let _st = opt.run();
+                Some(KBF)
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
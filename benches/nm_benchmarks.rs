@@ -1,10 +1,9 @@
-mod common;
-use common::fcns::{KBF, KBFConstraints};
 use nalgebra::{SVector, SMatrix};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::{Config, OptConf, AlgConf, NelderMeadConf};
+use non_convex_opt::benchmarks::KBF;
+use non_convex_opt::utils::config::{Config, OptConf, AlgConf, ObjectiveSense, NelderMeadConf, BoundHandling};
 
 fn bench_nm_unconstrained(c: &mut Criterion) {
     let config = Config {
@@ -12,13 +11,15 @@ fn bench_nm_unconstrained(c: &mut Criterion) {
             max_iter: 10,
             rtol: -1e8,
             atol: -1e8,
-            rtol_max_iter_fraction: 1.0,
+            objective_sense: ObjectiveSense::Minimize,
+            ..Default::default()
         },
         alg_conf: AlgConf::NM(NelderMeadConf {
             alpha: 1.0,
             gamma: 2.0,
             rho: 0.5,
             sigma: 0.5,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
@@ -29,13 +30,13 @@ fn bench_nm_unconstrained(c: &mut Criterion) {
                 SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 4.0]),
                 SMatrix::<f64, 1, 2>::from_row_slice(&[3.0, 3.0]),
             ]);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 config.clone(),
                 black_box(init_simplex),
                 KBF,
-                None::<KBFConstraints>,
-            );
-            let _st = opt.run();
+                None::<KBF>,
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -46,13 +47,15 @@ fn bench_nm_constrained(c: &mut Criterion) {
             max_iter: 10,
             rtol: -1e8,
             atol: -1e8,
-            rtol_max_iter_fraction: 1.0,
+            objective_sense: ObjectiveSense::Minimize,
+            ..Default::default()
         },
         alg_conf: AlgConf::NM(NelderMeadConf {
             alpha: 1.0,
             gamma: 2.0,
             rho: 0.5,
             sigma: 0.5,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
@@ -62,13 +65,13 @@ fn bench_nm_constrained(c: &mut Criterion) {
                 SVector::<f64, 3>::from_vec(vec![1.8, 1.0, 0.0]),
                 SVector::<f64, 3>::from_vec(vec![0.5, 4.0, 0.0]),
             ]);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 config.clone(),
                 black_box(init_simplex),
                 KBF,
-                Some(KBFConstraints),
-            );
-            let _st = opt.run();
+                Some(KBF),
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -3,10 +3,8 @@ use rand::random;
 use nalgebra::SMatrix;
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::{Config, OptConf, AlgConf, SAConf};
-
-mod common;
-use common::fcns::{KBF, KBFConstraints};
+use non_convex_opt::benchmarks::KBF;
+use non_convex_opt::utils::config::{Config, OptConf, AlgConf, ObjectiveSense, SAConf, BoundHandling, Neighbourhood};
 
 fn bench_sa_unconstrained(c: &mut Criterion) {
     let config = Config {
@@ -14,7 +12,8 @@ fn bench_sa_unconstrained(c: &mut Criterion) {
             max_iter: 10,
             rtol: -1e8,
             atol: -1e8,
-            rtol_max_iter_fraction: 1.0,
+            objective_sense: ObjectiveSense::Minimize,
+            ..Default::default()
         },
         alg_conf: AlgConf::SA(SAConf {
             initial_temp: 1000.0,
@@ -22,21 +21,21 @@ fn bench_sa_unconstrained(c: &mut Criterion) {
             step_size: 0.5,
             num_neighbors: 20,
             reheat_after: 50,
-            x_min: 0.0,
-            x_max: 10.0,
+            bound_handling: BoundHandling::Clamp,
+            neighbourhood: Neighbourhood::Real,
         }),
     };
 
     c.bench_function("sa_unconstrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 config.clone(),
                 black_box(init_pop),
                 KBF,
-                None::<KBFConstraints>
-            );
-            let _st = opt.run();
+                None::<KBF>
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -47,7 +46,8 @@ fn bench_sa_constrained(c: &mut Criterion) {
             max_iter: 10,
             rtol: -1e8,
             atol: -1e8,
-            rtol_max_iter_fraction: 1.0,
+            objective_sense: ObjectiveSense::Minimize,
+            ..Default::default()
         },
         alg_conf: AlgConf::SA(SAConf {
             initial_temp: 1000.0,
@@ -55,21 +55,21 @@ fn bench_sa_constrained(c: &mut Criterion) {
             step_size: 0.5,
             num_neighbors: 20,
             reheat_after: 50,
-            x_min: 0.0,
-            x_max: 10.0,
+            bound_handling: BoundHandling::Clamp,
+            neighbourhood: Neighbourhood::Real,
         }),
     };
 
     c.bench_function("sa_constrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 config.clone(),
                 black_box(init_pop),
                 KBF,
-                Some(KBFConstraints)
-            );
-            let _st = opt.run();
+                Some(KBF)
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
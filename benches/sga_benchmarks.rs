@@ -3,10 +3,11 @@ use rand::random;
 use nalgebra::SMatrix;
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::{Config, OptConf, AlgConf, SGAConf};
+use non_convex_opt::benchmarks::Rosenbrock;
+use non_convex_opt::utils::config::{Config, OptConf, AlgConf, SGAConf, BoundHandling, BoundsConf};
 
 mod common;
-use common::fcns::{RosenbrockFunction, RosenbrockConstraints};
+use common::fcns::RosenbrockConstraints;
 
 fn bench_sga_unconstrained(c: &mut Criterion) {
     let config = Config {
@@ -14,11 +15,13 @@ fn bench_sga_unconstrained(c: &mut Criterion) {
             max_iter: 10,
             rtol: -1e8,
             atol: -1e8,
-            rtol_max_iter_fraction: 1.0,
+            bounds: Some(BoundsConf { lower: vec![f64::NEG_INFINITY], upper: vec![f64::INFINITY] }), // Unbounded, not the benchmark's [-5, 10]
+            ..Default::default()
         },
         alg_conf: AlgConf::SGA(SGAConf {
             learning_rate: 0.01,
             momentum: 0.9,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
@@ -28,10 +31,10 @@ fn bench_sga_unconstrained(c: &mut Criterion) {
             let mut opt = NonConvexOpt::new(
                 config.clone(),
                 black_box(init_pop),
-                RosenbrockFunction,
+                Rosenbrock,
                 None::<RosenbrockConstraints>
-            );
-            let _st = opt.run();
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
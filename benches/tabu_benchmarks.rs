@@ -5,14 +5,13 @@ use serde_json;
 use nalgebra::SMatrix;
 
 use non_convex_opt::NonConvexOpt;
+use non_convex_opt::benchmarks::KBF;
 use non_convex_opt::utils::config::{Config};
 
-mod common;
-use common::fcns::{KBF, KBFConstraints};
-
 static CONFIG_JSON: &str = r#"
 {
     "opt_conf": {
+        "objective_sense": "Minimize",
         "max_iter": 100,
         "rtol": "-1e-8",
         "atol": "-1e-8",
@@ -37,6 +36,7 @@ static CONFIG_JSON: &str = r#"
 static REACTIVE_CONFIG_JSON: &str = r#"
 {
     "opt_conf": {
+        "objective_sense": "Minimize",
         "max_iter": 10,
         "rtol": "-1e-8",
         "atol": "-1e-8",
@@ -77,13 +77,13 @@ fn bench_tabu_unconstrained(c: &mut Criterion) {
     c.bench_function("tabu_unconstrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                None::<KBFConstraints>
-            );
-            let _st = opt.run();
+                None::<KBF>
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -93,13 +93,13 @@ fn bench_tabu_constrained(c: &mut Criterion) {
     c.bench_function("tabu_constrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                Some(KBFConstraints)
-            );
-            let _st = opt.run();
+                Some(KBF)
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -109,13 +109,13 @@ fn bench_reactive_tabu_unconstrained(c: &mut Criterion) {
     c.bench_function("reactive_tabu_unconstrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 REACTIVE_CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                None::<KBFConstraints>
-            );
-            let _st = opt.run();
+                None::<KBF>
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
@@ -125,13 +125,13 @@ fn bench_reactive_tabu_constrained(c: &mut Criterion) {
     c.bench_function("reactive_tabu_constrained", |b| {
         b.iter(|| {
             let init_pop = SMatrix::<f64, 1, 2>::from_fn(|_, _| random::<f64>() * 10.0);
-            let mut opt = NonConvexOpt::new(
+            let mut opt = NonConvexOpt::new_with_constraints(
                 REACTIVE_CONFIG.clone(),
                 black_box(init_pop),
                 KBF,
-                Some(KBFConstraints)
-            );
-            let _st = opt.run();
+                Some(KBF)
+            ).unwrap();
+            let _st = opt.run().unwrap();
         })
     });
 }
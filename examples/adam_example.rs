@@ -8,37 +8,37 @@ use image::ImageReader;
 use common::fcns::{MultiModalFunction, BoxConstraints};
 use common::img::{create_contour_data, setup_gif, find_closest_color, setup_chart, get_color_palette};
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::{Config, OptConf, AlgConf, AdamConf};
+use non_convex_opt::utils::opt_prob::BooleanConstraint;
+use non_convex_opt::utils::config::{Config, OptConf, AlgConf, ObjectiveSense, AdamConf, BoundHandling};
 
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Config {
         opt_conf: OptConf {
             max_iter: 100,
-            rtol: 1e-6,
-            atol: 1e-6,
-            rtol_max_iter_fraction: 1.0,
+            ..Default::default()
         },
         alg_conf: AlgConf::Adam(AdamConf {
             learning_rate: 0.05, 
             beta1: 0.9,
             beta2: 0.999,
             epsilon: 1e-8,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
     let obj_f = MultiModalFunction;
-    let constraints = BoxConstraints;
+    let constraints = BooleanConstraint(BoxConstraints);
 
-    let mut opt = NonConvexOpt::new(
+    let mut opt = NonConvexOpt::new_with_constraints(
         config, 
         SMatrix::<f64, 1, 2>::from_row_slice(&[4.0, 9.0]),
         obj_f.clone(), 
         Some(constraints.clone())
-    );
+    ).unwrap();
 
     let resolution = 100;
-    let (z_values, min_val, max_val) = create_contour_data(&obj_f, resolution);
+    let (z_values, min_val, max_val) = create_contour_data(&obj_f, ObjectiveSense::Maximize, resolution);
     let color_palette = get_color_palette();
     let mut encoder = setup_gif("examples/gifs/adam_kbf.gif")?;
 
@@ -83,7 +83,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         frame.buffer = std::borrow::Cow::from(indexed_pixels);
         encoder.write_frame(&frame)?;
 
-        opt.step();
+        opt.step().unwrap();
     }
 
     std::fs::remove_file("examples/adam_frame.png")?;   
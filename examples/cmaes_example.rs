@@ -6,17 +6,18 @@ use plotters::prelude::*;
 use gif::Frame;
 use image::ImageReader;
 
-use common::fcns::{KBF, KBFConstraints};
 use common::img::{create_contour_data, setup_gif, find_closest_color, setup_chart, get_color_palette};
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::Config;
+use non_convex_opt::benchmarks::KBF;
+use non_convex_opt::utils::config::{Config, ObjectiveSense};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let config_json = r#"
     {
         "opt_conf": {
+            "objective_sense": "Minimize",
             "max_iter": 20,
             "rtol": "1e-6",
             "atol": "1e-6",
@@ -33,20 +34,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config: Config = serde_json::from_str(config_json).unwrap();
 
     let obj_f = KBF;
-    let constraints = KBFConstraints;
+    let constraints = KBF;
 
     let mut init_x = SMatrix::<f64, 100, 2>::zeros();
     init_x.row_mut(0).copy_from(&RowVector2::new(4.0, 9.0));
     
-    let mut opt = NonConvexOpt::new(
+    let mut opt = NonConvexOpt::new_with_constraints(
         config,
         init_x,
-        obj_f.clone(), 
-        Some(constraints.clone())
-    );
+        obj_f, 
+        Some(constraints)
+    ).unwrap();
 
     let resolution = 100;
-    let (z_values, min_val, max_val) = create_contour_data(&obj_f, resolution);
+    let (z_values, min_val, max_val) = create_contour_data(&obj_f, ObjectiveSense::Minimize, resolution);
     let color_palette = get_color_palette();
     let mut encoder = setup_gif("examples/gifs/cmaes_kbf.gif")?;
 
@@ -104,7 +105,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         frame.buffer = std::borrow::Cow::from(indexed_pixels);
         encoder.write_frame(&frame)?;
 
-        opt.step();
+        opt.step().unwrap();
     }
 
     std::fs::remove_file("examples/cmaes_frame.png")?;
@@ -3,10 +3,12 @@ use plotters::prelude::*;
 use plotters::coord::types::RangedCoordf64;
 use gif::{Encoder, Repeat};
 use std::fs::File;
-use non_convex_opt::utils::opt_prob::{ObjectiveFunction, BooleanConstraintFunction};
+use non_convex_opt::utils::opt_prob::{ObjectiveFunction, ConstraintFunction, ObjectiveSense};
 
+// Fitness over the [0, 10] box, so the better points are the brighter ones whatever the sense
 pub fn create_contour_data<F: ObjectiveFunction<f64, U2>>(
     obj_f: &F, 
+    sense: ObjectiveSense,
     resolution: usize
 ) -> (Vec<Vec<f64>>, f64, f64) {
     let mut z = vec![vec![0.0; resolution]; resolution];
@@ -18,7 +20,7 @@ pub fn create_contour_data<F: ObjectiveFunction<f64, U2>>(
             let x = 10.0 * i as f64 / (resolution - 1) as f64;
             let y = 10.0 * j as f64 / (resolution - 1) as f64;
             let point = SVector::<f64, 2>::from_vec(vec![x, y]);
-            let val = obj_f.f(&point);
+            let val = sense.apply(obj_f.f(&point));
             z[i][j] = val;
             min_val = min_val.min(val);
             max_val = max_val.max(val);
@@ -58,7 +60,7 @@ pub fn find_closest_color(r: u8, g: u8, b: u8, palette: &[u8]) -> usize {
     best_idx
 }
 
-pub fn setup_chart<'a, F: BooleanConstraintFunction<f64, U2>>(
+pub fn setup_chart<'a, F: ConstraintFunction<f64, U2>>(
     frame: usize,
     algorithm_name: &'a str,
     resolution: usize,
@@ -99,7 +101,7 @@ pub fn setup_chart<'a, F: BooleanConstraintFunction<f64, U2>>(
             );
 
             let point = SVector::<f64, 2>::from_vec(vec![x, y]);
-            if !constraints.g(&point) {
+            if constraints.g(&point).iter().any(|&gi| gi > 0.0) {
                 let stripe_width = 0.2;
                 let stripe_pos = ((x + y) / stripe_width).floor() as i32;
                 if stripe_pos % 2 == 0 {
@@ -5,19 +5,18 @@ use plotters::prelude::*;
 use gif::Frame;
 use image::ImageReader;
 
-use common::fcns::{KBF, KBFConstraints};
 use common::img::{create_contour_data, setup_gif, find_closest_color, setup_chart, get_color_palette};
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::{Config, OptConf, AlgConf, GRASPConf};
+use non_convex_opt::benchmarks::KBF;
+use non_convex_opt::utils::config::{Config, OptConf, AlgConf, ObjectiveSense, GRASPConf, BoundHandling};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Config {
         opt_conf: OptConf {
             max_iter: 50,
-            rtol: 1e-6,
-            atol: 1e-6,
-            rtol_max_iter_fraction: 1.0,
+            objective_sense: ObjectiveSense::Minimize,
+            ..Default::default()
         },
         alg_conf: AlgConf::GRASP(GRASPConf {
             num_candidates: 50,
@@ -25,24 +24,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             num_neighbors: 20,
             step_size: 0.1,
             perturbation_prob: 0.3,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
     let obj_f = KBF;
-    let constraints = KBFConstraints;
+    let constraints = KBF;
 
-    let mut opt = NonConvexOpt::new(
+    let mut opt = NonConvexOpt::new_with_constraints(
         config, 
         SMatrix::<f64, 1, 2>::from_vec(vec![
             rand::random::<f64>() * 10.0,
             rand::random::<f64>() * 10.0
         ]),
-        obj_f.clone(), 
-        Some(constraints.clone())
-    );
+        obj_f, 
+        Some(constraints)
+    ).unwrap();
 
     let resolution = 100;
-    let (z_values, min_val, max_val) = create_contour_data(&obj_f, resolution);
+    let (z_values, min_val, max_val) = create_contour_data(&obj_f, ObjectiveSense::Minimize, resolution);
     let color_palette = get_color_palette();
     let mut encoder = setup_gif("examples/gifs/grasp_kbf.gif")?;
 
@@ -95,7 +95,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         frame.buffer = std::borrow::Cow::from(indexed_pixels);
         encoder.write_frame(&frame)?;
 
-        opt.step();
+        opt.step().unwrap();
     }
 
     std::fs::remove_file("examples/grasp_frame.png")?;   
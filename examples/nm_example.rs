@@ -5,30 +5,30 @@ use plotters::prelude::*;
 use gif::Frame;
 use image::ImageReader;
 
-use common::fcns::{KBF, KBFConstraints};
 use common::img::{create_contour_data, setup_gif, find_closest_color, setup_chart, get_color_palette};
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::{Config, OptConf, AlgConf, NelderMeadConf};
+use non_convex_opt::benchmarks::KBF;
+use non_convex_opt::utils::config::{Config, OptConf, AlgConf, ObjectiveSense, NelderMeadConf, BoundHandling};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Config {
         opt_conf: OptConf {
             max_iter: 100,
-            rtol: 1e-6,
-            atol: 1e-6,
-            rtol_max_iter_fraction: 1.0,
+            objective_sense: ObjectiveSense::Minimize,
+            ..Default::default()
         },
         alg_conf: AlgConf::NM(NelderMeadConf {
             alpha: 1.0,
             gamma: 2.0,
             rho: 0.5,
             sigma: 0.5,
+            bound_handling: BoundHandling::Clamp,
         }),
     };
 
     let obj_f = KBF;
-    let constraints = KBFConstraints;
+    let constraints = KBF;
 
     // Create initial simplex directly
     let init_simplex = SMatrix::<f64, 3, 2>::from_rows(&[
@@ -37,10 +37,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         SMatrix::<f64, 1, 2>::from_row_slice(&[3.0, 3.0]),
     ]);
 
-    let mut opt = NonConvexOpt::new(config, init_simplex, obj_f.clone(), Some(constraints.clone()));
+    let mut opt = NonConvexOpt::new_with_constraints(config, init_simplex, obj_f, Some(constraints)).unwrap();
 
     let resolution = 100;
-    let (z_values, min_val, max_val) = create_contour_data(&obj_f, resolution);
+    let (z_values, min_val, max_val) = create_contour_data(&obj_f, ObjectiveSense::Minimize, resolution);
     let color_palette = get_color_palette();
     let mut encoder = setup_gif("examples/gifs/nm_kbf.gif")?;
 
@@ -107,7 +107,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         frame.buffer = std::borrow::Cow::from(indexed_pixels);
         encoder.write_frame(&frame)?;
 
-        opt.step();
+        opt.step().unwrap();
     }
 
     std::fs::remove_file("examples/nm_frame.png")?;
@@ -5,17 +5,18 @@ use plotters::prelude::*;
 use gif::Frame;
 use image::ImageReader;
 
-use common::fcns::{KBF, KBFConstraints};
 use common::img::{create_contour_data, setup_gif, find_closest_color, setup_chart, get_color_palette};
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::Config;
+use non_convex_opt::benchmarks::KBF;
+use non_convex_opt::utils::config::{Config, ObjectiveSense};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let conf_json = r#"
     {
         "opt_conf": {
+            "objective_sense": "Minimize",
             "max_iter": 100,
             "rtol": "1e-6",
             "atol": "1e-6"
@@ -42,7 +43,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Config::new(conf_json).unwrap();
 
     let obj_f = KBF;
-    let constraints = KBFConstraints;
+    let constraints = KBF;
 
     // Initialize population with random points
     let mut init_pop = SMatrix::<f64, 100, 2>::zeros();
@@ -52,10 +53,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    let mut opt = NonConvexOpt::new(config, init_pop, obj_f.clone(), Some(constraints.clone()));
+    let mut opt = NonConvexOpt::new_with_constraints(config, init_pop, obj_f, Some(constraints)).unwrap();
 
     let resolution = 100;
-    let (z_values, min_val, max_val) = create_contour_data(&obj_f, resolution);
+    let (z_values, min_val, max_val) = create_contour_data(&obj_f, ObjectiveSense::Minimize, resolution);
     let color_palette = get_color_palette();
     let mut encoder = setup_gif("examples/gifs/pt_kbf.gif")?;
 
@@ -113,7 +114,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         frame.buffer = std::borrow::Cow::from(indexed_pixels);
         encoder.write_frame(&frame)?;
 
-        opt.step();
+        opt.step().unwrap();
     }
 
     std::fs::remove_file("examples/pt_frame.png")?;
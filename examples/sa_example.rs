@@ -5,16 +5,17 @@ use plotters::prelude::*;
 use gif::Frame;
 use image::ImageReader;
 
-use common::fcns::{KBF, KBFConstraints};
 use common::img::{create_contour_data, setup_gif, find_closest_color, setup_chart, get_color_palette};
 
 use non_convex_opt::NonConvexOpt;
-use non_convex_opt::utils::config::Config;
+use non_convex_opt::benchmarks::KBF;
+use non_convex_opt::utils::config::{Config, ObjectiveSense};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let conf_json = r#"
     {
         "opt_conf": {
+            "objective_sense": "Minimize",
             "max_iter": 100,
             "rtol": "1e-6",
             "atol": "1e-6",
@@ -26,9 +27,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "cooling_rate": 0.998,
                 "step_size": 0.5,
                 "num_neighbors": 20,
-                "reheat_after": 50,
-                "x_min": 0.0,
-                "x_max": 10.0
+                "reheat_after": 50
             }
         }
     }
@@ -37,20 +36,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Config::new(conf_json).unwrap();
 
     let obj_f = KBF;
-    let constraints = KBFConstraints;
+    let constraints = KBF;
 
-    let mut opt = NonConvexOpt::new(
+    let mut opt = NonConvexOpt::new_with_constraints(
         config, 
         SMatrix::<f64, 1, 2>::from_vec(vec![
             rand::random::<f64>() * 10.0,
             rand::random::<f64>() * 10.0
         ]),
-        obj_f.clone(), 
-        Some(constraints.clone())
-    );
+        obj_f, 
+        Some(constraints)
+    ).unwrap();
 
     let resolution = 100;
-    let (z_values, min_val, max_val) = create_contour_data(&obj_f, resolution);
+    let (z_values, min_val, max_val) = create_contour_data(&obj_f, ObjectiveSense::Minimize, resolution);
     let color_palette = get_color_palette();
     let mut encoder = setup_gif("examples/gifs/sa_kbf.gif")?;
 
@@ -103,7 +102,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         frame.buffer = std::borrow::Cow::from(indexed_pixels);
         encoder.write_frame(&frame)?;
 
-        opt.step();
+        opt.step().unwrap();
     }
 
     std::fs::remove_file("examples/sa_frame.png")?;   
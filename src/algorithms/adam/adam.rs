@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -14,6 +16,27 @@ use crate::utils::opt_prob::{
     OptimizationAlgorithm,
     State
 };
+use crate::utils::bounds::BoundHandling;
+use crate::utils::rng::{OptRng, new_rng};
+use crate::utils::checkpoint::{
+    AlgCheckpoint, 
+    CheckpointError, 
+    StateData, 
+    NonFinite,
+    vector_to_data, 
+    vector_from_data
+};
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AdamCheckpoint {
+    pub st: StateData,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub m: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub v: Vec<f64>,
+    pub rng: OptRng,
+}
 
 pub struct Adam<T, N, D> 
 where 
@@ -31,6 +54,7 @@ where
     pub opt_prob: OptProb<T, D>,
     m: OVector<T, D>,  // First moment estimate
     v: OVector<T, D>,  // Second moment estimate
+    rng: OptRng, // Only drawn from by BoundHandling::Resample
 }
 
 impl<T, N, D> Adam<T, N, D> 
@@ -45,9 +69,10 @@ where
                      + Allocator<U1, D>
                      + Allocator<N>
 {
-    pub fn new(conf: AdamConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>) -> Self {
+    pub fn new(conf: AdamConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
         let init_x: OVector<T, D> = init_pop.row(0).transpose().into_owned();
         let best_f = opt_prob.evaluate(&init_x);
+        let violation = opt_prob.violation(&init_x);
         let n = init_x.len();
 
         Self {
@@ -57,14 +82,27 @@ where
                 best_f,
                 pop: OMatrix::<T, N, D>::from_fn_generic(N::from_usize(1), D::from_usize(n), |_, j| init_x.clone()[j]),
                 fitness: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, best_f),
-                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, opt_prob.is_feasible(&init_x.clone())),
+                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, violation == T::zero()),
+                violation: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, violation),
                 iter: 1
             },
             opt_prob,
             m: OVector::zeros_generic(D::from_usize(n), U1),
             v: OVector::zeros_generic(D::from_usize(n), U1),
+            rng: new_rng(seed),
         }
     }
+
+    pub fn from_checkpoint(conf: AdamConf, opt_prob: OptProb<T, D>, checkpoint: AdamCheckpoint) -> Result<Self, CheckpointError> {
+        Ok(Self {
+            conf,
+            st: checkpoint.st.to_state()?,
+            opt_prob,
+            m: vector_from_data(&checkpoint.m)?,
+            v: vector_from_data(&checkpoint.v)?,
+            rng: checkpoint.rng,
+        })
+    }
 }
 
 impl<T, N, D> OptimizationAlgorithm<T, N, D> for Adam<T, N, D> 
@@ -80,8 +118,9 @@ where
                     + Allocator<U1, D>
 {
     fn step(&mut self) {
-        let grad = self.opt_prob.objective.gradient(&self.st.best_x)
-            .expect("ADAM requires gradient information");
+        let Some(grad) = self.opt_prob.require_gradient(&self.st.best_x, "Adam") else {
+            return;
+        };
                 
         // Biased moment estimates
         self.m = self.m.clone() * T::from_f64(self.conf.beta1).unwrap() + 
@@ -99,29 +138,26 @@ where
         let epsilon = T::from_f64(self.conf.epsilon).unwrap();
         
         let update = m_hat.component_div(&v_hat.map(|x| x.sqrt() + epsilon)) * step_size;
-        self.st.best_x += update;
+        let handling = self.conf.bound_handling;
+        let x = self.opt_prob.repair(&(&self.st.best_x + update), &self.st.best_x, handling, &mut self.rng);
 
-        // Clamp onto feasible set 
-        if let Some(ref constraints) = self.opt_prob.constraints {
-            if !constraints.g(&self.st.best_x) {
-                if let (Some(lb), Some(ub)) = (self.opt_prob.objective.x_lower_bound(&self.st.best_x), 
-                                             self.opt_prob.objective.x_upper_bound(&self.st.best_x)) {
-                    for i in 0..self.st.best_x.len() {
-                        self.st.best_x[i] = self.st.best_x[i].max(lb[i]).min(ub[i]);
-                    }
-                }
-            }
+        // A step the death penalty leaves outside the box is dropped
+        if handling == BoundHandling::DeathPenalty && self.opt_prob.bounds.as_ref().is_some_and(|b| !b.contains(&x)) {
+            self.st.iter += 1;
+            return;
         }
 
-        let fitness = self.opt_prob.evaluate(&self.st.best_x);
+        let fitness = self.opt_prob.evaluate(&x);
+
+        self.st.best_x = x;
         if fitness > self.st.best_f {
             self.st.best_f = fitness;
-            self.st.best_x = self.st.best_x.clone();
         }
 
         self.st.pop.row_mut(0).copy_from(&self.st.best_x.transpose());
         self.st.fitness[0] = fitness;
-        self.st.constraints[0] = self.opt_prob.is_feasible(&self.st.best_x);
+        self.st.violation[0] = self.opt_prob.violation(&self.st.best_x);
+        self.st.constraints[0] = self.st.violation[0] == T::zero();
 
         self.st.iter += 1;
     }
@@ -134,4 +170,13 @@ where
     {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::Adam(AdamCheckpoint {
+            st: StateData::from_state(&self.st),
+            m: vector_to_data(&self.m),
+            v: vector_to_data(&self.v),
+            rng: self.rng.clone(),
+        })
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    DVector,
+    OMatrix,
+    OVector,
+    U1,
+};
+
+use crate::algorithms;
+use crate::utils::alg_conf::auglag_conf::AugLagConf;
+use crate::utils::opt_prob::{
+    FloatNumber as FloatNum,
+    ObjectiveFunction,
+    ObjectiveSense,
+    OptProb,
+    OptimizationAlgorithm,
+    State,
+    Diagnostics
+};
+use crate::utils::result::LagrangeReport;
+use crate::utils::rng::{OptRng, new_rng};
+use crate::utils::checkpoint::{
+    AlgCheckpoint,
+    CheckpointError,
+    StateData,
+    MatrixData,
+    NonFinite,
+    vector_to_data,
+    vector_from_data,
+    matrix_to_data,
+    matrix_from_data,
+    scalar_to_data,
+    scalar_from_data
+};
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AugLagCheckpoint {
+    pub st: StateData,
+    pub inner: Box<AlgCheckpoint>,
+    pub spread: MatrixData,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub ineq_multipliers: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub eq_multipliers: Vec<f64>,
+    pub penalty: f64,
+    #[serde_as(as = "NonFinite")]
+    pub residual: f64,
+    pub outer_iter: usize,
+    pub inner_iter: usize,
+    pub rng: OptRng,
+}
+
+// Constraint values by the bits of x, filled as the inner solver evaluates and shared by every clone,
+// so the bookkeeping on points it already evaluated doesn't call the constraints again
+type ConstraintCache<T> = Arc<Mutex<HashMap<Vec<u64>, (DVector<T>, DVector<T>)>>>;
+
+fn cache_key<T: FloatNum, D: Dim>(x: &OVector<T, D>) -> Vec<u64>
+where
+    DefaultAllocator: Allocator<D>
+{
+    x.iter().map(|xi| xi.to_f64().unwrap().to_bits()).collect()
+}
+
+// Fitness minus P(x) = sum_j [lambda_j h_j + rho/2 h_j^2] + 1/(2 rho) sum_i [max(0, mu_i + rho g_i)^2 - mu_i^2],
+// the objective the inner solver maximizes for fixed multipliers
+#[derive(Clone)]
+pub struct AugmentedLagrangian<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    pub prob: OptProb<T, D>, // The constrained problem
+    pub ineq: DVector<T>, // mu, one per inequality
+    pub eq: DVector<T>, // lambda, one per equality
+    pub penalty: T, // rho
+    cache: ConstraintCache<T>,
+}
+
+impl<T, D> AugmentedLagrangian<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    pub fn new(prob: OptProb<T, D>, ineq: DVector<T>, eq: DVector<T>, penalty: T) -> Self {
+        Self { prob, ineq, eq, penalty, cache: Arc::default() }
+    }
+
+    // OptProb::constraint_values, only called for points not seen since the last prune
+    pub fn constraint_values(&self, x: &OVector<T, D>) -> (DVector<T>, DVector<T>) {
+        let key = cache_key(x);
+        if let Some(values) = self.cache.lock().unwrap().get(&key) {
+            return values.clone();
+        }
+        let values = self.prob.constraint_values(x);
+        self.cache.lock().unwrap().insert(key, values.clone());
+        values
+    }
+
+    // Forgets every point but the ones given
+    pub fn prune(&self, keep: &[&OVector<T, D>]) {
+        let keep: Vec<Vec<u64>> = keep.iter().map(|x| cache_key(*x)).collect();
+        self.cache.lock().unwrap().retain(|key, _| keep.contains(key));
+    }
+
+    pub fn penalty_term(&self, x: &OVector<T, D>) -> T {
+        let (g, h) = self.constraint_values(x);
+        self.penalty_from(&g, &h)
+    }
+
+    // P from constraint values already at hand
+    fn penalty_from(&self, g: &DVector<T>, h: &DVector<T>) -> T {
+        let rho = self.penalty;
+        let half = T::from_f64(0.5).unwrap();
+
+        let eq = h.iter()
+            .zip(self.eq.iter())
+            .fold(T::zero(), |acc, (&hj, &lj)| acc + lj * hj + half * rho * hj * hj);
+        let ineq = g.iter()
+            .zip(self.ineq.iter())
+            .fold(T::zero(), |acc, (&gi, &mi)| {
+                let shifted = (mi + rho * gi).max(T::zero());
+                acc + shifted * shifted - mi * mi
+            });
+        eq + half * ineq / rho
+    }
+}
+
+impl<T, D> ObjectiveFunction<T, D> for AugmentedLagrangian<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    OVector<T, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<T, D>) -> T {
+        self.prob.sense.apply(self.prob.objective.f(x)) - self.penalty_term(x)
+    }
+
+    // Constraints have no gradients, so P is differentiated by central differences. Those points are off the
+    // inner solver's path, so they skip the cache but still count as constraint calls
+    fn gradient(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        let mut grad = match self.prob.sense {
+            ObjectiveSense::Maximize => self.prob.objective.gradient(x)?,
+            ObjectiveSense::Minimize => -self.prob.objective.gradient(x)?,
+        };
+        let two = T::from_f64(2.0).unwrap();
+        for i in 0..x.len() {
+            let h = (x[i] + T::epsilon().cbrt() * x[i].abs().max(T::one())) - x[i];
+            let mut forward = x.clone();
+            forward[i] += h;
+            let mut backward = x.clone();
+            backward[i] -= h;
+            let (g_forward, h_forward) = self.prob.constraint_values(&forward);
+            let (g_backward, h_backward) = self.prob.constraint_values(&backward);
+            grad[i] -= (self.penalty_from(&g_forward, &h_forward) - self.penalty_from(&g_backward, &h_backward)) / (two * h);
+        }
+        Some(grad)
+    }
+
+    // Plus the 2 dim constraint calls of the central differences, so max_evals bounds them too
+    fn gradient_evaluations(&self, dim: usize) -> usize {
+        let penalty = if self.prob.constraints.is_some() { 2 * dim } else { 0 };
+        self.prob.objective.gradient_evaluations(dim) + penalty
+    }
+}
+
+pub struct AugLag<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+{
+    pub conf: AugLagConf,
+    pub st: State<T, N, D>,
+    pub opt_prob: OptProb<T, D>,
+    pub inner: Box<dyn OptimizationAlgorithm<T, N, D>>,
+    pub lagrangian: AugmentedLagrangian<T, D>,
+    pub spread: OMatrix<T, N, D>, // Initial population relative to its first row, kept for restarts
+    pub residual: T, // Of the last outer iteration, inf before the first
+    pub outer_iter: usize,
+    pub inner_iter: usize, // Inner steps taken in the current outer iteration
+    rng: OptRng,
+}
+
+impl<T, N, D> AugLag<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<bool, N>: Send + Sync,
+    OVector<bool, D>: Send + Sync,
+    OMatrix<bool, U1, N>: Send + Sync,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OMatrix<T, D, D>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    OMatrix<T, U1, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+                    + Allocator<D, D>
+                    + Allocator<U1, D>
+                    + Allocator<U1, N>
+{
+    pub fn new(conf: AugLagConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
+        let mut rng = new_rng(seed);
+
+        // Multipliers start at zero, sized by the constraints at the first point
+        let x0 = init_pop.row(0).transpose();
+        let mut lagrangian = AugmentedLagrangian::new(opt_prob.clone(), DVector::zeros(0), DVector::zeros(0), T::from_f64(conf.initial_penalty).unwrap());
+        let (g, h) = lagrangian.constraint_values(&x0);
+        lagrangian.ineq = DVector::zeros(g.len());
+        lagrangian.eq = DVector::zeros(h.len());
+
+        let mut spread = init_pop.clone();
+        for i in 0..spread.nrows() {
+            let offset = init_pop.row(i) - init_pop.row(0);
+            spread.set_row(i, &offset);
+        }
+
+        let inner = algorithms::build(
+            (*conf.inner).clone(),
+            init_pop.clone(),
+            opt_prob.subproblem(Box::new(lagrangian.clone())),
+            conf.inner_iters,
+            Some(rng.random()),
+        );
+
+        // Filled in from the inner solver by sync_state
+        let st = State {
+            best_x: init_pop.row(0).transpose(),
+            best_f: T::neg_infinity(),
+            pop: init_pop.clone(),
+            fitness: OVector::<T, N>::from_element_generic(N::from_usize(init_pop.nrows()), U1, T::neg_infinity()),
+            constraints: OVector::<bool, N>::from_element_generic(N::from_usize(init_pop.nrows()), U1, false),
+            violation: OVector::<T, N>::zeros_generic(N::from_usize(init_pop.nrows()), U1),
+            iter: 1
+        };
+
+        let mut aug_lag = Self {
+            conf,
+            st,
+            opt_prob,
+            inner,
+            lagrangian,
+            spread,
+            residual: T::infinity(),
+            outer_iter: 0,
+            inner_iter: 0,
+            rng,
+        };
+        aug_lag.sync_state();
+        aug_lag
+    }
+
+    pub fn from_checkpoint(conf: AugLagConf, opt_prob: OptProb<T, D>, checkpoint: AugLagCheckpoint) -> Result<Self, CheckpointError> {
+        let lagrangian = AugmentedLagrangian::new(
+            opt_prob.clone(),
+            vector_from_data(&checkpoint.ineq_multipliers)?,
+            vector_from_data(&checkpoint.eq_multipliers)?,
+            scalar_from_data(checkpoint.penalty),
+        );
+        let inner = algorithms::restore(
+            (*conf.inner).clone(),
+            *checkpoint.inner,
+            opt_prob.subproblem(Box::new(lagrangian.clone())),
+            conf.inner_iters,
+        )?;
+
+        Ok(Self {
+            conf,
+            st: checkpoint.st.to_state()?,
+            opt_prob,
+            inner,
+            lagrangian,
+            spread: matrix_from_data(&checkpoint.spread)?,
+            residual: scalar_from_data(checkpoint.residual),
+            outer_iter: checkpoint.outer_iter,
+            inner_iter: checkpoint.inner_iter,
+            rng: checkpoint.rng,
+        })
+    }
+
+    // Copies the inner solver's population and best into the state, in terms of the original problem.
+    // The best is the inner solver's, the current estimate of the constrained optimum, so best_f
+    // can drop when the multipliers change. Constraint values come from the cache, and only the
+    // population and best are kept in it for the next step
+    fn sync_state(&mut self) {
+        let inner = self.inner.state();
+        let n = inner.pop.nrows();
+        let rows: Vec<OVector<T, D>> = (0..n).map(|i| inner.pop.row(i).transpose()).collect();
+
+        let fitness: Vec<T> = rows.iter().zip(inner.fitness.iter()).map(|(x, &f)| self.objective_fitness(x, f)).collect();
+        let violation: Vec<T> = rows.iter().map(|x| {
+            let (g, h) = self.lagrangian.constraint_values(x);
+            self.opt_prob.violation_from(&g, &h)
+        }).collect();
+        let best_f = self.objective_fitness(&inner.best_x, inner.best_f);
+        let (best_x, pop) = (inner.best_x.clone(), inner.pop.clone());
+
+        let mut keep: Vec<&OVector<T, D>> = rows.iter().collect();
+        keep.push(&best_x);
+        self.lagrangian.prune(&keep);
+
+        self.st.best_x = best_x;
+        self.st.best_f = best_f;
+        self.st.pop = pop;
+        self.st.fitness = OVector::<T, N>::from_vec_generic(N::from_usize(n), U1, fitness);
+        self.st.constraints = OVector::<bool, N>::from_iterator_generic(N::from_usize(n), U1, violation.iter().map(|&v| v == T::zero()));
+        self.st.violation = OVector::<T, N>::from_vec_generic(N::from_usize(n), U1, violation);
+    }
+
+    // Fitness of the original problem, recovered from the augmented Lagrangian value without evaluating again
+    fn objective_fitness(&self, x: &OVector<T, D>, al_fitness: T) -> T {
+        if al_fitness.is_finite() {
+            al_fitness + self.lagrangian.penalty_term(x)
+        } else {
+            al_fitness
+        }
+    }
+
+    // Multiplier and penalty update at the inner solver's best, then a restart of the inner solver around it
+    fn outer_update(&mut self) {
+        let x = self.inner.state().best_x.clone();
+        let (g, h) = self.lagrangian.constraint_values(&x);
+        let rho = self.lagrangian.penalty;
+
+        // Residual of the equalities and of the complementarity-adjusted inequalities
+        let mut squares = h.iter().fold(T::zero(), |acc, &hj| acc + hj * hj);
+        for (i, &gi) in g.iter().enumerate().take(self.lagrangian.ineq.len()) {
+            let r = gi.max(-self.lagrangian.ineq[i] / rho);
+            squares += r * r;
+        }
+        let residual = squares.sqrt();
+
+        for (j, &hj) in h.iter().enumerate().take(self.lagrangian.eq.len()) {
+            self.lagrangian.eq[j] += rho * hj;
+        }
+        for (i, &gi) in g.iter().enumerate().take(self.lagrangian.ineq.len()) {
+            self.lagrangian.ineq[i] = (self.lagrangian.ineq[i] + rho * gi).max(T::zero());
+        }
+
+        if residual > T::from_f64(self.conf.residual_reduction).unwrap() * self.residual {
+            self.lagrangian.penalty = (rho * T::from_f64(self.conf.penalty_growth).unwrap())
+                .min(T::from_f64(self.conf.max_penalty).unwrap());
+        }
+        self.residual = residual;
+        self.outer_iter += 1;
+        self.inner_iter = 0;
+
+        // The initial population's spread around the new point, so populations and simplices don't start collapsed
+        let mut init_pop = self.spread.clone();
+        for i in 0..init_pop.nrows() {
+            let mut row = x.clone() + self.spread.row(i).transpose();
+            if let Some(bounds) = &self.opt_prob.bounds {
+                bounds.clamp_mut(&mut row);
+            }
+            init_pop.set_row(i, &row.transpose());
+        }
+
+        self.inner = algorithms::build(
+            (*self.conf.inner).clone(),
+            init_pop,
+            self.opt_prob.subproblem(Box::new(self.lagrangian.clone())),
+            self.conf.inner_iters,
+            Some(self.rng.random()),
+        );
+    }
+}
+
+impl<T, N, D> OptimizationAlgorithm<T, N, D> for AugLag<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<bool, N>: Send + Sync,
+    OVector<bool, D>: Send + Sync,
+    OMatrix<bool, U1, N>: Send + Sync,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OMatrix<T, D, D>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    OMatrix<T, U1, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+                    + Allocator<D, D>
+                    + Allocator<U1, D>
+                    + Allocator<U1, N>
+{
+    fn step(&mut self) {
+        self.inner.step();
+        self.inner_iter += 1;
+
+        if self.inner_iter >= self.conf.inner_iters {
+            self.outer_update();
+        }
+
+        self.sync_state();
+        self.st.iter += 1;
+    }
+
+    fn state(&self) -> &State<T, N, D> {
+        &self.st
+    }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::AugLag(AugLagCheckpoint {
+            st: StateData::from_state(&self.st),
+            inner: Box::new(self.inner.checkpoint()),
+            spread: matrix_to_data(&self.spread),
+            ineq_multipliers: vector_to_data(&self.lagrangian.ineq),
+            eq_multipliers: vector_to_data(&self.lagrangian.eq),
+            penalty: scalar_to_data(self.lagrangian.penalty),
+            residual: scalar_to_data(self.residual),
+            outer_iter: self.outer_iter,
+            inner_iter: self.inner_iter,
+            rng: self.rng.clone(),
+        })
+    }
+
+    fn get_simplex(&self) -> Option<&Vec<OVector<T, D>>> {
+        self.inner.get_simplex()
+    }
+
+    fn diagnostics(&self) -> Diagnostics {
+        let mut diagnostics = self.inner.diagnostics();
+        diagnostics.insert("outer_iter".to_string(), self.outer_iter as f64);
+        diagnostics.insert("penalty".to_string(), self.lagrangian.penalty.to_f64().unwrap());
+        diagnostics.insert("residual".to_string(), self.residual.to_f64().unwrap());
+        diagnostics
+    }
+
+    fn lagrange(&self) -> Option<LagrangeReport> {
+        let (g, h) = self.lagrangian.constraint_values(&self.st.best_x);
+        Some(LagrangeReport {
+            ineq_multipliers: vector_to_data(&self.lagrangian.ineq),
+            eq_multipliers: vector_to_data(&self.lagrangian.eq),
+            ineq_residuals: vector_to_data(&g),
+            eq_residuals: vector_to_data(&h),
+            penalty: scalar_to_data(self.lagrangian.penalty),
+        })
+    }
+}
+
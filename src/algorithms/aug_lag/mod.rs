@@ -0,0 +1 @@
+pub mod aug_lag;
@@ -1,5 +1,6 @@
-use rand;
 use rand_distr::{Normal, Distribution};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -15,7 +16,23 @@ use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     OptimizationAlgorithm,
-    State
+    State,
+    Diagnostics
+};
+use crate::utils::rng::{OptRng, new_rng};
+use crate::utils::constraint_handling::ConstraintHandler;
+use crate::utils::checkpoint::{
+    AlgCheckpoint, 
+    CheckpointError, 
+    StateData, 
+    MatrixData,
+    NonFinite,
+    vector_to_data, 
+    vector_from_data,
+    matrix_to_data,
+    matrix_from_data,
+    scalar_to_data,
+    scalar_from_data
 };
 
 use crate::algorithms::cma_es::{
@@ -24,6 +41,37 @@ use crate::algorithms::cma_es::{
     evolution::{update_paths, update_covariance, compute_y},
 };
 
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CMAESCheckpoint {
+    pub st: StateData,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub mean: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub pc: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub ps: Vec<f64>,
+    pub c_mat: MatrixData,
+    pub b_mat: MatrixData,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub d_vec: Vec<f64>,
+    #[serde_as(as = "NonFinite")]
+    pub sigma: f64,
+    pub weights: Vec<f64>,
+    pub mu: usize,
+    pub lambda: usize,
+    pub mueff: f64,
+    pub cc: f64,
+    pub cs: f64,
+    pub c1: f64,
+    pub cmu: f64,
+    pub damps: f64,
+    pub chi_n: f64,
+    pub rng: OptRng,
+    #[serde(default)]
+    pub handler: Option<ConstraintHandler>,
+}
+
 pub struct CMAES<T, N, D>
 where 
     T: FloatNum,
@@ -61,6 +109,8 @@ where
     pub cmu: T,                 // Learning rate for rank-mu update
     pub damps: T,               // Damping for sigma
     pub chi_n: T,               // Expected norm of N(0,I)
+    pub handler: ConstraintHandler,
+    pub rng: OptRng,
 }
 
 impl<T, N, D> CMAES<T, N, D> 
@@ -77,7 +127,7 @@ where
                     + Allocator<D, D>
                     + Allocator<U1, D>
 {
-    pub fn new(conf: CMAESConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>) -> Self {
+    pub fn new(conf: CMAESConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
         let init_x: OVector<T, D> = init_pop.row(0).transpose().into_owned();
 
         let n = init_x.len();
@@ -85,7 +135,7 @@ where
         
         // Initial population
         let normal = Normal::new(0.0, 1.0).unwrap();
-        let mut rng = rand::rng();
+        let mut rng = new_rng(seed);
         let mut samples = Vec::with_capacity(params.lambda);
         
         for _ in 0..params.lambda {
@@ -106,27 +156,35 @@ where
             &d_vec.clone(),                    
             &opt_prob,
             T::from_f64(conf.initial_sigma).unwrap(),
+            conf.bound_handling,
+            &mut rng,
         );
 
         let mut population: OMatrix<T, N, D> = OMatrix::from_element_generic(N::from_usize(params.lambda), D::from_usize(n), T::zero());
         let mut fitness: OVector<T, N> = OVector::from_element_generic(N::from_usize(params.lambda), U1, T::zero());
         let mut constraints: OVector<bool, N> = OVector::from_element_generic(N::from_usize(params.lambda), U1, true);
+        let mut violation: OVector<T, N> = OVector::from_element_generic(N::from_usize(params.lambda), U1, T::zero());
+        update_arrays(&mut population, &mut fitness, &mut constraints, &mut violation, &results);
 
-        for (i, (x, f, c)) in results.iter().enumerate() {
-            population.row_mut(i).copy_from(&x.transpose());
-            fitness[i] = *f;
-            constraints[i] = *c;
+        let mut handler = ConstraintHandler::new(opt_prob.constraint_handling.clone());
+        handler.init(violation.as_slice());
+        let mut best_f = T::neg_infinity();
+        let mut best_x = init_x.clone();
+        for i in 0..params.lambda {
+            // A death-penalised sample lies outside the box, so keep the initial mean over it
+            if fitness[i] > T::neg_infinity() && handler.update_best(fitness[i], violation[i], best_f) {
+                best_f = fitness[i];
+                best_x = population.row(i).transpose();
+            }
         }
 
-        let best_f = fitness[0];
-        let best_x = population.row(0).transpose();
-
         let st = State {
             best_x: best_x.clone(),
             best_f,
             pop: population.clone(),
             fitness: fitness.clone(),
             constraints: constraints.clone(),
+            violation,
             iter: 0,
         };
 
@@ -151,20 +209,56 @@ where
             cmu: params.cmu,
             damps: params.damps,
             chi_n: params.chi_n,
+            handler,
+            rng,
         }
     }
 
-    fn generate_samples(&self, n: usize) -> Vec<OVector<T, D>> {
+    pub fn from_checkpoint(conf: CMAESConf, opt_prob: OptProb<T, D>, checkpoint: CMAESCheckpoint) -> Result<Self, CheckpointError> {
+        let handler = checkpoint.handler.unwrap_or_else(|| ConstraintHandler::new(opt_prob.constraint_handling.clone()));
+        Ok(Self {
+            conf,
+            opt_prob,
+            st: checkpoint.st.to_state()?,
+            mean: vector_from_data(&checkpoint.mean)?,
+            pc: vector_from_data(&checkpoint.pc)?,
+            ps: vector_from_data(&checkpoint.ps)?,
+            c_mat: matrix_from_data(&checkpoint.c_mat)?,
+            b_mat: matrix_from_data(&checkpoint.b_mat)?,
+            d_vec: vector_from_data(&checkpoint.d_vec)?,
+            sigma: scalar_from_data(checkpoint.sigma),
+            weights: vector_from_data(&checkpoint.weights)?,
+            mu: checkpoint.mu,
+            lambda: checkpoint.lambda,
+            mueff: scalar_from_data(checkpoint.mueff),
+            cc: scalar_from_data(checkpoint.cc),
+            cs: scalar_from_data(checkpoint.cs),
+            c1: scalar_from_data(checkpoint.c1),
+            cmu: scalar_from_data(checkpoint.cmu),
+            damps: scalar_from_data(checkpoint.damps),
+            chi_n: scalar_from_data(checkpoint.chi_n),
+            handler,
+            rng: checkpoint.rng,
+        })
+    }
+
+    fn generate_samples(&mut self, n: usize) -> Vec<OVector<T, D>> {
         let normal = Normal::new(0.0, 1.0).unwrap();
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
         
         (0..self.lambda)
         .map(|_| {
-            let iter = (0..n).map(|_| T::from_f64(normal.sample(&mut rng)).unwrap());
+            let iter = (0..n).map(|_| T::from_f64(normal.sample(rng)).unwrap());
             OVector::<T, D>::from_iterator_generic(D::from_usize(n), U1, iter)
         })
         .collect()
     }
+
+    // Largest coordinate-wise search step, sigma * max sqrt(C_ii)
+    pub fn tol_x(&self) -> T {
+        self.c_mat.diagonal().iter()
+            .fold(T::zero(), |acc, &c| acc.max(c.sqrt())) * self.sigma
+    }
 }
 
 impl<T, N, D> OptimizationAlgorithm<T, N, D> for CMAES<T, N, D> 
@@ -193,14 +287,16 @@ where
             &self.b_mat,
             &self.d_vec,
             &self.opt_prob, 
-            self.sigma
+            self.sigma,
+            self.conf.bound_handling,
+            &mut self.rng,
         );
         
         // Update population arrays
-        update_arrays(&mut self.st.pop, &mut self.st.fitness, &mut self.st.constraints, &results);
+        update_arrays(&mut self.st.pop, &mut self.st.fitness, &mut self.st.constraints, &mut self.st.violation, &results);
         
         // Sort and update mean
-        let indices = sort(&self.st.fitness, &self.st.constraints, self.lambda);
+        let indices = sort(&self.st.fitness, &self.st.violation, &self.handler, &mut self.rng);
         let old_mean = self.mean.clone();
         self.mean = OVector::zeros_generic(D::from_usize(n), U1);
         
@@ -242,7 +338,8 @@ where
             &self.weights,
             self.sigma,
             self.mu,
-            n
+            n,
+            &mut self.rng
         );
 
         // Update step size
@@ -253,15 +350,51 @@ where
         ));
 
         // Update best solution if improved
-        if self.st.constraints[indices[0]] && self.st.fitness[indices[0]] > self.st.best_f {
-            self.st.best_f = self.st.fitness[indices[0]];
-            self.st.best_x = self.st.pop.row(indices[0]).transpose();
+        for i in 0..self.lambda {
+            if self.st.fitness[i] > T::neg_infinity() && self.handler.update_best(self.st.fitness[i], self.st.violation[i], self.st.best_f) {
+                self.st.best_f = self.st.fitness[i];
+                self.st.best_x = self.st.pop.row(i).transpose();
+            }
         }
 
+        self.handler.next_generation();
+
         self.st.iter += 1;
     }
 
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::CMAES(CMAESCheckpoint {
+            st: StateData::from_state(&self.st),
+            mean: vector_to_data(&self.mean),
+            pc: vector_to_data(&self.pc),
+            ps: vector_to_data(&self.ps),
+            c_mat: matrix_to_data(&self.c_mat),
+            b_mat: matrix_to_data(&self.b_mat),
+            d_vec: vector_to_data(&self.d_vec),
+            sigma: scalar_to_data(self.sigma),
+            weights: vector_to_data(&self.weights),
+            mu: self.mu,
+            lambda: self.lambda,
+            mueff: scalar_to_data(self.mueff),
+            cc: scalar_to_data(self.cc),
+            cs: scalar_to_data(self.cs),
+            c1: scalar_to_data(self.c1),
+            cmu: scalar_to_data(self.cmu),
+            damps: scalar_to_data(self.damps),
+            chi_n: scalar_to_data(self.chi_n),
+            rng: self.rng.clone(),
+            handler: Some(self.handler.clone()),
+        })
+    }
+
+    fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::from([
+            ("sigma".to_string(), self.sigma.to_f64().unwrap()),
+            ("tol_x".to_string(), self.tol_x().to_f64().unwrap()),
+        ])
+    }
 } 
\ No newline at end of file
@@ -8,7 +8,10 @@ use nalgebra::{
     Dyn
 };
 
+use rand::Rng;
+
 use crate::utils::opt_prob::FloatNumber as FloatNum;
+use crate::utils::rng::OptRng;
 
 pub fn compute_y<T, D>(mean: &OVector<T, D>, old_mean: &OVector<T, D>, sigma: T) -> OVector<T, D> 
 where 
@@ -81,6 +84,7 @@ pub fn update_covariance<T: FloatNum, N: Dim, D: Dim>(
     sigma: T,
     mu: usize,
     n: usize,
+    rng: &mut OptRng,
 ) 
 where 
     DefaultAllocator: Allocator<D, D> 
@@ -160,7 +164,7 @@ where
     for i in 0..n {
         // Initialize random vector
         let mut v: OVector<T, D> = OVector::from_fn_generic(D::from_usize(n), U1, |_, _| {
-        T::from_f64(rand::random::<f64>()).unwrap() * T::from_f64(2.0).unwrap() - T::one()
+        T::from_f64(rng.random::<f64>()).unwrap() * T::from_f64(2.0).unwrap() - T::one()
     });
         
         // Orthogonalize against previous eigenvectors - this prevents repetition of eigenvectors
@@ -8,7 +8,11 @@ use nalgebra::{
     U1
 };
 use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb};
+use crate::utils::bounds::BoundHandling;
+use crate::utils::rng::OptRng;
+use crate::utils::constraint_handling::ConstraintHandler;
 
+#[allow(clippy::too_many_arguments)]
 pub fn evaluate_samples<T, D>(
     samples: &[OVector<T, D>], // use slice
     mean: &OVector<T, D>,
@@ -16,7 +20,9 @@ pub fn evaluate_samples<T, D>(
     d_vec: &OVector<T, D>,
     opt_prob: &OptProb<T, D>,
     sigma: T,
-) -> Vec<(OVector<T, D>, T, bool)>
+    handling: BoundHandling,
+    rng: &mut OptRng,
+) -> Vec<(OVector<T, D>, T, T)>
 where
     T: FloatNum,
     D: Dim,
@@ -24,7 +30,7 @@ where
     OMatrix<T, D, D>: Send + Sync,
     DefaultAllocator: Allocator<D> + Allocator<D, D>,
 {
-    samples
+    let raw: Vec<OVector<T, D>> = samples
         .par_iter()
         .map(|x| {
             let y = b_mat * &d_vec.component_mul(x);
@@ -32,9 +38,19 @@ where
             for i in 0..sample.len() {
                 sample[i] += sigma * y[i];
             }
-            let fitness = opt_prob.evaluate(&sample);
-            let constraint = opt_prob.is_feasible(&sample);
-            (sample, fitness, constraint)
+            sample
+        })
+        .collect();
+
+    // Repaired in order so any random draws don't depend on scheduling
+    let repaired: Vec<OVector<T, D>> = raw.iter().map(|x| opt_prob.repair(x, mean, handling, rng)).collect();
+
+    repaired
+        .into_par_iter()
+        .map(|sample| {
+            let fitness = opt_prob.evaluate_bounded(&sample, handling);
+            let violation = opt_prob.violation(&sample);
+            (sample, fitness, violation)
         })
         .collect()
 }
@@ -43,7 +59,8 @@ pub fn update_arrays<T: FloatNum, N: Dim, D: Dim>(
     population: &mut OMatrix<T, N, D>,
     fitness: &mut OVector<T, N>,
     constraints: &mut OVector<bool, N>,
-    results: &[(OVector<T, D>, T, bool)]
+    violation: &mut OVector<T, N>,
+    results: &[(OVector<T, D>, T, T)]
 ) 
 where 
     DefaultAllocator: Allocator<N, D> 
@@ -51,17 +68,19 @@ where
                      + Allocator<D>
                      + Allocator<U1, D>
 {
-    for (i, (x, f, c)) in results.iter().enumerate() {
+    for (i, (x, f, v)) in results.iter().enumerate() {
         population.row_mut(i).copy_from(&x.transpose());
         fitness[i] = *f;
-        constraints[i] = *c;
+        constraints[i] = *v == T::zero();
+        violation[i] = *v;
     }
 }
 
 pub fn sort<T, N>(
     fitness: &OVector<T, N>,
-    constraints: &OVector<bool, N>,
-    lambda: usize
+    violation: &OVector<T, N>,
+    handler: &ConstraintHandler,
+    rng: &mut OptRng,
 ) -> Vec<usize> 
 where 
     T: FloatNum,
@@ -69,16 +88,5 @@ where
     OVector<T, N>: Send + Sync,
     DefaultAllocator: Allocator<N> 
 {
-    let mut indices: Vec<usize> = (0..lambda).collect();
-    indices.sort_by(|&i, &j| {
-        let feasible_i = constraints[i];
-        let feasible_j = constraints[j];
-        match (feasible_i, feasible_j) {
-            (true, true) => fitness[j].partial_cmp(&fitness[i]).unwrap(),
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            (false, false) => fitness[j].partial_cmp(&fitness[i]).unwrap(),
-        }
-    });
-    indices
+    handler.rank(fitness.as_slice(), violation.as_slice(), rng)
 } 
\ No newline at end of file
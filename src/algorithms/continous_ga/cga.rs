@@ -1,4 +1,5 @@
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -9,20 +10,37 @@ use nalgebra::{
     Dyn,
 };
 
-use crate::utils::config::{CGAConf, CrossoverConf, SelectionConf, MutationConf};
+use crate::utils::config::{CGAConf, SelectionConf};
 use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     OptimizationAlgorithm,
     State
 };
+use crate::utils::rng::{OptRng, new_rng};
+use crate::utils::checkpoint::{AlgCheckpoint, CheckpointError, StateData};
+use crate::utils::constraint_handling::ConstraintHandler;
+use crate::utils::variables::Variables;
 
 use crate::algorithms::continous_ga::{
     selection::*,
     crossover::*,
-    mutation::{MutationOperator, Gaussian, Uniform, NonUniform, Polynomial},
+    mutation::{MutationOperator, build_mutation},
 };
 
+type Operators<T, N, D> = (
+    Box<dyn SelectionOperator<T, N, D> + Send + Sync>,
+    Box<dyn CrossoverOperator<T, N, D> + Send + Sync>,
+    Box<dyn MutationOperator<T, D> + Send + Sync>,
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CGACheckpoint {
+    pub st: StateData,
+    pub rng: OptRng,
+    #[serde(default)]
+    pub handler: Option<ConstraintHandler>,
+}
 
 pub struct CGA<T, N, D> 
 where 
@@ -43,6 +61,8 @@ where
     pub selector: Box<dyn SelectionOperator<T, N, D> + Send + Sync>,
     pub crossover: Box<dyn CrossoverOperator<T, N, D> + Send + Sync>,
     pub mutation: Box<dyn MutationOperator<T, D> + Send + Sync>,
+    pub handler: ConstraintHandler,
+    pub rng: OptRng,
 }
 
 impl<T, N, D> CGA<T, N, D> 
@@ -59,72 +79,31 @@ where
                     + Allocator<N>
                     + Allocator<U1, D>
 {
-    pub fn new(conf: CGAConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, max_iter: usize) -> Self {
-        let selector: Box<dyn SelectionOperator<T, N, D> + Send + Sync> = match &conf.selection {
-            SelectionConf::RouletteWheel(_) => Box::new(RouletteWheel::new(
-                init_pop.nrows(), 
-                conf.common.num_parents
-            )),
-            SelectionConf::Tournament(tournament) => Box::new(Tournament::new(
-                init_pop.nrows(), 
-                conf.common.num_parents, 
-                tournament.tournament_size
-            )),
-            SelectionConf::Residual(_) => Box::new(Residual::new(
-                init_pop.nrows(), 
-                conf.common.num_parents
-            )),
-        };
-
-        let crossover: Box<dyn CrossoverOperator<T, N, D> + Send + Sync> = match &conf.crossover {
-            CrossoverConf::Random(random) => Box::new(Random::new(
-                random.crossover_prob, 
-                init_pop.nrows()
-            )),
-            CrossoverConf::Heuristic(heuristic) => Box::new(Heuristic::new(
-                heuristic.crossover_prob, 
-                init_pop.nrows()
-            )),
-        };
+    pub fn new(conf: CGAConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, max_iter: usize, seed: Option<u64>) -> Self {
+        let (selector, crossover, mutation) = Self::build_operators(&conf, init_pop.nrows(), max_iter, opt_prob.variables.as_ref());
 
-        let mutation: Box<dyn MutationOperator<T, D> + Send + Sync> = match &conf.mutation {
-            MutationConf::Gaussian(gaussian) => Box::new(Gaussian::new(
-                gaussian.mutation_rate, 
-                gaussian.sigma
-            )),
-            MutationConf::Uniform(uniform) => Box::new(Uniform::new(
-                uniform.mutation_rate
-            )),
-            MutationConf::NonUniform(non_uniform) => Box::new(NonUniform::new(
-                non_uniform.mutation_rate,
-                non_uniform.b,
-                max_iter
-            )),
-            MutationConf::Polynomial(polynomial) => Box::new(Polynomial::new(
-                polynomial.mutation_rate,
-                polynomial.eta_m
-            )),
-        };
-        
         // Calculate initial fitness and constraints in parallel
-        let (fitness, constraints): (Vec<T>, Vec<bool>) = (0..init_pop.nrows())
+        let (fitness, violation): (Vec<T>, Vec<T>) = (0..init_pop.nrows())
             .into_par_iter()
             .map(|i| {
                 let individual = init_pop.row(i).transpose();
                 let fit = opt_prob.evaluate(&individual);
-                let constr = opt_prob.is_feasible(&individual);
-                (fit, constr)
+                let viol = opt_prob.violation(&individual);
+                (fit, viol)
             })
             .unzip();
 
         let fitness = OVector::<T, N>::from_vec_generic(N::from_usize(init_pop.nrows()), U1, fitness);
-        let constraints =  OVector::<bool, N>::from_vec_generic(N::from_usize(init_pop.nrows()), U1, constraints);
+        let constraints = OVector::<bool, N>::from_iterator_generic(N::from_usize(init_pop.nrows()), U1, violation.iter().map(|&v| v == T::zero()));
+        let violation = OVector::<T, N>::from_vec_generic(N::from_usize(init_pop.nrows()), U1, violation);
         
         // Find best individual
+        let mut handler = ConstraintHandler::new(opt_prob.constraint_handling.clone());
+        handler.init(violation.as_slice());
         let mut best_idx = 0;
-        let mut best_fitness = fitness[0];
-        for i in 1..fitness.len() {
-            if fitness[i] > best_fitness && constraints[i] {
+        let mut best_fitness = T::neg_infinity();
+        for i in 0..fitness.len() {
+            if handler.update_best(fitness[i], violation[i], best_fitness) {
                 best_idx = i;
                 best_fitness = fitness[i];
             }
@@ -137,6 +116,7 @@ where
                 pop: init_pop,
                 fitness,
                 constraints,
+                violation,
                 best_x: best_individual,
                 best_f: best_fitness,
                 iter: 1
@@ -145,8 +125,43 @@ where
             selector,
             crossover,
             mutation,
+            handler,
+            rng: new_rng(seed),
         }
     }
+
+    pub fn from_checkpoint(conf: CGAConf, opt_prob: OptProb<T, D>, max_iter: usize, checkpoint: CGACheckpoint) -> Result<Self, CheckpointError> {
+        let st: State<T, N, D> = checkpoint.st.to_state()?;
+        let (selector, crossover, mutation) = Self::build_operators(&conf, st.pop.nrows(), max_iter, opt_prob.variables.as_ref());
+
+        let handler = checkpoint.handler.unwrap_or_else(|| ConstraintHandler::new(opt_prob.constraint_handling.clone()));
+
+        Ok(Self { conf, st, opt_prob, selector, crossover, mutation, handler, rng: checkpoint.rng })
+    }
+
+    // Operators are stateless and fully determined by the config
+    fn build_operators(conf: &CGAConf, pop_size: usize, max_iter: usize, variables: Option<&Variables>) -> Operators<T, N, D> {
+        let selector: Box<dyn SelectionOperator<T, N, D> + Send + Sync> = match &conf.selection {
+            SelectionConf::RouletteWheel(_) => Box::new(RouletteWheel::new(
+                pop_size, 
+                conf.common.num_parents
+            )),
+            SelectionConf::Tournament(tournament) => Box::new(Tournament::new(
+                pop_size, 
+                conf.common.num_parents, 
+                tournament.tournament_size
+            )),
+            SelectionConf::Residual(_) => Box::new(Residual::new(
+                pop_size, 
+                conf.common.num_parents
+            )),
+        };
+
+        let crossover = build_crossover(&conf.crossover, pop_size);
+        let mutation = build_mutation(&conf.mutation, max_iter, variables);
+
+        (selector, crossover, mutation)
+    }
 }
 
 impl<T, N, D> OptimizationAlgorithm<T, N, D> for CGA<T, N, D> 
@@ -165,80 +180,86 @@ where
                     + Allocator<Dyn>
 {
     fn step(&mut self) {
-        let selected = self.selector.select(&self.st.pop, &self.st.fitness, &self.st.constraints);
-        let mut offspring = self.crossover.crossover(&selected);
-
-        // Apply mutation
-        let fallback_vec_lower = OVector::<T, D>::from_element_generic(D::from_usize(offspring.ncols()), U1, T::from_f64(-10.0).unwrap());
-        let fallback_vec_upper = OVector::<T, D>::from_element_generic(D::from_usize(offspring.ncols()), U1, T::from_f64(10.0).unwrap());
+        // Infeasible individuals can still be selected, ranked by the configured constraint handling
+        let scores = self.handler.scores(self.st.fitness.as_slice(), self.st.violation.as_slice(), &mut self.rng);
+        let scores = OVector::<T, N>::from_vec_generic(N::from_usize(scores.len()), U1, scores);
+        let eligible = OVector::<bool, N>::from_element_generic(N::from_usize(scores.len()), U1, true);
+        let selected = self.selector.select(&self.st.pop, &scores, &eligible, &mut self.rng);
+        let mut offspring = self.crossover.crossover(&selected, &mut self.rng);
 
-        let bounds = (
-            self.opt_prob.objective.x_lower_bound(&offspring.row(0).transpose())
-                .unwrap_or_else(|| fallback_vec_lower)[0],
-            self.opt_prob.objective.x_upper_bound(&offspring.row(0).transpose())
-                .unwrap_or_else(|| fallback_vec_upper)[0],
-        );
+        // Apply mutation, then bring the offspring back into the box
+        let bounds = self.opt_prob.bounds_or_unbounded(offspring.ncols());
+        let handling = self.conf.common.bound_handling;
 
         for i in 0..offspring.nrows() {
             let individual = offspring.row(i).transpose();
-            let mutated = self.mutation.mutate(&individual, bounds, self.st.iter);
-            offspring.set_row(i, &mutated.transpose());
+            let mutated = self.mutation.mutate(&individual, &bounds, self.st.iter, &mut self.rng);
+            let repaired = self.opt_prob.repair(&mutated, &individual, handling, &mut self.rng);
+            offspring.set_row(i, &repaired.transpose());
         }
 
-        let (new_fitness, new_constraints): (Vec<T>, Vec<bool>) = (0..offspring.nrows())
+        let (new_fitness, new_violation): (Vec<T>, Vec<T>) = (0..offspring.nrows())
             .into_par_iter()
             .map(|i| {
                 let individual = offspring.row(i).transpose();
-                let fit = self.opt_prob.evaluate(&individual);
-                let constr = self.opt_prob.is_feasible(&individual);
-                (fit, constr)
+                let fit = self.opt_prob.evaluate_bounded(&individual, handling);
+                let viol = self.opt_prob.violation(&individual);
+                (fit, viol)
             })
             .unzip();
 
         let mut new_fitness = OVector::<T, N>::from_vec_generic(N::from_usize(offspring.nrows()), U1, new_fitness);
-        let mut new_constraints = OVector::<bool, N>::from_vec_generic(N::from_usize(offspring.nrows()), U1, new_constraints);
+        let mut new_constraints = OVector::<bool, N>::from_iterator_generic(N::from_usize(offspring.nrows()), U1, new_violation.iter().map(|&v| v == T::zero()));
+        let mut new_violation = OVector::<T, N>::from_vec_generic(N::from_usize(offspring.nrows()), U1, new_violation);
 
         // Elitism: Keep the best individual from previous generation
         let mut best_old_idx = 0;
-        let mut best_old_fitness = self.st.fitness[0];
         for i in 1..self.st.fitness.len() {
-            if self.st.fitness[i] > best_old_fitness && self.st.constraints[i] {
+            if self.handler.better(self.st.fitness[i], self.st.violation[i], self.st.fitness[best_old_idx], self.st.violation[best_old_idx]) {
                 best_old_idx = i;
-                best_old_fitness = self.st.fitness[i];
             }
         }
 
         // Replace worst offspring with best old individual if better
         let mut worst_new_idx = 0;
-        let mut worst_new_fitness = new_fitness[0];
         for i in 1..new_fitness.len() {
-            if new_fitness[i] < worst_new_fitness {
+            if self.handler.better(new_fitness[worst_new_idx], new_violation[worst_new_idx], new_fitness[i], new_violation[i]) {
                 worst_new_idx = i;
-                worst_new_fitness = new_fitness[i];
             }
         }
 
-        if best_old_fitness > worst_new_fitness {
+        if self.handler.better(self.st.fitness[best_old_idx], self.st.violation[best_old_idx], new_fitness[worst_new_idx], new_violation[worst_new_idx]) {
             offspring.set_row(worst_new_idx, &self.st.pop.row(best_old_idx));
-            new_fitness[worst_new_idx] = best_old_fitness;
+            new_fitness[worst_new_idx] = self.st.fitness[best_old_idx];
             new_constraints[worst_new_idx] = self.st.constraints[best_old_idx];
+            new_violation[worst_new_idx] = self.st.violation[best_old_idx];
         }
 
         self.st.pop = offspring;
         self.st.fitness = new_fitness;
         self.st.constraints = new_constraints;
+        self.st.violation = new_violation;
 
         for i in 0..self.st.fitness.len() {
-            if self.st.fitness[i] > self.st.best_f && self.st.constraints[i] {
+            if self.handler.update_best(self.st.fitness[i], self.st.violation[i], self.st.best_f) {
                 self.st.best_f = self.st.fitness[i];
                 self.st.best_x = self.st.pop.row(i).transpose();
             }
         }
 
+        self.handler.next_generation();
         self.st.iter += 1;
     }
 
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::CGA(CGACheckpoint {
+            st: StateData::from_state(&self.st),
+            rng: self.rng.clone(),
+            handler: Some(self.handler.clone()),
+        })
+    }
 }
\ No newline at end of file
@@ -10,6 +10,9 @@ use nalgebra::{
 };
 
 use crate::utils::opt_prob::FloatNumber as FloatNum;
+use crate::utils::rng::OptRng;
+use crate::utils::config::CrossoverConf;
+use crate::utils::permutation::{pmx, ox, cycle, to_order, from_order};
 
 pub trait CrossoverOperator<T, N, D> 
 where 
@@ -23,7 +26,7 @@ where
                     + Allocator<N, D>
                     + Allocator<N>
 {
-    fn crossover(&self, parents: &OMatrix<T, Dyn, D>) -> OMatrix<T, N, D>;
+    fn crossover(&self, parents: &OMatrix<T, Dyn, D>, rng: &mut OptRng) -> OMatrix<T, N, D>;
 }
 
 pub struct Random {
@@ -52,8 +55,7 @@ where
                     + Allocator<D>
                     + Allocator<U1, D>
 {
-    fn crossover(&self, parents: &OMatrix<T, Dyn, D>) -> OMatrix<T, N, D> {
-        let mut rng = rand::rng();
+    fn crossover(&self, parents: &OMatrix<T, Dyn, D>, rng: &mut OptRng) -> OMatrix<T, N, D> {
         let mut offspring = OMatrix::<T, N, D>::zeros_generic(N::from_usize(self.population_size), D::from_usize(parents.ncols()));
 
         let num_parents = parents.nrows();
@@ -113,8 +115,7 @@ where
                     + Allocator<D>
                     + Allocator<U1, D>
 {
-    fn crossover(&self, parents: &OMatrix<T, Dyn, D>) -> OMatrix<T, N, D> {
-        let mut rng = rand::rng();
+    fn crossover(&self, parents: &OMatrix<T, Dyn, D>, rng: &mut OptRng) -> OMatrix<T, N, D> {
         let mut offspring = OMatrix::<T, N, D>::zeros_generic(N::from_usize(self.population_size), D::from_usize(parents.ncols()));
 
         let num_parents = parents.nrows();
@@ -145,3 +146,165 @@ where
         offspring
     }
 }
+
+// Pairs of random parents make two children with child(p1, p2) and child(p2, p1), or are copied when
+// they don't cross over. Shared by the permutation crossovers
+fn permutation_offspring<T, N, D>(
+    parents: &OMatrix<T, Dyn, D>,
+    population_size: usize,
+    crossover_prob: f64,
+    rng: &mut OptRng,
+    child: impl Fn(&[usize], &[usize], usize, usize) -> Vec<usize>,
+) -> OMatrix<T, N, D>
+where 
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<Dyn, D>
+                    + Allocator<N, D>
+                    + Allocator<D>
+                    + Allocator<U1, D>
+{
+    let n = parents.ncols();
+    let mut offspring = OMatrix::<T, N, D>::zeros_generic(N::from_usize(population_size), D::from_usize(n));
+    let num_parents = parents.nrows();
+
+    let mut offspring_count = 0;
+    while offspring_count < population_size {
+        let p1: Vec<usize> = to_order(&parents.row(rng.random_range(0..num_parents)).transpose());
+        let p2: Vec<usize> = to_order(&parents.row(rng.random_range(0..num_parents)).transpose());
+        let (c1, c2) = if rng.random::<f64>() < crossover_prob {
+            let (a, b) = (rng.random_range(0..n), rng.random_range(0..n));
+            let (i, j) = (a.min(b), a.max(b));
+            (child(&p1, &p2, i, j), child(&p2, &p1, i, j))
+        } else {
+            (p1, p2)
+        };
+
+        for c in [c1, c2] {
+            if offspring_count < population_size {
+                offspring.set_row(offspring_count, &from_order::<T, D>(&c).transpose());
+                offspring_count += 1;
+            }
+        }
+    }
+    offspring
+}
+
+// Partially mapped crossover, keeping a segment of one parent in place and the positions of the other
+pub struct PMX {
+    pub crossover_prob: f64, // F64 for RNG
+    pub population_size: usize,
+}
+
+impl PMX {
+    pub fn new(crossover_prob: f64, population_size: usize) -> Self {
+        Self { crossover_prob, population_size }
+    }
+}
+
+impl<T, N, D> CrossoverOperator<T, N, D> for PMX 
+where 
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<T, N>: Send + Sync,
+    OMatrix<T, Dyn, D>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    DefaultAllocator: Allocator<Dyn, D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<D>
+                    + Allocator<U1, D>
+{
+    fn crossover(&self, parents: &OMatrix<T, Dyn, D>, rng: &mut OptRng) -> OMatrix<T, N, D> {
+        permutation_offspring(parents, self.population_size, self.crossover_prob, rng, pmx)
+    }
+}
+
+// Order crossover, keeping a segment of one parent and the relative order of the other
+pub struct OX {
+    pub crossover_prob: f64, // F64 for RNG
+    pub population_size: usize,
+}
+
+impl OX {
+    pub fn new(crossover_prob: f64, population_size: usize) -> Self {
+        Self { crossover_prob, population_size }
+    }
+}
+
+impl<T, N, D> CrossoverOperator<T, N, D> for OX 
+where 
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<T, N>: Send + Sync,
+    OMatrix<T, Dyn, D>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    DefaultAllocator: Allocator<Dyn, D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<D>
+                    + Allocator<U1, D>
+{
+    fn crossover(&self, parents: &OMatrix<T, Dyn, D>, rng: &mut OptRng) -> OMatrix<T, N, D> {
+        permutation_offspring(parents, self.population_size, self.crossover_prob, rng, ox)
+    }
+}
+
+// Cycle crossover, every item keeping the position it has in one of the parents
+pub struct Cycle {
+    pub crossover_prob: f64, // F64 for RNG
+    pub population_size: usize,
+}
+
+impl Cycle {
+    pub fn new(crossover_prob: f64, population_size: usize) -> Self {
+        Self { crossover_prob, population_size }
+    }
+}
+
+impl<T, N, D> CrossoverOperator<T, N, D> for Cycle 
+where 
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<T, N>: Send + Sync,
+    OMatrix<T, Dyn, D>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    DefaultAllocator: Allocator<Dyn, D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<D>
+                    + Allocator<U1, D>
+{
+    fn crossover(&self, parents: &OMatrix<T, Dyn, D>, rng: &mut OptRng) -> OMatrix<T, N, D> {
+        permutation_offspring(parents, self.population_size, self.crossover_prob, rng, |p1, p2, _, _| cycle(p1, p2))
+    }
+}
+
+// Crossover selected by the config, producing population_size offspring. Shared by CGA and NSGA2
+pub fn build_crossover<T, N, D>(conf: &CrossoverConf, population_size: usize) -> Box<dyn CrossoverOperator<T, N, D> + Send + Sync>
+where 
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OMatrix<T, Dyn, D>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    DefaultAllocator: Allocator<Dyn, D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<D>
+                    + Allocator<U1, D>
+{
+    match conf {
+        CrossoverConf::Random(random) => Box::new(Random::new(random.crossover_prob, population_size)),
+        CrossoverConf::Heuristic(heuristic) => Box::new(Heuristic::new(heuristic.crossover_prob, population_size)),
+        CrossoverConf::PMX(pmx) => Box::new(PMX::new(pmx.crossover_prob, population_size)),
+        CrossoverConf::OX(ox) => Box::new(OX::new(ox.crossover_prob, population_size)),
+        CrossoverConf::Cycle(cycle) => Box::new(Cycle::new(cycle.crossover_prob, population_size)),
+    }
+}
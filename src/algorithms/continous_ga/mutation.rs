@@ -8,12 +8,18 @@ use nalgebra::{
 };
 
 use crate::utils::opt_prob::FloatNumber as FloatNum;
+use crate::utils::rng::OptRng;
+use crate::utils::bounds::Bounds;
+use crate::utils::config::MutationConf;
+use crate::utils::variables::Variables;
+use crate::utils::permutation::{self, to_order, from_order};
 
 pub trait MutationOperator<T: FloatNum, D: Dim> 
 where 
     DefaultAllocator: Allocator<D>
 {
-    fn mutate(&self, individual: &OVector<T, D>, bounds: (T, T), generation: usize) -> OVector<T, D>;
+    // May leave the box, the caller repairs with the configured BoundHandling
+    fn mutate(&self, individual: &OVector<T, D>, bounds: &Bounds<T, D>, generation: usize, rng: &mut OptRng) -> OVector<T, D>;
 }
 
 pub struct Gaussian {
@@ -34,15 +40,14 @@ where
     OVector<T, D>: Send + Sync,
     DefaultAllocator: Allocator<D>
 {
-    fn mutate(&self, individual: &OVector<T, D>, bounds: (T, T), _generation: usize) -> OVector<T, D> {
-        let mut rng = rand::rng();
+    fn mutate(&self, individual: &OVector<T, D>, _bounds: &Bounds<T, D>, _generation: usize, rng: &mut OptRng) -> OVector<T, D> {
         let normal = Normal::new(0.0, self.sigma).unwrap();
         let mut mutated = individual.clone();
 
         for i in 0..individual.len() {
             if rng.random::<f64>() < self.mutation_rate {
-                let noise = T::from_f64(normal.sample(&mut rng)).unwrap();
-                mutated[i] = (mutated[i] + noise).clamp(bounds.0, bounds.1);
+                let noise = T::from_f64(normal.sample(rng)).unwrap();
+                mutated[i] += noise;
             }
         }
         mutated
@@ -66,14 +71,13 @@ where
     OVector<T, D>: Send + Sync,
     DefaultAllocator: Allocator<D>
 {
-    fn mutate(&self, individual: &OVector<T, D>, bounds: (T, T), _generation: usize) -> OVector<T, D> {
-        let mut rng = rand::rng();
+    fn mutate(&self, individual: &OVector<T, D>, bounds: &Bounds<T, D>, _generation: usize, rng: &mut OptRng) -> OVector<T, D> {
         let mut mutated = individual.clone();
 
         for i in 0..individual.len() {
             if rng.random::<f64>() < self.mutation_rate {
                 mutated[i] = T::from_f64(
-                    rng.random_range(bounds.0.to_f64().unwrap()..bounds.1.to_f64().unwrap())
+                    rng.random_range(bounds.lower[i].to_f64().unwrap()..bounds.upper[i].to_f64().unwrap())
                 ).unwrap();
             }
         }
@@ -100,17 +104,16 @@ where
     OVector<T, D>: Send + Sync,
     DefaultAllocator: Allocator<D>
 {
-    fn mutate(&self, individual: &OVector<T, D>, bounds: (T, T), generation: usize) -> OVector<T, D> {
-        let mut rng = rand::rng();
+    fn mutate(&self, individual: &OVector<T, D>, bounds: &Bounds<T, D>, generation: usize, rng: &mut OptRng) -> OVector<T, D> {
         let mut mutated = individual.clone();
         let r = T::from_f64(rng.random::<f64>() * generation as f64 / self.max_generations as f64).unwrap();
 
         for i in 0..individual.len() {
             if rng.random::<f64>() < self.mutation_rate {
                 let delta = if rng.random_bool(0.5) {
-                    bounds.1 - mutated[i]
+                    bounds.upper[i] - mutated[i]
                 } else {
-                    mutated[i] - bounds.0
+                    mutated[i] - bounds.lower[i]
                 };
                 
                 let power = T::from_f64(
@@ -122,8 +125,6 @@ where
                 } else {
                     mutated[i] -= delta * power;
                 }
-                
-                mutated[i] = mutated[i].clamp(bounds.0, bounds.1);
             }
         }
         mutated
@@ -148,8 +149,7 @@ where
     OVector<T, D>: Send + Sync,
     DefaultAllocator: Allocator<D>
 {
-    fn mutate(&self, individual: &OVector<T, D>, bounds: (T, T), _generation: usize) -> OVector<T, D> {
-        let mut rng = rand::rng();
+    fn mutate(&self, individual: &OVector<T, D>, bounds: &Bounds<T, D>, _generation: usize, rng: &mut OptRng) -> OVector<T, D> {
         let mut mutated = individual.clone();
 
         for i in 0..individual.len() {
@@ -161,10 +161,107 @@ where
                     1.0 - (2.0 * (1.0 - r)).powf(1.0 / (self.eta_m + 1.0))
                 };
                 
-                mutated[i] = (mutated[i] + T::from_f64(delta).unwrap() * 
-                    (bounds.1 - bounds.0)).clamp(bounds.0, bounds.1);
+                mutated[i] += T::from_f64(delta).unwrap() * bounds.width(i);
             }
         }
         mutated
     }
-}
\ No newline at end of file
+}
+// Moves on orders for permutation problems, applied once to a mutated individual
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermutationMove {
+    Swap,
+    Insert,
+    Inversion,
+    Scramble,
+}
+
+// Applies its move to each individual with probability mutation_rate, so orders stay orders
+pub struct Permutation {
+    pub mutation_rate: f64,
+    pub kind: PermutationMove,
+}
+
+impl Permutation {
+    pub fn new(mutation_rate: f64, kind: PermutationMove) -> Self {
+        Self { mutation_rate, kind }
+    }
+}
+
+impl<T, D> MutationOperator<T, D> for Permutation 
+where 
+    T: FloatNum,
+    D: Dim,
+    OVector<T, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+{
+    fn mutate(&self, individual: &OVector<T, D>, _bounds: &Bounds<T, D>, _generation: usize, rng: &mut OptRng) -> OVector<T, D> {
+        if rng.random::<f64>() >= self.mutation_rate {
+            return individual.clone();
+        }
+        let mut order = to_order(individual);
+        match self.kind {
+            PermutationMove::Swap => permutation::swap(&mut order, rng),
+            PermutationMove::Insert => permutation::insert(&mut order, rng),
+            PermutationMove::Inversion => permutation::inversion(&mut order, rng),
+            PermutationMove::Scramble => permutation::scramble(&mut order, rng),
+        }
+        from_order(&order)
+    }
+}
+
+// Wraps another operator so a gene it moves in an integer or discrete dimension changes by at least
+// one level, instead of rounding back to where it was
+pub struct IntegerAware<T: FloatNum, D: Dim>
+where 
+    DefaultAllocator: Allocator<D>
+{
+    pub inner: Box<dyn MutationOperator<T, D> + Send + Sync>,
+    pub variables: Variables,
+}
+
+impl<T: FloatNum, D: Dim> IntegerAware<T, D>
+where 
+    DefaultAllocator: Allocator<D>
+{
+    pub fn new(inner: Box<dyn MutationOperator<T, D> + Send + Sync>, variables: Variables) -> Self {
+        Self { inner, variables }
+    }
+}
+
+impl<T, D> MutationOperator<T, D> for IntegerAware<T, D> 
+where 
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    fn mutate(&self, individual: &OVector<T, D>, bounds: &Bounds<T, D>, generation: usize, rng: &mut OptRng) -> OVector<T, D> {
+        let mutated = self.inner.mutate(individual, bounds, generation, rng);
+        self.variables.step_away(&mutated, individual, rng)
+    }
+}
+
+// Mutation selected by the config, integer-aware when the problem has integer or discrete variables.
+// Shared by CGA and NSGA2
+pub fn build_mutation<T, D>(conf: &MutationConf, max_iter: usize, variables: Option<&Variables>) -> Box<dyn MutationOperator<T, D> + Send + Sync>
+where 
+    T: FloatNum,
+    D: Dim,
+    OVector<T, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+{
+    let mutation: Box<dyn MutationOperator<T, D> + Send + Sync> = match conf {
+        MutationConf::Gaussian(gaussian) => Box::new(Gaussian::new(gaussian.mutation_rate, gaussian.sigma)),
+        MutationConf::Uniform(uniform) => Box::new(Uniform::new(uniform.mutation_rate)),
+        MutationConf::NonUniform(non_uniform) => Box::new(NonUniform::new(non_uniform.mutation_rate, non_uniform.b, max_iter)),
+        MutationConf::Polynomial(polynomial) => Box::new(Polynomial::new(polynomial.mutation_rate, polynomial.eta_m)),
+        MutationConf::Swap(m) => Box::new(Permutation::new(m.mutation_rate, PermutationMove::Swap)),
+        MutationConf::Insert(m) => Box::new(Permutation::new(m.mutation_rate, PermutationMove::Insert)),
+        MutationConf::Inversion(m) => Box::new(Permutation::new(m.mutation_rate, PermutationMove::Inversion)),
+        MutationConf::Scramble(m) => Box::new(Permutation::new(m.mutation_rate, PermutationMove::Scramble)),
+    };
+    match variables.filter(|variables| !variables.is_continuous()) {
+        Some(variables) => Box::new(IntegerAware::new(mutation, variables.clone())),
+        None => mutation,
+    }
+}
@@ -10,6 +10,7 @@ use nalgebra::{
 };
 
 use crate::utils::opt_prob::FloatNumber as FloatNum;
+use crate::utils::rng::OptRng;
 
 pub trait SelectionOperator<T, N, D> 
 where 
@@ -27,10 +28,27 @@ where
         &self, 
         population: &OMatrix<T, N, D>, 
         fitness: &OVector<T, N>, 
-        constraints: &OVector<bool, N>
+        constraints: &OVector<bool, N>,
+        rng: &mut OptRng
     ) -> OMatrix<T, Dyn, D>;
 }
 
+// Fitness-proportionate operators need non-negative weights, so shift by the
+// worst valid fitness when some are negative (e.g. when minimizing a cost)
+fn proportional_weights<T, N>(fitness: &OVector<T, N>, constraints: &OVector<bool, N>) -> Vec<T>
+where
+    T: FloatNum,
+    N: Dim,
+    DefaultAllocator: Allocator<N>
+{
+    let min_valid = fitness.iter()
+        .zip(constraints.iter())
+        .filter(|(_, &valid)| valid)
+        .fold(T::infinity(), |acc, (&x, _)| acc.min(x));
+    let offset = if min_valid < T::zero() { -min_valid } else { T::zero() };
+    fitness.iter().map(|&x| x + offset).collect()
+}
+
 pub struct RouletteWheel { 
     pub population_size: usize,
     pub num_parents: usize,
@@ -54,22 +72,22 @@ where
                     + Allocator<N, D>
                     + Allocator<Dyn, D>  
 {
-    fn select(&self, population: &OMatrix<T, N, D>, fitness: &OVector<T, N>, constraints: &OVector<bool, N>) -> OMatrix<T, Dyn, D> {
+    fn select(&self, population: &OMatrix<T, N, D>, fitness: &OVector<T, N>, constraints: &OVector<bool, N>, rng: &mut OptRng) -> OMatrix<T, Dyn, D> {
         // Normalized selection probabilities only for valid individuals
-        let sum = fitness.iter()
+        let weights = proportional_weights(fitness, constraints);
+        let sum = weights.iter()
             .zip(constraints.iter())
             .filter(|(_, &valid)| valid)
             .fold(T::zero(), |acc, (&x, _)| acc + x);
 
         let mut llhoods: OVector<T, N> = OVector::zeros_generic(N::from_usize(fitness.len()), U1);
-        for (j, (&fit, &valid)) in fitness.iter().zip(constraints.iter()).enumerate() {
+        for (j, (&fit, &valid)) in weights.iter().zip(constraints.iter()).enumerate() {
             if valid {
                 llhoods[j] = fit / sum;
             }
         }
         
         let mut selected = OMatrix::<T, Dyn, D>::zeros_generic(Dyn::from_usize(self.num_parents), D::from_usize(population.ncols()));
-        let mut rng = rand::rng();
 
         for i in 0..self.num_parents {
             let mut r = T::from_f64(rng.random_range(0.0..1.0));
@@ -113,9 +131,8 @@ where
                     + Allocator<N, D>
                     + Allocator<N> 
 {
-    fn select(&self, population: &OMatrix<T, N, D>, fitness: &OVector<T, N>, constraints: &OVector<bool, N>) -> OMatrix<T, Dyn, D> {
+    fn select(&self, population: &OMatrix<T, N, D>, fitness: &OVector<T, N>, constraints: &OVector<bool, N>, rng: &mut OptRng) -> OMatrix<T, Dyn, D> {
         let mut selected = OMatrix::<T, Dyn, D>::zeros_generic(Dyn::from_usize(self.num_parents), D::from_usize(population.ncols()));
-        let mut rng = rand::rng(); 
 
         for i in 0..self.num_parents {
             let mut tournament_indices = Vec::new();
@@ -169,12 +186,12 @@ where
                     + Allocator<N, D>
                     + Allocator<N>  
 {
-    fn select(&self, population: &OMatrix<T, N, D>, fitness: &OVector<T, N>, constraints: &OVector<bool, N>) -> OMatrix<T, Dyn, D> {
+    fn select(&self, population: &OMatrix<T, N, D>, fitness: &OVector<T, N>, constraints: &OVector<bool, N>, rng: &mut OptRng) -> OMatrix<T, Dyn, D> {
         let mut selected = OMatrix::<T, Dyn, D>::zeros_generic(Dyn::from_usize(self.num_parents), D::from_usize(population.ncols()));
-        let mut rng = rand::rng();
 
         // Calculate expected values
-        let sum = fitness.iter()
+        let weights = proportional_weights(fitness, constraints);
+        let sum = weights.iter()
             .zip(constraints.iter())
             .filter(|(_, &valid)| valid)
             .fold(T::zero(), |acc, (&x, _)| acc + x);
@@ -186,7 +203,7 @@ where
         let mut remaining_indices = Vec::new();
 
         // Calculate expected values and residuals
-        for (j, (&fit, &valid)) in fitness.iter().zip(constraints.iter()).enumerate() {
+        for (j, (&fit, &valid)) in weights.iter().zip(constraints.iter()).enumerate() {
             if valid {
                 let expected = (fit / sum) * scale;
                 let int_part = expected.floor();
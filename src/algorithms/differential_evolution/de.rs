@@ -1,5 +1,7 @@
 use rayon::prelude::*;
 use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -14,8 +16,22 @@ use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     OptimizationAlgorithm,
-    State
+    State,
+    Diagnostics,
+    fitness_cmp
 };
+use crate::utils::rng::{OptRng, new_rng, stream_rngs};
+use crate::utils::checkpoint::{
+    AlgCheckpoint, 
+    CheckpointError, 
+    StateData, 
+    NonFinite,
+    vector_to_data, 
+    vector_from_data,
+    scalar_to_data,
+    scalar_from_data
+};
+use crate::utils::constraint_handling::ConstraintHandler;
 
 use crate::algorithms::differential_evolution::mutation::{
     MutationStrategy,
@@ -26,6 +42,22 @@ use crate::algorithms::differential_evolution::mutation::{
     Rand2Bin,
 };
 
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DECheckpoint {
+    pub st: StateData,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub archive: Vec<Vec<f64>>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub archive_fitness: Vec<f64>,
+    pub success_history: VecDeque<bool>,
+    pub current_f: f64,
+    pub current_cr: f64,
+    pub rng: OptRng,
+    #[serde(default)]
+    pub handler: Option<ConstraintHandler>,
+}
+
 pub struct DE<T, N, D> 
 where 
     T: FloatNum,
@@ -46,6 +78,8 @@ where
     success_history: VecDeque<bool>,
     current_f: f64,
     current_cr: f64,
+    handler: ConstraintHandler,
+    rng: OptRng,
 }
 
 impl<T, N, D> DE<T, N, D> 
@@ -61,30 +95,34 @@ where
                     + Allocator<N>
                     + Allocator<N, D>
 {
-    pub fn new(conf: DEConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>) -> Self {
+    pub fn new(conf: DEConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
         let population_size = init_pop.nrows();
         let mut fitness = OVector::<T, N>::zeros_generic(N::from_usize(population_size), U1);
         let mut constraints = OVector::<bool, N>::from_element_generic(N::from_usize(population_size), U1, true);
+        let mut violation = OVector::<T, N>::zeros_generic(N::from_usize(population_size), U1);
         
-        let evaluations: Vec<(T, bool)> = (0..population_size)
+        let evaluations: Vec<(T, T)> = (0..population_size)
             .into_par_iter()
             .map(|i| {
                 let x = init_pop.row(i).transpose();
                 let fit = opt_prob.evaluate(&x);
-                let constr = opt_prob.is_feasible(&x);
-                (fit, constr)
+                let viol = opt_prob.violation(&x);
+                (fit, viol)
             })
             .collect();
 
-        for (i, (fit, constr)) in evaluations.into_iter().enumerate() {
+        for (i, (fit, viol)) in evaluations.into_iter().enumerate() {
             fitness[i] = fit;
-            constraints[i] = constr;
+            constraints[i] = viol == T::zero();
+            violation[i] = viol;
         }
 
+        let mut handler = ConstraintHandler::new(opt_prob.constraint_handling.clone());
+        handler.init(violation.as_slice());
         let mut best_idx = 0;
-        let mut best_fitness = fitness[0];
-        for i in 1..population_size {
-            if fitness[i] > best_fitness && constraints[i] {
+        let mut best_fitness = T::neg_infinity();
+        for i in 0..population_size {
+            if handler.update_best(fitness[i], violation[i], best_fitness) {
                 best_idx = i;
                 best_fitness = fitness[i];
             }
@@ -108,6 +146,7 @@ where
                 pop: init_pop.clone(),
                 fitness,
                 constraints,
+                violation,
                 best_x: init_pop.row(best_idx).transpose(),
                 best_f: best_fitness,
                 iter: 1
@@ -118,10 +157,28 @@ where
             success_history: VecDeque::with_capacity(success_history_size),
             current_f: initial_f,
             current_cr: initial_cr,
+            handler,
+            rng: new_rng(seed),
         }
     }
+
+    pub fn from_checkpoint(conf: DEConf, opt_prob: OptProb<T, D>, checkpoint: DECheckpoint) -> Result<Self, CheckpointError> {
+        let handler = checkpoint.handler.unwrap_or_else(|| ConstraintHandler::new(opt_prob.constraint_handling.clone()));
+        Ok(Self {
+            conf,
+            st: checkpoint.st.to_state()?,
+            opt_prob,
+            archive: checkpoint.archive.iter().map(|x| vector_from_data(x)).collect::<Result<_, _>>()?,
+            archive_fitness: checkpoint.archive_fitness.into_iter().map(scalar_from_data).collect(),
+            success_history: checkpoint.success_history,
+            current_f: checkpoint.current_f,
+            current_cr: checkpoint.current_cr,
+            handler,
+            rng: checkpoint.rng,
+        })
+    }
     
-    fn generate_trial_vector(&self, target_idx: usize) -> (OVector<T, D>, T, bool) {
+    fn generate_trial_vector(&self, target_idx: usize, rng: &mut OptRng) -> (OVector<T, D>, T, T) {
         let strategy = match &self.conf.mutation_type {
             MutationType::Standard(standard) => &standard.strategy,
             MutationType::Adaptive(adaptive) => &adaptive.strategy,
@@ -141,12 +198,17 @@ where
             target_idx,
             T::from_f64(self.current_f).unwrap(),
             T::from_f64(self.current_cr).unwrap(),
+            rng,
         );
 
-        let fitness = self.opt_prob.evaluate(&trial);
-        let constraint = self.opt_prob.is_feasible(&trial);
+        let handling = self.conf.common.bound_handling;
+        let target = self.st.pop.row(target_idx).transpose();
+        let trial = self.opt_prob.repair(&trial, &target, handling, rng);
 
-        (trial, fitness, constraint)
+        let fitness = self.opt_prob.evaluate_bounded(&trial, handling);
+        let violation = self.opt_prob.violation(&trial);
+
+        (trial, fitness, violation)
     }
 
     fn update_parameters(&mut self) {
@@ -166,7 +228,7 @@ where
         } else {
             if let Some(worst_idx) = self.archive_fitness.iter()
                 .enumerate()
-                .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                .min_by(|(_, a), (_, b)| fitness_cmp(*a, *b))
                 .map(|(i, _)| i)
             {
                 if fitness > self.archive_fitness[worst_idx] {
@@ -180,23 +242,12 @@ where
     fn select_trial(
         &self,
         trial_fitness: T,
-        trial_constraint: bool,
+        trial_violation: T,
         current_fitness: T,
-        current_constraint: bool,
+        current_violation: T,
+        rng: &mut OptRng,
     ) -> bool {
-        match (trial_constraint, current_constraint) {
-            (true, true) => {
-                // Both feasible - compare fitness with tolerance
-                let eps = T::from_f64(1e-10).unwrap();
-                trial_fitness > current_fitness + eps
-            },
-            (true, false) => true,  // Prefer feasible
-            (false, true) => false, // Keep feasible
-            (false, false) => {
-                // Both infeasible - compare fitness
-                trial_fitness > current_fitness
-            }
-        }
+        self.handler.duel(trial_fitness, trial_violation, current_fitness, current_violation, rng)
     }
 }
 
@@ -217,34 +268,36 @@ where
     fn step(&mut self) {
         let pop_size = self.st.pop.nrows();
         
-        let trials: Vec<_> = (0..pop_size)
+        let trials: Vec<_> = stream_rngs(&mut self.rng, pop_size)
             .into_par_iter()
-            .map(|i| {
-                let (trial, trial_fitness, trial_constraint) = self.generate_trial_vector(i);
+            .enumerate()
+            .map(|(i, mut rng)| {
+                let (trial, trial_fitness, trial_violation) = self.generate_trial_vector(i, &mut rng);
                 
                 let success = self.select_trial(
                     trial_fitness,
-                    trial_constraint,
+                    trial_violation,
                     self.st.fitness[i],
-                    self.st.constraints[i]
+                    self.st.violation[i],
+                    &mut rng
                 );
 
-                (i, trial, trial_fitness, trial_constraint, success)
+                (i, trial, trial_fitness, trial_violation, success)
             })
             .collect();
 
         let mut successes = Vec::new();
 
         let updates: Vec<_> = trials.into_iter()
-            .filter_map(|(i, trial, trial_fitness, trial_constraint, success)| {
-                if trial_constraint && trial_fitness > self.st.fitness[i] {
+            .filter_map(|(i, trial, trial_fitness, trial_violation, success)| {
+                if trial_violation == T::zero() && trial_fitness > self.st.fitness[i] {
                     self.update_archive(trial.clone(), trial_fitness);
                 }
 
                 successes.push(success);
                 
                 if success {
-                    Some((i, trial, trial_fitness, trial_constraint))
+                    Some((i, trial, trial_fitness, trial_violation))
                 } else {
                     None
                 }
@@ -263,28 +316,53 @@ where
         let mut new_population = self.st.pop.clone();
         let mut new_fitness = self.st.fitness.clone();
         let mut new_constraints = self.st.constraints.clone();
+        let mut new_violation = self.st.violation.clone();
 
-        for (i, trial, trial_fitness, trial_constraint) in updates {
+        for (i, trial, trial_fitness, trial_violation) in updates {
             new_population.set_row(i, &trial.transpose());
             new_fitness[i] = trial_fitness;
-            new_constraints[i] = trial_constraint;
+            new_constraints[i] = trial_violation == T::zero();
+            new_violation[i] = trial_violation;
         }
 
         self.st.pop = new_population;
         self.st.fitness = new_fitness;
         self.st.constraints = new_constraints;
+        self.st.violation = new_violation;
 
         for i in 0..pop_size {
-            if self.st.constraints[i] && self.st.fitness[i] > self.st.best_f {
+            if self.handler.update_best(self.st.fitness[i], self.st.violation[i], self.st.best_f) {
                 self.st.best_f = self.st.fitness[i];
                 self.st.best_x = self.st.pop.row(i).transpose();
             }
         }
 
+        self.handler.next_generation();
+
         self.st.iter += 1;
     }
 
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::DE(DECheckpoint {
+            st: StateData::from_state(&self.st),
+            archive: self.archive.iter().map(vector_to_data).collect(),
+            archive_fitness: self.archive_fitness.iter().map(|&f| scalar_to_data(f)).collect(),
+            success_history: self.success_history.clone(),
+            current_f: self.current_f,
+            current_cr: self.current_cr,
+            rng: self.rng.clone(),
+            handler: Some(self.handler.clone()),
+        })
+    }
+
+    fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::from([
+            ("f".to_string(), self.current_f),
+            ("cr".to_string(), self.current_cr),
+        ])
+    }
 } 
\ No newline at end of file
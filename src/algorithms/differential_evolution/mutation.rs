@@ -8,6 +8,7 @@ use nalgebra::{
 };
 
 use crate::utils::opt_prob::FloatNumber as FloatNum;
+use crate::utils::rng::OptRng;
 
 pub trait MutationStrategy<T, N, D> 
 where 
@@ -28,6 +29,7 @@ where
         target_idx: usize,
         f: T,
         cr: T,
+        rng: &mut OptRng,
     ) -> OVector<T, D>;
 }
 
@@ -37,8 +39,7 @@ pub struct RandToBest1Bin;
 pub struct Best2Bin;
 pub struct Rand2Bin;
 
-fn get_random_indices(count: usize, exclude: usize, pop_size: usize) -> Vec<usize> {
-    let mut rng = rand::rng();
+fn get_random_indices(count: usize, exclude: usize, pop_size: usize, rng: &mut OptRng) -> Vec<usize> {
     let mut indices = Vec::new();
     while indices.len() < count {
         let idx = rng.random_range(0..pop_size);
@@ -49,14 +50,13 @@ fn get_random_indices(count: usize, exclude: usize, pop_size: usize) -> Vec<usiz
     indices
 }
 
-fn crossover<T, D>(donor: OVector<T, D>, target: OVector<T, D>, cr: T) -> OVector<T, D> 
+fn crossover<T, D>(donor: OVector<T, D>, target: OVector<T, D>, cr: T, rng: &mut OptRng) -> OVector<T, D> 
 where 
     T: FloatNum,
     D: Dim,
     OVector<T, D>: Send + Sync,
     DefaultAllocator: Allocator<D>
 {
-    let mut rng = rand::rng();
     let dim = donor.len();
     let mut trial = target.clone();
     let j_rand = rng.random_range(0..dim);
@@ -88,14 +88,15 @@ where
         target_idx: usize,
         f: T,
         cr: T,
+        rng: &mut OptRng,
     ) -> OVector<T, D> {
-        let indices = get_random_indices(3, target_idx, population.nrows());
+        let indices = get_random_indices(3, target_idx, population.nrows(), rng);
         let x_r1 = population.row(indices[0]).transpose();
         let x_r2 = population.row(indices[1]).transpose();
         let x_r3 = population.row(indices[2]).transpose();
         
         let donor = x_r1 + (x_r2 - x_r3) * f;
-        crossover(donor, population.row(target_idx).transpose(), cr)
+        crossover(donor, population.row(target_idx).transpose(), cr, rng)
     }
 }
 
@@ -118,14 +119,15 @@ where
         target_idx: usize,
         f: T,
         cr: T,
+        rng: &mut OptRng,
     ) -> OVector<T, D> {
         let best_x = best_x.expect("Best1Bin requires best_x");
-        let indices = get_random_indices(2, target_idx, population.nrows());
+        let indices = get_random_indices(2, target_idx, population.nrows(), rng);
         let x_r1 = population.row(indices[0]).transpose();
         let x_r2 = population.row(indices[1]).transpose();
         
         let donor = best_x + (x_r1 - x_r2) * f;
-        crossover(donor, population.row(target_idx).transpose(), cr)
+        crossover(donor, population.row(target_idx).transpose(), cr, rng)
     }
 }
 
@@ -148,15 +150,16 @@ where
         target_idx: usize,
         f: T,
         cr: T,
+        rng: &mut OptRng,
     ) -> OVector<T, D> {
         let best_x = best_x.expect("RandToBest1Bin requires best_x");
-        let indices = get_random_indices(2, target_idx, population.nrows());
+        let indices = get_random_indices(2, target_idx, population.nrows(), rng);
         let x_r1 = population.row(indices[0]).transpose();
         let x_r2 = population.row(indices[1]).transpose();
         let x_i = population.row(target_idx).transpose();
         
         let donor = x_i.clone() + (best_x - &x_i) * f + (x_r1 - x_r2) * f;
-        crossover(donor, x_i, cr)
+        crossover(donor, x_i, cr, rng)
     }
 }
 
@@ -179,16 +182,17 @@ where
         target_idx: usize,
         f: T,
         cr: T,
+        rng: &mut OptRng,
     ) -> OVector<T, D> {
         let best_x = best_x.expect("Best2Bin requires best_x");
-        let indices = get_random_indices(4, target_idx, population.nrows());
+        let indices = get_random_indices(4, target_idx, population.nrows(), rng);
         let x_r1 = population.row(indices[0]).transpose();
         let x_r2 = population.row(indices[1]).transpose();
         let x_r3 = population.row(indices[2]).transpose();
         let x_r4 = population.row(indices[3]).transpose();
         
         let donor = best_x + (x_r1 + x_r2 - x_r3 - x_r4) * f;
-        crossover(donor, population.row(target_idx).transpose(), cr)
+        crossover(donor, population.row(target_idx).transpose(), cr, rng)
     }
 }
 
@@ -211,8 +215,9 @@ where
         target_idx: usize,
         f: T,
         cr: T,
+        rng: &mut OptRng,
     ) -> OVector<T, D> {
-        let indices = get_random_indices(5, target_idx, population.nrows());
+        let indices = get_random_indices(5, target_idx, population.nrows(), rng);
         let x_r1 = population.row(indices[0]).transpose();
         let x_r2 = population.row(indices[1]).transpose();
         let x_r3 = population.row(indices[2]).transpose();
@@ -220,7 +225,7 @@ where
         let x_r5 = population.row(indices[4]).transpose();
         
         let donor = x_r1 + (x_r2 + x_r3 - x_r4 - x_r5) * f;
-        crossover(donor, population.row(target_idx).transpose(), cr)
+        crossover(donor, population.row(target_idx).transpose(), cr, rng)
     }
 }
 
@@ -1,5 +1,6 @@
 use rand::Rng;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -14,8 +15,17 @@ use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     OptimizationAlgorithm,
-    State
+    State,
+    fitness_cmp
 };
+use crate::utils::rng::{OptRng, new_rng, stream_rngs};
+use crate::utils::checkpoint::{AlgCheckpoint, CheckpointError, StateData};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GRASPCheckpoint {
+    pub st: StateData,
+    pub rng: OptRng,
+}
 
 pub struct GRASP<T, N, D> 
 where 
@@ -31,6 +41,7 @@ where
     pub conf: GRASPConf,
     pub st: State<T, N, D>,
     pub opt_prob: OptProb<T, D>,
+    rng: OptRng,
 }
 
 impl<T, N, D> GRASP<T, N, D> 
@@ -47,9 +58,10 @@ where
                      + Allocator<U1, D>
                      + Allocator<N>
 {
-    pub fn new(conf: GRASPConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>) -> Self {
+    pub fn new(conf: GRASPConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
         let init_x = init_pop.row(0).transpose();
         let best_f = opt_prob.evaluate(&init_x);
+        let violation = opt_prob.violation(&init_x);
         let n = init_x.len();
 
         Self {
@@ -60,27 +72,28 @@ where
                 best_f: best_f,
                 pop: OMatrix::<T, N, D>::from_fn_generic(N::from_usize(1), D::from_usize(n), |_, j| init_x.clone()[j]),
                 fitness: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, best_f),
-                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, opt_prob.is_feasible(&init_x.clone())),
+                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, violation == T::zero()),
+                violation: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, violation),
                 iter: 1
             },
             opt_prob,
+            rng: new_rng(seed),
         }
     }
 
+    pub fn from_checkpoint(conf: GRASPConf, opt_prob: OptProb<T, D>, checkpoint: GRASPCheckpoint) -> Result<Self, CheckpointError> {
+        Ok(Self { conf, st: checkpoint.st.to_state()?, opt_prob, rng: checkpoint.rng })
+    }
+
     // Greedy randomized construction phase
-    pub fn construct_solution(&self) -> OVector<T, D> {
-        let candidates: Vec<OVector<T, D>> = (0..self.conf.num_candidates)
+    pub fn construct_solution(&mut self) -> OVector<T, D> {
+        let bounds = self.opt_prob.bounds_or_unbounded(self.st.best_x.len());
+        let candidates: Vec<OVector<T, D>> = stream_rngs(&mut self.rng, self.conf.num_candidates)
             .into_par_iter()
-            .map(|_| {
-                let mut rng = rand::rng(); // Create new RNG for each thread
+            .map(|mut rng| { // One stream per candidate
                 let mut candidate = OVector::<T, D>::zeros_generic(D::from_usize(self.st.best_x.len()), U1);
                 for i in 0..self.st.best_x.len() {
-                    // Get bounds if they exist
-                    let lb = self.opt_prob.objective.x_lower_bound(&candidate)
-                        .map_or(T::from_f64(-10.0).unwrap(), |v| v[i]);
-                    let ub = self.opt_prob.objective.x_upper_bound(&candidate)
-                        .map_or(T::from_f64(10.0).unwrap(), |v| v[i]);
-                    
+                    let (lb, ub) = (bounds.lower[i], bounds.upper[i]);                    
                     // Generate value within restricted candidate list (RCL)
                     let alpha = T::from_f64(self.conf.alpha).unwrap();
                     let rcl_min = lb * (T::one() - alpha) + ub * alpha;
@@ -90,35 +103,34 @@ where
                         rng.random_range(rcl_min.to_f64().unwrap()..rcl_max.to_f64().unwrap())
                     ).unwrap();
                 }
-                candidate
+                self.opt_prob.snap(&candidate)
             })
             .collect();
 
-        // Select best feasible candidate
+        // Select best feasible candidate, evaluating each one once
         candidates.into_iter()
             .filter(|c| self.opt_prob.is_feasible(c))
-            .max_by(|a, b| {
-                let fa = self.opt_prob.evaluate(a);
-                let fb = self.opt_prob.evaluate(b);
-                fa.partial_cmp(&fb).unwrap()
-            })
+            .map(|c| (self.opt_prob.evaluate(&c), c))
+            .max_by(|(fa, _), (fb, _)| fitness_cmp(fa, fb))
+            .map(|(_, c)| c)
             .unwrap_or(self.st.best_x.clone())
     }
 
     // Local search phase
-    pub fn local_search(&self, solution: &OVector<T, D>) -> OVector<T, D> {
+    pub fn local_search(&mut self, solution: &OVector<T, D>) -> OVector<T, D> {
         let mut current = solution.clone();
         let mut current_fitness = self.opt_prob.evaluate(&current);
         let mut improved = true;
+        let handling = self.conf.bound_handling;
 
-        while improved {
+        // Stop early if the evaluation budget runs out, this loop has no iteration cap
+        while improved && !self.opt_prob.budget_exhausted() {
             improved = false;
             
             // Generate and evaluate neighborhood in parallel
-            let neighbors: Vec<OVector<T, D>> = (0..self.conf.num_neighbors)
+            let neighbors: Vec<OVector<T, D>> = stream_rngs(&mut self.rng, self.conf.num_neighbors)
                 .into_par_iter()
-                .map(|_| {
-                    let mut rng = rand::rng();
+                .map(|mut rng| {
                     let mut neighbor = current.clone();
                     
                     // Perturb random dimensions
@@ -129,20 +141,17 @@ where
                             ).unwrap();
                         }
                     }
-                    neighbor
+                    let neighbor = self.opt_prob.step_away(&neighbor, &current, &mut rng);
+                    self.opt_prob.repair(&neighbor, &current, handling, &mut rng)
                 })
                 .collect();
 
             // Find best feasible neighbor
-            if let Some(best_neighbor) = neighbors.into_iter()
+            if let Some((neighbor_fitness, best_neighbor)) = neighbors.into_iter()
                 .filter(|n| self.opt_prob.is_feasible(n))
-                .max_by(|a, b| {
-                    let fa = self.opt_prob.evaluate(a);
-                    let fb = self.opt_prob.evaluate(b);
-                    fa.partial_cmp(&fb).unwrap()
-                }) 
+                .map(|n| (self.opt_prob.evaluate_bounded(&n, handling), n))
+                .max_by(|(fa, _), (fb, _)| fitness_cmp(fa, fb))
             {
-                let neighbor_fitness = self.opt_prob.evaluate(&best_neighbor);
                 if neighbor_fitness > current_fitness {
                     current = best_neighbor;
                     current_fitness = neighbor_fitness;
@@ -181,7 +190,8 @@ where
         
         self.st.pop.row_mut(0).copy_from(&improved_solution.transpose());
         self.st.fitness[0] = fitness;
-        self.st.constraints[0] = self.opt_prob.is_feasible(&improved_solution);
+        self.st.violation[0] = self.opt_prob.violation(&improved_solution);
+        self.st.constraints[0] = self.st.violation[0] == T::zero();
 
         self.st.iter += 1;
     }
@@ -189,4 +199,11 @@ where
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::GRASP(GRASPCheckpoint {
+            st: StateData::from_state(&self.st),
+            rng: self.rng.clone(),
+        })
+    }
 } 
\ No newline at end of file
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -12,7 +14,16 @@ use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     OptimizationAlgorithm,
-    State
+    State,
+    Diagnostics
+};
+use crate::utils::checkpoint::{
+    AlgCheckpoint, 
+    CheckpointError, 
+    StateData, 
+    NonFinite,
+    vector_to_data, 
+    vector_from_data
 };
 
 use crate::algorithms::limited_memory_bfgs::linesearch::{
@@ -24,6 +35,18 @@ use crate::algorithms::limited_memory_bfgs::linesearch::{
     GoldenSectionLineSearch
 };
 
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LBFGSCheckpoint {
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub x: Vec<f64>,
+    pub st: StateData,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub s: Vec<Vec<f64>>,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub y: Vec<Vec<f64>>,
+}
+
 pub struct LBFGS<T, N, D> 
 where 
     T: FloatNum,
@@ -42,9 +65,6 @@ where
     pub linesearch: Box<dyn LineSearch<T, D> + Send + Sync>,
     s: Vec<OVector<T, D>>,
     y: Vec<OVector<T, D>>,
-    has_bounds: bool,
-    lower_bounds: Option<OVector<T, D>>,
-    upper_bounds: Option<OVector<T, D>>,
 }
 
 impl<T, N, D> LBFGS<T, N, D> 
@@ -62,20 +82,10 @@ where
     pub fn new(conf: LBFGSConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>) -> Self {
         let init_x = init_pop.row(0).transpose();
         let best_f = opt_prob.evaluate(&init_x);
+        let violation = opt_prob.violation(&init_x);
         let n = init_x.len();
 
-        let linesearch: Box<dyn LineSearch<T, D> + Send + Sync> = match &conf.line_search {
-            LineSearchConf::Backtracking(backtracking_conf) => Box::new(BacktrackingLineSearch::new(backtracking_conf)),
-            LineSearchConf::StrongWolfe(strong_wolfe_conf) => Box::new(StrongWolfeLineSearch::new(strong_wolfe_conf)),
-            LineSearchConf::HagerZhang(hager_zhang_conf) => Box::new(HagerZhangLineSearch::new(hager_zhang_conf)),
-            LineSearchConf::MoreThuente(more_thuente_conf) => Box::new(MoreThuenteLineSearch::new(more_thuente_conf)),
-            LineSearchConf::GoldenSection(golden_section_conf) => Box::new(GoldenSectionLineSearch::new(golden_section_conf)),
-        };
-        
-        // Check if problem has bounds
-        let lower_bounds = opt_prob.objective.x_lower_bound(&init_x);
-        let upper_bounds = opt_prob.objective.x_upper_bound(&init_x);
-        let has_bounds = lower_bounds.is_some() || upper_bounds.is_some();
+        let linesearch = Self::build_linesearch(&conf);
 
         Self { 
             conf, 
@@ -86,28 +96,44 @@ where
                 best_f: best_f,
                 pop: OMatrix::<T, N, D>::from_fn_generic(N::from_usize(1), D::from_usize(n), |_, j| init_x.clone()[j]),
                 fitness: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, best_f),
-                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, opt_prob.is_feasible(&init_x.clone())),
+                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, violation == T::zero()),
+                violation: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, violation),
                 iter: 1
             },
             linesearch,
             s: Vec::new(),
             y: Vec::new(),
-            has_bounds,
-            lower_bounds,
-            upper_bounds,
         }
     }
 
-    fn project_onto_bounds(&self, x: &mut OVector<T, D>) {
-        if let Some(ref lb) = self.lower_bounds {
-            for i in 0..x.len() {
-                x[i] = x[i].max(lb[i]);
-            }
+    pub fn from_checkpoint(conf: LBFGSConf, opt_prob: OptProb<T, D>, checkpoint: LBFGSCheckpoint) -> Result<Self, CheckpointError> {
+        let x = vector_from_data(&checkpoint.x)?;
+        let linesearch = Self::build_linesearch(&conf);
+
+        Ok(Self {
+            conf,
+            opt_prob,
+            x,
+            st: checkpoint.st.to_state()?,
+            linesearch,
+            s: checkpoint.s.iter().map(|v| vector_from_data(v)).collect::<Result<_, _>>()?,
+            y: checkpoint.y.iter().map(|v| vector_from_data(v)).collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn build_linesearch(conf: &LBFGSConf) -> Box<dyn LineSearch<T, D> + Send + Sync> {
+        match &conf.line_search {
+            LineSearchConf::Backtracking(backtracking_conf) => Box::new(BacktrackingLineSearch::new(backtracking_conf)),
+            LineSearchConf::StrongWolfe(strong_wolfe_conf) => Box::new(StrongWolfeLineSearch::new(strong_wolfe_conf)),
+            LineSearchConf::HagerZhang(hager_zhang_conf) => Box::new(HagerZhangLineSearch::new(hager_zhang_conf)),
+            LineSearchConf::MoreThuente(more_thuente_conf) => Box::new(MoreThuenteLineSearch::new(more_thuente_conf)),
+            LineSearchConf::GoldenSection(golden_section_conf) => Box::new(GoldenSectionLineSearch::new(golden_section_conf)),
         }
-        if let Some(ref ub) = self.upper_bounds {
-            for i in 0..x.len() {
-                x[i] = x[i].min(ub[i]);
-            }
+    }
+
+    fn project_onto_bounds(&self, x: &mut OVector<T, D>) {
+        if let Some(ref bounds) = self.opt_prob.bounds {
+            bounds.clamp_mut(x);
         }
     }
 
@@ -117,11 +143,11 @@ where
         
         for i in 0..g.len() {
             if g[i] != T::zero() {
-                if let (Some(ref lb), Some(ref ub)) = (&self.lower_bounds, &self.upper_bounds) {
-                    if g[i] < T::zero() {
-                        t = t.min((ub[i] - self.x[i]) / g[i]);
-                    } else {
-                        t = t.min((lb[i] - self.x[i]) / g[i]);
+                if let Some(ref bounds) = self.opt_prob.bounds {
+                    // Infinite sides never limit the step
+                    let limit = if g[i] < T::zero() { bounds.upper[i] } else { bounds.lower[i] };
+                    if limit.is_finite() {
+                        t = t.min((limit - self.x[i]) / g[i]);
                     }
                 }
             }
@@ -144,6 +170,7 @@ where
         let alpha = self.linesearch.search(&self.st.best_x, &p, self.st.best_f, g, &self.opt_prob);
         let mut x_new = &self.x + &p * alpha;
         self.project_onto_bounds(&mut x_new);
+        let x_new = self.opt_prob.snap(&x_new);
 
         self.update_s_y_vectors(&x_new, g);
         self.update_best_solution(&x_new);
@@ -155,7 +182,7 @@ where
         
         let alpha = self.linesearch.search(&self.st.best_x, &p, self.st.best_f, g, &self.opt_prob);
         
-        let x_new = &self.x + &p * alpha;
+        let x_new = self.opt_prob.snap(&(&self.x + &p * alpha));
         
         self.update_s_y_vectors(&x_new, g);
         self.update_best_solution(&x_new);
@@ -232,7 +259,16 @@ where
 
     fn update_s_y_vectors(&mut self, x_new: &OVector<T, D>, g: &OVector<T, D>) {
         let s_new = x_new - &self.st.best_x;
-        let y_new = self.opt_prob.objective.gradient(x_new).unwrap() - g;
+        let Some(g_new) = self.opt_prob.require_gradient(x_new, "LBFGS") else {
+            return;
+        };
+        // Curvature pairs are stored for the minimisation of -f, so y is the change in its gradient
+        let y_new = g - g_new;
+
+        // A pair without positive curvature (e.g. from a failed line search or noisy gradients) would break the inverse Hessian
+        if s_new.dot(&y_new) <= T::epsilon() * s_new.dot(&s_new).sqrt() * y_new.dot(&y_new).sqrt() {
+            return;
+        }
 
         if self.s.len() == self.conf.common.memory_size {
             self.s.remove(0);
@@ -251,9 +287,7 @@ where
     }
 
     fn is_at_bound(&self, i: usize) -> bool {
-        let at_lower = self.lower_bounds.as_ref().map_or(false, |lb| self.st.best_x[i] == lb[i]);
-        let at_upper = self.upper_bounds.as_ref().map_or(false, |ub| self.st.best_x[i] == ub[i]);
-        at_lower || at_upper
+        self.opt_prob.bounds.as_ref().map_or(false, |b| self.st.best_x[i] == b.lower[i] || self.st.best_x[i] == b.upper[i])
     }
 }
 
@@ -270,16 +304,18 @@ where
                     + Allocator<U1, D>
 {
     fn step(&mut self) {
-        let g = self.opt_prob.objective.gradient(&self.x).unwrap();
+        let Some(g) = self.opt_prob.require_gradient(&self.x, "LBFGS") else {
+            return;
+        };
         
-        if self.has_bounds {
+        if self.opt_prob.bounds.is_some() {
             self.step_with_bounds(&g); // L-BFGS-B
         } else {
             self.step_without_bounds(&g); // L-BFGS
         }
 
         let fitness = self.opt_prob.evaluate(&self.x);
-        let constraints = self.opt_prob.is_feasible(&self.x);
+        let violation = self.opt_prob.violation(&self.x);
 
         if fitness > self.st.best_f {
             self.st.best_f = fitness;
@@ -288,7 +324,8 @@ where
 
         self.st.pop.row_mut(0).copy_from(&self.x.transpose());
         self.st.fitness[0] = fitness;
-        self.st.constraints[0] = constraints;
+        self.st.constraints[0] = violation == T::zero();
+        self.st.violation[0] = violation;
 
         self.st.iter += 1;
     }
@@ -296,4 +333,19 @@ where
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::LBFGS(LBFGSCheckpoint {
+            x: vector_to_data(&self.x),
+            st: StateData::from_state(&self.st),
+            s: self.s.iter().map(vector_to_data).collect(),
+            y: self.y.iter().map(vector_to_data).collect(),
+        })
+    }
+
+    fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::from([
+            ("memory_used".to_string(), self.s.len() as f64),
+        ])
+    }
 }
\ No newline at end of file
@@ -33,6 +33,16 @@ where
     ) -> T;
 }
 
+// The point alpha along p, snapped so integer and discrete dimensions are only evaluated on their levels
+fn trial<T, D>(x: &OVector<T, D>, p: &OVector<T, D>, alpha: T, opt_prob: &OptProb<T, D>) -> OVector<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    opt_prob.snap(&(x + p * alpha))
+}
+
 pub struct BacktrackingLineSearch {
     conf: BacktrackingConf,
 }
@@ -60,12 +70,16 @@ where
         opt_prob: &OptProb<T, D>,
     ) -> T {
         let mut alpha = T::one();
-        let mut x_new = x + p * alpha;
+        let mut x_new = trial(x, p, alpha, opt_prob);
         
-        // Repeat until the Armijo condition is satisfied (for maximization)
-        while opt_prob.evaluate(&x_new) < f + T::from_f64(self.conf.c1).unwrap() * alpha * g.dot(p) {
+        // Repeat until the Armijo condition is satisfied (for maximization), the evaluation budget is spent
+        // or the trial snaps back onto x, which shorter steps can't change
+        while opt_prob.evaluate(&x_new) < f + T::from_f64(self.conf.c1).unwrap() * alpha * g.dot(p) 
+            && !opt_prob.budget_exhausted() 
+            && x_new != *x
+        {
             alpha = alpha * T::from_f64(self.conf.rho).unwrap();
-            x_new = x + p * alpha;
+            x_new = trial(x, p, alpha, opt_prob);
         }
         
         alpha
@@ -106,9 +120,11 @@ where
         let initial_gp = g.dot(p);
 
         for _ in 0..self.conf.max_iters {
-            let x_new = x + p * alpha;
+            let x_new = trial(x, p, alpha, opt_prob);
             let f_new = opt_prob.evaluate(&x_new);
-            let g_new = opt_prob.objective.gradient(&x_new).unwrap();
+            let Some(g_new) = opt_prob.require_gradient(&x_new, "LBFGS") else {
+                return alpha;
+            };
             let g_new_p = g_new.dot(p);
             
             // For maximization:
@@ -164,9 +180,11 @@ where
         let initial_gp = g.dot(p);
 
         for _ in 0..self.conf.max_iters {
-            let x_new = x + p * alpha;
+            let x_new = trial(x, p, alpha, opt_prob);
             let f_new = opt_prob.evaluate(&x_new);
-            let g_new = opt_prob.objective.gradient(&x_new).unwrap();
+            let Some(g_new) = opt_prob.require_gradient(&x_new, "LBFGS") else {
+                return alpha;
+            };
             let g_new_p = g_new.dot(p);
 
             // For maximization:
@@ -222,9 +240,11 @@ where
         let initial_gp = g.dot(p);
 
         for _ in 0..self.conf.max_iters {
-            let x_new = x + p * alpha;
+            let x_new = trial(x, p, alpha, opt_prob);
             let f_new = opt_prob.evaluate(&x_new);
-            let g_new = opt_prob.objective.gradient(&x_new).unwrap();
+            let Some(g_new) = opt_prob.require_gradient(&x_new, "LBFGS") else {
+                return alpha;
+            };
             let g_new_p = g_new.dot(p);
 
             // For maximization:
@@ -270,26 +290,26 @@ impl GoldenSectionLineSearch {
         let mut b = T::one();
         let mut c = b * golden_ratio;
         
-        let mut fa = opt_prob.evaluate(&(x + p * a));
-        let mut fb = opt_prob.evaluate(&(x + p * b));
-        let mut fc = opt_prob.evaluate(&(x + p * c));
+        let mut fa = opt_prob.evaluate(&trial(x, p, a, opt_prob));
+        let mut fb = opt_prob.evaluate(&trial(x, p, b, opt_prob));
+        let mut fc = opt_prob.evaluate(&trial(x, p, c, opt_prob));
         
         // Expand the bracket until we find a triplet where the middle point is higher
-        while fb < fa || fb < fc {
+        while (fb < fa || fb < fc) && !opt_prob.budget_exhausted() {
             if fb < fa {
                 c = b;
                 b = a;
                 a = b / bracket_factor;
                 fc = fb;
                 fb = fa;
-                fa = opt_prob.evaluate(&(x + p * a));
+                fa = opt_prob.evaluate(&trial(x, p, a, opt_prob));
             } else {
                 a = b;
                 b = c;
                 c = b * bracket_factor;
                 fa = fb;
                 fb = fc;
-                fc = opt_prob.evaluate(&(x + p * c));
+                fc = opt_prob.evaluate(&trial(x, p, c, opt_prob));
             }
         }
         
@@ -319,8 +339,8 @@ where
         let (mut a, b, mut c) = self.bracket_maximum(x, p, opt_prob);
         let mut x0 = b - resphi * (c - a);
         let mut x1 = a + resphi * (c - a);
-        let mut f0 = opt_prob.evaluate(&(x + p * x0));
-        let mut f1 = opt_prob.evaluate(&(x + p * x1));
+        let mut f0 = opt_prob.evaluate(&trial(x, p, x0, opt_prob));
+        let mut f1 = opt_prob.evaluate(&trial(x, p, x1, opt_prob));
 
         loop {
             if (c - a).abs() < tol {
@@ -332,13 +352,13 @@ where
                 x1 = x0;
                 f1 = f0;
                 x0 = b - resphi * (c - a);
-                f0 = opt_prob.evaluate(&(x + p * x0));
+                f0 = opt_prob.evaluate(&trial(x, p, x0, opt_prob));
             } else {
                 a = x0;
                 x0 = x1;
                 f0 = f1;
                 x1 = a + resphi * (c - a);
-                f1 = opt_prob.evaluate(&(x + p * x1));
+                f1 = opt_prob.evaluate(&trial(x, p, x1, opt_prob));
             }
         }
     }
@@ -1,12 +1,141 @@
+use nalgebra::{
+    allocator::Allocator, 
+    DefaultAllocator, 
+    Dim, 
+    OVector, 
+    OMatrix,
+    U1
+};
+
 pub mod adam;
+pub mod aug_lag;
 pub mod cma_es;
 pub mod continous_ga;
 pub mod differential_evolution;
 pub mod grasp;
 pub mod limited_memory_bfgs;
+pub mod moead;
 pub mod multi_swarm;
 pub mod nelder_mead;
+pub mod nsga2;
 pub mod parallel_tempering;
 pub mod simulated_annealing;
 pub mod sg_ascent;
-pub mod tabu_search;
\ No newline at end of file
+pub mod tabu_search;
+
+use crate::utils::config::AlgConf;
+use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb, OptimizationAlgorithm};
+use crate::utils::checkpoint::{AlgCheckpoint, CheckpointError};
+
+use self::{
+    continous_ga::cga::CGA,
+    parallel_tempering::pt::PT,
+    adam::adam::Adam,
+    sg_ascent::sga::SGAscent,
+    tabu_search::tabu::TabuSearch,
+    grasp::grasp::GRASP,
+    nelder_mead::nm::NelderMead,
+    limited_memory_bfgs::lbfgs::LBFGS,
+    multi_swarm::mspo::MSPO,
+    simulated_annealing::sa::SimulatedAnnealing,
+    differential_evolution::de::DE,
+    cma_es::cma_es::CMAES,
+    aug_lag::aug_lag::AugLag,
+    nsga2::nsga2::NSGA2,
+    moead::moead::MOEAD,
+};
+
+// The algorithm selected by alg_conf, single-point algorithms start from the first row of init_pop
+pub fn build<T, N, D>(
+    alg_conf: AlgConf,
+    init_pop: OMatrix<T, N, D>,
+    opt_prob: OptProb<T, D>,
+    max_iter: usize,
+    seed: Option<u64>,
+) -> Box<dyn OptimizationAlgorithm<T, N, D>>
+where 
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<bool, N>: Send + Sync,
+    OVector<bool, D>: Send + Sync,
+    OMatrix<bool, U1, N>: Send + Sync,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OMatrix<T, D, D>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    OMatrix<T, U1, D>: Send + Sync,
+    DefaultAllocator: Allocator<D> 
+                    + Allocator<N>
+                    + Allocator<N, D>
+                    + Allocator<D, D>
+                    + Allocator<U1, D>
+                    + Allocator<U1, N>
+{
+    match alg_conf {
+        AlgConf::CGA(cga_conf) => Box::new(CGA::new(cga_conf, init_pop, opt_prob, max_iter, seed)),
+        AlgConf::PT(pt_conf) => Box::new(PT::new(pt_conf, init_pop, opt_prob, max_iter, seed)),
+        AlgConf::TS(ts_conf) => Box::new(TabuSearch::new(ts_conf, init_pop.row(0).into_owned(), opt_prob, seed)),
+        AlgConf::Adam(adam_conf) => Box::new(Adam::new(adam_conf, init_pop.row(0).into_owned(), opt_prob, seed)),
+        AlgConf::GRASP(grasp_conf) => Box::new(GRASP::new(grasp_conf, init_pop.row(0).into_owned(), opt_prob, seed)),
+        AlgConf::SGA(sga_conf) => Box::new(SGAscent::new(sga_conf, init_pop.row(0).into_owned(), opt_prob, seed)),
+        AlgConf::NM(nm_conf) => Box::new(NelderMead::new(nm_conf, init_pop, opt_prob, seed)),
+        AlgConf::LBFGS(lbfgs_conf) => Box::new(LBFGS::new(lbfgs_conf, init_pop.row(0).into_owned(), opt_prob)),
+        AlgConf::MSPO(mspo_conf) => Box::new(MSPO::new(mspo_conf, init_pop, opt_prob, seed)),
+        AlgConf::SA(sa_conf) => Box::new(SimulatedAnnealing::new(sa_conf, init_pop.row(0).into_owned(), opt_prob, seed)),
+        AlgConf::DE(de_conf) => Box::new(DE::new(de_conf, init_pop, opt_prob, seed)),
+        AlgConf::CMAES(cma_es_conf) => Box::new(CMAES::new(cma_es_conf, init_pop, opt_prob, seed)),
+        AlgConf::AugLag(aug_lag_conf) => Box::new(AugLag::new(aug_lag_conf, init_pop, opt_prob, seed)),
+        AlgConf::NSGA2(nsga2_conf) => Box::new(NSGA2::new(nsga2_conf, init_pop, opt_prob, max_iter, seed)),
+        AlgConf::MOEAD(moead_conf) => Box::new(MOEAD::new(moead_conf, init_pop, opt_prob, seed)),
+    }
+}
+
+// Rebuilds an algorithm from its checkpoint, which has to match alg_conf
+pub fn restore<T, N, D>(
+    alg_conf: AlgConf,
+    checkpoint: AlgCheckpoint,
+    opt_prob: OptProb<T, D>,
+    max_iter: usize,
+) -> Result<Box<dyn OptimizationAlgorithm<T, N, D>>, CheckpointError>
+where 
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<bool, N>: Send + Sync,
+    OVector<bool, D>: Send + Sync,
+    OMatrix<bool, U1, N>: Send + Sync,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OMatrix<T, D, D>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    OMatrix<T, U1, D>: Send + Sync,
+    DefaultAllocator: Allocator<D> 
+                    + Allocator<N>
+                    + Allocator<N, D>
+                    + Allocator<D, D>
+                    + Allocator<U1, D>
+                    + Allocator<U1, N>
+{
+    Ok(match (alg_conf, checkpoint) {
+        (AlgConf::CGA(c), AlgCheckpoint::CGA(ck)) => Box::new(CGA::from_checkpoint(c, opt_prob, max_iter, ck)?),
+        (AlgConf::PT(c), AlgCheckpoint::PT(ck)) => Box::new(PT::from_checkpoint(c, opt_prob, max_iter, ck)?),
+        (AlgConf::TS(c), AlgCheckpoint::TS(ck)) => Box::new(TabuSearch::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::Adam(c), AlgCheckpoint::Adam(ck)) => Box::new(Adam::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::GRASP(c), AlgCheckpoint::GRASP(ck)) => Box::new(GRASP::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::SGA(c), AlgCheckpoint::SGA(ck)) => Box::new(SGAscent::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::NM(c), AlgCheckpoint::NM(ck)) => Box::new(NelderMead::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::LBFGS(c), AlgCheckpoint::LBFGS(ck)) => Box::new(LBFGS::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::MSPO(c), AlgCheckpoint::MSPO(ck)) => Box::new(MSPO::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::SA(c), AlgCheckpoint::SA(ck)) => Box::new(SimulatedAnnealing::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::DE(c), AlgCheckpoint::DE(ck)) => Box::new(DE::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::CMAES(c), AlgCheckpoint::CMAES(ck)) => Box::new(CMAES::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::AugLag(c), AlgCheckpoint::AugLag(ck)) => Box::new(AugLag::from_checkpoint(c, opt_prob, ck)?),
+        (AlgConf::NSGA2(c), AlgCheckpoint::NSGA2(ck)) => Box::new(NSGA2::from_checkpoint(c, opt_prob, max_iter, ck)?),
+        (AlgConf::MOEAD(c), AlgCheckpoint::MOEAD(ck)) => Box::new(MOEAD::from_checkpoint(c, opt_prob, ck)?),
+        (alg_conf, ck) => return Err(CheckpointError::AlgorithmMismatch {
+            expected: alg_conf.name().to_string(),
+            found: ck.name().to_string(),
+        }),
+    })
+}
@@ -0,0 +1,336 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    DVector,
+    OMatrix,
+    OVector,
+    U1,
+    Dyn,
+};
+
+use crate::utils::config::{MOEADConf, DEStrategy, Scalarization};
+use crate::utils::opt_prob::{
+    FloatNumber as FloatNum,
+    OptProb,
+    OptimizationAlgorithm,
+    State,
+    fitness_cmp
+};
+use crate::utils::rng::{OptRng, new_rng};
+use crate::utils::checkpoint::{AlgCheckpoint, CheckpointError, StateData, NonFinite};
+use crate::utils::constraint_handling::{ConstraintHandler, ConstraintHandling};
+use crate::utils::bounds::BoundHandling;
+use crate::utils::result::ParetoFront;
+use crate::utils::pareto::{non_dominated_sort, pareto_front, uniform_weights};
+
+use crate::algorithms::differential_evolution::mutation::{
+    MutationStrategy, Rand1Bin, Best1Bin, RandToBest1Bin, Best2Bin, Rand2Bin,
+};
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MOEADCheckpoint {
+    pub st: StateData,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub objectives: Vec<Vec<f64>>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub ideal: Vec<f64>,
+    pub rng: OptRng,
+    pub handler: ConstraintHandler,
+}
+
+pub struct MOEAD<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OVector<bool, N>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+{
+    pub conf: MOEADConf,
+    pub st: State<T, N, D>, // fitness holds the sum of the objectives
+    pub opt_prob: OptProb<T, D>,
+    pub objectives: Vec<DVector<T>>, // In fitness units, member i solves the subproblem of weights[i]
+    pub ideal: DVector<T>, // Best value seen in each objective
+    pub weights: Vec<DVector<T>>,
+    pub neighbours: Vec<Vec<usize>>, // Subproblems with the closest weight vectors, closest first
+    pub handler: ConstraintHandler, // Tracks best_x by feasibility rules on the summed objectives
+    pub rng: OptRng,
+}
+
+impl<T, N, D> MOEAD<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OVector<bool, N>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<U1, D>
+{
+    pub fn new(conf: MOEADConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
+        let (weights, neighbours) = Self::subproblems(&conf, opt_prob.num_objectives(), init_pop.nrows());
+
+        let mut objectives = Vec::with_capacity(init_pop.nrows());
+        let mut violation = Vec::with_capacity(init_pop.nrows());
+        for row in init_pop.row_iter() {
+            let (f, v) = evaluate(&opt_prob, conf.bound_handling, &row.transpose());
+            objectives.push(f);
+            violation.push(v);
+        }
+
+        let mut ideal = DVector::from_element(opt_prob.num_objectives(), T::neg_infinity());
+        for f in &objectives {
+            update_ideal(&mut ideal, f);
+        }
+
+        let fitness: Vec<T> = objectives.iter().map(|f| f.sum()).collect();
+        let n = N::from_usize(init_pop.nrows());
+        let mut st = State {
+            best_x: init_pop.row(0).transpose(),
+            best_f: T::neg_infinity(),
+            pop: init_pop,
+            fitness: OVector::<T, N>::from_vec_generic(n, U1, fitness),
+            constraints: OVector::<bool, N>::from_iterator_generic(n, U1, violation.iter().map(|&v| v == T::zero())),
+            violation: OVector::<T, N>::from_vec_generic(n, U1, violation),
+            iter: 1,
+        };
+
+        let mut handler = ConstraintHandler::new(ConstraintHandling::FeasibilityRules);
+        update_best(&mut handler, &mut st);
+
+        Self { conf, st, opt_prob, objectives, ideal, weights, neighbours, handler, rng: new_rng(seed) }
+    }
+
+    pub fn from_checkpoint(conf: MOEADConf, opt_prob: OptProb<T, D>, checkpoint: MOEADCheckpoint) -> Result<Self, CheckpointError> {
+        let st: State<T, N, D> = checkpoint.st.to_state()?;
+        if checkpoint.objectives.len() != st.pop.nrows() {
+            return Err(CheckpointError::DimensionMismatch { expected: st.pop.nrows(), found: checkpoint.objectives.len() });
+        }
+
+        let to_vector = |f: &Vec<f64>| DVector::from_iterator(f.len(), f.iter().map(|&fi| T::from_f64(fi).unwrap()));
+        let (weights, neighbours) = Self::subproblems(&conf, checkpoint.ideal.len(), st.pop.nrows());
+
+        Ok(Self {
+            conf,
+            st,
+            opt_prob,
+            objectives: checkpoint.objectives.iter().map(to_vector).collect(),
+            ideal: to_vector(&checkpoint.ideal),
+            weights,
+            neighbours,
+            handler: checkpoint.handler,
+            rng: checkpoint.rng,
+        })
+    }
+
+    // Weight vectors and neighbourhoods are fixed by the config
+    fn subproblems(conf: &MOEADConf, num_objectives: usize, pop_size: usize) -> (Vec<DVector<T>>, Vec<Vec<usize>>) {
+        let weights = uniform_weights(num_objectives, conf.divisions);
+        assert_eq!(weights.len(), pop_size, "MOEAD needs one member per weight vector");
+
+        let distance = |a: &[f64], b: &[f64]| a.iter().zip(b.iter()).map(|(ai, bi)| (ai - bi).powi(2)).sum::<f64>();
+        let size = conf.neighbourhood_size.min(pop_size);
+        let neighbours = weights.iter()
+            .map(|wi| {
+                let mut order: Vec<usize> = (0..weights.len()).collect();
+                order.sort_by(|&a, &b| distance(&weights[a], wi).total_cmp(&distance(&weights[b], wi)));
+                order.truncate(size);
+                order
+            })
+            .collect();
+
+        let weights = weights.iter()
+            .map(|w| DVector::from_iterator(w.len(), w.iter().map(|&wi| T::from_f64(wi).unwrap())))
+            .collect();
+        (weights, neighbours)
+    }
+
+    // Subproblem value of objectives f for weights w, smaller is better
+    fn scalarize(&self, f: &DVector<T>, w: &DVector<T>) -> T {
+        let gap = &self.ideal - f;
+        let g = match &self.conf.scalarization {
+            Scalarization::Tchebycheff => {
+                // Zero weights would ignore an objective entirely
+                let floor = T::from_f64(1e-6).unwrap();
+                gap.iter().zip(w.iter()).fold(T::neg_infinity(), |acc, (&d, &wi)| acc.max(wi.max(floor) * d))
+            },
+            Scalarization::PBI { theta } => {
+                let direction = w / w.dot(w).sqrt();
+                let d1 = gap.dot(&direction);
+                let perpendicular = &gap - &direction * d1;
+                d1 + T::from_f64(*theta).unwrap() * perpendicular.dot(&perpendicular).sqrt()
+            },
+        };
+        if g.is_nan() { T::infinity() } else { g }
+    }
+
+    // Feasibility first, then the subproblem value
+    fn improves(&self, f: &DVector<T>, v: T, j: usize) -> bool {
+        let vj = self.st.violation[j];
+        if v != vj {
+            return v < vj;
+        }
+        self.scalarize(f, &self.weights[j]) <= self.scalarize(&self.objectives[j], &self.weights[j])
+    }
+
+    fn replace(&mut self, j: usize, x: &OVector<T, D>, f: &DVector<T>, v: T) {
+        self.st.pop.set_row(j, &x.transpose());
+        self.st.fitness[j] = f.sum();
+        self.st.constraints[j] = v == T::zero();
+        self.st.violation[j] = v;
+        self.objectives[j] = f.clone();
+    }
+}
+
+impl<T, N, D> OptimizationAlgorithm<T, N, D> for MOEAD<T, N, D>
+where
+    T: FloatNum,
+    D: Dim,
+    N: Dim,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OVector<bool, N>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    OMatrix<T, Dyn, D>: Send + Sync,
+    DefaultAllocator: Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<U1, D>
+                    + Allocator<D>
+                    + Allocator<Dyn, D>
+{
+    fn step(&mut self) {
+        let pop_size = self.st.pop.nrows();
+        let dim = self.st.pop.ncols();
+        let f = T::from_f64(self.conf.f).unwrap();
+        let cr = T::from_f64(self.conf.cr).unwrap();
+        let strategy: &dyn MutationStrategy<T, Dyn, D> = match self.conf.strategy {
+            DEStrategy::Rand1Bin => &Rand1Bin,
+            DEStrategy::Best1Bin => &Best1Bin,
+            DEStrategy::RandToBest1Bin => &RandToBest1Bin,
+            DEStrategy::Best2Bin => &Best2Bin,
+            DEStrategy::Rand2Bin => &Rand2Bin,
+        };
+
+        let mut order: Vec<usize> = (0..pop_size).collect();
+        order.shuffle(&mut self.rng);
+        for i in order {
+            // Mate and replace within the neighbourhood most of the time, across the population otherwise
+            let mut pool = if self.rng.random::<f64>() < self.conf.neighbourhood_prob {
+                self.neighbours[i].clone()
+            } else {
+                (0..pop_size).collect()
+            };
+
+            // The target goes first so DE draws its donors from the rest of the pool. The pool member
+            // best on subproblem i stands in for best_x
+            let mut mating = vec![i];
+            mating.extend(pool.iter().filter(|&&j| j != i));
+            let best = *mating.iter()
+                .min_by(|&&a, &&b| {
+                    let (ga, gb) = (self.scalarize(&self.objectives[a], &self.weights[i]), self.scalarize(&self.objectives[b], &self.weights[i]));
+                    fitness_cmp(&self.st.violation[b], &self.st.violation[a]).then(fitness_cmp(&gb, &ga))
+                })
+                .unwrap();
+            let best_x = self.st.pop.row(best).transpose();
+            let parents = OMatrix::<T, Dyn, D>::from_fn_generic(Dyn(mating.len()), D::from_usize(dim), |r, c| self.st.pop[(mating[r], c)]);
+
+            let trial = strategy.generate_trial(&parents, Some(&best_x), 0, f, cr, &mut self.rng);
+            let target = self.st.pop.row(i).transpose();
+            let child = self.opt_prob.repair(&trial, &target, self.conf.bound_handling, &mut self.rng);
+            let (child_f, child_v) = evaluate(&self.opt_prob, self.conf.bound_handling, &child);
+            update_ideal(&mut self.ideal, &child_f);
+
+            // The child takes over at most max_replacements subproblems it solves at least as well
+            pool.shuffle(&mut self.rng);
+            let mut replaced = 0;
+            for j in pool {
+                if replaced >= self.conf.max_replacements {
+                    break;
+                }
+                if self.improves(&child_f, child_v, j) {
+                    self.replace(j, &child, &child_f, child_v);
+                    replaced += 1;
+                }
+            }
+        }
+
+        update_best(&mut self.handler, &mut self.st);
+        self.st.iter += 1;
+    }
+
+    fn state(&self) -> &State<T, N, D> {
+        &self.st
+    }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::MOEAD(MOEADCheckpoint {
+            st: StateData::from_state(&self.st),
+            objectives: self.objectives.iter().map(|f| f.iter().map(|fi| fi.to_f64().unwrap()).collect()).collect(),
+            ideal: self.ideal.iter().map(|zi| zi.to_f64().unwrap()).collect(),
+            rng: self.rng.clone(),
+            handler: self.handler.clone(),
+        })
+    }
+
+    fn pareto_front(&self) -> Option<ParetoFront> {
+        let fronts = non_dominated_sort(&self.objectives, self.st.violation.as_slice());
+        Some(pareto_front(&self.st.pop, &self.objectives, &fronts[0], self.opt_prob.sense))
+    }
+}
+
+// Objectives and violation of x, -inf everywhere when the death penalty leaves it outside the box
+fn evaluate<T, D>(opt_prob: &OptProb<T, D>, handling: BoundHandling, x: &OVector<T, D>) -> (DVector<T>, T)
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    let objectives = match &opt_prob.bounds {
+        Some(bounds) if handling == BoundHandling::DeathPenalty && !bounds.contains(x) => {
+            DVector::from_element(opt_prob.num_objectives(), T::neg_infinity())
+        },
+        _ => opt_prob.evaluate_multi(x),
+    };
+    (objectives, opt_prob.violation(x))
+}
+
+fn update_ideal<T: FloatNum>(ideal: &mut DVector<T>, f: &DVector<T>) {
+    for (zi, &fi) in ideal.iter_mut().zip(f.iter()) {
+        if fi.is_finite() && fi > *zi {
+            *zi = fi;
+        }
+    }
+}
+
+fn update_best<T, N, D>(handler: &mut ConstraintHandler, st: &mut State<T, N, D>)
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<U1, D>
+{
+    for i in 0..st.fitness.len() {
+        if handler.update_best(st.fitness[i], st.violation[i], st.best_f) {
+            st.best_f = st.fitness[i];
+            st.best_x = st.pop.row(i).transpose();
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -9,13 +10,25 @@ use nalgebra::{
 };
 
 use crate::utils::config::MSPOConf;
-use crate::algorithms::multi_swarm::swarm::{Swarm, initialize_swarms};
+use crate::algorithms::multi_swarm::swarm::{Swarm, SwarmCheckpoint, initialize_swarms};
 use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     OptimizationAlgorithm,
-    State
+    State,
 };
+use crate::utils::rng::{OptRng, new_rng, stream_rngs};
+use crate::utils::constraint_handling::ConstraintHandler;
+use crate::utils::checkpoint::{AlgCheckpoint, CheckpointError, StateData};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MSPOCheckpoint {
+    pub st: StateData,
+    pub swarms: Vec<SwarmCheckpoint>,
+    pub rng: OptRng,
+    #[serde(default)]
+    pub handler: Option<ConstraintHandler>,
+}
 
 pub struct MSPO<T, N, D> 
 where 
@@ -31,6 +44,8 @@ where
     pub st: State<T, N, D>,
     pub swarms: Vec<Swarm<T, D>>,
     pub opt_prob: OptProb<T, D>,
+    handler: ConstraintHandler,
+    rng: OptRng,
 }
 
 impl<T, N, D> MSPO<T, N, D> 
@@ -46,28 +61,40 @@ where
                     + Allocator<U1, D>
                     + Allocator<D, D>
 {
-    pub fn new(conf: MSPOConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>) -> Self {
+    pub fn new(conf: MSPOConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
         let dim = init_pop.ncols();
         let total_particles = init_pop.nrows();
         assert!(total_particles >= conf.num_swarms * conf.swarm_size, 
             "Initial population size must be at least num_swarms * swarm_size");
 
-        let (best_x, best_fitness) = Self::find_best_solution(&init_pop, &opt_prob);
-
         // Initialize swarms with different regions
-        let swarms = initialize_swarms(&conf, dim, &init_pop, &opt_prob);
-        let (fitness, constraints): (Vec<T>, Vec<bool>) = (0..init_pop.nrows())
+        let mut rng = new_rng(seed);
+        let swarms = initialize_swarms(&conf, dim, &init_pop, &opt_prob, &mut rng);
+        let (fitness, violation): (Vec<T>, Vec<T>) = (0..init_pop.nrows())
             .into_par_iter()
             .map(|i| {
                 let x = init_pop.row(i).transpose();
                 let fit = opt_prob.evaluate(&x);
-                let constr = opt_prob.is_feasible(&x);
-                (fit, constr)
+                let viol = opt_prob.violation(&x);
+                (fit, viol)
             })
             .unzip();
 
         let fitness = OVector::<T, N>::from_vec_generic(N::from_usize(init_pop.nrows()), U1, fitness);
-        let constraints =  OVector::<bool, N>::from_vec_generic(N::from_usize(init_pop.nrows()), U1, constraints);
+        let constraints = OVector::<bool, N>::from_iterator_generic(N::from_usize(init_pop.nrows()), U1, violation.iter().map(|&v| v == T::zero()));
+        let violation = OVector::<T, N>::from_vec_generic(N::from_usize(init_pop.nrows()), U1, violation);
+
+        let mut handler = ConstraintHandler::new(opt_prob.constraint_handling.clone());
+        handler.init(violation.as_slice());
+        let mut best_idx = 0;
+        let mut best_fitness = T::neg_infinity();
+        for i in 0..init_pop.nrows() {
+            if handler.update_best(fitness[i], violation[i], best_fitness) {
+                best_idx = i;
+                best_fitness = fitness[i];
+            }
+        }
+        let best_x = init_pop.row(best_idx).transpose();
 
         let st = State {
             best_x,
@@ -75,6 +102,7 @@ where
             pop: init_pop,
             fitness,
             constraints,
+            violation,
             iter: 1
         };
 
@@ -83,43 +111,38 @@ where
             st,
             swarms,
             opt_prob,
+            handler,
+            rng,
         }
     }
 
-    fn find_best_solution(
-        population: &OMatrix<T, N, D>, 
-        opt_prob: &OptProb<T, D>
-    ) -> (OVector<T, D>, T) {
-        (0..population.nrows())
-            .filter_map(|i| {
-                let x = population.row(i).transpose();
-                if opt_prob.is_feasible(&x) {
-                    Some((x.clone(), opt_prob.evaluate(&x)))
-                } else {
-                    None
-                }
-            })
-            .max_by(|(_, f1), (_, f2)| f1.partial_cmp(f2).unwrap())
-            .unwrap_or_else(|| {
-                let x = population.row(0).transpose();
-                (x.clone(), opt_prob.evaluate(&x))
-            })
+    pub fn from_checkpoint(conf: MSPOConf, opt_prob: OptProb<T, D>, checkpoint: MSPOCheckpoint) -> Result<Self, CheckpointError> {
+        let handler = checkpoint.handler.unwrap_or_else(|| ConstraintHandler::new(opt_prob.constraint_handling.clone()));
+        Ok(Self {
+            conf,
+            st: checkpoint.st.to_state()?,
+            swarms: checkpoint.swarms.iter().map(Swarm::from_checkpoint).collect::<Result<_, _>>()?,
+            opt_prob,
+            handler,
+            rng: checkpoint.rng,
+        })
     }
 
     fn exchange_information(&mut self) {
         // Collect all best positions and their fitness values
         let best_positions: Vec<_> = self.swarms.iter()
-            .map(|swarm| (swarm.global_best_position.clone(), swarm.global_best_fitness))
+            .map(|swarm| (swarm.global_best_position.clone(), swarm.global_best_fitness, swarm.global_best_violation))
             .collect();
+        let handler = &self.handler;
 
         // Sort swarms by fitness
         let mut swarm_indices: Vec<_> = (0..self.swarms.len()).collect();
-        swarm_indices.sort_by(|&i, &j| best_positions[i].1.partial_cmp(&best_positions[j].1).unwrap());
+        swarm_indices.sort_by(|&i, &j| handler.compare(best_positions[i].1, best_positions[i].2, best_positions[j].1, best_positions[j].2));
 
         // Exchange information between swarms
         self.swarms.par_iter_mut().enumerate().for_each(|(_i, swarm)| {
             let better_swarms: Vec<_> = swarm_indices.iter()
-                .filter(|&&idx| best_positions[idx].1 > swarm.global_best_fitness)
+                .filter(|&&idx| handler.better(best_positions[idx].1, best_positions[idx].2, swarm.global_best_fitness, swarm.global_best_violation))
                 .collect();
 
             if !better_swarms.is_empty() {
@@ -127,19 +150,18 @@ where
                 let mut particles: Vec<_> = swarm.particles.iter_mut()
                     .enumerate()
                     .collect();
-                particles.sort_by(|(_, p1), (_, p2)| p1.best_fitness.partial_cmp(&p2.best_fitness).unwrap());
+                particles.sort_by(|(_, p1), (_, p2)| handler.compare(p1.best_fitness, p1.best_violation, p2.best_fitness, p2.best_violation));
 
-                // Only check constraints when actually updating best positions
                 for (_, particle) in particles.iter_mut().take(num_exchange) {
                     for &better_idx in &better_swarms {
-                        let (better_pos, better_fitness) = &best_positions[*better_idx];
-                        if *better_fitness > particle.best_fitness * (T::one() + T::from_f64(self.conf.improvement_threshold).unwrap()) {
-                            // Check feasibility only when we find a potentially better solution
-                            if self.opt_prob.is_feasible(better_pos) {
-                                particle.best_position = better_pos.clone();
-                                particle.best_fitness = *better_fitness;
-                                break;
-                            }
+                        let (better_pos, better_fitness, better_violation) = &best_positions[*better_idx];
+                        // Must beat the particle's best raised by the improvement threshold
+                        let threshold = particle.best_fitness + particle.best_fitness.abs() * T::from_f64(self.conf.improvement_threshold).unwrap();
+                        if handler.better(*better_fitness, *better_violation, threshold, particle.best_violation) {
+                            particle.best_position = better_pos.clone();
+                            particle.best_fitness = *better_fitness;
+                            particle.best_violation = *better_violation;
+                            break;
                         }
                     }
                 }
@@ -178,20 +200,20 @@ where
 {
     fn step(&mut self) {
         // Update each swarm independently
+        let mut streams = stream_rngs(&mut self.rng, self.swarms.len());
         let results: Vec<_> = self.swarms
             .par_iter_mut()
-            .map(|swarm| {
-                swarm.update(&self.opt_prob);
-                (swarm.global_best_position.clone(), swarm.global_best_fitness)
+            .zip(streams.par_iter_mut())
+            .map(|(swarm, rng)| {
+                swarm.update(&self.opt_prob, &self.handler, self.conf.bound_handling, rng);
+                (swarm.global_best_position.clone(), swarm.global_best_fitness, swarm.global_best_violation)
             })
             .collect();
 
-        for (pos, fitness) in results {
-            if fitness > self.st.best_f {
-                if self.opt_prob.is_feasible(&pos) {
-                    self.st.best_f = fitness;
-                    self.st.best_x = pos;
-                }
+        for (pos, fitness, violation) in results {
+            if self.handler.update_best(fitness, violation, self.st.best_f) {
+                self.st.best_f = fitness;
+                self.st.best_x = pos;
             }
         }
 
@@ -202,22 +224,33 @@ where
 
         self.st.pop = self.get_population();
 
-        let (fitness, constraints): (Vec<T>, Vec<bool>) = (0..self.st.pop.nrows())
+        let (fitness, violation): (Vec<T>, Vec<T>) = (0..self.st.pop.nrows())
             .into_par_iter()
             .map(|i| {
                 let x = self.st.pop.row(i).transpose();
                 let fit = self.opt_prob.evaluate(&x);
-                let constr = self.opt_prob.is_feasible(&x);
-                (fit, constr)
+                let viol = self.opt_prob.violation(&x);
+                (fit, viol)
             })
             .unzip();
 
         self.st.fitness = OVector::<T, N>::from_vec_generic(N::from_usize(self.st.pop.nrows()), U1, fitness);
-        self.st.constraints = OVector::<bool, N>::from_vec_generic(N::from_usize(self.st.pop.nrows()), U1, constraints);
+        self.st.constraints = OVector::<bool, N>::from_iterator_generic(N::from_usize(self.st.pop.nrows()), U1, violation.iter().map(|&v| v == T::zero()));
+        self.st.violation = OVector::<T, N>::from_vec_generic(N::from_usize(self.st.pop.nrows()), U1, violation);
+        self.handler.next_generation();
         self.st.iter += 1;
     }
 
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::MSPO(MSPOCheckpoint {
+            st: StateData::from_state(&self.st),
+            swarms: self.swarms.iter().map(|s| s.checkpoint()).collect(),
+            rng: self.rng.clone(),
+            handler: Some(self.handler.clone()),
+        })
+    }
 } 
\ No newline at end of file
@@ -1,5 +1,18 @@
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb};
+use crate::utils::rng::OptRng;
+use crate::utils::bounds::{Bounds, BoundHandling};
+use crate::utils::constraint_handling::ConstraintHandler;
+use crate::utils::checkpoint::{
+    CheckpointError, 
+    NonFinite, 
+    vector_to_data, 
+    vector_from_data, 
+    scalar_to_data, 
+    scalar_from_data
+};
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -8,6 +21,22 @@ use nalgebra::{
     U1,
 };
 
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParticleCheckpoint {
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub position: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub velocity: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub best_position: Vec<f64>,
+    #[serde_as(as = "NonFinite")]
+    pub best_fitness: f64,
+    #[serde_as(as = "NonFinite")]
+    #[serde(default)]
+    pub best_violation: f64,
+}
+
 pub struct Particle<T, D> 
 where 
     T: FloatNum,
@@ -20,6 +49,7 @@ where
     pub velocity: OVector<T, D>,
     pub best_position: OVector<T, D>,
     pub best_fitness: T,
+    pub best_violation: T,
 }
 
 impl<T: FloatNum, D: Dim> Particle<T, D> 
@@ -28,15 +58,37 @@ where
                     + Allocator<U1, D>
                     + Allocator<U1>
 {
-    pub fn new(position: OVector<T, D>, velocity: OVector<T, D>, fitness: T) -> Self {
+    pub fn new(position: OVector<T, D>, velocity: OVector<T, D>, fitness: T, violation: T) -> Self {
         Self {
             position: position.clone(),
             velocity,
             best_position: position,
             best_fitness: fitness,
+            best_violation: violation,
+        }
+    }
+
+    pub fn checkpoint(&self) -> ParticleCheckpoint {
+        ParticleCheckpoint {
+            position: vector_to_data(&self.position),
+            velocity: vector_to_data(&self.velocity),
+            best_position: vector_to_data(&self.best_position),
+            best_fitness: scalar_to_data(self.best_fitness),
+            best_violation: scalar_to_data(self.best_violation),
         }
     }
 
+    pub fn from_checkpoint(checkpoint: &ParticleCheckpoint) -> Result<Self, CheckpointError> {
+        Ok(Self {
+            position: vector_from_data(&checkpoint.position)?,
+            velocity: vector_from_data(&checkpoint.velocity)?,
+            best_position: vector_from_data(&checkpoint.best_position)?,
+            best_fitness: scalar_from_data(checkpoint.best_fitness),
+            best_violation: scalar_from_data(checkpoint.best_violation),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn update_velocity_and_position(
         &mut self,
         global_best: &OVector<T, D>,
@@ -44,10 +96,11 @@ where
         c1: T,
         c2: T,
         opt_prob: &OptProb<T, D>,
-        bounds: (T, T),
+        handler: &ConstraintHandler,
+        bounds: &Bounds<T, D>,
+        handling: BoundHandling,
+        rng: &mut OptRng,
     ) {
-        let mut rng = rand::rng();
-        
         // Update velocity
         for i in 0..self.velocity.len() {
             let r1 = T::from_f64(rng.random::<f64>()).unwrap();
@@ -57,27 +110,19 @@ where
             let social = c2 * r2 * (global_best[i] - self.position[i]);
             
             // Add velocity clamping
-            let v_max = (bounds.1 - bounds.0) * T::from_f64(0.1).unwrap();
+            let v_max = bounds.width(i) * T::from_f64(0.1).unwrap();
             self.velocity[i] = (w * self.velocity[i] + cognitive + social).clamp(-v_max, v_max);
         }
 
-        // Update position with bounds checking only
-        let new_positions: Vec<T> = self.position
-            .iter()
-            .zip(self.velocity.iter())
-            .map(|(&p, &v)| {
-                let new_pos = p + v;
-                new_pos.clamp(bounds.0, bounds.1)
-            })
-            .collect();
-
-        let final_position = OVector::<T, D>::from_vec_generic(D::from_usize(new_positions.len()), U1, new_positions);
-        self.position = final_position;
+        // Update position, repairing it if it leaves the box and rounding integer and discrete dimensions.
+        // The velocity stays continuous so small pulls can add up to a full level
+        self.position = opt_prob.snap(&bounds.repair(&(&self.position + &self.velocity), &self.position, handling, rng));
         
-        // Only update best position if new position is better AND feasible
-        let new_fitness = opt_prob.evaluate(&self.position);
-        if new_fitness > self.best_fitness && opt_prob.is_feasible(&self.position) {
+        let new_fitness = opt_prob.evaluate_bounded(&self.position, handling);
+        let new_violation = opt_prob.violation(&self.position);
+        if handler.better(new_fitness, new_violation, self.best_fitness, self.best_violation) {
             self.best_fitness = new_fitness;
+            self.best_violation = new_violation;
             self.best_position = self.position.clone();
         }
     }
@@ -1,5 +1,7 @@
 use rand::Rng;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -11,9 +13,36 @@ use nalgebra::{
 };
 
 use crate::utils::config::{MSPOConf};
-use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb};
+use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb, fitness_cmp};
+use crate::utils::bounds::{Bounds, BoundHandling};
+use crate::utils::constraint_handling::{ConstraintHandler, feasibility_rules};
+use crate::utils::rng::{OptRng, stream_rngs};
+use crate::utils::checkpoint::{
+    CheckpointError, 
+    NonFinite, 
+    vector_to_data, 
+    vector_from_data, 
+    scalar_to_data, 
+    scalar_from_data
+};
+
+use crate::algorithms::multi_swarm::particle::{Particle, ParticleCheckpoint};
 
-use crate::algorithms::multi_swarm::particle::Particle;
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SwarmCheckpoint {
+    pub particles: Vec<ParticleCheckpoint>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub global_best_position: Vec<f64>,
+    #[serde_as(as = "NonFinite")]
+    pub global_best_fitness: f64,
+    #[serde_as(as = "NonFinite")]
+    #[serde(default)]
+    pub global_best_violation: f64,
+    pub w: f64,
+    pub c1: f64,
+    pub c2: f64,
+}
 
 pub struct Swarm<T, D> 
 where 
@@ -25,11 +54,10 @@ where
     pub particles: Vec<Particle<T, D>>,   
     pub global_best_position: OVector<T, D>,
     pub global_best_fitness: T,
+    pub global_best_violation: T,
     pub w: T,
     pub c1: T,
     pub c2: T,
-    pub x_min: f64,
-    pub x_max: f64,
 }
 
 impl<T, D> Swarm<T, D> 
@@ -48,29 +76,32 @@ where
         w: T,
         c1: T,
         c2: T,
-        bounds: (T, T),
+        bounds: &Bounds<T, D>,
         opt_prob: &OptProb<T, D>,
         init_pop: OMatrix<T, Dyn, D>,
+        rng: &mut OptRng,
     ) -> Self {
-        let particles: Vec<_> = (0..num_particles)
+        let particles: Vec<_> = stream_rngs(rng, num_particles)
             .into_par_iter()
-            .map(|i| {
-                let mut rng = rand::rng();
+            .enumerate()
+            .map(|(i, mut rng)| {
                 let mut position = OVector::<T, D>::zeros_generic(D::from_usize(dim), U1);
                 let fitness;
+                let mut violation = T::zero();
                 
                 if i < init_pop.nrows() {
                     // Use initial population if available
                     position = init_pop.row(i).transpose();
                     fitness = opt_prob.evaluate(&position);
+                    violation = opt_prob.violation(&position);
                 } else {
                     // Generate random position if needed
                     loop {
-                        let values = (0..dim).map(|_| {
+                        let values = (0..dim).map(|k| {
                             let r = T::from_f64(rng.random::<f64>()).unwrap();
-                            bounds.0 + (bounds.1 - bounds.0) * r
+                            bounds.lower[k] + bounds.width(k) * r
                         });
-                        let position: OVector<T, D> = OVector::from_iterator_generic(D::from_usize(dim), U1, values);
+                        let position = opt_prob.snap(&OVector::from_iterator_generic(D::from_usize(dim), U1, values));
                         
                         if opt_prob.is_feasible(&position) {
                             fitness = opt_prob.evaluate(&position);
@@ -79,23 +110,25 @@ where
                     }
                 }
                 
-                let values = (0..dim).map(|_| {
+                let values = (0..dim).map(|k| {
                     let r = T::from_f64(rng.random::<f64>()).unwrap();
-                    (bounds.1 - bounds.0) * (r - T::from_f64(0.5).unwrap()) * T::from_f64(0.1).unwrap()
+                    bounds.width(k) * (r - T::from_f64(0.5).unwrap()) * T::from_f64(0.1).unwrap()
                 });
 
                 let velocity: OVector<T, D> = OVector::from_iterator_generic(D::from_usize(dim), U1, values);
 
-                Particle::new(position, velocity, fitness)
+                Particle::new(position, velocity, fitness, violation)
             })
             .collect();
 
         let mut best_fitness = T::neg_infinity();
+        let mut best_violation = T::infinity();
         let mut best_position = OVector::<T, D>::zeros_generic(D::from_usize(dim), U1);
 
         for particle in &particles {
-            if particle.best_fitness > best_fitness {
+            if feasibility_rules(particle.best_fitness, particle.best_violation, best_fitness, best_violation) == std::cmp::Ordering::Greater {
                 best_fitness = particle.best_fitness;
+                best_violation = particle.best_violation;
                 best_position = particle.position.clone();
             }
         }
@@ -104,42 +137,70 @@ where
             particles,
             global_best_position: best_position,
             global_best_fitness: best_fitness,
+            global_best_violation: best_violation,
             w,
             c1,
             c2,
-            x_min: bounds.0.to_f64().unwrap(),
-            x_max: bounds.1.to_f64().unwrap(),
         }
     }
 
+    pub fn checkpoint(&self) -> SwarmCheckpoint {
+        SwarmCheckpoint {
+            particles: self.particles.iter().map(|p| p.checkpoint()).collect(),
+            global_best_position: vector_to_data(&self.global_best_position),
+            global_best_fitness: scalar_to_data(self.global_best_fitness),
+            global_best_violation: scalar_to_data(self.global_best_violation),
+            w: scalar_to_data(self.w),
+            c1: scalar_to_data(self.c1),
+            c2: scalar_to_data(self.c2),
+        }
+    }
+
+    pub fn from_checkpoint(checkpoint: &SwarmCheckpoint) -> Result<Self, CheckpointError> {
+        Ok(Self {
+            particles: checkpoint.particles.iter().map(Particle::from_checkpoint).collect::<Result<_, _>>()?,
+            global_best_position: vector_from_data(&checkpoint.global_best_position)?,
+            global_best_fitness: scalar_from_data(checkpoint.global_best_fitness),
+            global_best_violation: scalar_from_data(checkpoint.global_best_violation),
+            w: scalar_from_data(checkpoint.w),
+            c1: scalar_from_data(checkpoint.c1),
+            c2: scalar_from_data(checkpoint.c2),
+        })
+    }
+
     pub fn update(
         &mut self,
         opt_prob: &OptProb<T, D>,
+        handler: &ConstraintHandler,
+        handling: BoundHandling,
+        rng: &mut OptRng,
     ) {
-        let bounds = (
-            T::from_f64(self.x_min).unwrap(),
-            T::from_f64(self.x_max).unwrap()
-        );
+        let bounds = opt_prob.bounds_or_unbounded(self.global_best_position.len());
 
-        self.particles.par_iter_mut().for_each(|particle| {
+        let mut streams = stream_rngs(rng, self.particles.len());
+        self.particles.par_iter_mut().zip(streams.par_iter_mut()).for_each(|(particle, rng)| {
             particle.update_velocity_and_position(
                 &self.global_best_position, 
                 self.w, 
                 self.c1, 
                 self.c2,
                 opt_prob,
-                bounds,
+                handler,
+                &bounds,
+                handling,
+                rng,
             );
         });
 
         let best_particle = self.particles.par_iter()
             .reduce_with(|p1, p2| {
-                if p1.best_fitness > p2.best_fitness { p1 } else { p2 }
+                if handler.better(p1.best_fitness, p1.best_violation, p2.best_fitness, p2.best_violation) { p1 } else { p2 }
             })
             .unwrap();
 
-        if best_particle.best_fitness > self.global_best_fitness {
+        if handler.better(best_particle.best_fitness, best_particle.best_violation, self.global_best_fitness, self.global_best_violation) {
             self.global_best_fitness = best_particle.best_fitness;
+            self.global_best_violation = best_particle.best_violation;
             self.global_best_position = best_particle.best_position.clone();
         }
     }
@@ -149,7 +210,8 @@ pub fn initialize_swarms<T, N, D>(
     conf: &MSPOConf,
     dim: usize,
     init_pop: &OMatrix<T, N, D>,
-    opt_prob: &OptProb<T, D>
+    opt_prob: &OptProb<T, D>,
+    rng: &mut OptRng,
 ) -> Vec<Swarm<T, D>> 
 where 
     T: FloatNum,
@@ -163,6 +225,8 @@ where
                     + Allocator<D, D>
 {
     let particles_per_swarm = conf.swarm_size;
+    let bounds = opt_prob.bounds_or_unbounded(dim);
+    let mean_width = (0..dim).map(|k| bounds.width(k)).fold(T::zero(), |acc, w| acc + w) / T::from_usize(dim).unwrap();
     let pop_per_swarm = init_pop.nrows() / conf.num_swarms;
 
     // Find several promising regions 
@@ -171,7 +235,7 @@ where
     sorted_indices.sort_by(|&i, &j| {
         let fi = opt_prob.evaluate(&init_pop.row(i).transpose());
         let fj = opt_prob.evaluate(&init_pop.row(j).transpose());
-        fj.partial_cmp(&fi).unwrap()
+        fitness_cmp(&fj, &fi)
     });
 
     // Select diverse centers from top solutions
@@ -180,36 +244,40 @@ where
         if promising_centers.iter().all(|c| {
             // Ensure centers are sufficiently far apart
             let dist = (c - &center).dot(&(c - &center)).sqrt();
-            dist > T::from_f64(0.1 * (conf.x_max - conf.x_min)).unwrap()
+            dist > mean_width * T::from_f64(0.1).unwrap()
         }) {
             promising_centers.push(center);
         }
     }
 
     // Initialize swarms around these promising regions
-    (0..conf.num_swarms)
+    stream_rngs(rng, conf.num_swarms)
         .into_par_iter()
-        .map(|i| {
+        .enumerate()
+        .map(|(i, mut rng)| {
             let center = if i < promising_centers.len() {
                 promising_centers[i].clone()
             } else {
                 // Random center for remaining swarms
-                OVector::<T, D>::from_iterator_generic(D::from_usize(dim), U1, (0..dim).map(|_| {
-                    T::from_f64(conf.x_min + rand::random::<f64>() * (conf.x_max - conf.x_min)).unwrap()
+                OVector::<T, D>::from_iterator_generic(D::from_usize(dim), U1, (0..dim).map(|k| {
+                    bounds.lower[k] + bounds.width(k) * T::from_f64(rng.random::<f64>()).unwrap()
                 }))
             };
 
             // Initialize particles around the center
-            let radius = T::from_f64(0.2 * (conf.x_max - conf.x_min)).unwrap(); // Local search radius
             let start_idx = i * pop_per_swarm;
             let mut swarm_pop: OMatrix<T, Dyn, D> = init_pop.rows(start_idx, particles_per_swarm).into_owned();
             
             // Adjust some particles to be near the center
             for j in 0..particles_per_swarm/2 {
                 for k in 0..dim {
-                    let r = T::from_f64(rand::random::<f64>()).unwrap();
+                    let r = T::from_f64(rng.random::<f64>()).unwrap();
+                    let radius = bounds.width(k) * T::from_f64(0.2).unwrap(); // Local search radius
                     swarm_pop[(j, k)] = center[k] + (r - T::from_f64(0.5).unwrap()) * radius;
                 }
+                // Centers near a side would otherwise start particles outside the box
+                let x = opt_prob.snap(&bounds.clamp(&swarm_pop.row(j).transpose()));
+                swarm_pop.set_row(j, &x.transpose());
             }
 
             Swarm::new(
@@ -218,9 +286,10 @@ where
                 T::from_f64(conf.w).unwrap(),
                 T::from_f64(conf.c1).unwrap(),
                 T::from_f64(conf.c2).unwrap(),
-                (T::from_f64(conf.x_min).unwrap(), T::from_f64(conf.x_max).unwrap()),
+                &bounds,
                 opt_prob,
-                swarm_pop
+                swarm_pop,
+                &mut rng
             )
         })
         .collect()
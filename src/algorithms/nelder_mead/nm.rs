@@ -1,4 +1,6 @@
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -13,8 +15,28 @@ use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     OptimizationAlgorithm,
-    State
+    State,
+    Diagnostics,
+    fitness_cmp
 };
+use crate::utils::rng::{OptRng, new_rng};
+use crate::utils::checkpoint::{
+    AlgCheckpoint, 
+    CheckpointError, 
+    StateData, 
+    NonFinite,
+    vector_to_data, 
+    vector_from_data
+};
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NelderMeadCheckpoint {
+    pub st: StateData,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub simplex: Vec<Vec<f64>>,
+    pub rng: OptRng,
+}
 
 pub struct NelderMead<T, N, D> 
 where 
@@ -29,6 +51,7 @@ where
     pub st: State<T, N, D>,
     pub opt_prob: OptProb<T, D>,
     pub simplex: Vec<OVector<T, D>>,
+    rng: OptRng, // Only drawn from by BoundHandling::Resample
 }
 
 impl<T, N, D> NelderMead<T, N, D> 
@@ -42,30 +65,31 @@ where
                     + Allocator<N>
                     + Allocator<D, U1>
 {
-    pub fn new(conf: NelderMeadConf, init_x: OMatrix<T, N, D>, opt_prob: OptProb<T, D>) -> Self {
+    pub fn new(conf: NelderMeadConf, init_x: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
+        // NonConvexOpt::new checks for the n + 1 vertices, see validation.rs
         let n: usize = init_x.ncols();
-        assert_eq!(init_x.nrows(), n + 1, "Initial simplex must have n + 1 vertices");
+        let num_vertices = init_x.nrows();
         
-        let simplex: Vec<_> = (0..(n+1))
+        let simplex: Vec<_> = (0..num_vertices)
             .map(|j| init_x.row(j).transpose())
             .collect();
 
         let fitness_values = OVector::<T, N>::from_iterator_generic(
-            N::from_usize(n + 1), 
+            N::from_usize(num_vertices), 
             U1,                 
             simplex.iter().map(|vertex| opt_prob.evaluate(vertex))
         );
             
         let best_idx = fitness_values.iter()
             .enumerate()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .max_by(|(_, a), (_, b)| fitness_cmp(*a, *b))
             .map(|(idx, _)| idx)
             .unwrap();
 
-        let pop = OMatrix::<T, N, D>::from_iterator_generic(
-            N::from_usize(n + 1),                       
+        let pop = OMatrix::<T, N, D>::from_fn_generic(
+            N::from_usize(num_vertices),                       
             D::from_usize(n),   
-            simplex.iter().flat_map(|v| v.iter().cloned())
+            |i, j| simplex[i][j]
         );
             
         Self {
@@ -75,14 +99,26 @@ where
                 best_f: fitness_values[best_idx],
                 pop,
                 fitness: fitness_values,
-                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(n + 1), U1, true),
+                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(num_vertices), U1, true),
+                violation: OVector::<T, N>::zeros_generic(N::from_usize(num_vertices), U1), // Infeasible vertices are scored -inf instead
                 iter: 1
             },
             opt_prob,
             simplex,
+            rng: new_rng(seed),
         }
     }
 
+    pub fn from_checkpoint(conf: NelderMeadConf, opt_prob: OptProb<T, D>, checkpoint: NelderMeadCheckpoint) -> Result<Self, CheckpointError> {
+        Ok(Self {
+            conf,
+            st: checkpoint.st.to_state()?,
+            opt_prob,
+            simplex: checkpoint.simplex.iter().map(|v| vector_from_data(v)).collect::<Result<_, _>>()?,
+            rng: checkpoint.rng,
+        })
+    }
+
     pub fn centroid(&self, worst_idx: usize) -> OVector<T, D> {
         let mut centroid = OVector::<T, D>::zeros_generic(D::from_usize(self.st.best_x.len()), U1);
         for (i, vertex) in self.simplex.iter().enumerate() {
@@ -94,21 +130,33 @@ where
         &centroid * (T::one() / scale)
     }
 
+    // Largest distance from the best point to any vertex
+    pub fn simplex_size(&self) -> T {
+        self.simplex.iter()
+            .map(|v| {
+                let diff = v - &self.st.best_x;
+                diff.dot(&diff).sqrt()
+            })
+            .fold(T::zero(), |acc, d| acc.max(d))
+    }
+
     fn get_sorted_indices(&self) -> Vec<usize> {
         let mut indices: Vec<usize> = (0..self.simplex.len()).collect();
-        indices.sort_by(|&i, &j| self.st.fitness[j].partial_cmp(&self.st.fitness[i]).unwrap());
+        indices.sort_by(|&i, &j| fitness_cmp(&self.st.fitness[j], &self.st.fitness[i]));
         indices
     }
 
     fn try_reflection_expansion(&mut self, worst_idx: usize, best_idx: usize, centroid: &OVector<T, D>) -> bool {
         // Reflect worst point across centroid
         let reflected = centroid + (centroid - &self.simplex[worst_idx]) * T::from_f64(self.conf.alpha).unwrap();
+        let reflected = self.repair(&reflected, &self.simplex[worst_idx].clone());
         let reflected_fitness = self.evaluate_point(&reflected);
         
         if reflected_fitness > self.st.fitness[worst_idx] {
             if reflected_fitness > self.st.fitness[best_idx] {
                 // Try expansion
                 let expanded = centroid + (&reflected - centroid) * T::from_f64(self.conf.gamma).unwrap();
+                let expanded = self.repair(&expanded, &reflected);
                 let expanded_fitness = self.evaluate_point(&expanded);
                 
                 if expanded_fitness > reflected_fitness {
@@ -127,6 +175,7 @@ where
 
     fn try_contraction(&mut self, worst_idx: usize, _best_idx: usize, centroid: &OVector<T, D>) -> bool {
         let contracted = centroid + (&self.simplex[worst_idx] - centroid) * T::from_f64(self.conf.rho).unwrap();
+        let contracted = self.repair(&contracted, centroid);
         let contracted_fitness = self.evaluate_point(&contracted);
         
         if contracted_fitness > self.st.fitness[worst_idx] {
@@ -139,11 +188,16 @@ where
 
     fn shrink_simplex(&mut self, best_idx: usize) -> bool {
         let best = self.simplex[best_idx].clone();
-        let shrink_results: Vec<_> = (0..self.simplex.len()).into_par_iter()
+        let shrunk: Vec<_> = (0..self.simplex.len())
             .filter(|&i| i != best_idx)
             .map(|i| {
                 let new_vertex = &best + (&self.simplex[i] - &best) * T::from_f64(self.conf.sigma).unwrap();
-                let new_fitness = self.opt_prob.evaluate(&new_vertex);
+                (i, self.repair(&new_vertex, &best))
+            })
+            .collect();
+        let shrink_results: Vec<_> = shrunk.into_par_iter()
+            .map(|(i, new_vertex)| {
+                let new_fitness = self.opt_prob.evaluate_bounded(&new_vertex, self.conf.bound_handling);
                 (i, new_vertex, new_fitness)
             })
             .collect();
@@ -154,10 +208,15 @@ where
         true
     }
 
-    // Negativ inf when infeasible
+    // Brings a trial point back into the box, parent is the point it was stepped from
+    fn repair(&mut self, point: &OVector<T, D>, parent: &OVector<T, D>) -> OVector<T, D> {
+        self.opt_prob.repair(point, parent, self.conf.bound_handling, &mut self.rng)
+    }
+
+    // Negative inf when infeasible, or outside the box under the death penalty
     fn evaluate_point(&self, point: &OVector<T, D>) -> T {
         if self.opt_prob.is_feasible(point) {
-            self.opt_prob.evaluate(point)
+            self.opt_prob.evaluate_bounded(point, self.conf.bound_handling)
         } else {
             T::neg_infinity()
         }
@@ -171,7 +230,7 @@ where
     fn update_best_solution(&mut self) {
         let best_idx = self.st.fitness.iter()
             .enumerate()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .max_by(|(_, a), (_, b)| fitness_cmp(*a, *b))
             .map(|(idx, _)| idx)
             .unwrap();
             
@@ -181,10 +240,10 @@ where
             self.st.best_x = self.simplex[best_idx].clone();
         }
 
-        self.st.pop = OMatrix::<T, N, D>::from_iterator_generic(
+        self.st.pop = OMatrix::<T, N, D>::from_fn_generic(
             N::from_usize(self.simplex.len()),                       
             D::from_usize(self.st.best_x.len()),   
-            self.simplex.iter().flat_map(|v| v.iter().cloned())
+            |i, j| self.simplex[i][j]
         );
     }
 }
@@ -214,13 +273,28 @@ where
             || self.shrink_simplex(best_idx);
 
         self.update_best_solution();
+        self.st.iter += 1;
     }
 
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
 
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::NM(NelderMeadCheckpoint {
+            st: StateData::from_state(&self.st),
+            simplex: self.simplex.iter().map(vector_to_data).collect(),
+            rng: self.rng.clone(),
+        })
+    }
+
     fn get_simplex(&self) -> Option<&Vec<OVector<T, D>>> {
         Some(&self.simplex)
     }
+
+    fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::from([
+            ("simplex_size".to_string(), self.simplex_size().to_f64().unwrap()),
+        ])
+    }
 }
\ No newline at end of file
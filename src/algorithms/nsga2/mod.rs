@@ -0,0 +1 @@
+pub mod nsga2;
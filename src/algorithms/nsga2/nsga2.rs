@@ -0,0 +1,311 @@
+use std::cmp::Ordering;
+use rand::Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    DVector,
+    OMatrix,
+    OVector,
+    U1,
+    Dyn,
+};
+
+use crate::utils::config::NSGA2Conf;
+use crate::utils::opt_prob::{
+    FloatNumber as FloatNum,
+    OptProb,
+    OptimizationAlgorithm,
+    Diagnostics,
+    State,
+    fitness_cmp
+};
+use crate::utils::rng::{OptRng, new_rng};
+use crate::utils::checkpoint::{AlgCheckpoint, CheckpointError, StateData, NonFinite};
+use crate::utils::constraint_handling::{ConstraintHandler, ConstraintHandling};
+use crate::utils::bounds::BoundHandling;
+use crate::utils::result::ParetoFront;
+use crate::utils::pareto::{non_dominated_sort, front_ranks, crowding_distance, crowded_cmp, pareto_front};
+
+use crate::algorithms::continous_ga::{
+    crossover::{CrossoverOperator, build_crossover},
+    mutation::{MutationOperator, build_mutation},
+};
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NSGA2Checkpoint {
+    pub st: StateData,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub objectives: Vec<Vec<f64>>,
+    pub rng: OptRng,
+    pub handler: ConstraintHandler,
+}
+
+pub struct NSGA2<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OVector<bool, N>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+{
+    pub conf: NSGA2Conf,
+    pub st: State<T, N, D>, // fitness holds the sum of the objectives
+    pub opt_prob: OptProb<T, D>,
+    pub objectives: Vec<DVector<T>>, // In fitness units, one per member of the population
+    pub rank: Vec<usize>, // Front of each member, 0 is non-dominated
+    pub crowding: Vec<T>,
+    pub crossover: Box<dyn CrossoverOperator<T, N, D> + Send + Sync>,
+    pub mutation: Box<dyn MutationOperator<T, D> + Send + Sync>,
+    pub handler: ConstraintHandler, // Tracks best_x by feasibility rules on the summed objectives
+    pub rng: OptRng,
+}
+
+impl<T, N, D> NSGA2<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OVector<bool, N>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<U1, D>
+{
+    pub fn new(conf: NSGA2Conf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, max_iter: usize, seed: Option<u64>) -> Self {
+        let crossover = build_crossover(&conf.crossover, init_pop.nrows());
+        let mutation = build_mutation(&conf.mutation, max_iter, opt_prob.variables.as_ref());
+
+        let rows: Vec<OVector<T, D>> = init_pop.row_iter().map(|row| row.transpose()).collect();
+        let (objectives, violation) = evaluate_all(&opt_prob, conf.bound_handling, &rows);
+        let (rank, crowding) = rank_and_crowding(&objectives, &violation);
+
+        let fitness: Vec<T> = objectives.iter().map(|f| f.sum()).collect();
+        let n = N::from_usize(init_pop.nrows());
+        let mut st = State {
+            best_x: rows[0].clone(),
+            best_f: T::neg_infinity(),
+            pop: init_pop,
+            fitness: OVector::<T, N>::from_vec_generic(n, U1, fitness),
+            constraints: OVector::<bool, N>::from_iterator_generic(n, U1, violation.iter().map(|&v| v == T::zero())),
+            violation: OVector::<T, N>::from_vec_generic(n, U1, violation),
+            iter: 1,
+        };
+
+        let mut handler = ConstraintHandler::new(ConstraintHandling::FeasibilityRules);
+        update_best(&mut handler, &mut st);
+
+        Self {
+            conf,
+            st,
+            opt_prob,
+            objectives,
+            rank,
+            crowding,
+            crossover,
+            mutation,
+            handler,
+            rng: new_rng(seed),
+        }
+    }
+
+    pub fn from_checkpoint(conf: NSGA2Conf, opt_prob: OptProb<T, D>, max_iter: usize, checkpoint: NSGA2Checkpoint) -> Result<Self, CheckpointError> {
+        let st: State<T, N, D> = checkpoint.st.to_state()?;
+        if checkpoint.objectives.len() != st.pop.nrows() {
+            return Err(CheckpointError::DimensionMismatch { expected: st.pop.nrows(), found: checkpoint.objectives.len() });
+        }
+
+        let objectives: Vec<DVector<T>> = checkpoint.objectives.iter()
+            .map(|f| DVector::from_iterator(f.len(), f.iter().map(|&fi| T::from_f64(fi).unwrap())))
+            .collect();
+        let (rank, crowding) = rank_and_crowding(&objectives, st.violation.as_slice());
+        let crossover = build_crossover(&conf.crossover, st.pop.nrows());
+        let mutation = build_mutation(&conf.mutation, max_iter, opt_prob.variables.as_ref());
+
+        Ok(Self {
+            conf,
+            st,
+            opt_prob,
+            objectives,
+            rank,
+            crowding,
+            crossover,
+            mutation,
+            handler: checkpoint.handler,
+            rng: checkpoint.rng
+        })
+    }
+
+    // Binary (or larger) tournament on rank, then crowding distance
+    fn tournament(&mut self) -> usize {
+        let n = self.rank.len();
+        let mut winner = self.rng.random_range(0..n);
+        for _ in 1..self.conf.tournament_size {
+            let challenger = self.rng.random_range(0..n);
+            if crowded_cmp(self.rank[challenger], self.crowding[challenger], self.rank[winner], self.crowding[winner]) == Ordering::Greater {
+                winner = challenger;
+            }
+        }
+        winner
+    }
+}
+
+impl<T, N, D> OptimizationAlgorithm<T, N, D> for NSGA2<T, N, D>
+where
+    T: FloatNum,
+    D: Dim,
+    N: Dim,
+    OVector<T, D>: Send + Sync,
+    OVector<T, N>: Send + Sync,
+    OVector<bool, N>: Send + Sync,
+    OMatrix<T, N, D>: Send + Sync,
+    OMatrix<T, Dyn, D>: Send + Sync,
+    DefaultAllocator: Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<U1, D>
+                    + Allocator<D>
+                    + Allocator<Dyn, D>
+{
+    fn step(&mut self) {
+        let pop_size = self.st.pop.nrows();
+        let dim = self.st.pop.ncols();
+
+        // Mating pool by crowded tournaments, then the CGA operators
+        let winners: Vec<usize> = (0..pop_size).map(|_| self.tournament()).collect();
+        let parents = OMatrix::<T, Dyn, D>::from_fn_generic(Dyn(pop_size), D::from_usize(dim), |i, j| self.st.pop[(winners[i], j)]);
+        let offspring = self.crossover.crossover(&parents, &mut self.rng);
+
+        let bounds = self.opt_prob.bounds_or_unbounded(dim);
+        let handling = self.conf.bound_handling;
+        let mut children = Vec::with_capacity(pop_size);
+        for i in 0..pop_size {
+            let individual = offspring.row(i).transpose();
+            let mutated = self.mutation.mutate(&individual, &bounds, self.st.iter, &mut self.rng);
+            children.push(self.opt_prob.repair(&mutated, &individual, handling, &mut self.rng));
+        }
+        let (child_objectives, child_violation) = evaluate_all(&self.opt_prob, handling, &children);
+
+        // Parents and children compete together, whole fronts are kept until one no longer fits
+        let mut candidates: Vec<OVector<T, D>> = self.st.pop.row_iter().map(|row| row.transpose()).collect();
+        candidates.extend(children);
+        let mut objectives = std::mem::take(&mut self.objectives);
+        objectives.extend(child_objectives);
+        let mut violation: Vec<T> = self.st.violation.iter().cloned().collect();
+        violation.extend(child_violation);
+
+        let mut survivors = Vec::with_capacity(pop_size);
+        for front in non_dominated_sort(&objectives, &violation) {
+            if survivors.len() + front.len() <= pop_size {
+                survivors.extend(front);
+                continue;
+            }
+
+            // The least crowded members of the last front fill the remaining places
+            let crowding = crowding_distance(&objectives, &front);
+            let mut order: Vec<usize> = (0..front.len()).collect();
+            order.sort_by(|&a, &b| fitness_cmp(&crowding[b], &crowding[a]));
+            survivors.extend(order.iter().take(pop_size - survivors.len()).map(|&k| front[k]));
+            break;
+        }
+
+        let n = N::from_usize(pop_size);
+        self.st.pop = OMatrix::<T, N, D>::from_fn_generic(n, D::from_usize(dim), |i, j| candidates[survivors[i]][j]);
+        self.objectives = survivors.iter().map(|&i| objectives[i].clone()).collect();
+        let violation: Vec<T> = survivors.iter().map(|&i| violation[i]).collect();
+        (self.rank, self.crowding) = rank_and_crowding(&self.objectives, &violation);
+
+        self.st.fitness = OVector::<T, N>::from_iterator_generic(n, U1, self.objectives.iter().map(|f| f.sum()));
+        self.st.constraints = OVector::<bool, N>::from_iterator_generic(n, U1, violation.iter().map(|&v| v == T::zero()));
+        self.st.violation = OVector::<T, N>::from_vec_generic(n, U1, violation);
+        update_best(&mut self.handler, &mut self.st);
+
+        self.st.iter += 1;
+    }
+
+    fn state(&self) -> &State<T, N, D> {
+        &self.st
+    }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::NSGA2(NSGA2Checkpoint {
+            st: StateData::from_state(&self.st),
+            objectives: self.objectives.iter().map(|f| f.iter().map(|fi| fi.to_f64().unwrap()).collect()).collect(),
+            rng: self.rng.clone(),
+            handler: self.handler.clone(),
+        })
+    }
+
+    fn diagnostics(&self) -> Diagnostics {
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.insert("front_size".to_string(), self.rank.iter().filter(|&&r| r == 0).count() as f64);
+        diagnostics.insert("num_fronts".to_string(), (self.rank.iter().max().unwrap_or(&0) + 1) as f64);
+        diagnostics
+    }
+
+    fn pareto_front(&self) -> Option<ParetoFront> {
+        let first: Vec<usize> = (0..self.rank.len()).filter(|&i| self.rank[i] == 0).collect();
+        Some(pareto_front(&self.st.pop, &self.objectives, &first, self.opt_prob.sense))
+    }
+}
+
+// Objectives and violation of every point, points left outside the box by the death penalty score -inf everywhere
+fn evaluate_all<T, D>(opt_prob: &OptProb<T, D>, handling: BoundHandling, points: &[OVector<T, D>]) -> (Vec<DVector<T>>, Vec<T>)
+where
+    T: FloatNum,
+    D: Dim,
+    OVector<T, D>: Send + Sync,
+    DefaultAllocator: Allocator<D>
+{
+    points.par_iter()
+        .map(|x| {
+            let objectives = match &opt_prob.bounds {
+                Some(bounds) if handling == BoundHandling::DeathPenalty && !bounds.contains(x) => {
+                    DVector::from_element(opt_prob.num_objectives(), T::neg_infinity())
+                },
+                _ => opt_prob.evaluate_multi(x),
+            };
+            (objectives, opt_prob.violation(x))
+        })
+        .unzip()
+}
+
+fn rank_and_crowding<T: FloatNum>(objectives: &[DVector<T>], violation: &[T]) -> (Vec<usize>, Vec<T>) {
+    let fronts = non_dominated_sort(objectives, violation);
+    let mut crowding = vec![T::zero(); objectives.len()];
+    for front in &fronts {
+        for (k, d) in crowding_distance(objectives, front).into_iter().enumerate() {
+            crowding[front[k]] = d;
+        }
+    }
+    (front_ranks(&fronts, objectives.len()), crowding)
+}
+
+fn update_best<T, N, D>(handler: &mut ConstraintHandler, st: &mut State<T, N, D>)
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N, D>
+                    + Allocator<N>
+                    + Allocator<U1, D>
+{
+    for i in 0..st.fitness.len() {
+        if handler.update_best(st.fitness[i], st.violation[i], st.best_f) {
+            st.best_f = st.fitness[i];
+            st.best_x = st.pop.row(i).transpose();
+        }
+    }
+}
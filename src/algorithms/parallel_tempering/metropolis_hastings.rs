@@ -10,6 +10,8 @@ use nalgebra::{
 };
 
 use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb};
+use crate::utils::rng::OptRng;
+use crate::utils::bounds::BoundHandling;
 
 pub enum MoveType {
     RandomDrift,
@@ -28,6 +30,7 @@ where
     pub alpha: T,
     pub omega: T,
     pub mala_step_size: T,
+    pub bound_handling: BoundHandling,
 }
 
 impl<T, D> MetropolisHastings<T, D> 
@@ -37,30 +40,32 @@ where
     DefaultAllocator: Allocator<D> 
                     + Allocator<D, D>
 {
-    pub fn new(prob: OptProb<T, D>, mala_step_size: T, alpha: T, omega: T, generic_x: OVector<T, D>) -> Self {
+    pub fn new(prob: OptProb<T, D>, mala_step_size: T, alpha: T, omega: T, bound_handling: BoundHandling, generic_x: OVector<T, D>) -> Self {
         let k = T::from_f64(1.38064852e-23).unwrap(); // Boltzmann constant
 
-        let move_type = if prob.objective.gradient(&generic_x).is_some() {
+        let move_type = if prob.gradient(&generic_x).is_some() {
             MoveType::MALA
         } else {
             MoveType::RandomDrift
         };
 
-        MetropolisHastings { k, move_type, prob, mala_step_size, alpha, omega }
+        MetropolisHastings { k, move_type, prob, mala_step_size, alpha, omega, bound_handling }
     }
 
-    pub fn local_move(&self, x_old: &OVector<T, D>, step_size: &OMatrix<T, D, D>, t: T) -> OVector<T, D> {
+    pub fn local_move(&self, x_old: &OVector<T, D>, step_size: &OMatrix<T, D, D>, t: T, rng: &mut OptRng) -> OVector<T, D> {
         match self.move_type {
             MoveType::MALA => {
-                let grad = self.prob.objective.gradient(x_old).expect("Gradient should be available for MALA");
-                self.local_move_mala(x_old, &grad, t)
+                // Stays put once the budget can't pay for the gradient
+                match self.prob.gradient(x_old) {
+                    Some(grad) => self.local_move_mala(x_old, &grad, t, rng),
+                    None => x_old.clone(),
+                }
             }
-            MoveType::RandomDrift => self.local_move_random_drift(x_old, step_size),
+            MoveType::RandomDrift => self.local_move_random_drift(x_old, step_size, rng),
         }
     }
 
-    fn local_move_random_drift(&self, x_old: &OVector<T, D>, step_size: &OMatrix<T, D, D>) -> OVector<T, D> {
-        let mut rng = rand::rng();
+    fn local_move_random_drift(&self, x_old: &OVector<T, D>, step_size: &OMatrix<T, D, D>, rng: &mut OptRng) -> OVector<T, D> {
         let mut x_new = x_old.clone();
         let random_vec= OVector::<T, D>::from_fn_generic(
             D::from_usize(x_old.len()),
@@ -71,8 +76,7 @@ where
         x_new
     }
 
-    fn local_move_mala(&self, x_old: &OVector<T, D>, grad: &OVector<T, D>, t: T) -> OVector<T, D> {
-        let mut rng = rand::rng();
+    fn local_move_mala(&self, x_old: &OVector<T, D>, grad: &OVector<T, D>, t: T, rng: &mut OptRng) -> OVector<T, D> {
         let step = self.mala_step_size / t;
         let drift = grad * step;
     
@@ -85,14 +89,6 @@ where
         x_old + drift + noise
     }
 
-    fn project(&self, x: &OVector<T, D>) -> OVector<T, D> {
-        if let (Some(x_ub), Some(x_lb)) = (&self.prob.objective.x_upper_bound(x), &self.prob.objective.x_lower_bound(x)) {
-            x.component_mul(&(x_ub.clone() - x_lb.clone())) + x_lb.clone()
-        } else {
-            x.clone()
-        }
-    }
-
     pub fn accept_reject(
         &self, 
         x_old: &OVector<T, D>,
@@ -100,6 +96,7 @@ where
         constraints_new: bool,
         t: T,
         t_swap: T, 
+        rng: &mut OptRng,
     ) -> bool {
         // Reject if new solution violates constraints
         if !constraints_new {
@@ -112,15 +109,15 @@ where
         let r: T;
         if t_swap > T::from_f64(0.0).unwrap() { // Pass in next temperature to signal global move
             let delta_t = (T::one() / t - T::one() / t_swap).powf(-T::one());
-            let delta_f = self.prob.objective.f(&self.project(x_new)) - self.prob.objective.f(&self.project(x_old));
+            let delta_f = self.prob.evaluate_bounded(x_new, self.bound_handling) - self.prob.evaluate_bounded(x_old, self.bound_handling);
             r = (delta_f / (self.k * delta_x * delta_t)).exp();
         } else { // Pass in negative anything to signal local move
             
-            let delta_f = self.prob.objective.f(&self.project(x_new)) - self.prob.objective.f(&self.project(x_old));
+            let delta_f = self.prob.evaluate_bounded(x_new, self.bound_handling) - self.prob.evaluate_bounded(x_old, self.bound_handling);
 
             // Correct asymmetry in proposal distribution if MALA
-            let langevin_correction = if let Some(grad) = self.prob.objective.gradient(&self.project(x_old)) {
-                let proposal_grad = self.prob.objective.gradient(&self.project(x_new)).unwrap();
+            let grads = self.prob.gradient(x_old).and_then(|grad| Some((grad, self.prob.gradient(x_new)?)));
+            let langevin_correction = if let Some((grad, proposal_grad)) = grads {
                 let grad_term = -(
                     (x_new - x_old - grad.clone() * self.mala_step_size / t)
                         .dot(&(x_new - x_old - grad.clone() * self.mala_step_size / t))
@@ -138,7 +135,6 @@ where
             r = ((delta_f) / (self.k * delta_x * t) + langevin_correction).exp();
         }
 
-        let mut rng = rand::rng(); 
         let u = T::from_f64(rng.random::<f64>()).unwrap();
         u < r
     }
@@ -1,5 +1,7 @@
 use rand::Rng;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -17,12 +19,52 @@ use crate::utils::opt_prob::{
     OptimizationAlgorithm,
     State
 };
+use crate::utils::rng::{OptRng, new_rng, stream_rngs};
+use crate::utils::constraint_handling::ConstraintHandler;
+use crate::utils::checkpoint::{
+    AlgCheckpoint, 
+    CheckpointError, 
+    StateData, 
+    MatrixData, 
+    NonFinite,
+    vector_to_data, 
+    vector_from_data, 
+    bools_from_data,
+    violation_from_data,
+    matrix_to_data, 
+    matrix_from_data,
+    scalar_to_data,
+    scalar_from_data
+};
 
 use crate::algorithms::parallel_tempering::{
     replica_exchange::{SwapCheck, Periodic, Stochastic, Always},
     metropolis_hastings::MetropolisHastings,
 };
 
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PTCheckpoint {
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub p_schedule: Vec<f64>,
+    pub population: Vec<MatrixData>,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub fitness: Vec<Vec<f64>>,
+    pub constraints: Vec<Vec<bool>>,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    #[serde(default)]
+    pub violation: Vec<Vec<f64>>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub best_individual: Vec<f64>,
+    #[serde_as(as = "NonFinite")]
+    pub best_fitness: f64,
+    pub step_sizes: Vec<Vec<MatrixData>>,
+    pub st: StateData,
+    pub rng: OptRng,
+    #[serde(default)]
+    pub handler: Option<ConstraintHandler>,
+}
+
 pub struct PT<T, N, D> 
 where 
     T: FloatNum,
@@ -43,11 +85,14 @@ where
     pub population: Vec<OMatrix<T, N, D>>, // For each replica
     pub fitness: Vec<OVector<T, N>>,
     pub constraints: Vec<OVector<bool, N>>,
+    pub violation: Vec<OVector<T, N>>,
     pub opt_prob: OptProb<T, D>,
     pub best_individual: OVector<T, D>,
     pub best_fitness: T,
     pub step_sizes: Vec<Vec<OMatrix<T, D, D>>>,
     pub st: State<T, N, D>, // Store a copy of final replica's population and fitness values
+    pub handler: ConstraintHandler,
+    pub rng: OptRng,
 }
 
 impl<T, N, D> PT<T, N, D> 
@@ -65,21 +110,10 @@ where
                     + Allocator<N>
                     + Allocator<D, D>
 {
-    pub fn new(conf: PTConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, max_iter: usize) -> Self {
+    pub fn new(conf: PTConf, init_pop: OMatrix<T, N, D>, opt_prob: OptProb<T, D>, max_iter: usize, seed: Option<u64>) -> Self {
 
-        let swap_check = match &conf.swap_conf {
-            SwapConf::Periodic(p) => SwapCheck::Periodic(Periodic::new(p.swap_frequency, max_iter)),
-            SwapConf::Stochastic(s) => SwapCheck::Stochastic(Stochastic::new(s.swap_probability)),
-            SwapConf::Always(_) => SwapCheck::Always(Always::new()),
-        };
-
-        let metropolis_hastings = MetropolisHastings::<T, D>::new(
-            opt_prob.clone(), 
-            T::from_f64(conf.common.mala_step_size).unwrap(),
-            T::from_f64(conf.common.alpha).unwrap(), 
-            T::from_f64(conf.common.omega).unwrap(),
-            init_pop.row(0).transpose(),
-        );
+        let swap_check = Self::build_swap_check(&conf, max_iter);
+        let metropolis_hastings = Self::build_metropolis_hastings(&conf, &opt_prob, init_pop.row(0).transpose());
 
         // Power law schedule for cyclic annealing
         let p_init = conf.common.power_law_init;
@@ -96,7 +130,7 @@ where
             .collect();
 
         // Initialize populations in parallel
-        let init_results: Vec<(OMatrix<T, N, D>, OVector<T, N>, OVector<bool, N>)> = (0..conf.common.num_replicas)
+        let init_results: Vec<(OMatrix<T, N, D>, OVector<T, N>, OVector<T, N>)> = (0..conf.common.num_replicas)
             .into_par_iter()
             .map(|_| {
                 let mut pop = OMatrix::<T, N, D>::zeros_generic(N::from_usize(init_pop.nrows()), D::from_usize(init_pop.ncols()));
@@ -112,18 +146,18 @@ where
                     })
                     .collect();
 
-                let constr: Vec<bool> = (0..init_pop.nrows())
+                let viol: Vec<T> = (0..init_pop.nrows())
                     .into_par_iter()
                     .map(|i| {
                         let individual = init_pop.row(i).transpose();
-                        opt_prob.is_feasible(&individual)
+                        opt_prob.violation(&individual)
                     })
                     .collect();
 
                 (
                     pop,
                     OVector::<T, N>::from_vec_generic(N::from_usize(init_pop.nrows()), U1, fit),
-                    OVector::<bool, N>::from_vec_generic(N::from_usize(init_pop.nrows()), U1, constr)
+                    OVector::<T, N>::from_vec_generic(N::from_usize(init_pop.nrows()), U1, viol)
                 )
             })
             .collect();
@@ -132,26 +166,32 @@ where
         let mut population = Vec::with_capacity(conf.common.num_replicas);
         let mut fitness = Vec::with_capacity(conf.common.num_replicas);
         let mut constraints = Vec::with_capacity(conf.common.num_replicas);
+        let mut violation = Vec::with_capacity(conf.common.num_replicas);
 
-        for (pop, fit, constr) in init_results {
+        for (pop, fit, viol) in init_results {
             population.push(pop);
             fitness.push(fit);
-            constraints.push(constr);
+            constraints.push(viol.map(|v| v == T::zero()));
+            violation.push(viol);
         }
 
         // Find best individual across all replicas
+        let mut handler = ConstraintHandler::new(opt_prob.constraint_handling.clone());
+        handler.init(violation.iter().flat_map(|v| v.iter().cloned()).collect::<Vec<T>>().as_slice());
         let mut best_idx = 0;
-        let mut best_fitness = fitness[0][0];
+        let mut best_row = 0;
+        let mut best_fitness = T::neg_infinity();
         for i in 0..conf.common.num_replicas {
             for j in 0..fitness[i].len() {
-                if fitness[i][j] > best_fitness && constraints[i][j] {
+                if handler.update_best(fitness[i][j], violation[i][j], best_fitness) {
                     best_fitness = fitness[i][j];
                     best_idx = i;
+                    best_row = j;
                 }
             }
         }
 
-        let best_individual = population[best_idx].row(0).transpose().into_owned();
+        let best_individual = population[best_idx].row(best_row).transpose().into_owned();
         let step_sizes: Vec<Vec<OMatrix<T, D, D>>> = (0..conf.common.num_replicas)
             .map(|_| {
                 (0..population[0].nrows())
@@ -168,6 +208,7 @@ where
             population: population.clone(),
             fitness: fitness.clone(),
             constraints: constraints.clone(),
+            violation: violation.clone(),
             opt_prob,
             best_individual: best_individual.clone(),
             best_fitness: best_fitness.clone(),
@@ -178,11 +219,62 @@ where
                 pop: population[0].clone(),
                 fitness: fitness[0].clone(),
                 constraints: constraints[0].clone(),
+                violation: violation[0].clone(),
                 iter: 1
-            }
+            },
+            handler,
+            rng: new_rng(seed),
         }
     }
 
+    pub fn from_checkpoint(conf: PTConf, opt_prob: OptProb<T, D>, max_iter: usize, checkpoint: PTCheckpoint) -> Result<Self, CheckpointError> {
+        let st: State<T, N, D> = checkpoint.st.to_state()?;
+        let swap_check = Self::build_swap_check(&conf, max_iter);
+        let metropolis_hastings = Self::build_metropolis_hastings(&conf, &opt_prob, st.best_x.clone());
+        let handler = checkpoint.handler.unwrap_or_else(|| ConstraintHandler::new(opt_prob.constraint_handling.clone()));
+
+        Ok(Self {
+            conf,
+            metropolis_hastings,
+            swap_check,
+            p_schedule: checkpoint.p_schedule.into_iter().map(scalar_from_data).collect(),
+            population: checkpoint.population.iter().map(matrix_from_data).collect::<Result<_, _>>()?,
+            fitness: checkpoint.fitness.iter().map(|f| vector_from_data(f)).collect::<Result<_, _>>()?,
+            constraints: checkpoint.constraints.iter().map(|c| bools_from_data(c)).collect::<Result<_, _>>()?,
+            violation: (0..checkpoint.constraints.len())
+                .map(|i| violation_from_data(checkpoint.violation.get(i).map_or(&[][..], |v| v), &checkpoint.constraints[i]))
+                .collect::<Result<_, _>>()?,
+            opt_prob,
+            best_individual: vector_from_data(&checkpoint.best_individual)?,
+            best_fitness: scalar_from_data(checkpoint.best_fitness),
+            step_sizes: checkpoint.step_sizes.iter()
+                .map(|replica| replica.iter().map(matrix_from_data).collect::<Result<_, _>>())
+                .collect::<Result<_, _>>()?,
+            st,
+            handler,
+            rng: checkpoint.rng,
+        })
+    }
+
+    fn build_swap_check(conf: &PTConf, max_iter: usize) -> SwapCheck {
+        match &conf.swap_conf {
+            SwapConf::Periodic(p) => SwapCheck::Periodic(Periodic::new(p.swap_frequency, max_iter)),
+            SwapConf::Stochastic(s) => SwapCheck::Stochastic(Stochastic::new(s.swap_probability)),
+            SwapConf::Always(_) => SwapCheck::Always(Always::new()),
+        }
+    }
+
+    fn build_metropolis_hastings(conf: &PTConf, opt_prob: &OptProb<T, D>, generic_x: OVector<T, D>) -> MetropolisHastings<T, D> {
+        MetropolisHastings::<T, D>::new(
+            opt_prob.clone(), 
+            T::from_f64(conf.common.mala_step_size).unwrap(),
+            T::from_f64(conf.common.alpha).unwrap(), 
+            T::from_f64(conf.common.omega).unwrap(),
+            conf.common.bound_handling,
+            generic_x,
+        )
+    }
+
     // Replica exchange
     pub fn swap(&mut self) {
         let n = self.population.len();
@@ -192,30 +284,32 @@ where
         let mut swap_bool = DMatrix::from_element(n, n, false);
 
         // Randomly pick pairs to swap
-        let mut rng = rand::rng();
         let num_attempts = n / 2;
         let swap_pairs: Vec<(usize, usize)> = (0..num_attempts)
             .map(|_| {
-                let i = rng.random_range(0..n-1);
-                let j = rng.random_range(i+1..n);
+                let i = self.rng.random_range(0..n-1);
+                let j = self.rng.random_range(i+1..n);
                 (i, j)
             })
             .collect();
 
         // Determine which pairs to swap with mh criterion
+        let mut streams = stream_rngs(&mut self.rng, num_attempts * m);
         let swap_results: Vec<Vec<(usize, usize, usize, bool)>> = swap_pairs.par_iter()
-            .map(|&(i, j)| {
+            .zip(streams.par_chunks_mut(m.max(1)))
+            .map(|(&(i, j), pair_rngs)| {
                 let power = self.p_schedule[self.st.iter].to_f64().unwrap();
                 let t_i = T::from_f64((i as f64 / self.conf.common.num_replicas as f64).powf(power)).unwrap();
                 let t_j = T::from_f64((j as f64 / self.conf.common.num_replicas as f64).powf(power)).unwrap();
 
-                (0..m).into_par_iter()
-                    .map(|k| {
+                pair_rngs.par_iter_mut()
+                    .enumerate()
+                    .map(|(k, rng)| {
                         let x_old = self.population[i].row(k).transpose();
                         let x_new = self.population[j].row(k).transpose();
                         let constraints_new = self.constraints[j][k];
                         
-                        let accept = self.metropolis_hastings.accept_reject(&x_old, &x_new, constraints_new, t_i, t_j);
+                        let accept = self.metropolis_hastings.accept_reject(&x_old, &x_new, constraints_new, t_i, t_j, rng);
                         
                         (i, j, k, accept)
                     })
@@ -229,7 +323,7 @@ where
                 if *accept {
                     // Add distance penalty to acceptance probability
                     let dist_factor = 0.9 + 0.1 * (1.0 - (*j - *i) as f64 / n as f64);
-                    let accept_with_penalty = rng.random::<f64>() < dist_factor;
+                    let accept_with_penalty = self.rng.random::<f64>() < dist_factor;
                     
                     swap_bool[(*i, *j)] = swap_bool[(*i, *j)] || accept_with_penalty;
                     swap_bool[(*j, *i)] = swap_bool[(*i, *j)];
@@ -241,6 +335,7 @@ where
         let mut new_population = self.population.clone();
         let mut new_fitness = self.fitness.clone();
         let mut new_constraints = self.constraints.clone();
+        let mut new_violation = self.violation.clone();
 
         // Process accepted swaps
         for i in 0..n {
@@ -261,6 +356,10 @@ where
                         let temp_const = self.constraints[i][k];
                         new_constraints[i][k] = self.constraints[j][k];
                         new_constraints[j][k] = temp_const;
+
+                        let temp_viol = self.violation[i][k];
+                        new_violation[i][k] = self.violation[j][k];
+                        new_violation[j][k] = temp_viol;
                     }
                 }
             }
@@ -269,6 +368,7 @@ where
         self.population = new_population;
         self.fitness = new_fitness;
         self.constraints = new_constraints;
+        self.violation = new_violation;
     }
 }
 
@@ -297,24 +397,31 @@ where
             .collect();
         
         // Local move
-        let updates: Vec<Vec<Option<(OVector<T, D>, T, bool, OMatrix<T, D, D>)>>> = (0..self.conf.common.num_replicas)
-            .into_par_iter()
-            .map(|i| {
-                (0..self.population[i].nrows())
-                    .into_par_iter()
-                    .map(|j| {
+        let m = self.population[0].nrows();
+        let mut streams = stream_rngs(&mut self.rng, self.conf.common.num_replicas * m);
+        let updates: Vec<Vec<Option<(OVector<T, D>, T, T, OMatrix<T, D, D>)>>> = streams
+            .par_chunks_mut(m.max(1))
+            .enumerate()
+            .map(|(i, replica_rngs)| {
+                replica_rngs.par_iter_mut()
+                    .enumerate()
+                    .map(|(j, rng)| {
                         let x_old = self.population[i].row(j).transpose();
-                        let x_new = self.metropolis_hastings.local_move(&x_old, &self.step_sizes[i][j], temperatures[i]);
-                        let constr_new = self.opt_prob.is_feasible(&x_new);
+                        let x_new = self.metropolis_hastings.local_move(&x_old, &self.step_sizes[i][j], temperatures[i], rng);
+                        let x_new = self.opt_prob.step_away(&x_new, &x_old, rng);
+                        let x_new = self.opt_prob.repair(&x_new, &x_old, self.conf.common.bound_handling, rng);
+                        let viol_new = self.opt_prob.violation(&x_new);
+                        let constr_new = viol_new == T::zero();
                         
                         if self.metropolis_hastings.accept_reject(
                             &x_old,
                             &x_new,
                             constr_new,
                             temperatures[i],
-                            -T::from_f64(1.0).unwrap() // Send in negative to signal local move 
+                            -T::from_f64(1.0).unwrap(), // Send in negative to signal local move 
+                            rng
                         ) {
-                            let new_step_size = if self.opt_prob.objective.gradient(&x_old).is_none() {
+                            let new_step_size = if self.opt_prob.gradient(&x_old).is_none() {
                                 self.metropolis_hastings.update_step_size(
                                     &self.step_sizes[i][j],
                                     &x_old,
@@ -324,12 +431,12 @@ where
                                 self.step_sizes[i][j].clone()
                             };
                             
-                            let fitness_new = self.opt_prob.evaluate(&x_new);
+                            let fitness_new = self.opt_prob.evaluate_bounded(&x_new, self.conf.common.bound_handling);
                             
                             Some((
                                 x_new.clone(),
                                 fitness_new,
-                                constr_new,
+                                viol_new,
                                 new_step_size
                             ))
                         } else {
@@ -343,10 +450,11 @@ where
         // Apply updates
         for (i, replica) in updates.iter().enumerate() {
             for (j, update) in replica.iter().enumerate() {
-                if let Some((x_new, fitness_new, constr_new, step_size_new)) = update {
+                if let Some((x_new, fitness_new, viol_new, step_size_new)) = update {
                     self.population[i].row_mut(j).copy_from(&x_new.transpose());
                     self.fitness[i][j] = *fitness_new;
-                    self.constraints[i][j] = *constr_new;
+                    self.constraints[i][j] = *viol_new == T::zero();
+                    self.violation[i][j] = *viol_new;
                     self.step_sizes[i][j] = step_size_new.clone();
                 }
             }
@@ -355,34 +463,29 @@ where
         // Replica exchange
         if match &self.swap_check {
             SwapCheck::Periodic(p) => p.should_swap(self.st.iter),
-            SwapCheck::Stochastic(s) => s.should_swap(self.st.iter),
+            SwapCheck::Stochastic(s) => s.should_swap(self.st.iter, &mut self.rng),
             SwapCheck::Always(a) => a.should_swap(self.st.iter),
         } {
             self.swap();
         }
 
         // Update best individual
-        let mut best_idx = 0;
-        let mut best_row = 0;
-        let mut best_fitness = self.fitness[0][0];
         for i in 0..self.conf.common.num_replicas {
             for j in 0..self.fitness[i].len() {
-                if self.fitness[i][j] > best_fitness && self.constraints[i][j] {
-                    best_fitness = self.fitness[i][j];
-                    best_idx = i;
-                    best_row = j;
+                if self.handler.update_best(self.fitness[i][j], self.violation[i][j], self.best_fitness) {
+                    self.best_fitness = self.fitness[i][j];
+                    self.best_individual = self.population[i].row(j).transpose().into_owned();
                 }
             }
         }
-        let best_individual = self.population[best_idx].row(best_row).transpose().into_owned();
-        self.best_individual = best_individual.clone();
-        self.best_fitness = best_fitness.clone();
+        self.handler.next_generation();
 
         self.st.best_x = self.best_individual.clone();
-        self.st.best_f = best_fitness.clone();
+        self.st.best_f = self.best_fitness;
         self.st.pop = self.population[0].clone();
         self.st.fitness = self.fitness[0].clone();
         self.st.constraints = self.constraints[0].clone();
+        self.st.violation = self.violation[0].clone();
 
         self.st.iter += 1;
 
@@ -391,4 +494,22 @@ where
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::PT(PTCheckpoint {
+            p_schedule: self.p_schedule.iter().map(|&p| scalar_to_data(p)).collect(),
+            population: self.population.iter().map(matrix_to_data).collect(),
+            fitness: self.fitness.iter().map(vector_to_data).collect(),
+            constraints: self.constraints.iter().map(|c| c.iter().cloned().collect()).collect(),
+            violation: self.violation.iter().map(vector_to_data).collect(),
+            best_individual: vector_to_data(&self.best_individual),
+            best_fitness: scalar_to_data(self.best_fitness),
+            step_sizes: self.step_sizes.iter()
+                .map(|replica| replica.iter().map(matrix_to_data).collect())
+                .collect(),
+            st: StateData::from_state(&self.st),
+            rng: self.rng.clone(),
+            handler: Some(self.handler.clone()),
+        })
+    }
 }
\ No newline at end of file
@@ -1,3 +1,7 @@
+use rand::Rng;
+
+use crate::utils::rng::OptRng;
+
 pub enum SwapCheck {
     Periodic(Periodic),
     Stochastic(Stochastic),
@@ -28,8 +32,8 @@ impl Stochastic {
         Self { swap_probability }
     }
 
-    pub fn should_swap(&self, _current_step: usize) -> bool {
-        rand::random::<f64>() < self.swap_probability
+    pub fn should_swap(&self, _current_step: usize, rng: &mut OptRng) -> bool {
+        rng.random::<f64>() < self.swap_probability
     }
 }
 
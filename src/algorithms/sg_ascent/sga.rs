@@ -1,4 +1,6 @@
 use rand_distr::{Normal, Distribution};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -15,6 +17,27 @@ use crate::utils::opt_prob::{
     OptimizationAlgorithm,
     State
 };
+use crate::utils::bounds::BoundHandling;
+use crate::utils::rng::{OptRng, new_rng};
+use crate::utils::checkpoint::{
+    AlgCheckpoint, 
+    CheckpointError, 
+    StateData, 
+    NonFinite,
+    vector_to_data, 
+    vector_from_data
+};
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SGACheckpoint {
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub x: Vec<f64>,
+    pub st: StateData,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub velocity: Vec<f64>,
+    pub rng: OptRng,
+}
 
 pub struct SGAscent<T, N, D> 
 where 
@@ -33,6 +56,7 @@ where
     pub st: State<T, N, D>,
     velocity: OVector<T, D>,
     noise_dist: Normal<f64>,
+    rng: OptRng,
 }
 
 impl<T, N, D> SGAscent<T, N, D> 
@@ -47,9 +71,10 @@ where
                      + Allocator<U1, D>
                      + Allocator<N>
 {
-    pub fn new(conf: SGAConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>) -> Self {
+    pub fn new(conf: SGAConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
         let init_x: OVector<T, D> = init_pop.row(0).transpose().into_owned();
         let best_f = opt_prob.evaluate(&init_x);
+        let violation = opt_prob.violation(&init_x);
         let noise_dist = Normal::new(0.0, conf.learning_rate).unwrap();
         let n = init_x.len();
 
@@ -62,13 +87,29 @@ where
                 best_f,
                 pop: OMatrix::<T, N, D>::from_fn_generic(N::from_usize(1), D::from_usize(n), |_, j| init_x.clone()[j]),
                 fitness: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, best_f),
-                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, opt_prob.is_feasible(&init_x.clone())),
+                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, violation == T::zero()),
+                violation: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, violation),
                 iter: 1
             },
             velocity: OVector::zeros_generic(D::from_usize(n), U1),
             noise_dist,
+            rng: new_rng(seed),
         }
     }
+
+    pub fn from_checkpoint(conf: SGAConf, opt_prob: OptProb<T, D>, checkpoint: SGACheckpoint) -> Result<Self, CheckpointError> {
+        let noise_dist = Normal::new(0.0, conf.learning_rate).unwrap();
+
+        Ok(Self {
+            conf,
+            opt_prob,
+            x: vector_from_data(&checkpoint.x)?,
+            st: checkpoint.st.to_state()?,
+            velocity: vector_from_data(&checkpoint.velocity)?,
+            noise_dist,
+            rng: checkpoint.rng,
+        })
+    }
 }
 
 impl<T, N, D> OptimizationAlgorithm<T, N, D> for SGAscent<T, N, D> 
@@ -84,13 +125,15 @@ where
                     + Allocator<U1, D>
 {
     fn step(&mut self) {
-        let gradient = self.opt_prob.objective.gradient(&self.x).unwrap();
+        let Some(gradient) = self.opt_prob.require_gradient(&self.x, "SGA") else {
+            return;
+        };
         
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
         let noise = OVector::<T, D>::from_iterator_generic(
             D::from_usize(self.x.len()),
             U1,
-            (0..self.x.len()).map(|_| T::from_f64(self.noise_dist.sample(&mut rng)).unwrap())
+            (0..self.x.len()).map(|_| T::from_f64(self.noise_dist.sample(rng)).unwrap())
         );
         
         let noisy_gradient = gradient + noise;
@@ -98,24 +141,39 @@ where
         self.velocity = &self.velocity * T::from_f64(self.conf.momentum).unwrap() + 
                        &noisy_gradient * T::from_f64(self.conf.learning_rate).unwrap();
 
-        self.x += &self.velocity;
-        
-        if self.opt_prob.is_feasible(&self.x) {
-            let fitness = self.opt_prob.evaluate(&self.x);
-            
-            if fitness > self.st.best_f {
-                self.st.best_f = fitness;
-                self.st.best_x = self.x.clone();
-            }
+        let handling = self.conf.bound_handling;
+        let x = self.opt_prob.repair(&(&self.x + &self.velocity), &self.x, handling, &mut self.rng);
+
+        // A step the death penalty leaves outside the box is dropped
+        if handling == BoundHandling::DeathPenalty && self.opt_prob.bounds.as_ref().is_some_and(|b| !b.contains(&x)) {
+            self.st.iter += 1;
+            return;
+        }
+        self.x = x;
+
+        let fitness = self.opt_prob.evaluate(&self.x);
+        if self.opt_prob.is_feasible(&self.x) && fitness > self.st.best_f {
+            self.st.best_f = fitness;
+            self.st.best_x = self.x.clone();
         }
 
         self.st.pop.row_mut(0).copy_from(&self.x.transpose());
-        self.st.fitness[0] = self.opt_prob.evaluate(&self.x);
-        self.st.constraints[0] = self.opt_prob.is_feasible(&self.x);
+        self.st.fitness[0] = fitness;
+        self.st.violation[0] = self.opt_prob.violation(&self.x);
+        self.st.constraints[0] = self.st.violation[0] == T::zero();
         self.st.iter += 1;
     }
 
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::SGA(SGACheckpoint {
+            x: vector_to_data(&self.x),
+            st: StateData::from_state(&self.st),
+            velocity: vector_to_data(&self.velocity),
+            rng: self.rng.clone(),
+        })
+    }
 } 
\ No newline at end of file
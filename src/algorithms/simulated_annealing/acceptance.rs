@@ -7,6 +7,7 @@ use nalgebra::{
 };
 
 use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb};
+use crate::utils::rng::OptRng;
 
 
 pub enum AcceptanceType {
@@ -20,6 +21,7 @@ where
     D: Dim,
     DefaultAllocator: Allocator<D>  
 {
+    #[allow(clippy::too_many_arguments)]
     fn accept(
         &self, 
         current_x: &OVector<T, D>,
@@ -28,6 +30,7 @@ where
         new_fitness: T,
         temperature: T,
         step_size: T,
+        rng: &mut OptRng,
     ) -> bool;
 }
 
@@ -49,7 +52,7 @@ where
     DefaultAllocator: Allocator<D>  
 {
     pub fn new(prob: OptProb<T, D>, generic_x: OVector<T, D>) -> Self {
-        let acceptance_type = if prob.objective.gradient(&generic_x).is_some() {
+        let acceptance_type = if prob.gradient(&generic_x).is_some() {
             AcceptanceType::MALA
         } else {
             AcceptanceType::Metropolis
@@ -62,6 +65,7 @@ where
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn accept(
         &self,
         current_x: &OVector<T, D>,
@@ -70,9 +74,8 @@ where
         new_fitness: T,
         temperature: T,
         step_size: T,
+        rng: &mut OptRng,
     ) -> bool {
-        let mut rng = rand::rng();
-
         if new_fitness > current_fitness {
             return true;
         }
@@ -86,8 +89,13 @@ where
                 (delta_f / (temperature * delta_x * self.k)).exp()
             },
             AcceptanceType::MALA => {
-                let grad = self.prob.objective.gradient(current_x).unwrap();
-                let proposal_grad = self.prob.objective.gradient(new_x).unwrap();
+                // Rejects once the budget can't pay for the gradients
+                let Some(grad) = self.prob.gradient(current_x) else {
+                    return false;
+                };
+                let Some(proposal_grad) = self.prob.gradient(new_x) else {
+                    return false;
+                };
                 
                 let langevin_correction = -(
                     (new_x - current_x - grad.clone() * step_size * temperature)
@@ -1,6 +1,5 @@
 use rand::Rng;
 use rand_distr::{Normal, StandardNormal};
-use rayon::prelude::*;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -10,6 +9,7 @@ use nalgebra::{
 };
 
 use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb};
+use crate::utils::rng::OptRng;
 
 pub enum MoveType {
     RandomDrift,
@@ -35,7 +35,7 @@ where
     DefaultAllocator: Allocator<D>   
 {
     pub fn new(prob: OptProb<T, D>, generic_x: OVector<T, D>, mala_step_size: T) -> Self {
-        let move_type = if prob.objective.gradient(&generic_x).is_some() {
+        let move_type = if prob.gradient(&generic_x).is_some() {
             MoveType::MALA
         } else {
             MoveType::RandomDrift
@@ -44,46 +44,34 @@ where
         Self { move_type, prob, mala_step_size }
     }
 
-    pub fn generate(&self, current: &OVector<T, D>, step_size: f64, bounds: (T, T), temperature: T) -> OVector<T, D> {
+    pub fn generate(&self, current: &OVector<T, D>, step_size: f64, temperature: T, rng: &mut OptRng) -> OVector<T, D> {
         match self.move_type {
-            MoveType::RandomDrift => self.random_drift(current, step_size, bounds),
-            MoveType::MALA => self.mala_move(current, temperature, bounds),
+            MoveType::RandomDrift => self.random_drift(current, step_size, rng),
+            MoveType::MALA => self.mala_move(current, temperature, rng),
         }
     }
 
-    fn random_drift(&self, current: &OVector<T, D>, step_size: f64, bounds: (T, T)) -> OVector<T, D> {
-        let mut neighbor = current.clone();
-        
-        neighbor.as_mut_slice()
-            .par_chunks_mut(1)
-            .enumerate()
-            .for_each(|(i, val)| {
-                let mut rng = rand::rng();
-                let step = T::from_f64(
-                    rng.sample::<f64, _>(Normal::new(0.0, step_size).unwrap())
-                ).unwrap();
-                val[0] = (current[i] + step).clamp(bounds.0, bounds.1);
-            });
-        
-        neighbor
+    fn random_drift(&self, current: &OVector<T, D>, step_size: f64, rng: &mut OptRng) -> OVector<T, D> {
+        let normal = Normal::new(0.0, step_size).unwrap();
+        OVector::<T, D>::from_fn_generic(D::from_usize(current.len()), U1, |i, _| {
+            let step = T::from_f64(rng.sample::<f64, _>(normal)).unwrap();
+            current[i] + step
+        })
     }
 
-    fn mala_move(&self, current: &OVector<T, D>, temperature: T, bounds: (T, T)) -> OVector<T, D> {
-        let mut rng = rand::rng();
+    fn mala_move(&self, current: &OVector<T, D>, temperature: T, rng: &mut OptRng) -> OVector<T, D> {
         let step = self.mala_step_size * temperature;
         
-        let grad = self.prob.objective.gradient(current).unwrap();
+        // Stays put once the budget can't pay for the gradient
+        let Some(grad) = self.prob.gradient(current) else {
+            return current.clone();
+        };
         let drift = grad * step;
         
         let noise = OVector::<T, D>::from_fn_generic(D::from_usize(current.len()), U1, |_, _| {
             T::from_f64(rng.sample::<f64, _>(StandardNormal)).unwrap()
         }) * (step * T::from_f64(2.0).unwrap()).sqrt();
         
-        let mut new_pos = current + drift + noise;
-        for i in 0..new_pos.len() {
-            new_pos[i] = new_pos[i].clamp(bounds.0, bounds.1); // Not valid without Jacobian, but hey!
-        }
-        
-        new_pos
+        current + drift + noise
     }
 } 
\ No newline at end of file
@@ -1,4 +1,6 @@
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -13,7 +15,19 @@ use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     OptimizationAlgorithm,
-    State
+    State,
+    Diagnostics
+};
+use crate::utils::rng::{OptRng, new_rng, stream_rngs};
+use crate::utils::checkpoint::{
+    AlgCheckpoint, 
+    CheckpointError, 
+    StateData, 
+    NonFinite,
+    vector_to_data, 
+    vector_from_data,
+    scalar_to_data,
+    scalar_from_data
 };
 
 use crate::algorithms::simulated_annealing::{
@@ -22,6 +36,23 @@ use crate::algorithms::simulated_annealing::{
     acceptance::MetropolisAcceptance,
 };
 
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SACheckpoint {
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub x: Vec<f64>,
+    #[serde_as(as = "NonFinite")]
+    pub fitness: f64,
+    pub constraints: bool,
+    #[serde_as(as = "NonFinite")]
+    #[serde(default)]
+    pub violation: f64,
+    pub st: StateData,
+    pub temperature: f64,
+    pub no_improve_count: usize,
+    pub rng: OptRng,
+}
+
 pub struct SimulatedAnnealing<T, N, D> 
 where 
     T: FloatNum,
@@ -38,12 +69,14 @@ where
     pub x: OVector<T, D>,
     pub fitness: T,
     pub constraints: bool,
+    pub violation: T,
     pub st: State<T, N, D>,
     pub temperature: T,
     no_improve_count: usize,
     neighbor_gen: GaussianGenerator<T, D>,
     cooling_schedule: ExponentialCooling,
     acceptance: MetropolisAcceptance<T, D>,
+    rng: OptRng,
 }
 
 impl<T, N, D> SimulatedAnnealing<T, N, D> 
@@ -58,9 +91,10 @@ where
                      + Allocator<U1, D>
                      + Allocator<N>
 {
-    pub fn new(conf: SAConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>) -> Self {
+    pub fn new(conf: SAConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
         let init_x = init_pop.row(0).transpose();
         let best_f = opt_prob.evaluate(&init_x);
+        let violation = opt_prob.violation(&init_x);
         let n = init_x.len();
         
         Self {
@@ -68,22 +102,45 @@ where
             opt_prob: opt_prob.clone(),
             x: init_x.clone(),
             fitness: best_f,
-            constraints: opt_prob.is_feasible(&init_x),
+            constraints: violation == T::zero(),
+            violation,
             st: State{
                 best_x: init_x.clone(),
                 best_f: best_f,
                 pop: OMatrix::<T, N, D>::from_fn_generic(N::from_usize(1), D::from_usize(n), |_, j| init_x.clone()[j]),
                 fitness: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, best_f),
-                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, opt_prob.is_feasible(&init_x.clone())),
+                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, violation == T::zero()),
+                violation: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, violation),
                 iter: 1
             },
             temperature: T::from_f64(conf.initial_temp).unwrap(),
             no_improve_count: 0,
             neighbor_gen: GaussianGenerator::new(opt_prob.clone(), init_x.clone(), T::from_f64(conf.step_size).unwrap()),
             cooling_schedule: ExponentialCooling,
-              acceptance: MetropolisAcceptance::new(opt_prob, init_x),
+            acceptance: MetropolisAcceptance::new(opt_prob, init_x),
+            rng: new_rng(seed),
         }
     }
+
+    pub fn from_checkpoint(conf: SAConf, opt_prob: OptProb<T, D>, checkpoint: SACheckpoint) -> Result<Self, CheckpointError> {
+        let x: OVector<T, D> = vector_from_data(&checkpoint.x)?;
+
+        Ok(Self {
+            neighbor_gen: GaussianGenerator::new(opt_prob.clone(), x.clone(), T::from_f64(conf.step_size).unwrap()),
+            acceptance: MetropolisAcceptance::new(opt_prob.clone(), x.clone()),
+            conf,
+            opt_prob,
+            x,
+            fitness: scalar_from_data(checkpoint.fitness),
+            constraints: checkpoint.constraints,
+            violation: scalar_from_data(checkpoint.violation),
+            st: checkpoint.st.to_state()?,
+            temperature: scalar_from_data(checkpoint.temperature),
+            no_improve_count: checkpoint.no_improve_count,
+            cooling_schedule: ExponentialCooling,
+            rng: checkpoint.rng,
+        })
+    }
 }
 
 impl<T, N, D> OptimizationAlgorithm<T, N, D> for SimulatedAnnealing<T, N, D>
@@ -107,29 +164,35 @@ where
                 .sqrt())
             .max(min_step);
         
-        let bounds = (
-            T::from_f64(self.conf.x_min).unwrap(),
-            T::from_f64(self.conf.x_max).unwrap()
-        );
+        let handling = self.conf.bound_handling;
 
-        let neighbors: Vec<_> = (0..self.conf.num_neighbors)
+        let neighbors: Vec<_> = stream_rngs(&mut self.rng, self.conf.num_neighbors)
             .into_par_iter()
-            .map(|_| self.neighbor_gen.generate(&self.x, step_size, bounds, self.temperature))
+            .map(|mut rng| {
+                // Orders move to a neighbouring order, which needs no repair
+                if self.conf.neighbourhood.is_permutation() {
+                    return self.conf.neighbourhood.neighbour(&self.x, &mut rng);
+                }
+                let neighbor = self.neighbor_gen.generate(&self.x, step_size, self.temperature, &mut rng);
+                let neighbor = self.opt_prob.step_away(&neighbor, &self.x, &mut rng);
+                self.opt_prob.repair(&neighbor, &self.x, handling, &mut rng) // Not valid for MALA without a Jacobian, but hey!
+            })
             .collect();
 
         let mut improved = false;
         for neighbor in neighbors {
-            let neighbor_fitness = self.opt_prob.evaluate(&neighbor);
+            let neighbor_fitness = self.opt_prob.evaluate_bounded(&neighbor, handling);
             
-            if neighbor_fitness > self.st.best_f && self.opt_prob.is_feasible(&neighbor) {
+            let violation = self.opt_prob.violation(&neighbor);
+            let feasible = violation == T::zero();
+
+            if neighbor_fitness > self.st.best_f && feasible {
                 self.st.best_f = neighbor_fitness;
                 self.st.best_x = neighbor.clone();
                 self.no_improve_count = 0;
                 improved = true;
             }
 
-            let feasible = self.opt_prob.is_feasible(&neighbor);
-
             // Use Metropolis criterion for current solution
             if self.acceptance.accept(
                 &self.x,
@@ -137,11 +200,13 @@ where
                 &neighbor,
                 neighbor_fitness,
                 self.temperature,
-                T::from_f64(step_size).unwrap()) && feasible
+                T::from_f64(step_size).unwrap(),
+                &mut self.rng) && feasible
             {
                 self.x = neighbor;
                 self.fitness = neighbor_fitness;
                 self.constraints = feasible;
+                self.violation = violation;
             }
         }
 
@@ -164,6 +229,7 @@ where
         self.st.pop.row_mut(0).copy_from(&self.x.transpose());
         self.st.fitness[0] = self.fitness;
         self.st.constraints[0] = self.constraints;
+        self.st.violation[0] = self.violation;
 
         self.st.iter += 1;
     }
@@ -171,4 +237,24 @@ where
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::SA(SACheckpoint {
+            x: vector_to_data(&self.x),
+            fitness: scalar_to_data(self.fitness),
+            constraints: self.constraints,
+            violation: scalar_to_data(self.violation),
+            st: StateData::from_state(&self.st),
+            temperature: scalar_to_data(self.temperature),
+            no_improve_count: self.no_improve_count,
+            rng: self.rng.clone(),
+        })
+    }
+
+    fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::from([
+            ("temperature".to_string(), self.temperature.to_f64().unwrap()),
+            ("no_improve_count".to_string(), self.no_improve_count as f64),
+        ])
+    }
 } 
\ No newline at end of file
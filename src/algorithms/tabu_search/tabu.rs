@@ -1,5 +1,7 @@
 use rand::Rng;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -14,11 +16,34 @@ use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     OptimizationAlgorithm,
-    State
+    State,
+    Diagnostics
+};
+use crate::utils::rng::{OptRng, new_rng, stream_rngs};
+use crate::utils::checkpoint::{
+    AlgCheckpoint, 
+    CheckpointError, 
+    StateData, 
+    NonFinite,
+    vector_to_data, 
+    vector_from_data
 };
 
 use crate::algorithms::tabu_search::tabu_list::{TabuList, TabuType};
 
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TabuCheckpoint {
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub x: Vec<f64>,
+    pub st: StateData,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub tabu_items: Vec<Vec<f64>>,
+    pub tabu_max_size: usize,
+    pub iterations_since_improvement: usize,
+    pub rng: OptRng,
+}
+
 pub struct TabuSearch<T, N, D> 
 where 
     T: FloatNum,
@@ -36,6 +61,7 @@ where
     pub st: State<T, N, D>,
     tabu_list: TabuList<T, D>,
     iterations_since_improvement: usize,
+    rng: OptRng,
 }
 
 impl<T, N, D> TabuSearch<T, N, D> 
@@ -50,9 +76,10 @@ where
                      + Allocator<U1, D>
                      + Allocator<N>
 {
-    pub fn new(conf: TabuConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>) -> Self {
+    pub fn new(conf: TabuConf, init_pop: OMatrix<T, U1, D>, opt_prob: OptProb<T, D>, seed: Option<u64>) -> Self {
         let init_x = init_pop.row(0).transpose();
         let best_f = opt_prob.evaluate(&init_x);
+        let violation = opt_prob.violation(&init_x);
         let tabu_type = TabuType::from(&conf);
         let n = init_x.len();
 
@@ -65,15 +92,37 @@ where
                 best_f,
                 pop: OMatrix::<T, N, D>::from_fn_generic(N::from_usize(1), D::from_usize(n), |_, j| init_x.clone()[j]),
                 fitness: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, best_f),
-                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, opt_prob.is_feasible(&init_x.clone())),
+                constraints: OVector::<bool, N>::from_element_generic(N::from_usize(1), U1, violation == T::zero()),
+                violation: OVector::<T, N>::from_element_generic(N::from_usize(1), U1, violation),
                 iter: 1
             },
             tabu_list: TabuList::new(conf.common.tabu_list_size, tabu_type),
             iterations_since_improvement: 0,
+            rng: new_rng(seed),
         }
     }
 
-    fn generate_neighbor(&self, rng: &mut impl Rng) -> OVector<T, D> {
+    pub fn from_checkpoint(conf: TabuConf, opt_prob: OptProb<T, D>, checkpoint: TabuCheckpoint) -> Result<Self, CheckpointError> {
+        let tabu_type = TabuType::from(&conf);
+        let items = checkpoint.tabu_items.iter()
+            .map(|x| vector_from_data(x))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            conf,
+            opt_prob,
+            x: vector_from_data(&checkpoint.x)?,
+            st: checkpoint.st.to_state()?,
+            tabu_list: TabuList::from_parts(items, checkpoint.tabu_max_size, tabu_type),
+            iterations_since_improvement: checkpoint.iterations_since_improvement,
+            rng: checkpoint.rng,
+        })
+    }
+
+    fn generate_neighbor(&self, rng: &mut OptRng) -> OVector<T, D> {
+        if self.conf.common.neighbourhood.is_permutation() {
+            return self.conf.common.neighbourhood.neighbour(&self.x, rng);
+        }
         let mut neighbor = self.x.clone();
         neighbor.iter_mut().for_each(|val| {
             if rng.random_bool(self.conf.common.perturbation_prob) {
@@ -82,13 +131,14 @@ where
                 ).unwrap();
             }
         });
-        neighbor
+        let neighbor = self.opt_prob.step_away(&neighbor, &self.x, rng);
+        self.opt_prob.repair(&neighbor, &self.x, self.conf.common.bound_handling, rng)
     }
 
     fn evaluate_neighbor(&self, neighbor: &OVector<T, D>) -> Option<T> {
         if self.opt_prob.is_feasible(neighbor) 
             && !self.tabu_list.is_tabu(neighbor, T::from_f64(self.conf.common.tabu_threshold).unwrap()) {
-            Some(self.opt_prob.evaluate(neighbor))
+            Some(self.opt_prob.evaluate_bounded(neighbor, self.conf.common.bound_handling))
         } else {
             None
         }
@@ -114,10 +164,9 @@ where
         let mut best_neighbor_fitness = T::neg_infinity();
         
         // Generate and evaluate neighborhood
-        let neighbors: Vec<_> = (0..self.conf.common.num_neighbors)
+        let neighbors: Vec<_> = stream_rngs(&mut self.rng, self.conf.common.num_neighbors)
             .into_par_iter()
-            .map(|_| {
-                let mut local_rng = rand::rng();
+            .map(|mut local_rng| {
                 let neighbor = self.generate_neighbor(&mut local_rng);
                 let fitness = self.evaluate_neighbor(&neighbor);
                 (neighbor, fitness)
@@ -140,6 +189,10 @@ where
             
             self.x = best_neighbor.clone();
 
+            // Only feasible neighbors are scored
+            self.st.constraints[0] = true;
+            self.st.violation[0] = T::zero();
+
             if best_neighbor_fitness > self.st.best_f {
                 self.st.best_f = best_neighbor_fitness;
                 self.st.best_x = best_neighbor;
@@ -157,4 +210,21 @@ where
     fn state(&self) -> &State<T, N, D> {
         &self.st
     }
+
+    fn checkpoint(&self) -> AlgCheckpoint {
+        AlgCheckpoint::TS(TabuCheckpoint {
+            x: vector_to_data(&self.x),
+            st: StateData::from_state(&self.st),
+            tabu_items: self.tabu_list.items().iter().map(vector_to_data).collect(),
+            tabu_max_size: self.tabu_list.max_size(),
+            iterations_since_improvement: self.iterations_since_improvement,
+            rng: self.rng.clone(),
+        })
+    }
+
+    fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::from([
+            ("iterations_since_improvement".to_string(), self.iterations_since_improvement as f64),
+        ])
+    }
 } 
\ No newline at end of file
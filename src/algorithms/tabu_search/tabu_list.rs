@@ -63,6 +63,19 @@ where
         }
     }
 
+    // Rebuild a list saved in a checkpoint
+    pub fn from_parts(items: VecDeque<OVector<T, D>>, max_size: usize, tabu_type: TabuType) -> Self {
+        Self { items, max_size, tabu_type }
+    }
+
+    pub fn items(&self) -> &VecDeque<OVector<T, D>> {
+        &self.items
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
     pub fn is_tabu(&self, x: &OVector<T, D>, threshold: T) -> bool {
         self.items.par_iter().any(|tabu_x| {
             let diff = x - tabu_x;
@@ -0,0 +1,274 @@
+use std::f64::consts::PI;
+
+use crate::benchmarks::{Benchmark, ConstrainedBenchmark, Optimum, uniform_bounds};
+use crate::utils::bounds::BoundsConf;
+
+// Constrained problems from the G suite of the CEC 2006 competition, each defined in one dimension, and
+// Keane's bump function. Pass the same value as objective and constraints:
+//     NonConvexOpt::new_with_constraints(conf, init_pop, G06, Some(G06))
+
+// 13 variables, a quadratic objective and nine linear inequalities, six of them active at the optimum
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct G01;
+
+impl Benchmark for G01 {
+    fn name(&self) -> String {
+        "G01".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        5.0 * x[..4].iter().sum::<f64>() - 5.0 * x[..4].iter().map(|xi| xi * xi).sum::<f64>() - x[4..].iter().sum::<f64>()
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        Some(x.iter().enumerate().map(|(i, xi)| if i < 4 { 5.0 - 10.0 * xi } else { -1.0 }).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        let upper = (0..13).map(|i| if (9..12).contains(&i) { 100.0 } else { 1.0 }).collect();
+        BoundsConf { lower: vec![0.0], upper }
+    }
+
+    fn dim(&self) -> Option<usize> {
+        Some(13)
+    }
+
+    fn optimum(&self, _dim: usize) -> Option<Optimum> {
+        let x = (0..13).map(|i| if (9..12).contains(&i) { 3.0 } else { 1.0 }).collect();
+        Some(Optimum { value: -15.0, x: Some(x) })
+    }
+}
+
+impl ConstrainedBenchmark for G01 {
+    fn ineq(&self, x: &[f64]) -> Vec<f64> {
+        vec![
+            2.0 * x[0] + 2.0 * x[1] + x[9] + x[10] - 10.0,
+            2.0 * x[0] + 2.0 * x[2] + x[9] + x[11] - 10.0,
+            2.0 * x[1] + 2.0 * x[2] + x[10] + x[11] - 10.0,
+            -8.0 * x[0] + x[9],
+            -8.0 * x[1] + x[10],
+            -8.0 * x[2] + x[11],
+            -2.0 * x[3] - x[4] + x[9],
+            -2.0 * x[5] - x[6] + x[10],
+            -2.0 * x[7] - x[8] + x[11],
+        ]
+    }
+}
+
+// A cubic objective in a thin crescent between two circles
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct G06;
+
+impl Benchmark for G06 {
+    fn name(&self) -> String {
+        "G06".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        (x[0] - 10.0).powi(3) + (x[1] - 20.0).powi(3)
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        Some(vec![3.0 * (x[0] - 10.0).powi(2), 3.0 * (x[1] - 20.0).powi(2)])
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        BoundsConf { lower: vec![13.0, 0.0], upper: vec![100.0, 100.0] }
+    }
+
+    fn dim(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn optimum(&self, _dim: usize) -> Option<Optimum> {
+        Some(Optimum { value: -6_961.813_875_580_15, x: Some(vec![14.095, 0.842_960_789_215_479_6]) })
+    }
+}
+
+impl ConstrainedBenchmark for G06 {
+    fn ineq(&self, x: &[f64]) -> Vec<f64> {
+        vec![
+            -(x[0] - 5.0).powi(2) - (x[1] - 5.0).powi(2) + 100.0,
+            (x[0] - 6.0).powi(2) + (x[1] - 5.0).powi(2) - 82.81,
+        ]
+    }
+}
+
+// Many sharp peaks, the best one inside a small feasible region
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct G08;
+
+impl Benchmark for G08 {
+    fn name(&self) -> String {
+        "G08".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        -(2.0 * PI * x[0]).sin().powi(3) * (2.0 * PI * x[1]).sin() / (x[0].powi(3) * (x[0] + x[1]))
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        let (s0, c0) = (2.0 * PI * x[0]).sin_cos();
+        let (s1, c1) = (2.0 * PI * x[1]).sin_cos();
+        let num = s0.powi(3) * s1;
+        let den = x[0].powi(3) * (x[0] + x[1]);
+        let (dnum0, dnum1) = (6.0 * PI * s0 * s0 * c0 * s1, 2.0 * PI * s0.powi(3) * c1);
+        let (dden0, dden1) = (4.0 * x[0].powi(3) + 3.0 * x[0] * x[0] * x[1], x[0].powi(3));
+        Some(vec![
+            -(dnum0 * den - num * dden0) / (den * den),
+            -(dnum1 * den - num * dden1) / (den * den),
+        ])
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 10.0)
+    }
+
+    fn dim(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn optimum(&self, _dim: usize) -> Option<Optimum> {
+        Some(Optimum { value: -0.095_825_041_418_035_9, x: Some(vec![1.227_971_352_607_526, 4.245_373_366_122_749]) })
+    }
+}
+
+impl ConstrainedBenchmark for G08 {
+    fn ineq(&self, x: &[f64]) -> Vec<f64> {
+        vec![
+            x[0] * x[0] - x[1] + 1.0,
+            1.0 - x[0] + (x[1] - 4.0).powi(2),
+        ]
+    }
+}
+
+// A parabola as the only constraint, an equality
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct G11;
+
+impl Benchmark for G11 {
+    fn name(&self) -> String {
+        "G11".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        x[0] * x[0] + (x[1] - 1.0).powi(2)
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        Some(vec![2.0 * x[0], 2.0 * (x[1] - 1.0)])
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(-1.0, 1.0)
+    }
+
+    fn dim(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    // Also at x[0] = +1/sqrt(2)
+    fn optimum(&self, _dim: usize) -> Option<Optimum> {
+        Some(Optimum { value: 0.75, x: Some(vec![-0.5f64.sqrt(), 0.5]) })
+    }
+}
+
+impl ConstrainedBenchmark for G11 {
+    fn eq(&self, x: &[f64]) -> Vec<f64> {
+        vec![x[1] - x[0] * x[0]]
+    }
+}
+
+// A linear objective over two disconnected feasible regions
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct G24;
+
+impl Benchmark for G24 {
+    fn name(&self) -> String {
+        "G24".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        -x[0] - x[1]
+    }
+
+    fn grad(&self, _x: &[f64]) -> Option<Vec<f64>> {
+        Some(vec![-1.0, -1.0])
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        BoundsConf { lower: vec![0.0], upper: vec![3.0, 4.0] }
+    }
+
+    fn dim(&self) -> Option<usize> {
+        Some(2)
+    }
+
+    fn optimum(&self, _dim: usize) -> Option<Optimum> {
+        Some(Optimum { value: -5.508_013_271_595_36, x: Some(vec![2.329_520_197_477_623, 3.178_493_074_117_665]) })
+    }
+}
+
+impl ConstrainedBenchmark for G24 {
+    fn ineq(&self, x: &[f64]) -> Vec<f64> {
+        vec![
+            -2.0 * x[0].powi(4) + 8.0 * x[0].powi(3) - 8.0 * x[0] * x[0] + x[1] - 2.0,
+            -4.0 * x[0].powi(4) + 32.0 * x[0].powi(3) - 88.0 * x[0] * x[0] + 96.0 * x[0] + x[1] - 36.0,
+        ]
+    }
+}
+
+// Keane's bump function, the KBF of the examples, negated for minimization. Scalable, with the optimum
+// on the product constraint and only known in two dimensions
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct KBF;
+
+impl Benchmark for KBF {
+    fn name(&self) -> String {
+        "KBF".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        let sum_cos4: f64 = x.iter().map(|xi| xi.cos().powi(4)).sum();
+        let prod_cos2: f64 = x.iter().map(|xi| xi.cos().powi(2)).product();
+        let sum_ix2: f64 = x.iter().enumerate().map(|(i, xi)| (i + 1) as f64 * xi * xi).sum();
+        // The lower corner of the box, where the value tends to 0
+        if sum_ix2 == 0.0 {
+            return 0.0;
+        }
+        -(sum_cos4 - 2.0 * prod_cos2).abs() / sum_ix2.sqrt()
+    }
+
+    // A subgradient where the term inside the absolute value is 0, and 0 at the lower corner
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        let sum_ix2: f64 = x.iter().enumerate().map(|(i, xi)| (i + 1) as f64 * xi * xi).sum();
+        if sum_ix2 == 0.0 {
+            return Some(vec![0.0; x.len()]);
+        }
+        let inner = x.iter().map(|xi| xi.cos().powi(4)).sum::<f64>() - 2.0 * x.iter().map(|xi| xi.cos().powi(2)).product::<f64>();
+        let norm = sum_ix2.sqrt();
+        Some((0..x.len()).map(|k| {
+            let (sin, cos) = x[k].sin_cos();
+            let others: f64 = x.iter().enumerate().filter(|&(j, _)| j != k).map(|(_, xj)| xj.cos().powi(2)).product();
+            let d_inner = -4.0 * cos.powi(3) * sin + 4.0 * sin * cos * others;
+            -inner.signum() * d_inner / norm + inner.abs() * (k + 1) as f64 * x[k] / (norm * sum_ix2)
+        }).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 10.0)
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        (dim == 2).then(|| Optimum { value: -0.364_979_746_6, x: Some(vec![1.600_253_76, 0.468_675_907]) })
+    }
+}
+
+impl ConstrainedBenchmark for KBF {
+    fn ineq(&self, x: &[f64]) -> Vec<f64> {
+        vec![
+            0.75 - x.iter().product::<f64>(),
+            x.iter().sum::<f64>() - 7.5 * x.len() as f64,
+        ]
+    }
+}
@@ -0,0 +1,316 @@
+use std::f64::consts::{E, PI};
+
+use crate::benchmarks::{Benchmark, Optimum, uniform_bounds};
+use crate::utils::bounds::BoundsConf;
+
+// Scalable functions, defined in any dimension (Rosenbrock from two)
+
+fn at(value: f64, x: f64, dim: usize) -> Option<Optimum> {
+    Some(Optimum { value, x: Some(vec![x; dim]) })
+}
+
+// Sum of squares, unimodal and separable
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Sphere;
+
+impl Benchmark for Sphere {
+    fn name(&self) -> String {
+        "Sphere".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        x.iter().map(|xi| xi * xi).sum()
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        Some(x.iter().map(|xi| 2.0 * xi).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(-5.12, 5.12)
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        at(0.0, 0.0, dim)
+    }
+}
+
+// A narrow curved valley, easy to find and hard to follow
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rosenbrock;
+
+impl Benchmark for Rosenbrock {
+    fn name(&self) -> String {
+        "Rosenbrock".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        x.windows(2).map(|w| 100.0 * (w[1] - w[0] * w[0]).powi(2) + (1.0 - w[0]).powi(2)).sum()
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        let mut grad = vec![0.0; x.len()];
+        for i in 0..x.len().saturating_sub(1) {
+            let valley = x[i + 1] - x[i] * x[i];
+            grad[i] += -400.0 * x[i] * valley - 2.0 * (1.0 - x[i]);
+            grad[i + 1] += 200.0 * valley;
+        }
+        Some(grad)
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(-5.0, 10.0)
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        at(0.0, 1.0, dim)
+    }
+}
+
+// A sphere with a regular grid of local minima on top
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rastrigin;
+
+impl Benchmark for Rastrigin {
+    fn name(&self) -> String {
+        "Rastrigin".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        10.0 * x.len() as f64 + x.iter().map(|xi| xi * xi - 10.0 * (2.0 * PI * xi).cos()).sum::<f64>()
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        Some(x.iter().map(|xi| 2.0 * xi + 20.0 * PI * (2.0 * PI * xi).sin()).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(-5.12, 5.12)
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        at(0.0, 0.0, dim)
+    }
+}
+
+// Nearly flat outside a deep central hole, with small ripples everywhere
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Ackley;
+
+impl Benchmark for Ackley {
+    fn name(&self) -> String {
+        "Ackley".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        let n = x.len() as f64;
+        let r = (x.iter().map(|xi| xi * xi).sum::<f64>() / n).sqrt();
+        let c = x.iter().map(|xi| (2.0 * PI * xi).cos()).sum::<f64>() / n;
+        -20.0 * (-0.2 * r).exp() - c.exp() + 20.0 + E
+    }
+
+    // Zero at the origin, where the first term has a kink
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        let n = x.len() as f64;
+        let r = (x.iter().map(|xi| xi * xi).sum::<f64>() / n).sqrt();
+        let c = x.iter().map(|xi| (2.0 * PI * xi).cos()).sum::<f64>() / n;
+        Some(x.iter().map(|xi| {
+            let radial = if r > 0.0 { 4.0 * (-0.2 * r).exp() * xi / (n * r) } else { 0.0 };
+            radial + 2.0 * PI / n * c.exp() * (2.0 * PI * xi).sin()
+        }).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(-32.768, 32.768)
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        at(0.0, 0.0, dim)
+    }
+}
+
+// A wide bowl with many shallow minima from the product of cosines
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Griewank;
+
+impl Benchmark for Griewank {
+    fn name(&self) -> String {
+        "Griewank".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        let sum = x.iter().map(|xi| xi * xi).sum::<f64>() / 4000.0;
+        let product: f64 = x.iter().enumerate().map(|(i, xi)| (xi / ((i + 1) as f64).sqrt()).cos()).product();
+        1.0 + sum - product
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        let scale = |i: usize| ((i + 1) as f64).sqrt();
+        Some((0..x.len()).map(|i| {
+            let others: f64 = (0..x.len()).filter(|&j| j != i).map(|j| (x[j] / scale(j)).cos()).product();
+            x[i] / 2000.0 + others * (x[i] / scale(i)).sin() / scale(i)
+        }).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(-600.0, 600.0)
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        at(0.0, 0.0, dim)
+    }
+}
+
+// The best minimum sits near a corner, far from the second best
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Schwefel;
+
+const SCHWEFEL_SHIFT: f64 = 418.982_887_272_433_8;
+const SCHWEFEL_X: f64 = 420.968_746_359_982;
+
+impl Benchmark for Schwefel {
+    fn name(&self) -> String {
+        "Schwefel".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        SCHWEFEL_SHIFT * x.len() as f64 - x.iter().map(|xi| xi * xi.abs().sqrt().sin()).sum::<f64>()
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        Some(x.iter().map(|xi| {
+            let s = xi.abs().sqrt();
+            -(s.sin() + s * s.cos() / 2.0)
+        }).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(-500.0, 500.0)
+    }
+
+    // Zero to about 1e-12 per dimension
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        at(0.0, SCHWEFEL_X, dim)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Levy;
+
+impl Benchmark for Levy {
+    fn name(&self) -> String {
+        "Levy".to_string()
+    }
+
+    // NaN for an empty x, which OptProb reports as a non-finite objective
+    fn value(&self, x: &[f64]) -> f64 {
+        if x.is_empty() {
+            return f64::NAN;
+        }
+        let w: Vec<f64> = x.iter().map(|xi| 1.0 + (xi - 1.0) / 4.0).collect();
+        let last = w[w.len() - 1];
+        let middle: f64 = w[..w.len() - 1].iter().map(|wi| (wi - 1.0).powi(2) * (1.0 + 10.0 * (PI * wi + 1.0).sin().powi(2))).sum();
+        (PI * w[0]).sin().powi(2) + middle + (last - 1.0).powi(2) * (1.0 + (2.0 * PI * last).sin().powi(2))
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        if x.is_empty() {
+            return None;
+        }
+        let w: Vec<f64> = x.iter().map(|xi| 1.0 + (xi - 1.0) / 4.0).collect();
+        let n = w.len();
+        let mut grad = vec![0.0; n];
+        grad[0] += PI * (2.0 * PI * w[0]).sin();
+        for i in 0..n - 1 {
+            let d = w[i] - 1.0;
+            grad[i] += 2.0 * d * (1.0 + 10.0 * (PI * w[i] + 1.0).sin().powi(2)) + d * d * 10.0 * PI * (2.0 * (PI * w[i] + 1.0)).sin();
+        }
+        let d = w[n - 1] - 1.0;
+        grad[n - 1] += 2.0 * d * (1.0 + (2.0 * PI * w[n - 1]).sin().powi(2)) + d * d * 2.0 * PI * (4.0 * PI * w[n - 1]).sin();
+        Some(grad.iter().map(|g| g / 4.0).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(-10.0, 10.0)
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        at(0.0, 1.0, dim)
+    }
+}
+
+// Steep ridges that get narrower with m, the optimum is only known in some dimensions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Michalewicz {
+    pub m: i32,
+}
+
+impl Default for Michalewicz {
+    fn default() -> Self {
+        Self { m: 10 }
+    }
+}
+
+impl Benchmark for Michalewicz {
+    fn name(&self) -> String {
+        "Michalewicz".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        -x.iter().enumerate().map(|(i, xi)| xi.sin() * ((i + 1) as f64 * xi * xi / PI).sin().powi(2 * self.m)).sum::<f64>()
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        Some(x.iter().enumerate().map(|(i, xi)| {
+            let k = (i + 1) as f64;
+            let u = k * xi * xi / PI;
+            let ridge = u.sin().powi(2 * self.m - 1);
+            -(xi.cos() * ridge * u.sin() + xi.sin() * 2.0 * self.m as f64 * ridge * u.cos() * 2.0 * k * xi / PI)
+        }).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, PI)
+    }
+
+    // For the usual m = 10
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        if self.m != 10 {
+            return None;
+        }
+        match dim {
+            2 => Some(Optimum { value: -1.801_303_410_098_553_8, x: Some(vec![2.202_905_520_569_12, PI / 2.0]) }),
+            5 => Some(Optimum { value: -4.687_658, x: None }),
+            10 => Some(Optimum { value: -9.660_15, x: None }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StyblinskiTang;
+
+const STYBLINSKI_TANG_X: f64 = -2.903_534_018_185_96;
+
+impl Benchmark for StyblinskiTang {
+    fn name(&self) -> String {
+        "StyblinskiTang".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        x.iter().map(|xi| xi.powi(4) - 16.0 * xi * xi + 5.0 * xi).sum::<f64>() / 2.0
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        Some(x.iter().map(|xi| 2.0 * xi.powi(3) - 16.0 * xi + 2.5).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(-5.0, 5.0)
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        let value = (STYBLINSKI_TANG_X.powi(4) - 16.0 * STYBLINSKI_TANG_X.powi(2) + 5.0 * STYBLINSKI_TANG_X) / 2.0;
+        at(value * dim as f64, STYBLINSKI_TANG_X, dim)
+    }
+}
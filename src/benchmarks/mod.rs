@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    DVector,
+    OVector,
+    U1
+};
+
+pub mod functions;
+pub mod transforms;
+pub mod constrained;
+pub mod multi_objective;
+
+pub use self::functions::{Sphere, Rosenbrock, Rastrigin, Ackley, Griewank, Schwefel, Levy, Michalewicz, StyblinskiTang};
+pub use self::transforms::{Shifted, Rotated};
+pub use self::constrained::{G01, G06, G08, G11, G24, KBF};
+pub use self::multi_objective::{ZDT1, ZDT2, ZDT3, ZDT4, ZDT6, DTLZ1, DTLZ2, DTLZ3, DTLZ4};
+
+use crate::utils::opt_prob::{ObjectiveFunction, ConstraintFunction, MultiObjectiveFunction};
+use crate::utils::bounds::BoundsConf;
+
+// Standard test problems with known optima, for checking a config before trusting it on a real problem.
+// They are written for minimization, as in the literature, so run them with "objective_sense": "Minimize".
+// Every benchmark is an ObjectiveFunction (or MultiObjectiveFunction) reporting its own bounds
+
+// Global minimum, x is None where only the value is known
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Optimum {
+    pub value: f64,
+    pub x: Option<Vec<f64>>,
+}
+
+pub trait Benchmark: Clone + Send + Sync + 'static {
+    fn name(&self) -> String;
+
+    fn value(&self, x: &[f64]) -> f64;
+
+    // None where the function isn't differentiable
+    fn grad(&self, _x: &[f64]) -> Option<Vec<f64>> {
+        None
+    }
+
+    fn bounds(&self, dim: usize) -> BoundsConf;
+
+    // Some for problems only defined in one dimension
+    fn dim(&self) -> Option<usize> {
+        None
+    }
+
+    // None where the optimum at dim isn't known
+    fn optimum(&self, dim: usize) -> Option<Optimum>;
+}
+
+// g_i(x) <= 0 and h_j(x) = 0 on top of a Benchmark, passed to NonConvexOpt::new_with_constraints
+pub trait ConstrainedBenchmark: Benchmark {
+    fn ineq(&self, _x: &[f64]) -> Vec<f64> {
+        Vec::new()
+    }
+
+    fn eq(&self, _x: &[f64]) -> Vec<f64> {
+        Vec::new()
+    }
+
+    fn eq_tol(&self) -> f64 {
+        1e-4
+    }
+}
+
+pub trait MultiObjectiveBenchmark: Clone + Send + Sync + 'static {
+    fn name(&self) -> String;
+
+    fn values(&self, x: &[f64]) -> Vec<f64>;
+
+    fn num_objectives(&self) -> usize;
+
+    fn bounds(&self, dim: usize) -> BoundsConf;
+
+    // At least points objective vectors spread over the true Pareto front, for the indicators
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>>;
+}
+
+// A single value fills every dimension. Per-dimension values of the wrong length give a vector of their
+// own length, which NonConvexOpt reports as invalid bounds, or None when the dimension is fixed by D
+fn side<D>(values: &[f64], dim: usize) -> Option<OVector<f64, D>>
+where
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    if values.len() == 1 {
+        return Some(OVector::<f64, D>::from_element_generic(D::from_usize(dim), U1, values[0]));
+    }
+    D::try_to_usize().is_none_or(|n| n == values.len())
+        .then(|| OVector::<f64, D>::from_column_slice_generic(D::from_usize(values.len()), U1, values))
+}
+
+impl<B, D> ObjectiveFunction<f64, D> for B
+where
+    B: Benchmark,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> f64 {
+        self.value(x.as_slice())
+    }
+
+    fn gradient(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
+        self.grad(x.as_slice()).and_then(|g| side(&g, x.len()))
+    }
+
+    fn x_lower_bound(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
+        side(&self.bounds(x.len()).lower, x.len())
+    }
+
+    fn x_upper_bound(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
+        side(&self.bounds(x.len()).upper, x.len())
+    }
+}
+
+impl<B, D> ConstraintFunction<f64, D> for B
+where
+    B: ConstrainedBenchmark,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(self.ineq(x.as_slice()))
+    }
+
+    fn h(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(self.eq(x.as_slice()))
+    }
+
+    fn eq_tol(&self) -> f64 {
+        ConstrainedBenchmark::eq_tol(self)
+    }
+}
+
+impl<B, D> MultiObjectiveFunction<f64, D> for B
+where
+    B: MultiObjectiveBenchmark,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(self.values(x.as_slice()))
+    }
+
+    fn num_objectives(&self) -> usize {
+        MultiObjectiveBenchmark::num_objectives(self)
+    }
+
+    fn x_lower_bound(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
+        side(&self.bounds(x.len()).lower, x.len())
+    }
+
+    fn x_upper_bound(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
+        side(&self.bounds(x.len()).upper, x.len())
+    }
+}
+
+// Same value in every dimension
+fn uniform_bounds(lower: f64, upper: f64) -> BoundsConf {
+    BoundsConf { lower: vec![lower], upper: vec![upper] }
+}
@@ -0,0 +1,328 @@
+use std::f64::consts::PI;
+
+use crate::benchmarks::{MultiObjectiveBenchmark, uniform_bounds};
+use crate::utils::bounds::BoundsConf;
+use crate::utils::pareto::{num_weights, uniform_weights};
+
+// ZDT problems with two objectives and DTLZ problems with any number of them, every objective minimized.
+// Scalable in the number of variables, usually 30 for ZDT1-3, 10 for ZDT4 and ZDT6, and the number of
+// objectives plus 4 (DTLZ1) or 9 for DTLZ
+
+fn zdt_g(x: &[f64]) -> f64 {
+    1.0 + 9.0 * x[1..].iter().sum::<f64>() / (x.len() - 1).max(1) as f64
+}
+
+// f2 = 1 - shape(f1) for points evenly spaced in f1 between start and 1
+fn zdt_front(points: usize, start: f64, shape: impl Fn(f64) -> f64) -> Vec<Vec<f64>> {
+    (0..points.max(2)).map(|k| {
+        let f1 = start + (1.0 - start) * k as f64 / (points.max(2) - 1) as f64;
+        vec![f1, 1.0 - shape(f1)]
+    }).collect()
+}
+
+// Convex front
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ZDT1;
+
+impl MultiObjectiveBenchmark for ZDT1 {
+    fn name(&self) -> String {
+        "ZDT1".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        let g = zdt_g(x);
+        vec![x[0], g * (1.0 - (x[0] / g).sqrt())]
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 1.0)
+    }
+
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        zdt_front(points, 0.0, f64::sqrt)
+    }
+}
+
+// Concave front
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ZDT2;
+
+impl MultiObjectiveBenchmark for ZDT2 {
+    fn name(&self) -> String {
+        "ZDT2".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        let g = zdt_g(x);
+        vec![x[0], g * (1.0 - (x[0] / g).powi(2))]
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 1.0)
+    }
+
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        zdt_front(points, 0.0, |f1| f1 * f1)
+    }
+}
+
+// Five disconnected pieces of front
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ZDT3;
+
+impl MultiObjectiveBenchmark for ZDT3 {
+    fn name(&self) -> String {
+        "ZDT3".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        let g = zdt_g(x);
+        let ratio = x[0] / g;
+        vec![x[0], g * (1.0 - ratio.sqrt() - ratio * (10.0 * PI * x[0]).sin())]
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 1.0)
+    }
+
+    // The non-dominated part of g = 1, sampled finely enough to leave at least points of it
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        let curve = zdt_front(10 * points.max(2), 0.0, |f1| f1.sqrt() + f1 * (10.0 * PI * f1).sin());
+        let mut best = f64::INFINITY;
+        curve.into_iter().filter(|p| {
+            let kept = p[1] < best;
+            best = best.min(p[1]);
+            kept
+        }).collect()
+    }
+}
+
+// The front of ZDT1 behind many local fronts
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ZDT4;
+
+impl MultiObjectiveBenchmark for ZDT4 {
+    fn name(&self) -> String {
+        "ZDT4".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        let g = 1.0 + 10.0 * (x.len() - 1) as f64 + x[1..].iter().map(|xi| xi * xi - 10.0 * (4.0 * PI * xi).cos()).sum::<f64>();
+        vec![x[0], g * (1.0 - (x[0] / g).sqrt())]
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self, dim: usize) -> BoundsConf {
+        let side = |first: f64, rest: f64| (0..dim).map(|i| if i == 0 { first } else { rest }).collect();
+        BoundsConf { lower: side(0.0, -5.0), upper: side(1.0, 5.0) }
+    }
+
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        zdt_front(points, 0.0, f64::sqrt)
+    }
+}
+
+// A concave front, with solutions sparse near it and denser away from it
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ZDT6;
+
+const ZDT6_MIN_F1: f64 = 0.280_775_319_681_486_4;
+
+impl MultiObjectiveBenchmark for ZDT6 {
+    fn name(&self) -> String {
+        "ZDT6".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        let f1 = 1.0 - (-4.0 * x[0]).exp() * (6.0 * PI * x[0]).sin().powi(6);
+        let g = 1.0 + 9.0 * (x[1..].iter().sum::<f64>() / (x.len() - 1).max(1) as f64).powf(0.25);
+        vec![f1, g * (1.0 - (f1 / g).powi(2))]
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 1.0)
+    }
+
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        zdt_front(points, ZDT6_MIN_F1, |f1| f1 * f1)
+    }
+}
+
+// The last variables, k = dim - m + 1 of them, set the distance to the front.
+// None with fewer than m - 1 variables, where the objectives are left NaN for OptProb to report
+fn dtlz_distance(x: &[f64], m: usize) -> Option<&[f64]> {
+    x.get(m.checked_sub(1)?..)
+}
+
+fn dtlz1_g(xm: &[f64]) -> f64 {
+    100.0 * (xm.len() as f64 + xm.iter().map(|xi| (xi - 0.5).powi(2) - (20.0 * PI * (xi - 0.5)).cos()).sum::<f64>())
+}
+
+fn dtlz2_g(xm: &[f64]) -> f64 {
+    xm.iter().map(|xi| (xi - 0.5).powi(2)).sum()
+}
+
+// Objectives on the unit sphere scaled by 1 + g, the position variables raised to alpha
+fn spherical(x: &[f64], m: usize, g: f64, alpha: f64) -> Vec<f64> {
+    let angle = |xi: f64| xi.powf(alpha) * PI / 2.0;
+    (0..m).map(|i| {
+        let cosines: f64 = x[..m - 1 - i].iter().map(|&xi| angle(xi).cos()).product();
+        let sine = if i > 0 { angle(x[m - 1 - i]).sin() } else { 1.0 };
+        (1.0 + g) * cosines * sine
+    }).collect()
+}
+
+// Das-Dennis points, at least points of them, mapped onto the front
+fn dtlz_front(m: usize, points: usize, onto: impl Fn(Vec<f64>) -> Vec<f64>) -> Vec<Vec<f64>> {
+    let divisions = (1..).find(|&h| num_weights(m, h) >= points).unwrap();
+    uniform_weights(m, divisions).into_iter().map(onto).collect()
+}
+
+fn unit_sphere(w: Vec<f64>) -> Vec<f64> {
+    let norm = w.iter().map(|wi| wi * wi).sum::<f64>().sqrt();
+    w.iter().map(|wi| wi / norm).collect()
+}
+
+// Linear front, sum of the objectives 0.5, behind 11^k - 1 local fronts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DTLZ1 {
+    pub num_objectives: usize,
+}
+
+impl MultiObjectiveBenchmark for DTLZ1 {
+    fn name(&self) -> String {
+        "DTLZ1".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        let m = self.num_objectives;
+        let Some(xm) = dtlz_distance(x, m) else { return vec![f64::NAN; m] };
+        let g = dtlz1_g(xm);
+        (0..m).map(|i| {
+            let product: f64 = x[..m - 1 - i].iter().product();
+            let last = if i > 0 { 1.0 - x[m - 1 - i] } else { 1.0 };
+            0.5 * product * last * (1.0 + g)
+        }).collect()
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 1.0)
+    }
+
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        dtlz_front(self.num_objectives, points, |w| w.iter().map(|wi| wi / 2.0).collect())
+    }
+}
+
+// Front on the positive part of the unit sphere
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DTLZ2 {
+    pub num_objectives: usize,
+}
+
+impl MultiObjectiveBenchmark for DTLZ2 {
+    fn name(&self) -> String {
+        "DTLZ2".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        let m = self.num_objectives;
+        let Some(xm) = dtlz_distance(x, m) else { return vec![f64::NAN; m] };
+        spherical(x, m, dtlz2_g(xm), 1.0)
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 1.0)
+    }
+
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        dtlz_front(self.num_objectives, points, unit_sphere)
+    }
+}
+
+// The front of DTLZ2 behind the local fronts of DTLZ1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DTLZ3 {
+    pub num_objectives: usize,
+}
+
+impl MultiObjectiveBenchmark for DTLZ3 {
+    fn name(&self) -> String {
+        "DTLZ3".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        let m = self.num_objectives;
+        let Some(xm) = dtlz_distance(x, m) else { return vec![f64::NAN; m] };
+        spherical(x, m, dtlz1_g(xm), 1.0)
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 1.0)
+    }
+
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        dtlz_front(self.num_objectives, points, unit_sphere)
+    }
+}
+
+// The front of DTLZ2, with solutions crowded towards its edges
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DTLZ4 {
+    pub num_objectives: usize,
+}
+
+impl MultiObjectiveBenchmark for DTLZ4 {
+    fn name(&self) -> String {
+        "DTLZ4".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        let m = self.num_objectives;
+        let Some(xm) = dtlz_distance(x, m) else { return vec![f64::NAN; m] };
+        spherical(x, m, dtlz2_g(xm), 100.0)
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        uniform_bounds(0.0, 1.0)
+    }
+
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        dtlz_front(self.num_objectives, points, unit_sphere)
+    }
+}
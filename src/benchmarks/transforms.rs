@@ -0,0 +1,127 @@
+use rand::Rng;
+use rand_distr::StandardNormal;
+use nalgebra::{DMatrix, DVector};
+
+use crate::benchmarks::{Benchmark, Optimum};
+use crate::utils::bounds::BoundsConf;
+use crate::utils::rng::OptRng;
+
+// Moved and turned copies of a benchmark, so an algorithm can't profit from an optimum at the centre or
+// on the diagonal, or from separable coordinates. The bounds stay those of the original, so the moved
+// optimum must stay inside them, as Shifted::random makes sure of
+
+// f(x - shift), minimized at the original optimum plus shift
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shifted<B> {
+    pub inner: B,
+    pub shift: Vec<f64>,
+}
+
+impl<B: Benchmark> Shifted<B> {
+    pub fn new(inner: B, shift: Vec<f64>) -> Self {
+        Self { inner, shift }
+    }
+
+    // Moves the optimum to a uniform point in the middle 80% of the box in each dimension
+    pub fn random(inner: B, dim: usize, rng: &mut OptRng) -> Self {
+        let bounds = inner.bounds(dim);
+        let side = |values: &[f64], i: usize| if values.len() == 1 { values[0] } else { values[i] };
+        let optimum = inner.optimum(dim).and_then(|o| o.x);
+        let shift = (0..dim).map(|i| {
+            let (lo, hi) = (side(&bounds.lower, i), side(&bounds.upper, i));
+            let target = lo + (hi - lo) * (0.1 + 0.8 * rng.random::<f64>());
+            target - optimum.as_ref().map_or((lo + hi) / 2.0, |x| x[i])
+        }).collect();
+        Self { inner, shift }
+    }
+
+    fn unshift(&self, x: &[f64]) -> Vec<f64> {
+        x.iter().zip(self.shift.iter()).map(|(xi, oi)| xi - oi).collect()
+    }
+}
+
+impl<B: Benchmark> Benchmark for Shifted<B> {
+    fn name(&self) -> String {
+        format!("Shifted{}", self.inner.name())
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        self.inner.value(&self.unshift(x))
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        self.inner.grad(&self.unshift(x))
+    }
+
+    fn bounds(&self, dim: usize) -> BoundsConf {
+        self.inner.bounds(dim)
+    }
+
+    fn dim(&self) -> Option<usize> {
+        Some(self.shift.len())
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        self.inner.optimum(dim).map(|o| Optimum {
+            value: o.value,
+            x: o.x.map(|x| x.iter().zip(self.shift.iter()).map(|(xi, oi)| xi + oi).collect()),
+        })
+    }
+}
+
+// f(R x) for an orthogonal R, minimized at R^T times the original optimum
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rotated<B> {
+    pub inner: B,
+    pub rotation: DMatrix<f64>,
+}
+
+impl<B: Benchmark> Rotated<B> {
+    pub fn new(inner: B, rotation: DMatrix<f64>) -> Self {
+        Self { inner, rotation }
+    }
+
+    // A uniformly random rotation from the QR decomposition of a Gaussian matrix. Shift the result as
+    // well when the optimum isn't at the origin, the rotated one can leave the box
+    pub fn random(inner: B, dim: usize, rng: &mut OptRng) -> Self {
+        let gaussian = DMatrix::<f64>::from_fn(dim, dim, |_, _| rng.sample(StandardNormal));
+        let qr = gaussian.qr();
+        let signs = qr.r().diagonal().map(|r| if r < 0.0 { -1.0 } else { 1.0 });
+        let rotation = qr.q() * DMatrix::from_diagonal(&signs);
+        Self { inner, rotation }
+    }
+
+    fn rotate(&self, x: &[f64]) -> Vec<f64> {
+        (&self.rotation * DVector::from_column_slice(x)).as_slice().to_vec()
+    }
+}
+
+impl<B: Benchmark> Benchmark for Rotated<B> {
+    fn name(&self) -> String {
+        format!("Rotated{}", self.inner.name())
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        self.inner.value(&self.rotate(x))
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        let grad = self.inner.grad(&self.rotate(x))?;
+        Some((self.rotation.transpose() * DVector::from_vec(grad)).as_slice().to_vec())
+    }
+
+    fn bounds(&self, dim: usize) -> BoundsConf {
+        self.inner.bounds(dim)
+    }
+
+    fn dim(&self) -> Option<usize> {
+        Some(self.rotation.nrows())
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        self.inner.optimum(dim).map(|o| Optimum {
+            value: o.value,
+            x: o.x.map(|x| (self.rotation.transpose() * DVector::from_vec(x)).as_slice().to_vec()),
+        })
+    }
+}
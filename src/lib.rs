@@ -1,3 +1,4 @@
+use std::time::Instant;
 use nalgebra::{
     allocator::Allocator, 
     DefaultAllocator, 
@@ -9,48 +10,34 @@ use nalgebra::{
 
 pub mod algorithms;
 pub mod utils;
-use crate::utils::config::{Config, AlgConf, OptConf};
-
-use crate::algorithms::{
-    continous_ga::cga::CGA,
-    parallel_tempering::pt::PT,
-    adam::adam::Adam,
-    sg_ascent::sga::SGAscent,
-    tabu_search::tabu::TabuSearch,
-    grasp::grasp::GRASP,
-    nelder_mead::nm::NelderMead,
-    limited_memory_bfgs::lbfgs::LBFGS,
-    multi_swarm::mspo::MSPO,
-    simulated_annealing::sa::SimulatedAnnealing,
-    differential_evolution::de::DE,
-    cma_es::cma_es::CMAES,
-};
+pub mod benchmarks;
+use crate::utils::config::{Config, OptConf, GradientConf};
 
 use crate::utils::opt_prob::{
     FloatNumber as FloatNum, 
     OptProb, 
     ObjectiveFunction, 
+    MultiObjectiveFunction,
+    SumOfObjectives,
     BooleanConstraintFunction,
+    BooleanConstraint,
+    ConstraintFunction,
     OptimizationAlgorithm,
+    EvalCounts,
+    Diagnostics,
     State
 };
-
-pub struct Result<T, N, D> 
-where
-    T: FloatNum,
-    D: Dim,
-    N: Dim,
-    DefaultAllocator: Allocator<D>
-                    + Allocator<N, D>
-                    + Allocator<N>
-{
-    pub best_x: OVector<T, D>,
-    pub best_f: T,
-    pub final_pop: OMatrix<T, N, D>,
-    pub final_fitness: OVector<T, N>,
-    pub final_constraints: OVector<bool, N>,
-    pub convergence_iter: usize,
-}
+use crate::utils::observer::{Observer, ObserverAction};
+use crate::utils::indicators::Indicator;
+use crate::utils::checkpoint::Checkpoint;
+use crate::utils::stopping::{StoppingCriterion, StopContext, TerminationReason};
+use crate::utils::result::{OptimizationResult, History, HistoryEntry};
+use crate::utils::error::OptError;
+use crate::utils::validation::validate;
+use crate::utils::finite_diff::{FiniteDifference, ComplexStep};
+use crate::utils::bounds::{Bounds, BoundsConf};
+use crate::utils::variables::Variables;
+use crate::utils::rng::new_rng;
 
 pub struct NonConvexOpt<T, N, D> 
 where 
@@ -74,7 +61,15 @@ where
 {
     pub alg: Box<dyn OptimizationAlgorithm<T, N, D>>,
     pub conf: OptConf,
+    opt_prob: OptProb<T, D>, // Shares evaluation counters with the algorithm's copy
     pub converged: bool,
+    pub stopped: bool, // Early termination requested by an observer
+    pub stop_reason: Option<String>, // Name of the stopping criterion that fired
+    observers: Vec<Box<dyn Observer<T, N, D>>>,
+    stopping: Vec<Box<dyn StoppingCriterion<T, N, D>>>,
+    indicators: Vec<Indicator>,
+    started: Instant,
+    history: Option<History>,
 }
 
 impl<T, N, D> NonConvexOpt<T, N, D> 
@@ -102,63 +97,418 @@ where
         init_pop: OMatrix<T, N, D>, 
         obj_f: F, 
         constr_f: Option<G>,
-    ) -> Self {
-        let opt_prob = OptProb::new(
-            Box::new(obj_f), 
-            match constr_f {
-                Some(constr_f) => Some(Box::new(constr_f)),
-                None => None,
+    ) -> Result<Self, OptError> {
+        Self::new_with_constraints(conf, init_pop, obj_f, constr_f.map(Self::boolean_constraint))
+    }
+
+    // Like new, with real-valued constraints whose violation the algorithms can see
+    pub fn new_with_constraints<F: ObjectiveFunction<T, D> + 'static, G: ConstraintFunction<T, D> + 'static>(
+        conf: Config, 
+        init_pop: OMatrix<T, N, D>, 
+        obj_f: F, 
+        constr_f: Option<G>,
+    ) -> Result<Self, OptError> {
+        let opt_prob = Self::build_problem(&conf, obj_f, constr_f);
+        Self::start(conf, init_pop, opt_prob)
+    }
+
+    // For several objectives, NSGA2 returns their Pareto front while other algorithms maximize or minimize their sum
+    pub fn new_multi_objective<M: MultiObjectiveFunction<T, D> + 'static, G: ConstraintFunction<T, D> + 'static>(
+        conf: Config, 
+        init_pop: OMatrix<T, N, D>, 
+        obj_f: M, 
+        constr_f: Option<G>,
+    ) -> Result<Self, OptError> {
+        let opt_prob = Self::build_multi_problem(&conf, obj_f, constr_f);
+        Self::start(conf, init_pop, opt_prob)
+    }
+
+    // Like new, with the initial population drawn by the sampler in opt_conf.sampling
+    pub fn new_sampled<F: ObjectiveFunction<T, D> + 'static, G: BooleanConstraintFunction<T, D> + 'static>(
+        conf: Config, 
+        obj_f: F, 
+        constr_f: Option<G>,
+    ) -> Result<Self, OptError> {
+        Self::new_sampled_with_constraints(conf, obj_f, constr_f.map(Self::boolean_constraint))
+    }
+
+    pub fn new_sampled_with_constraints<F: ObjectiveFunction<T, D> + 'static, G: ConstraintFunction<T, D> + 'static>(
+        conf: Config, 
+        obj_f: F, 
+        constr_f: Option<G>,
+    ) -> Result<Self, OptError> {
+        let opt_prob = Self::build_problem(&conf, obj_f, constr_f);
+        Self::start_sampled(conf, opt_prob)
+    }
+
+    pub fn new_sampled_multi_objective<M: MultiObjectiveFunction<T, D> + 'static, G: ConstraintFunction<T, D> + 'static>(
+        conf: Config, 
+        obj_f: M, 
+        constr_f: Option<G>,
+    ) -> Result<Self, OptError> {
+        let opt_prob = Self::build_multi_problem(&conf, obj_f, constr_f);
+        Self::start_sampled(conf, opt_prob)
+    }
+
+    fn start_sampled(conf: Config, opt_prob: OptProb<T, D>) -> Result<Self, OptError> {
+        let invalid = |reason: String| OptError::InvalidConfig { alg: "Sampling".to_string(), reason };
+        let sampling = conf.opt_conf.sampling.clone().ok_or_else(|| invalid("new_sampled needs opt_conf.sampling".to_string()))?;
+        if conf.alg_conf.is_permutation() {
+            return Err(invalid("permutation problems start from orders, pass them to new".to_string()));
+        }
+        let dim = match (D::try_to_usize(), sampling.dim) {
+            (Some(fixed), Some(dim)) if fixed != dim => return Err(invalid(format!("dim {} doesn't match the problem's {} dimensions", dim, fixed))),
+            (Some(fixed), _) => fixed,
+            (None, Some(dim)) => dim,
+            (None, None) => return Err(invalid("dim is needed for a dynamically sized problem".to_string())),
+        };
+
+        // The bounds and variable ranges are known before any point is
+        let x0 = OVector::<T, D>::zeros_generic(D::from_usize(dim), U1);
+        let opt_prob = Self::configure(&conf, &x0, opt_prob)?;
+        let init_pop = sampling.sampler.population(sampling.pop_size, &opt_prob, &mut new_rng(conf.opt_conf.seed))?;
+        Self::start(conf, init_pop, opt_prob)
+    }
+
+    // Linear constraints, bounds and variable types, the dimension taken from x0
+    fn configure(conf: &Config, x0: &OVector<T, D>, mut opt_prob: OptProb<T, D>) -> Result<OptProb<T, D>, OptError> {
+        opt_prob.linear = conf.opt_conf.linear_constraints.as_ref().map(|c| c.to_linear()).transpose()?;
+        opt_prob.bounds = Bounds::resolve(conf.opt_conf.bounds.as_ref(), opt_prob.objective.as_ref(), x0)?;
+        if let Some(types) = &conf.opt_conf.variables {
+            opt_prob = opt_prob.with_variables(Variables::from_conf(types, x0.len())?)?;
+        }
+        // Orders hold the indices 0..n, whatever the configured bounds
+        if conf.alg_conf.is_permutation() && !x0.is_empty() {
+            let last = T::from_usize(x0.len() - 1).unwrap();
+            opt_prob = opt_prob.with_bounds(Bounds::uniform(x0.len(), T::zero(), last)?);
+        }
+        Ok(opt_prob)
+    }
+
+    fn start(conf: Config, init_pop: OMatrix<T, N, D>, mut opt_prob: OptProb<T, D>) -> Result<Self, OptError> {
+        if init_pop.nrows() > 0 {
+            opt_prob = Self::configure(&conf, &init_pop.row(0).transpose(), opt_prob)?;
+        } else {
+            opt_prob.linear = conf.opt_conf.linear_constraints.as_ref().map(|c| c.to_linear()).transpose()?;
+        }
+        validate(&conf, &init_pop, &opt_prob)?;
+
+        // Start from the closest feasible points when repairs project onto the polytope
+        let mut init_pop = init_pop;
+        if let Some(linear) = opt_prob.linear.as_ref().filter(|linear| linear.project) {
+            for i in 0..init_pop.nrows() {
+                let x = linear.project(&init_pop.row(i).transpose(), opt_prob.bounds.as_ref());
+                init_pop.set_row(i, &x.transpose());
             }
-        );
-
-        let alg: Box<dyn OptimizationAlgorithm<T, N, D>> = match conf.alg_conf {
-            AlgConf::CGA(cga_conf) => Box::new(CGA::new(cga_conf, init_pop, opt_prob, conf.opt_conf.max_iter)),
-            AlgConf::PT(pt_conf) => Box::new(PT::new(pt_conf, init_pop, opt_prob, conf.opt_conf.max_iter)),
-            AlgConf::TS(ts_conf) => Box::new(TabuSearch::new(ts_conf, init_pop.row(0).into_owned(), opt_prob)),
-            AlgConf::Adam(adam_conf) => Box::new(Adam::new(adam_conf, init_pop.row(0).into_owned(), opt_prob)),
-            AlgConf::GRASP(grasp_conf) => Box::new(GRASP::new(grasp_conf, init_pop.row(0).into_owned(), opt_prob)),
-            AlgConf::SGA(sga_conf) => Box::new(SGAscent::new(sga_conf, init_pop.row(0).into_owned(), opt_prob)),
-            AlgConf::NM(nm_conf) => Box::new(NelderMead::new(nm_conf, init_pop, opt_prob)),
-            AlgConf::LBFGS(lbfgs_conf) => Box::new(LBFGS::new(lbfgs_conf, init_pop.row(0).into_owned(), opt_prob)),
-            AlgConf::MSPO(mspo_conf) => Box::new(MSPO::new(mspo_conf, init_pop, opt_prob)),
-            AlgConf::SA(sa_conf) => Box::new(SimulatedAnnealing::new(sa_conf, init_pop.row(0).into_owned(), opt_prob)),
-            AlgConf::DE(de_conf) => Box::new(DE::new(de_conf, init_pop, opt_prob)),
-            AlgConf::CMAES(cma_es_conf) => Box::new(CMAES::new(cma_es_conf, init_pop, opt_prob)),
+        }
+        // And from integer and discrete values, within their range
+        if let (Some(variables), Some(bounds)) = (&opt_prob.variables, &opt_prob.bounds) {
+            for i in 0..init_pop.nrows() {
+                let x = variables.snap(&bounds.clamp(&init_pop.row(i).transpose()));
+                init_pop.set_row(i, &x.transpose());
+            }
+        }
+        let problem = opt_prob.clone();
+
+        let alg = algorithms::build(conf.alg_conf, init_pop, opt_prob, conf.opt_conf.max_iter, conf.opt_conf.seed);
+
+        let stopping = vec![conf.opt_conf.stopping.build(&conf.opt_conf)];
+        let history = conf.opt_conf.record_history.then(History::default);
+        let mut opt = Self {
+            alg,
+            conf: conf.opt_conf,
+            opt_prob: problem,
+            converged: false,
+            stopped: false,
+            stop_reason: None,
+            observers: Vec::new(),
+            stopping,
+            indicators: Vec::new(),
+            started: Instant::now(),
+            history,
         };
+        // Scoring the initial population can already hit a NaN
+        if let Some(err) = opt.opt_prob.take_error() {
+            return Err(err);
+        }
+
+        opt.record_history();
+        Ok(opt)
+    }
+
+    // Continue a run from a checkpoint, the config must be the one the checkpoint was taken with
+    pub fn resume<F: ObjectiveFunction<T, D> + 'static, G: BooleanConstraintFunction<T, D> + 'static>(
+        conf: Config, 
+        checkpoint: Checkpoint, 
+        obj_f: F, 
+        constr_f: Option<G>,
+    ) -> Result<Self, OptError> {
+        Self::resume_with_constraints(conf, checkpoint, obj_f, constr_f.map(Self::boolean_constraint))
+    }
+
+    pub fn resume_with_constraints<F: ObjectiveFunction<T, D> + 'static, G: ConstraintFunction<T, D> + 'static>(
+        conf: Config, 
+        checkpoint: Checkpoint, 
+        obj_f: F, 
+        constr_f: Option<G>,
+    ) -> Result<Self, OptError> {
+        let opt_prob = Self::build_problem(&conf, obj_f, constr_f);
+        Self::restart(conf, checkpoint, opt_prob)
+    }
 
-        Self { alg, conf: conf.opt_conf, converged: false }
+    pub fn resume_multi_objective<M: MultiObjectiveFunction<T, D> + 'static, G: ConstraintFunction<T, D> + 'static>(
+        conf: Config, 
+        checkpoint: Checkpoint, 
+        obj_f: M, 
+        constr_f: Option<G>,
+    ) -> Result<Self, OptError> {
+        let opt_prob = Self::build_multi_problem(&conf, obj_f, constr_f);
+        Self::restart(conf, checkpoint, opt_prob)
     }
 
-    fn check_convergence(&self, current_best: T, previous_best: T) -> bool {
-        let converged = (-current_best).exp() <= T::from_f64(self.conf.atol).unwrap()
-            || ((current_best - previous_best).abs() <= T::from_f64(self.conf.rtol).unwrap() && self.alg.state().iter > (self.conf.max_iter as f64 * self.conf.rtol_max_iter_fraction).floor() as usize);
-        if converged {
-            println!("Converged in {} iterations", self.alg.state().iter);
+    fn restart(conf: Config, checkpoint: Checkpoint, mut opt_prob: OptProb<T, D>) -> Result<Self, OptError> {
+        opt_prob.restore_evaluations(checkpoint.evals);
+        opt_prob.linear = conf.opt_conf.linear_constraints.as_ref().map(|c| c.to_linear()).transpose()?;
+        if let Some(bounds) = &checkpoint.bounds {
+            let bounds = bounds.to_bounds(bounds.lower.len())?;
+            opt_prob = opt_prob.with_bounds(bounds);
         }
+        // The checkpointed bounds already have the variables' ranges
+        if let Some(types) = &conf.opt_conf.variables {
+            let dim = checkpoint.bounds.as_ref().map_or(types.len(), |bounds| bounds.lower.len());
+            opt_prob = Variables::from_conf(types, dim)
+                .and_then(|variables| opt_prob.with_variables(variables))?;
+        }
+        let problem = opt_prob.clone();
+        let max_iter = conf.opt_conf.max_iter;
+
+        let alg = algorithms::restore(conf.alg_conf, checkpoint.alg, opt_prob, max_iter)?;
+
+        let stopping = vec![conf.opt_conf.stopping.build(&conf.opt_conf)];
+        let history = conf.opt_conf.record_history.then(|| checkpoint.history.unwrap_or_default());
         
-        converged
+        // Backdate the start so elapsed time (and wall-clock limits) carry over
+        let started = Instant::now().checked_sub(checkpoint.elapsed).unwrap_or_else(Instant::now);
+        Ok(Self {
+            alg,
+            conf: conf.opt_conf,
+            opt_prob: problem,
+            converged: checkpoint.converged,
+            stopped: false,
+            stop_reason: checkpoint.stop_reason,
+            observers: Vec::new(),
+            stopping,
+            indicators: Vec::new(),
+            started,
+            history,
+        })
     }
 
-    pub fn step(&mut self) {
-        if self.converged {
-            return;
+    fn boolean_constraint<G: BooleanConstraintFunction<T, D> + 'static>(constr_f: G) -> BooleanConstraint<Box<dyn BooleanConstraintFunction<T, D>>> {
+        BooleanConstraint(Box::new(constr_f))
+    }
+
+    fn build_problem<F: ObjectiveFunction<T, D> + 'static, G: ConstraintFunction<T, D> + 'static>(
+        conf: &Config, 
+        obj_f: F, 
+        constr_f: Option<G>,
+    ) -> OptProb<T, D> {
+        let objective: Box<dyn ObjectiveFunction<T, D>> = match conf.opt_conf.gradient {
+            GradientConf::Analytic => Box::new(obj_f),
+            GradientConf::FiniteDifference { method, step } => {
+                let obj_f: Box<dyn ObjectiveFunction<T, D>> = Box::new(obj_f);
+                Box::new(FiniteDifference::new(obj_f, method, step))
+            },
+            GradientConf::ComplexStep { h } => {
+                let obj_f: Box<dyn ObjectiveFunction<T, D>> = Box::new(obj_f);
+                Box::new(ComplexStep::with_step(obj_f, h))
+            },
+        };
+
+        OptProb::new(objective, None)
+        .with_constraints(match constr_f {
+            Some(constr_f) => Some(Box::new(constr_f)),
+            None => None,
+        })
+        .with_sense(conf.opt_conf.objective_sense)
+        .with_max_evals(conf.opt_conf.max_evals)
+        .with_constraint_handling(conf.opt_conf.constraint_handling.clone())
+    }
+
+    // The scalar objective is the sum, so bounds, gradients and single-objective algorithms still work
+    fn build_multi_problem<M: MultiObjectiveFunction<T, D> + 'static, G: ConstraintFunction<T, D> + 'static>(
+        conf: &Config, 
+        obj_f: M, 
+        constr_f: Option<G>,
+    ) -> OptProb<T, D> {
+        let multi: Box<dyn MultiObjectiveFunction<T, D>> = Box::new(obj_f);
+        Self::build_problem(conf, SumOfObjectives(multi.clone()), constr_f).with_multi_objective(multi)
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint { 
+            alg: self.alg.checkpoint(), 
+            converged: self.converged, 
+            evals: self.evaluations(), 
+            stop_reason: self.stop_reason.clone(),
+            elapsed: self.started.elapsed(),
+            history: self.history.clone(),
+            bounds: self.opt_prob.bounds.as_ref().map(BoundsConf::from_bounds),
+        }
+    }
+
+    fn check_convergence(&mut self, previous_best: T, diagnostics: &Diagnostics) -> bool {
+        let st = self.state();
+        let ctx = StopContext {
+            st: &st,
+            previous_best: self.conf.objective_sense.apply(previous_best),
+            sense: self.conf.objective_sense,
+            diagnostics,
+            elapsed: self.started.elapsed(),
+            opt_prob: &self.opt_prob,
+        };
+
+        // Every criterion sees every step, the first to fire is reported
+        let fired: Vec<String> = self.stopping.iter_mut().filter_map(|c| c.fired(&ctx)).collect();
+        if let Some(name) = fired.into_iter().next() {
+            println!("Converged in {} iterations ({})", st.iter, name);
+            self.stop_reason = Some(name);
+            return true;
+        }
+        
+        false
+    }
+
+    // Extra criteria are checked alongside the one from the config, the run stops when any fires
+    pub fn add_stopping_criterion(&mut self, criterion: Box<dyn StoppingCriterion<T, N, D>>) {
+        self.stopping.push(criterion);
+    }
+
+    pub fn add_observer(&mut self, observer: Box<dyn Observer<T, N, D>>) {
+        self.observers.push(observer);
+    }
+
+    // Tracked on the Pareto front after every step and added to the diagnostics seen by stopping
+    // criteria and observers. Single-objective algorithms have no front and never report them
+    pub fn add_indicator(&mut self, indicator: Indicator) {
+        self.indicators.push(indicator);
+    }
+
+    pub fn evaluations(&self) -> EvalCounts {
+        self.opt_prob.evaluations()
+    }
+
+    pub fn budget_exhausted(&self) -> bool {
+        self.opt_prob.budget_exhausted()
+    }
+
+    // Errors (e.g. a NaN objective) end the step early, before convergence checks and observers
+    pub fn step(&mut self) -> Result<(), OptError> {
+        if self.converged || self.stopped || self.budget_exhausted() {
+            return Ok(());
         }
 
         let previous_best_fitness = self.alg.state().best_f;
         self.alg.step();
-        let current_best_fitness = self.alg.state().best_f;
+        if let Some(err) = self.opt_prob.take_error() {
+            return Err(err);
+        }
 
-        self.converged = self.check_convergence(
-            current_best_fitness, 
-            previous_best_fitness
-        );
+        let mut diagnostics = self.alg.diagnostics();
+        self.track_indicators(&mut diagnostics);
+        self.converged = self.check_convergence(previous_best_fitness, &diagnostics);
+
+        self.record_history();
+        self.notify_observers(&diagnostics);
+        Ok(())
     }
 
-    pub fn run(&mut self) -> &State<T, N, D> {
-        while !self.converged && self.alg.state().iter < self.conf.max_iter {
-            self.step();
+    fn track_indicators(&self, diagnostics: &mut Diagnostics) {
+        if self.indicators.is_empty() {
+            return;
         }
-        self.alg.state()
+
+        if let Some(front) = self.alg.pareto_front() {
+            for indicator in &self.indicators {
+                diagnostics.insert(indicator.name().to_string(), indicator.evaluate(&front.f, self.conf.objective_sense));
+            }
+        }
+    }
+
+    fn record_history(&mut self) {
+        if self.history.is_none() {
+            return;
+        }
+
+        let entry = HistoryEntry::from_state(&self.state());
+        if let Some(history) = self.history.as_mut() {
+            history.push(entry);
+        }
+    }
+
+    pub fn history(&self) -> Option<&History> {
+        self.history.as_ref()
+    }
+
+    fn notify_observers(&mut self, diagnostics: &Diagnostics) {
+        if self.observers.is_empty() {
+            return;
+        }
+
+        let st = self.state();
+        for observer in self.observers.iter_mut() {
+            // Every observer sees the step, even if an earlier one asked to stop
+            if observer.notify(st.iter, &st, diagnostics) == ObserverAction::Stop {
+                self.stopped = true;
+            }
+        }
+    }
+
+    pub fn run(&mut self) -> Result<OptimizationResult<T, D>, OptError> {
+        while !self.converged 
+            && !self.stopped 
+            && !self.budget_exhausted() 
+            && self.alg.state().iter < self.conf.max_iter 
+        {
+            self.step()?;
+        }
+
+        let st = self.state();
+        Ok(OptimizationResult {
+            best_x: st.best_x,
+            best_f: st.best_f,
+            termination: self.termination().unwrap_or(TerminationReason::MaxIter),
+            iterations: st.iter,
+            evals: self.evaluations(),
+            elapsed: self.started.elapsed(),
+            history: self.history.clone(),
+            lagrange: self.alg.lagrange(),
+            pareto_front: self.alg.pareto_front(),
+        })
+    }
+
+    // Why the run ended, None while it can still continue
+    pub fn termination(&self) -> Option<TerminationReason> {
+        if self.converged {
+            Some(TerminationReason::Criterion(self.stop_reason.clone().unwrap_or_default()))
+        } else if self.stopped {
+            Some(TerminationReason::Observer)
+        } else if self.budget_exhausted() {
+            Some(TerminationReason::MaxEvals)
+        } else if self.alg.state().iter >= self.conf.max_iter {
+            Some(TerminationReason::MaxIter)
+        } else {
+            None
+        }
+    }
+
+    // Current state with best_f and fitness in the objective's own units
+    pub fn state(&self) -> State<T, N, D> {
+        let sense = self.conf.objective_sense;
+        let mut st = self.alg.state().clone();
+        st.best_f = sense.apply(st.best_f);
+        st.fitness.iter_mut().for_each(|f| *f = sense.apply(*f));
+        st
     }
 
     pub fn get_best_individual(&self) -> OVector<T, D> {
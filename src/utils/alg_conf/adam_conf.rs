@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct AdamConf {
     #[serde(default = "default_learning_rate")]
@@ -10,6 +12,8 @@ pub struct AdamConf {
     pub beta2: f64,     
     #[serde(default = "default_epsilon")]
     pub epsilon: f64,    
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
 }
 
 fn default_learning_rate() -> f64 { 0.001 }
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::config::AlgConf;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AugLagConf {
+    pub inner: Box<AlgConf>, // Solves each unconstrained subproblem, any algorithm but AugLag itself
+    #[serde(default = "default_inner_iters")]
+    pub inner_iters: usize, // Inner steps per outer iteration
+    #[serde(default = "default_initial_penalty")]
+    pub initial_penalty: f64,
+    #[serde(default = "default_penalty_growth")]
+    pub penalty_growth: f64, // Applied when the residual hasn't shrunk enough
+    #[serde(default = "default_max_penalty")]
+    pub max_penalty: f64,
+    #[serde(default = "default_residual_reduction")]
+    pub residual_reduction: f64, // Fraction of the previous residual an outer iteration has to reach
+}
+
+fn default_inner_iters() -> usize { 50 }
+fn default_initial_penalty() -> f64 { 10.0 }
+fn default_penalty_growth() -> f64 { 10.0 }
+fn default_max_penalty() -> f64 { 1e8 }
+fn default_residual_reduction() -> f64 { 0.25 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CGAConf {    
     pub common: CommonConf,
@@ -12,12 +14,24 @@ pub struct CGAConf {
 pub struct CommonConf {
     #[serde(default = "default_num_parents")]
     pub num_parents: usize,
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum CrossoverConf {
     Random(RandomCrossoverConf),
     Heuristic(HeuristicCrossoverConf),
+    // For permutation problems, with one of the permutation mutations
+    PMX(PermutationCrossoverConf),
+    OX(PermutationCrossoverConf),
+    Cycle(PermutationCrossoverConf),
+}
+
+impl CrossoverConf {
+    pub fn is_permutation(&self) -> bool {
+        matches!(self, CrossoverConf::PMX(_) | CrossoverConf::OX(_) | CrossoverConf::Cycle(_))
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -32,6 +46,13 @@ pub struct HeuristicCrossoverConf {
     pub crossover_prob: f64,
 }
 
+// Pairs that don't cross over are copied
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PermutationCrossoverConf {
+    #[serde(default = "default_crossover_prob")]
+    pub crossover_prob: f64,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum SelectionConf {
     RouletteWheel(RouletteWheelSelectionConf),
@@ -57,6 +78,17 @@ pub enum MutationConf {
     Uniform(UniformMutationConf),
     NonUniform(NonUniformMutationConf),
     Polynomial(PolynomialMutationConf),
+    // For permutation problems, with one of the permutation crossovers
+    Swap(PermutationMutationConf),
+    Insert(PermutationMutationConf),
+    Inversion(PermutationMutationConf),
+    Scramble(PermutationMutationConf),
+}
+
+impl MutationConf {
+    pub fn is_permutation(&self) -> bool {
+        matches!(self, MutationConf::Swap(_) | MutationConf::Insert(_) | MutationConf::Inversion(_) | MutationConf::Scramble(_))
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -89,10 +121,18 @@ pub struct PolynomialMutationConf {
     pub eta_m: f64,
 }
 
+// The rate is per individual, each mutated one gets a single move
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PermutationMutationConf {
+    #[serde(default = "default_permutation_mutation_rate")]
+    pub mutation_rate: f64,
+}
+
 fn default_num_parents() -> usize { 2 }
 fn default_crossover_prob() -> f64 { 0.8 }
 fn default_tournament_size() -> usize { 5 }
 fn default_mutation_rate() -> f64 { 0.01 }
 fn default_sigma() -> f64 { 0.1 }
 fn default_b() -> f64 { 5.0 }
-fn default_eta_m() -> f64 { 20.0 }
\ No newline at end of file
+fn default_eta_m() -> f64 { 20.0 }
+fn default_permutation_mutation_rate() -> f64 { 0.2 }
\ No newline at end of file
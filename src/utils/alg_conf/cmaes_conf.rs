@@ -1,11 +1,15 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CMAESConf {
     #[serde(default = "default_num_parents")]
     pub num_parents: usize,
     #[serde(default = "default_initial_sigma")]
     pub initial_sigma: f64,
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
 }
 
 fn default_num_parents() -> usize { 50 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct DEConf {
     pub common: CommonConf,
@@ -12,6 +14,8 @@ pub struct CommonConf {
     pub archive_size: usize,
     #[serde(default = "default_success_history_size")]
     pub success_history_size: usize,
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -53,6 +57,19 @@ pub enum DEStrategy {
     Rand2Bin,
 }
 
+impl DEStrategy {
+    // Target plus the distinct donors the strategy draws
+    pub fn min_population(&self) -> usize {
+        match self {
+            DEStrategy::Rand1Bin => 4,
+            DEStrategy::Best1Bin => 3,
+            DEStrategy::RandToBest1Bin => 3,
+            DEStrategy::Best2Bin => 5,
+            DEStrategy::Rand2Bin => 6,
+        }
+    }
+}
+
 fn default_archive_size() -> usize { 10 }
 fn default_f() -> f64 { 0.8 }
 fn default_cr() -> f64 { 0.9 }
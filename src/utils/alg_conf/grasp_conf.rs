@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct GRASPConf {
     #[serde(default = "default_grasp_num_candidates")]
@@ -12,6 +14,8 @@ pub struct GRASPConf {
     pub step_size: f64,
     #[serde(default = "default_grasp_perturbation_prob")]
     pub perturbation_prob: f64,
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
 }
 
 fn default_grasp_num_candidates() -> usize { 100 }
@@ -9,4 +9,7 @@ pub mod lbfgs_conf;
 pub mod mspo_conf;
 pub mod sa_conf;
 pub mod de_conf;
-pub mod cmaes_conf;
\ No newline at end of file
+pub mod cmaes_conf;
+pub mod auglag_conf;
+pub mod nsga2_conf;
+pub mod moead_conf;
\ No newline at end of file
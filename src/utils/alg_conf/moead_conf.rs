@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::alg_conf::de_conf::DEStrategy;
+use crate::utils::bounds::BoundHandling;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MOEADConf {
+    pub divisions: usize, // Das-Dennis lattice, one weight vector per member of the population
+    #[serde(default)]
+    pub scalarization: Scalarization,
+    #[serde(default = "default_neighbourhood_size")]
+    pub neighbourhood_size: usize, // Closest weight vectors, including the subproblem's own
+    #[serde(default = "default_neighbourhood_prob")]
+    pub neighbourhood_prob: f64, // Chance of mating and replacing within the neighbourhood rather than the whole population
+    #[serde(default = "default_max_replacements")]
+    pub max_replacements: usize, // Subproblems a single child can take over
+    #[serde(default = "default_strategy")]
+    pub strategy: DEStrategy,
+    #[serde(default = "default_f")]
+    pub f: f64,
+    #[serde(default = "default_cr")]
+    pub cr: f64,
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
+}
+
+// How each subproblem turns the objectives into one value to minimize, measured from the ideal point
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub enum Scalarization {
+    // max_m w_m |z_m - f_m|
+    #[default]
+    Tchebycheff,
+    // d1 + theta * d2, distance to the ideal point along w plus theta times the distance from that line
+    PBI {
+        #[serde(default = "default_theta")]
+        theta: f64,
+    },
+}
+
+fn default_neighbourhood_size() -> usize { 20 }
+fn default_neighbourhood_prob() -> f64 { 0.9 }
+fn default_max_replacements() -> usize { 2 }
+fn default_strategy() -> DEStrategy { DEStrategy::Rand1Bin }
+fn default_f() -> f64 { 0.5 }
+fn default_cr() -> f64 { 1.0 }
+fn default_theta() -> f64 { 5.0 }
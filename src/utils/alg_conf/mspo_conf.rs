@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct MSPOConf {
     #[serde(default = "default_num_swarms")]
@@ -12,16 +14,14 @@ pub struct MSPOConf {
     pub c1: f64, // Cognitive coefficient
     #[serde(default = "default_c2")]
     pub c2: f64, // Social coefficient
-    #[serde(default = "default_x_min")]
-    pub x_min: f64, // Lower bound for initialization
-    #[serde(default = "default_x_max")]
-    pub x_max: f64, // Upper bound for initialization
     #[serde(default = "default_exchange_interval")]
     pub exchange_interval: usize, // How often to exchange information between swarms
     #[serde(default = "default_exchange_ratio")]
     pub exchange_ratio: f64, // Fraction of particles to exchange information
     #[serde(default = "default_improvement_threshold")]
     pub improvement_threshold: f64, // Minimum relative improvement needed for exchange
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
 }
 
 fn default_num_swarms() -> usize { 5 }
@@ -29,8 +29,6 @@ fn default_swarm_size() -> usize { 50 }
 fn default_w() -> f64 { 0.729 } // Clerc's constriction coefficient
 fn default_c1() -> f64 { 2.05 }
 fn default_c2() -> f64 { 2.05 }
-fn default_x_min() -> f64 { -10.0 }
-fn default_x_max() -> f64 { 10.0 }
 fn default_exchange_interval() -> usize { 10 }
 fn default_exchange_ratio() -> f64 { 0.1 }
 fn default_improvement_threshold() -> f64 { 0.1 } // 10% improvement needed by default
\ No newline at end of file
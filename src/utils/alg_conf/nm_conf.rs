@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct NelderMeadConf {
     #[serde(default = "default_alpha")]
@@ -10,6 +12,8 @@ pub struct NelderMeadConf {
     pub rho: f64,    // Contraction coefficient
     #[serde(default = "default_sigma")]
     pub sigma: f64,  // Shrink coefficient
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
 }
 
 fn default_alpha() -> f64 { 1.0 }
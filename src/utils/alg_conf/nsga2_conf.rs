@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::alg_conf::cga_conf::{CrossoverConf, MutationConf};
+use crate::utils::bounds::BoundHandling;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct NSGA2Conf {
+    pub crossover: CrossoverConf,
+    pub mutation: MutationConf,
+    #[serde(default = "default_tournament_size")]
+    pub tournament_size: usize, // Parents are picked by crowded comparison among this many members
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
+}
+
+fn default_tournament_size() -> usize { 2 }
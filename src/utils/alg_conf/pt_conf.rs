@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct PTConf {
     pub common: CommonConf,
@@ -22,6 +24,8 @@ pub struct CommonConf {
     pub omega: f64,
     #[serde(default = "default_mala_step_size")]
     pub mala_step_size: f64,
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
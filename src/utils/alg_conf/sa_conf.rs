@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+use crate::utils::permutation::Neighbourhood;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SAConf {
     #[serde(default = "default_initial_temp")]
@@ -12,16 +15,14 @@ pub struct SAConf {
     pub num_neighbors: usize,
     #[serde(default = "default_reheat_after")]
     pub reheat_after: usize,
-    #[serde(default = "default_x_min")]
-    pub x_min: f64,
-    #[serde(default = "default_x_max")]
-    pub x_max: f64,
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
+    #[serde(default)]
+    pub neighbourhood: Neighbourhood,
 }
 
 fn default_initial_temp() -> f64 { 1000.0 }
 fn default_cooling_rate() -> f64 { 0.998 }
 fn default_step_size() -> f64 { 0.5 }
 fn default_num_neighbors() -> usize { 20 }
-fn default_reheat_after() -> usize { 50 }
-fn default_x_min() -> f64 { -10.0 }
-fn default_x_max() -> f64 { 10.0 } 
\ No newline at end of file
+fn default_reheat_after() -> usize { 50 }
\ No newline at end of file
@@ -1,11 +1,15 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SGAConf {
     #[serde(default = "default_learning_rate")]
     pub learning_rate: f64,
     #[serde(default = "default_momentum")]
     pub momentum: f64,
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
 }
 
 fn default_learning_rate() -> f64 { 0.01 }
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::bounds::BoundHandling;
+use crate::utils::permutation::Neighbourhood;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct TabuConf {
     pub common: CommonConf,
@@ -24,6 +27,10 @@ pub struct CommonConf {
     pub perturbation_prob: f64,
     #[serde(default = "default_tabu_threshold")]
     pub tabu_threshold: f64,
+    #[serde(default)]
+    pub bound_handling: BoundHandling,
+    #[serde(default)]
+    pub neighbourhood: Neighbourhood,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
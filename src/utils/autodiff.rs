@@ -0,0 +1,111 @@
+use rayon::prelude::*;
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    OMatrix,
+    OVector,
+    U1
+};
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, ObjectiveFunction};
+use crate::utils::dual::{Dual, HyperDual};
+
+// Objective written once for any T: FloatNumber, so it can also be evaluated on dual numbers
+pub trait AutoDiffObjective<D: Dim>: Clone + Send + Sync + 'static
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f<T: FloatNum>(&self, x: &OVector<T, D>) -> T;
+
+    fn x_lower_bound<T: FloatNum>(&self, _x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        None
+    }
+
+    fn x_upper_bound<T: FloatNum>(&self, _x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        None
+    }
+}
+
+// Provides exact gradients (and Hessians) of an AutoDiffObjective by forward-mode differentiation,
+// one dual-number pass per coordinate run in parallel
+#[derive(Debug, Clone)]
+pub struct AutoDiff<F> {
+    pub objective: F,
+}
+
+impl<F> AutoDiff<F> {
+    pub fn new(objective: F) -> Self {
+        Self { objective }
+    }
+
+    // d²f / dx_i dx_j from n(n + 1) / 2 hyper-dual passes
+    pub fn hessian<T, D>(&self, x: &OVector<T, D>) -> OMatrix<T, D, D>
+    where
+        T: FloatNum,
+        D: Dim,
+        F: AutoDiffObjective<D>,
+        DefaultAllocator: Allocator<D> + Allocator<D, D>
+    {
+        let d = x.shape_generic().0;
+        let n = x.len();
+        let xs: Vec<T> = x.iter().cloned().collect();
+        let pairs: Vec<(usize, usize)> = (0..n).flat_map(|i| (i..n).map(move |j| (i, j))).collect();
+
+        let entries: Vec<T> = pairs
+            .par_iter()
+            .map(|&(i, j)| {
+                let xh = OVector::<HyperDual<T>, D>::from_iterator_generic(d, U1, xs.iter().enumerate().map(|(k, &xk)| {
+                    let inner = Dual::new(xk, if k == i { T::one() } else { T::zero() });
+                    let outer = Dual::constant(if k == j { T::one() } else { T::zero() });
+                    Dual::new(inner, outer)
+                }));
+                self.objective.f(&xh).eps.eps
+            })
+            .collect();
+
+        let mut hessian = OMatrix::<T, D, D>::zeros_generic(d, d);
+        for (&(i, j), &h) in pairs.iter().zip(entries.iter()) {
+            hessian[(i, j)] = h;
+            hessian[(j, i)] = h;
+        }
+        hessian
+    }
+}
+
+impl<T, D, F> ObjectiveFunction<T, D> for AutoDiff<F>
+where
+    T: FloatNum,
+    D: Dim,
+    F: AutoDiffObjective<D>,
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<T, D>) -> T {
+        self.objective.f(x)
+    }
+
+    fn gradient(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        let d = x.shape_generic().0;
+        let xs: Vec<T> = x.iter().cloned().collect();
+
+        let grad: Vec<T> = (0..xs.len())
+            .into_par_iter()
+            .map(|i| {
+                let xd = OVector::<Dual<T>, D>::from_iterator_generic(d, U1, xs.iter().enumerate().map(|(k, &xk)| {
+                    if k == i { Dual::variable(xk) } else { Dual::constant(xk) }
+                }));
+                self.objective.f(&xd).eps
+            })
+            .collect();
+
+        Some(OVector::<T, D>::from_iterator_generic(d, U1, grad))
+    }
+
+    fn x_lower_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        self.objective.x_lower_bound(x)
+    }
+
+    fn x_upper_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        self.objective.x_upper_bound(x)
+    }
+}
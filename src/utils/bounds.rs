@@ -0,0 +1,211 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    OVector,
+    U1
+};
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, ObjectiveFunction};
+use crate::utils::checkpoint::NonFinite;
+use crate::utils::error::OptError;
+use crate::utils::rng::OptRng;
+
+// How a point that left the box is brought back. Clamping piles samples onto the boundary,
+// which biases samplers such as CMA-ES and DE, so the other strategies keep them spread out
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundHandling {
+    #[default]
+    Clamp,
+    Reflect, // Mirror off the violated side, repeatedly if the box is narrow
+    Wrap, // Periodic, leaving through one side re-enters through the other
+    Midpoint, // Halfway between the parent and the violated side
+    Resample, // Uniformly from the box
+    DeathPenalty, // Left as is and given the worst fitness without being evaluated
+}
+
+// Per-dimension box [lower, upper]. Either side may be infinite
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounds<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    pub lower: OVector<T, D>,
+    pub upper: OVector<T, D>,
+}
+
+impl<T, D> Bounds<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    pub fn new(lower: OVector<T, D>, upper: OVector<T, D>) -> Result<Self, OptError> {
+        if lower.len() != upper.len() {
+            return Err(OptError::InvalidBounds(format!("{} lower bounds but {} upper bounds", lower.len(), upper.len())));
+        }
+        if lower.iter().chain(upper.iter()).any(|v| v.is_nan()) {
+            return Err(OptError::InvalidBounds("bounds contain NaN".to_string()));
+        }
+        if let Some(i) = (0..lower.len()).find(|&i| lower[i] > upper[i]) {
+            return Err(OptError::InvalidBounds(format!("lower bound {:?} is above upper bound {:?} in dimension {}", lower[i], upper[i], i)));
+        }
+        Ok(Self { lower, upper })
+    }
+
+    // The same interval in every dimension
+    pub fn uniform(dim: usize, lower: T, upper: T) -> Result<Self, OptError> {
+        Self::new(
+            OVector::<T, D>::from_element_generic(D::from_usize(dim), U1, lower),
+            OVector::<T, D>::from_element_generic(D::from_usize(dim), U1, upper),
+        )
+    }
+
+    pub fn unbounded(dim: usize) -> Self {
+        Self::uniform(dim, T::neg_infinity(), T::infinity()).unwrap()
+    }
+
+    pub fn dim(&self) -> usize {
+        self.lower.len()
+    }
+
+    // Both sides finite in every dimension, needed by anything that samples from the box
+    pub fn is_finite(&self) -> bool {
+        self.lower.iter().chain(self.upper.iter()).all(|v| v.is_finite())
+    }
+
+    pub fn contains(&self, x: &OVector<T, D>) -> bool {
+        (0..x.len()).all(|i| x[i] >= self.lower[i] && x[i] <= self.upper[i])
+    }
+
+    pub fn width(&self, i: usize) -> T {
+        self.upper[i] - self.lower[i]
+    }
+
+    pub fn clamp(&self, x: &OVector<T, D>) -> OVector<T, D> {
+        let mut x = x.clone();
+        self.clamp_mut(&mut x);
+        x
+    }
+
+    pub fn clamp_mut(&self, x: &mut OVector<T, D>) {
+        for i in 0..x.len() {
+            x[i] = x[i].max(self.lower[i]).min(self.upper[i]);
+        }
+    }
+
+    // Brings x back into the box, parent is the in-bounds point x was generated from.
+    // Wrap and Resample need both sides of a dimension, and fall back to Reflect and Midpoint otherwise
+    pub fn repair(&self, x: &OVector<T, D>, parent: &OVector<T, D>, handling: BoundHandling, rng: &mut OptRng) -> OVector<T, D> {
+        let mut x = x.clone();
+        if handling == BoundHandling::DeathPenalty {
+            return x;
+        }
+
+        let two = T::from_f64(2.0).unwrap();
+        for i in 0..x.len() {
+            let (lo, hi) = (self.lower[i], self.upper[i]);
+            if x[i] >= lo && x[i] <= hi {
+                continue;
+            }
+            if lo == hi {
+                x[i] = lo;
+                continue;
+            }
+            let violated = if x[i] < lo { lo } else { hi };
+            let finite = lo.is_finite() && hi.is_finite();
+
+            x[i] = match handling {
+                BoundHandling::Clamp => violated,
+                BoundHandling::Wrap if finite => {
+                    let w = hi - lo;
+                    lo + ((x[i] - lo) % w + w) % w
+                },
+                BoundHandling::Reflect | BoundHandling::Wrap => {
+                    if finite {
+                        let w = hi - lo;
+                        let y = ((x[i] - lo) % (two * w) + two * w) % (two * w);
+                        lo + if y > w { two * w - y } else { y }
+                    } else {
+                        // One finite side, mirror off it once
+                        two * violated - x[i]
+                    }
+                },
+                BoundHandling::Resample if finite => {
+                    lo + (hi - lo) * T::from_f64(rng.random::<f64>()).unwrap()
+                },
+                BoundHandling::Midpoint | BoundHandling::Resample => (parent[i].max(lo).min(hi) + violated) / two,
+                BoundHandling::DeathPenalty => unreachable!(),
+            };
+        }
+        x
+    }
+
+    // Config bounds win over the objective's x_lower_bound/x_upper_bound, which are read once at x0
+    pub fn resolve(conf: Option<&BoundsConf>, objective: &dyn ObjectiveFunction<T, D>, x0: &OVector<T, D>) -> Result<Option<Self>, OptError> {
+        if let Some(conf) = conf {
+            return conf.to_bounds(x0.len()).map(Some);
+        }
+
+        let dim = x0.len();
+        let lower = objective.x_lower_bound(x0);
+        let upper = objective.x_upper_bound(x0);
+        if lower.is_none() && upper.is_none() {
+            return Ok(None);
+        }
+
+        let unbounded = Self::unbounded(dim);
+        let lower = lower.unwrap_or(unbounded.lower);
+        let upper = upper.unwrap_or(unbounded.upper);
+        if lower.len() != dim || upper.len() != dim {
+            return Err(OptError::InvalidBounds(format!("bounds have {} and {} values for a {}-dimensional problem", lower.len(), upper.len(), dim)));
+        }
+        Self::new(lower, upper).map(Some)
+    }
+}
+
+// Bounds as written in OptConf and checkpoints. A single value applies to every dimension,
+// infinite sides are written as "inf"/"-inf"
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BoundsConf {
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub lower: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub upper: Vec<f64>,
+}
+
+impl BoundsConf {
+    pub fn from_bounds<T, D>(bounds: &Bounds<T, D>) -> Self
+    where
+        T: FloatNum,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+    {
+        Self {
+            lower: bounds.lower.iter().map(|v| v.to_f64().unwrap()).collect(),
+            upper: bounds.upper.iter().map(|v| v.to_f64().unwrap()).collect(),
+        }
+    }
+
+    pub fn to_bounds<T, D>(&self, dim: usize) -> Result<Bounds<T, D>, OptError>
+    where
+        T: FloatNum,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+    {
+        let side = |values: &[f64]| -> Result<OVector<T, D>, OptError> {
+            match values.len() {
+                1 => Ok(OVector::<T, D>::from_element_generic(D::from_usize(dim), U1, T::from_f64(values[0]).unwrap())),
+                n if n == dim => Ok(OVector::<T, D>::from_iterator_generic(D::from_usize(dim), U1, values.iter().map(|&v| T::from_f64(v).unwrap()))),
+                n => Err(OptError::InvalidBounds(format!("bounds have {} values for a {}-dimensional problem", n, dim))),
+            }
+        };
+        Bounds::new(side(&self.lower)?, side(&self.upper)?)
+    }
+}
@@ -0,0 +1,303 @@
+use std::time::Duration;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as _;
+use serde_with::{serde_as, DeserializeAs, SerializeAs};
+use thiserror::Error;
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    OVector,
+    OMatrix,
+    U1
+};
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, State, EvalCounts};
+use crate::utils::result::History;
+use crate::utils::bounds::BoundsConf;
+
+pub use crate::algorithms::{
+    continous_ga::cga::CGACheckpoint,
+    parallel_tempering::pt::PTCheckpoint,
+    tabu_search::tabu::TabuCheckpoint,
+    adam::adam::AdamCheckpoint,
+    grasp::grasp::GRASPCheckpoint,
+    sg_ascent::sga::SGACheckpoint,
+    nelder_mead::nm::NelderMeadCheckpoint,
+    limited_memory_bfgs::lbfgs::LBFGSCheckpoint,
+    multi_swarm::mspo::MSPOCheckpoint,
+    simulated_annealing::sa::SACheckpoint,
+    differential_evolution::de::DECheckpoint,
+    cma_es::cma_es::CMAESCheckpoint,
+    aug_lag::aug_lag::AugLagCheckpoint,
+    nsga2::nsga2::NSGA2Checkpoint,
+    moead::moead::MOEADCheckpoint,
+};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CheckpointError {
+    #[error("Failed to serialize checkpoint: {0}")]
+    SerializationError(String),
+
+    #[error("Failed to deserialize checkpoint: {0}")]
+    DeserializationError(String),
+
+    #[error("Checkpoint holds a {found} run but the config is for {expected}")]
+    AlgorithmMismatch { expected: String, found: String },
+
+    #[error("Checkpoint has {found} values where {expected} were expected")]
+    DimensionMismatch { expected: usize, found: usize },
+}
+
+// Everything needed to continue a run exactly where it stopped
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Checkpoint {
+    pub alg: AlgCheckpoint,
+    pub converged: bool,
+    #[serde(default)]
+    pub evals: EvalCounts,
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub elapsed: Duration,
+    #[serde(default)]
+    pub history: Option<History>,
+    #[serde(default)]
+    pub bounds: Option<BoundsConf>, // As resolved by NonConvexOpt::new
+}
+
+// Mirrors AlgConf, one variant per algorithm
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum AlgCheckpoint {
+    CGA(CGACheckpoint),
+    PT(PTCheckpoint),
+    TS(TabuCheckpoint),
+    Adam(AdamCheckpoint),
+    GRASP(GRASPCheckpoint),
+    SGA(SGACheckpoint),
+    NM(NelderMeadCheckpoint),
+    LBFGS(LBFGSCheckpoint),
+    MSPO(MSPOCheckpoint),
+    SA(SACheckpoint),
+    DE(DECheckpoint),
+    CMAES(CMAESCheckpoint),
+    AugLag(AugLagCheckpoint),
+    NSGA2(NSGA2Checkpoint),
+    MOEAD(MOEADCheckpoint),
+}
+
+impl AlgCheckpoint {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlgCheckpoint::CGA(_) => "CGA",
+            AlgCheckpoint::PT(_) => "PT",
+            AlgCheckpoint::TS(_) => "TS",
+            AlgCheckpoint::Adam(_) => "Adam",
+            AlgCheckpoint::GRASP(_) => "GRASP",
+            AlgCheckpoint::SGA(_) => "SGA",
+            AlgCheckpoint::NM(_) => "NM",
+            AlgCheckpoint::LBFGS(_) => "LBFGS",
+            AlgCheckpoint::MSPO(_) => "MSPO",
+            AlgCheckpoint::SA(_) => "SA",
+            AlgCheckpoint::DE(_) => "DE",
+            AlgCheckpoint::CMAES(_) => "CMAES",
+            AlgCheckpoint::AugLag(_) => "AugLag",
+            AlgCheckpoint::NSGA2(_) => "NSGA2",
+            AlgCheckpoint::MOEAD(_) => "MOEAD",
+        }
+    }
+}
+
+impl Checkpoint {
+    pub fn to_json(&self) -> Result<String, CheckpointError> {
+        serde_json::to_string(self)
+            .map_err(|e| CheckpointError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, CheckpointError> {
+        serde_json::from_str(json)
+            .map_err(|e| CheckpointError::DeserializationError(e.to_string()))
+    }
+
+    // Compact binary (CBOR) encoding
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CheckpointError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes)
+            .map_err(|e| CheckpointError::SerializationError(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CheckpointError> {
+        ciborium::from_reader(bytes)
+            .map_err(|e| CheckpointError::DeserializationError(e.to_string()))
+    }
+}
+
+// JSON has no inf/NaN, but they do show up (e.g. fitness of infeasible points), so write those as strings
+pub struct NonFinite;
+
+impl<T: FloatNum> SerializeAs<T> for NonFinite {
+    fn serialize_as<S: Serializer>(x: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        let x = x.to_f64().unwrap();
+        if x.is_finite() || !serializer.is_human_readable() {
+            serializer.serialize_f64(x)
+        } else {
+            serializer.serialize_str(&x.to_string())
+        }
+    }
+}
+
+impl<'de, T: FloatNum> DeserializeAs<'de, T> for NonFinite {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Number(f64),
+            Text(String),
+        }
+
+        let x: f64 = match Repr::deserialize(deserializer)? {
+            Repr::Number(x) => x,
+            Repr::Text(s) => s.parse().map_err(D::Error::custom)?,
+        };
+        Ok(T::from_f64(x).unwrap())
+    }
+}
+
+// Column-major matrix, stored as f64 so checkpoints don't depend on the float type
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MatrixData {
+    pub nrows: usize,
+    pub ncols: usize,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub data: Vec<f64>,
+}
+
+fn check_dim<D: Dim>(found: usize) -> Result<D, CheckpointError> {
+    match D::try_to_usize() {
+        Some(expected) if expected != found => Err(CheckpointError::DimensionMismatch { expected, found }),
+        _ => Ok(D::from_usize(found)),
+    }
+}
+
+pub fn vector_to_data<T: FloatNum, D: Dim>(v: &OVector<T, D>) -> Vec<f64>
+where
+    DefaultAllocator: Allocator<D>
+{
+    v.iter().map(|x| x.to_f64().unwrap()).collect()
+}
+
+pub fn vector_from_data<T: FloatNum, D: Dim>(data: &[f64]) -> Result<OVector<T, D>, CheckpointError>
+where
+    DefaultAllocator: Allocator<D>
+{
+    let d = check_dim::<D>(data.len())?;
+    Ok(OVector::<T, D>::from_iterator_generic(d, U1, data.iter().map(|&x| T::from_f64(x).unwrap())))
+}
+
+pub fn bools_from_data<D: Dim>(data: &[bool]) -> Result<OVector<bool, D>, CheckpointError>
+where
+    DefaultAllocator: Allocator<D>
+{
+    let d = check_dim::<D>(data.len())?;
+    Ok(OVector::<bool, D>::from_iterator_generic(d, U1, data.iter().cloned()))
+}
+
+// Checkpoints taken before violations were stored fall back to 0 or 1 from the feasibility flags
+pub fn violation_from_data<T: FloatNum, D: Dim>(violation: &[f64], constraints: &[bool]) -> Result<OVector<T, D>, CheckpointError>
+where
+    DefaultAllocator: Allocator<D>
+{
+    if violation.is_empty() && !constraints.is_empty() {
+        let fallback: Vec<f64> = constraints.iter().map(|&c| if c { 0.0 } else { 1.0 }).collect();
+        return vector_from_data(&fallback);
+    }
+    vector_from_data(violation)
+}
+
+pub fn matrix_to_data<T: FloatNum, R: Dim, C: Dim>(m: &OMatrix<T, R, C>) -> MatrixData
+where
+    DefaultAllocator: Allocator<R, C>
+{
+    MatrixData {
+        nrows: m.nrows(),
+        ncols: m.ncols(),
+        data: m.iter().map(|x| x.to_f64().unwrap()).collect(),
+    }
+}
+
+pub fn matrix_from_data<T: FloatNum, R: Dim, C: Dim>(m: &MatrixData) -> Result<OMatrix<T, R, C>, CheckpointError>
+where
+    DefaultAllocator: Allocator<R, C>
+{
+    if m.data.len() != m.nrows * m.ncols {
+        return Err(CheckpointError::DimensionMismatch { expected: m.nrows * m.ncols, found: m.data.len() });
+    }
+    let r = check_dim::<R>(m.nrows)?;
+    let c = check_dim::<C>(m.ncols)?;
+    Ok(OMatrix::<T, R, C>::from_iterator_generic(r, c, m.data.iter().map(|&x| T::from_f64(x).unwrap())))
+}
+
+pub fn scalar_to_data<T: FloatNum>(x: T) -> f64 {
+    x.to_f64().unwrap()
+}
+
+pub fn scalar_from_data<T: FloatNum>(x: f64) -> T {
+    T::from_f64(x).unwrap()
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StateData {
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub best_x: Vec<f64>,
+    #[serde_as(as = "NonFinite")]
+    pub best_f: f64,
+    pub pop: MatrixData,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub fitness: Vec<f64>,
+    pub constraints: Vec<bool>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    #[serde(default)]
+    pub violation: Vec<f64>,
+    pub iter: usize,
+}
+
+impl StateData {
+    pub fn from_state<T, N, D>(st: &State<T, N, D>) -> Self
+    where
+        T: FloatNum,
+        N: Dim,
+        D: Dim,
+        DefaultAllocator: Allocator<D> + Allocator<N> + Allocator<N, D>
+    {
+        Self {
+            best_x: vector_to_data(&st.best_x),
+            best_f: scalar_to_data(st.best_f),
+            pop: matrix_to_data(&st.pop),
+            fitness: vector_to_data(&st.fitness),
+            constraints: st.constraints.iter().cloned().collect(),
+            violation: vector_to_data(&st.violation),
+            iter: st.iter,
+        }
+    }
+
+    pub fn to_state<T, N, D>(&self) -> Result<State<T, N, D>, CheckpointError>
+    where
+        T: FloatNum,
+        N: Dim,
+        D: Dim,
+        DefaultAllocator: Allocator<D> + Allocator<N> + Allocator<N, D>
+    {
+        Ok(State {
+            best_x: vector_from_data(&self.best_x)?,
+            best_f: scalar_from_data(self.best_f),
+            pop: matrix_from_data(&self.pop)?,
+            fitness: vector_from_data(&self.fitness)?,
+            constraints: bools_from_data(&self.constraints)?,
+            violation: violation_from_data(&self.violation, &self.constraints)?,
+            iter: self.iter,
+        })
+    }
+}
@@ -5,7 +5,7 @@ use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 
 pub use crate::utils::alg_conf::{
-    cga_conf::{CGAConf, CommonConf, CrossoverConf, SelectionConf, MutationConf},
+    cga_conf::{CGAConf, CommonConf, CrossoverConf, SelectionConf, MutationConf, PermutationCrossoverConf, PermutationMutationConf},
     pt_conf::{PTConf, SwapConf},
     tabu_conf::{TabuConf, ListType, ReactiveConf, StandardConf},
     adam_conf::AdamConf,
@@ -17,7 +17,20 @@ pub use crate::utils::alg_conf::{
     sa_conf::SAConf,
     de_conf::{DEConf, DEStrategy},
     cmaes_conf::CMAESConf,
+    auglag_conf::AugLagConf,
+    nsga2_conf::NSGA2Conf,
+    moead_conf::{MOEADConf, Scalarization},
 };
+pub use crate::utils::opt_prob::ObjectiveSense;
+pub use crate::utils::stopping::StoppingConf;
+pub use crate::utils::finite_diff::{GradientConf, FiniteDiffMethod, StepSize};
+pub use crate::utils::gradient_check::GradientCheckConf;
+pub use crate::utils::bounds::{BoundsConf, BoundHandling};
+pub use crate::utils::constraint_handling::ConstraintHandling;
+pub use crate::utils::linear_constraints::LinearConstraintsConf;
+pub use crate::utils::variables::{VarType, CategoricalEncoding};
+pub use crate::utils::permutation::Neighbourhood;
+pub use crate::utils::sampling::{Sampler, SamplingConf};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum AlgConf {
@@ -33,6 +46,55 @@ pub enum AlgConf {
     SA(SAConf),
     DE(DEConf),
     CMAES(CMAESConf),
+    AugLag(AugLagConf),
+    NSGA2(NSGA2Conf),
+    MOEAD(MOEADConf),
+}
+
+impl AlgConf {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlgConf::CGA(_) => "CGA",
+            AlgConf::PT(_) => "PT",
+            AlgConf::TS(_) => "TS",
+            AlgConf::Adam(_) => "Adam",
+            AlgConf::GRASP(_) => "GRASP",
+            AlgConf::SGA(_) => "SGA",
+            AlgConf::NM(_) => "NM",
+            AlgConf::LBFGS(_) => "LBFGS",
+            AlgConf::MSPO(_) => "MSPO",
+            AlgConf::SA(_) => "SA",
+            AlgConf::DE(_) => "DE",
+            AlgConf::CMAES(_) => "CMAES",
+            AlgConf::AugLag(_) => "AugLag",
+            AlgConf::NSGA2(_) => "NSGA2",
+            AlgConf::MOEAD(_) => "MOEAD",
+        }
+    }
+
+    // Algorithms that rank by the objective vector of a MultiObjectiveFunction
+    pub fn is_multi_objective(&self) -> bool {
+        matches!(self, AlgConf::NSGA2(_) | AlgConf::MOEAD(_))
+    }
+
+    // Whether candidates are orders of 0..n, moved by permutation operators or neighbourhoods
+    pub fn is_permutation(&self) -> bool {
+        match self {
+            AlgConf::CGA(conf) => conf.crossover.is_permutation() || conf.mutation.is_permutation(),
+            AlgConf::NSGA2(conf) => conf.crossover.is_permutation() || conf.mutation.is_permutation(),
+            AlgConf::TS(conf) => conf.common.neighbourhood.is_permutation(),
+            AlgConf::SA(conf) => conf.neighbourhood.is_permutation(),
+            AlgConf::AugLag(conf) => conf.inner.is_permutation(),
+            _ => false,
+        }
+    }
+
+    pub fn requires_gradient(&self) -> bool {
+        match self {
+            AlgConf::AugLag(conf) => conf.inner.requires_gradient(),
+            _ => matches!(self, AlgConf::Adam(_) | AlgConf::SGA(_) | AlgConf::LBFGS(_)),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -54,6 +116,30 @@ pub struct OptConf {
     pub atol: f64,
     #[serde(default = "default_rtol_max_iter_fraction")] 
     pub rtol_max_iter_fraction: f64,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub objective_sense: ObjectiveSense,
+    #[serde(default)]
+    pub max_evals: Option<usize>, // Budget on objective evaluations
+    #[serde(default)]
+    pub stopping: StoppingConf,
+    #[serde(default)]
+    pub record_history: bool, // Keep per-iteration statistics in the result
+    #[serde(default)]
+    pub gradient: GradientConf,
+    #[serde(default)]
+    pub bounds: Option<BoundsConf>, // Overrides ObjectiveFunction::x_lower_bound/x_upper_bound
+    #[serde(default)]
+    pub check_gradient: Option<GradientCheckConf>, // Verify the gradient at the initial population for Adam, SGA and L-BFGS
+    #[serde(default)]
+    pub constraint_handling: ConstraintHandling, // How CGA, DE, MSPO, CMA-ES and PT rank infeasible candidates
+    #[serde(default)]
+    pub linear_constraints: Option<LinearConstraintsConf>, // A x <= b and C x = d, on top of any ConstraintFunction
+    #[serde(default)]
+    pub variables: Option<Vec<VarType>>, // Per-dimension types, a single one applies to every dimension
+    #[serde(default)]
+    pub sampling: Option<SamplingConf>, // Initial population for NonConvexOpt::new_sampled
 }
 
 fn default_max_iter() -> usize { 1000 }
@@ -61,6 +147,30 @@ fn default_rtol() -> f64 { 1e-6 }
 fn default_atol() -> f64 { 1e-6 }
 fn default_rtol_max_iter_fraction() -> f64 { 1.0 }
 
+// The same values an empty JSON object deserializes to
+impl Default for OptConf {
+    fn default() -> Self {
+        OptConf {
+            max_iter: default_max_iter(),
+            rtol: default_rtol(),
+            atol: default_atol(),
+            rtol_max_iter_fraction: default_rtol_max_iter_fraction(),
+            seed: None,
+            objective_sense: ObjectiveSense::default(),
+            max_evals: None,
+            stopping: StoppingConf::default(),
+            record_history: false,
+            gradient: GradientConf::default(),
+            bounds: None,
+            check_gradient: None,
+            constraint_handling: ConstraintHandling::default(),
+            linear_constraints: None,
+            variables: None,
+            sampling: None,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("Failed to deserialize configuration: {0}")]
@@ -0,0 +1,283 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, fitness_cmp};
+use crate::utils::checkpoint::NonFinite;
+use crate::utils::rng::OptRng;
+
+// How population algorithms rank candidates that may violate the constraints.
+// Violations are the totals from OptProb::violation, zero when feasible
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub enum ConstraintHandling {
+    // Deb: feasible beats infeasible, feasible points by fitness, infeasible ones by violation
+    #[default]
+    FeasibilityRules,
+    // fitness - penalty * violation
+    StaticPenalty {
+        #[serde(default = "default_static_penalty")]
+        penalty: f64,
+    },
+    // fitness - (c * iter)^alpha * violation^beta, harsher as the run goes on
+    DynamicPenalty {
+        #[serde(default = "default_c")]
+        c: f64,
+        #[serde(default = "default_alpha")]
+        alpha: f64,
+        #[serde(default = "default_beta")]
+        beta: f64,
+    },
+    // fitness - penalty * violation. The penalty is multiplied by increase when the best of the last
+    // window generations was always infeasible, and divided by decrease when it was always feasible
+    AdaptivePenalty {
+        #[serde(default = "default_initial_penalty")]
+        initial: f64,
+        #[serde(default = "default_increase")]
+        increase: f64,
+        #[serde(default = "default_decrease")]
+        decrease: f64,
+        #[serde(default = "default_window")]
+        window: usize,
+    },
+    // Runarsson and Yao: a bubble sort where neighbours are compared by fitness with probability pf,
+    // or always when both are feasible, and by violation otherwise
+    StochasticRanking {
+        #[serde(default = "default_pf")]
+        pf: f64,
+    },
+    // Takahama and Sakai: violations up to epsilon count as feasible. Epsilon starts at the violation of
+    // the theta-quantile of the initial population and shrinks as (1 - iter/tc)^cp, reaching 0 at tc
+    Epsilon {
+        #[serde(default = "default_theta")]
+        theta: f64,
+        #[serde(default = "default_cp")]
+        cp: f64,
+        #[serde(default = "default_tc")]
+        tc: usize,
+    },
+}
+
+fn default_static_penalty() -> f64 { 1e3 }
+fn default_c() -> f64 { 0.5 }
+fn default_alpha() -> f64 { 2.0 }
+fn default_beta() -> f64 { 2.0 }
+fn default_initial_penalty() -> f64 { 1.0 }
+fn default_increase() -> f64 { 2.0 }
+fn default_decrease() -> f64 { 1.5 }
+fn default_window() -> usize { 5 }
+fn default_pf() -> f64 { 0.45 }
+fn default_theta() -> f64 { 0.2 }
+fn default_cp() -> f64 { 5.0 }
+fn default_tc() -> usize { 100 }
+
+// The comparator shared by the population algorithms, along with the little state some techniques
+// need between generations. Kept in checkpoints so resumed runs rank the same way
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConstraintHandler {
+    pub conf: ConstraintHandling,
+    pub iter: usize,
+    pub penalty: f64, // Current AdaptivePenalty coefficient
+    pub recent_feasible: VecDeque<bool>, // Whether the best was feasible, for the last AdaptivePenalty window
+    #[serde_as(as = "NonFinite")]
+    pub epsilon: f64,
+    #[serde_as(as = "NonFinite")]
+    pub epsilon0: f64,
+    #[serde_as(as = "NonFinite")]
+    pub best_violation: f64, // Violation of State::best_x, inf before anything has been seen
+}
+
+impl ConstraintHandler {
+    pub fn new(conf: ConstraintHandling) -> Self {
+        let penalty = match &conf {
+            ConstraintHandling::AdaptivePenalty { initial, .. } => *initial,
+            _ => 0.0,
+        };
+        Self {
+            conf,
+            iter: 1,
+            penalty,
+            recent_feasible: VecDeque::new(),
+            epsilon: 0.0,
+            epsilon0: 0.0,
+            best_violation: f64::INFINITY,
+        }
+    }
+
+    // Called once with the violations of the initial population
+    pub fn init<T: FloatNum>(&mut self, violation: &[T]) {
+        if let ConstraintHandling::Epsilon { theta, .. } = self.conf {
+            let mut sorted: Vec<f64> = violation.iter().map(|v| v.to_f64().unwrap()).collect();
+            sorted.sort_by(fitness_cmp);
+            let idx = ((theta * sorted.len() as f64) as usize).min(sorted.len().saturating_sub(1));
+            self.epsilon0 = sorted.get(idx).copied().unwrap_or(0.0);
+            self.epsilon = self.epsilon0;
+        }
+    }
+
+    // Called at the end of every generation, after the best has been updated
+    pub fn next_generation(&mut self) {
+        self.iter += 1;
+
+        match self.conf {
+            ConstraintHandling::AdaptivePenalty { increase, decrease, window, .. } => {
+                self.recent_feasible.push_back(self.best_violation == 0.0);
+                while self.recent_feasible.len() > window {
+                    self.recent_feasible.pop_front();
+                }
+                if self.recent_feasible.len() == window {
+                    if self.recent_feasible.iter().all(|&f| f) {
+                        self.penalty /= decrease;
+                    } else if self.recent_feasible.iter().all(|&f| !f) {
+                        self.penalty *= increase;
+                    }
+                }
+            },
+            ConstraintHandling::Epsilon { cp, tc, .. } => {
+                self.epsilon = if self.iter < tc {
+                    self.epsilon0 * (1.0 - self.iter as f64 / tc as f64).powf(cp)
+                } else {
+                    0.0
+                };
+            },
+            _ => {},
+        }
+    }
+
+    // Fitness with the penalty subtracted, unchanged for the non-penalty techniques
+    pub fn penalized<T: FloatNum>(&self, fitness: T, violation: T) -> T {
+        if violation == T::zero() {
+            return fitness;
+        }
+        let coeff = match self.conf {
+            ConstraintHandling::StaticPenalty { penalty } => penalty,
+            ConstraintHandling::DynamicPenalty { c, alpha, beta } => {
+                return fitness - T::from_f64((c * self.iter as f64).powf(alpha)).unwrap() * violation.powf(T::from_f64(beta).unwrap());
+            },
+            ConstraintHandling::AdaptivePenalty { .. } => self.penalty,
+            _ => return fitness,
+        };
+        fitness - T::from_f64(coeff).unwrap() * violation
+    }
+
+    // Greater when a is the better candidate
+    pub fn compare<T: FloatNum>(&self, fa: T, va: T, fb: T, vb: T) -> Ordering {
+        match self.conf {
+            ConstraintHandling::StaticPenalty { .. }
+            | ConstraintHandling::DynamicPenalty { .. }
+            | ConstraintHandling::AdaptivePenalty { .. } => fitness_cmp(&self.penalized(fa, va), &self.penalized(fb, vb)),
+            ConstraintHandling::Epsilon { .. } => {
+                let eps = T::from_f64(self.epsilon).unwrap();
+                if (va <= eps && vb <= eps) || va == vb {
+                    fitness_cmp(&fa, &fb)
+                } else {
+                    fitness_cmp(&vb, &va)
+                }
+            },
+            // Deterministic, the pf draw is left to rank and duel
+            ConstraintHandling::FeasibilityRules | ConstraintHandling::StochasticRanking { .. } => feasibility_rules(fa, va, fb, vb),
+        }
+    }
+
+    pub fn better<T: FloatNum>(&self, fa: T, va: T, fb: T, vb: T) -> bool {
+        self.compare(fa, va, fb, vb) == Ordering::Greater
+    }
+
+    // Whether a wins a one-on-one selection such as DE's trial against target. Stochastic ranking
+    // compares by fitness with probability pf, as for a neighbour pair in rank, the rest like better
+    pub fn duel<T: FloatNum>(&self, fa: T, va: T, fb: T, vb: T, rng: &mut OptRng) -> bool {
+        match self.conf {
+            ConstraintHandling::StochasticRanking { pf } => {
+                let by_fitness = (va == T::zero() && vb == T::zero()) || rng.random::<f64>() < pf;
+                if by_fitness {
+                    fitness_cmp(&fa, &fb) == Ordering::Greater
+                } else {
+                    fitness_cmp(&vb, &va) == Ordering::Greater
+                }
+            },
+            _ => self.better(fa, va, fb, vb),
+        }
+    }
+
+    // Indices ordered best first. Only stochastic ranking draws from rng
+    pub fn rank<T: FloatNum>(&self, fitness: &[T], violation: &[T], rng: &mut OptRng) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..fitness.len()).collect();
+
+        if let ConstraintHandling::StochasticRanking { pf } = self.conf {
+            for _ in 0..indices.len() {
+                let mut swapped = false;
+                for j in 0..indices.len().saturating_sub(1) {
+                    let (a, b) = (indices[j], indices[j + 1]);
+                    let by_fitness = (violation[a] == T::zero() && violation[b] == T::zero()) || rng.random::<f64>() < pf;
+                    let swap = if by_fitness {
+                        fitness_cmp(&fitness[b], &fitness[a]) == Ordering::Greater
+                    } else {
+                        fitness_cmp(&violation[a], &violation[b]) == Ordering::Greater
+                    };
+                    if swap {
+                        indices.swap(j, j + 1);
+                        swapped = true;
+                    }
+                }
+                if !swapped {
+                    break;
+                }
+            }
+        } else {
+            indices.sort_by(|&i, &j| self.compare(fitness[j], violation[j], fitness[i], violation[i]));
+        }
+        indices
+    }
+
+    // A single score per candidate that orders them like rank does, for fitness-proportionate operators.
+    // Rule-based techniques score infeasible points below the worst feasible one, as in Deb's paper
+    pub fn scores<T: FloatNum>(&self, fitness: &[T], violation: &[T], rng: &mut OptRng) -> Vec<T> {
+        let threshold = match self.conf {
+            ConstraintHandling::FeasibilityRules => T::zero(),
+            ConstraintHandling::Epsilon { .. } => T::from_f64(self.epsilon).unwrap(),
+            ConstraintHandling::StochasticRanking { .. } => {
+                let n = fitness.len();
+                let mut scores = vec![T::zero(); n];
+                for (pos, idx) in self.rank(fitness, violation, rng).into_iter().enumerate() {
+                    scores[idx] = T::from_usize(n - pos).unwrap();
+                }
+                return scores;
+            },
+            _ => return fitness.iter().zip(violation).map(|(&f, &v)| self.penalized(f, v)).collect(),
+        };
+
+        let worst_feasible = fitness.iter()
+            .zip(violation)
+            .filter(|(f, &v)| v <= threshold && f.is_finite())
+            .fold(None, |acc: Option<T>, (&f, _)| Some(acc.map_or(f, |a| a.min(f))))
+            .unwrap_or(T::zero());
+        fitness.iter()
+            .zip(violation)
+            .map(|(&f, &v)| if v <= threshold { f } else { worst_feasible - v })
+            .collect()
+    }
+
+    // Whether a candidate should replace the best found so far. Always by the feasibility rules,
+    // whatever the technique, so a feasible best is never traded for an infeasible one
+    pub fn update_best<T: FloatNum>(&mut self, fitness: T, violation: T, best_f: T) -> bool {
+        let best_violation = T::from_f64(self.best_violation).unwrap();
+        if feasibility_rules(fitness, violation, best_f, best_violation) == Ordering::Greater {
+            self.best_violation = violation.to_f64().unwrap();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// Deb's rules on their own, for places that rank before any handler state exists
+pub fn feasibility_rules<T: FloatNum>(fa: T, va: T, fb: T, vb: T) -> Ordering {
+    match (va == T::zero(), vb == T::zero()) {
+        (true, true) => fitness_cmp(&fa, &fb),
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => fitness_cmp(&vb, &va).then_with(|| fitness_cmp(&fa, &fb)),
+    }
+}
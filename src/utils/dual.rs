@@ -0,0 +1,412 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::num::FpCategory;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign,
+    Neg, Rem, RemAssign, Sub, SubAssign
+};
+use num_traits::{Float, FromPrimitive, Num, NumCast, One, ToPrimitive, Zero};
+use simba::scalar::SubsetOf;
+
+use crate::utils::opt_prob::FloatNumber as FloatNum;
+
+// Dual number re + eps * ε with ε² = 0. Evaluating f at x + ε carries f'(x) along in eps,
+// so any objective written over T: FloatNumber can be differentiated exactly in forward mode.
+// Comparisons only look at re, so branches in the objective behave as they do for floats
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Dual<T> {
+    pub re: T,
+    pub eps: T,
+}
+
+// Nested duals carry second derivatives: with x + ε1 e_i + ε2 e_j, the ε1ε2 part is d²f / dx_i dx_j
+pub type HyperDual<T> = Dual<Dual<T>>;
+
+impl<T: FloatNum> Dual<T> {
+    pub fn new(re: T, eps: T) -> Self {
+        Self { re, eps }
+    }
+
+    pub fn constant(re: T) -> Self {
+        Self { re, eps: T::zero() }
+    }
+
+    pub fn variable(re: T) -> Self {
+        Self { re, eps: T::one() }
+    }
+
+    // Applies a function with value f and derivative df at re
+    fn chain(self, f: T, df: T) -> Self {
+        Self { re: f, eps: df * self.eps }
+    }
+
+    fn constant_part(self, re: T) -> Self {
+        Self::constant(re)
+    }
+}
+
+impl<T: FloatNum> From<T> for Dual<T> {
+    fn from(re: T) -> Self {
+        Self::constant(re)
+    }
+}
+
+impl<T: FloatNum + fmt::Display> fmt::Display for Dual<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} + {}ε", self.re, self.eps)
+    }
+}
+
+impl<T: FloatNum> PartialEq for Dual<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.re == other.re
+    }
+}
+
+impl<T: FloatNum> PartialOrd for Dual<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.re.partial_cmp(&other.re)
+    }
+}
+
+impl<T: FloatNum> Add for Dual<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.eps + rhs.eps)
+    }
+}
+
+impl<T: FloatNum> Sub for Dual<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.eps - rhs.eps)
+    }
+}
+
+impl<T: FloatNum> Mul for Dual<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.re * rhs.re, self.eps * rhs.re + self.re * rhs.eps)
+    }
+}
+
+impl<T: FloatNum> Div for Dual<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let q = self.re / rhs.re;
+        Self::new(q, (self.eps - q * rhs.eps) / rhs.re)
+    }
+}
+
+impl<T: FloatNum> Rem for Dual<T> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        Self::new(self.re % rhs.re, self.eps - (self.re / rhs.re).trunc() * rhs.eps)
+    }
+}
+
+impl<T: FloatNum> Neg for Dual<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.eps)
+    }
+}
+
+impl<T: FloatNum> AddAssign for Dual<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: FloatNum> SubAssign for Dual<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: FloatNum> MulAssign for Dual<T> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: FloatNum> DivAssign for Dual<T> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T: FloatNum> RemAssign for Dual<T> {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+impl<T: FloatNum> Zero for Dual<T> {
+    fn zero() -> Self {
+        Self::constant(T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.re.is_zero()
+    }
+}
+
+impl<T: FloatNum> One for Dual<T> {
+    fn one() -> Self {
+        Self::constant(T::one())
+    }
+}
+
+impl<T: FloatNum> Num for Dual<T> {
+    type FromStrRadixErr = T::FromStrRadixErr;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        T::from_str_radix(s, radix).map(Self::constant)
+    }
+}
+
+impl<T: FloatNum> ToPrimitive for Dual<T> {
+    fn to_i64(&self) -> Option<i64> {
+        self.re.to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.re.to_u64()
+    }
+
+    fn to_f32(&self) -> Option<f32> {
+        self.re.to_f32()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.re.to_f64()
+    }
+}
+
+impl<T: FloatNum> NumCast for Dual<T> {
+    fn from<N: ToPrimitive>(n: N) -> Option<Self> {
+        <T as NumCast>::from(n).map(Self::constant)
+    }
+}
+
+impl<T: FloatNum> FromPrimitive for Dual<T> {
+    fn from_i64(n: i64) -> Option<Self> {
+        T::from_i64(n).map(Self::constant)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        T::from_u64(n).map(Self::constant)
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        T::from_f64(n).map(Self::constant)
+    }
+}
+
+impl<T: FloatNum> SubsetOf<f64> for Dual<T> {
+    fn to_superset(&self) -> f64 {
+        self.re.to_superset()
+    }
+
+    fn from_superset_unchecked(element: &f64) -> Self {
+        Self::constant(T::from_superset_unchecked(element))
+    }
+
+    fn is_in_subset(element: &f64) -> bool {
+        T::is_in_subset(element)
+    }
+}
+
+impl<T: FloatNum> Float for Dual<T> {
+    fn nan() -> Self { Self::constant(T::nan()) }
+    fn infinity() -> Self { Self::constant(T::infinity()) }
+    fn neg_infinity() -> Self { Self::constant(T::neg_infinity()) }
+    fn neg_zero() -> Self { Self::constant(T::neg_zero()) }
+    fn min_value() -> Self { Self::constant(T::min_value()) }
+    fn min_positive_value() -> Self { Self::constant(T::min_positive_value()) }
+    fn max_value() -> Self { Self::constant(T::max_value()) }
+    fn epsilon() -> Self { Self::constant(T::epsilon()) }
+
+    fn is_nan(self) -> bool { self.re.is_nan() }
+    fn is_infinite(self) -> bool { self.re.is_infinite() }
+    fn is_finite(self) -> bool { self.re.is_finite() }
+    fn is_normal(self) -> bool { self.re.is_normal() }
+    fn classify(self) -> FpCategory { self.re.classify() }
+    fn is_sign_positive(self) -> bool { self.re.is_sign_positive() }
+    fn is_sign_negative(self) -> bool { self.re.is_sign_negative() }
+    fn integer_decode(self) -> (u64, i16, i8) { self.re.integer_decode() }
+
+    // Piecewise constant, so the derivative is zero almost everywhere
+    fn floor(self) -> Self { self.constant_part(self.re.floor()) }
+    fn ceil(self) -> Self { self.constant_part(self.re.ceil()) }
+    fn round(self) -> Self { self.constant_part(self.re.round()) }
+    fn trunc(self) -> Self { self.constant_part(self.re.trunc()) }
+    fn signum(self) -> Self { self.constant_part(self.re.signum()) }
+
+    fn fract(self) -> Self {
+        Self::new(self.re.fract(), self.eps)
+    }
+
+    fn abs(self) -> Self {
+        self.chain(self.re.abs(), self.re.signum())
+    }
+
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        self * a + b
+    }
+
+    fn recip(self) -> Self {
+        let r = self.re.recip();
+        self.chain(r, -r * r)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        if n == 0 {
+            return Self::one();
+        }
+        self.chain(self.re.powi(n), T::from_i32(n).unwrap() * self.re.powi(n - 1))
+    }
+
+    fn powf(self, n: Self) -> Self {
+        let p = self.re.powf(n.re);
+
+        // Skip terms whose tangent is zero so e.g. 0^0.5 or (-1)^2.0 don't pick up NaN from ln or x^(n - 1)
+        let mut eps = T::zero();
+        if !self.eps.is_zero() {
+            eps += n.re * self.re.powf(n.re - T::one()) * self.eps;
+        }
+        if !n.eps.is_zero() {
+            eps += p * self.re.ln() * n.eps;
+        }
+        Self::new(p, eps)
+    }
+
+    fn sqrt(self) -> Self {
+        let s = self.re.sqrt();
+        self.chain(s, (s + s).recip())
+    }
+
+    fn cbrt(self) -> Self {
+        let c = self.re.cbrt();
+        self.chain(c, (T::from_f64(3.0).unwrap() * c * c).recip())
+    }
+
+    fn exp(self) -> Self {
+        let e = self.re.exp();
+        self.chain(e, e)
+    }
+
+    fn exp2(self) -> Self {
+        let e = self.re.exp2();
+        self.chain(e, e * T::from_f64(std::f64::consts::LN_2).unwrap())
+    }
+
+    fn exp_m1(self) -> Self {
+        self.chain(self.re.exp_m1(), self.re.exp())
+    }
+
+    fn ln(self) -> Self {
+        self.chain(self.re.ln(), self.re.recip())
+    }
+
+    fn log(self, base: Self) -> Self {
+        self.ln() / base.ln()
+    }
+
+    fn log2(self) -> Self {
+        self.chain(self.re.log2(), (self.re * T::from_f64(std::f64::consts::LN_2).unwrap()).recip())
+    }
+
+    fn log10(self) -> Self {
+        self.chain(self.re.log10(), (self.re * T::from_f64(std::f64::consts::LN_10).unwrap()).recip())
+    }
+
+    fn ln_1p(self) -> Self {
+        self.chain(self.re.ln_1p(), (T::one() + self.re).recip())
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other.re.is_nan() || self.re >= other.re { self } else { other }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other.re.is_nan() || self.re <= other.re { self } else { other }
+    }
+
+    fn abs_sub(self, other: Self) -> Self {
+        if self.re <= other.re { Self::zero() } else { self - other }
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        let h = self.re.hypot(other.re);
+        Self::new(h, (self.re * self.eps + other.re * other.eps) / h)
+    }
+
+    fn sin(self) -> Self {
+        self.chain(self.re.sin(), self.re.cos())
+    }
+
+    fn cos(self) -> Self {
+        self.chain(self.re.cos(), -self.re.sin())
+    }
+
+    fn tan(self) -> Self {
+        let t = self.re.tan();
+        self.chain(t, T::one() + t * t)
+    }
+
+    fn sin_cos(self) -> (Self, Self) {
+        (self.sin(), self.cos())
+    }
+
+    fn asin(self) -> Self {
+        self.chain(self.re.asin(), (T::one() - self.re * self.re).sqrt().recip())
+    }
+
+    fn acos(self) -> Self {
+        self.chain(self.re.acos(), -(T::one() - self.re * self.re).sqrt().recip())
+    }
+
+    fn atan(self) -> Self {
+        self.chain(self.re.atan(), (T::one() + self.re * self.re).recip())
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        let r2 = self.re * self.re + other.re * other.re;
+        Self::new(self.re.atan2(other.re), (other.re * self.eps - self.re * other.eps) / r2)
+    }
+
+    fn sinh(self) -> Self {
+        self.chain(self.re.sinh(), self.re.cosh())
+    }
+
+    fn cosh(self) -> Self {
+        self.chain(self.re.cosh(), self.re.sinh())
+    }
+
+    fn tanh(self) -> Self {
+        let t = self.re.tanh();
+        self.chain(t, T::one() - t * t)
+    }
+
+    fn asinh(self) -> Self {
+        self.chain(self.re.asinh(), (self.re * self.re + T::one()).sqrt().recip())
+    }
+
+    fn acosh(self) -> Self {
+        self.chain(self.re.acosh(), (self.re * self.re - T::one()).sqrt().recip())
+    }
+
+    fn atanh(self) -> Self {
+        self.chain(self.re.atanh(), (T::one() - self.re * self.re).recip())
+    }
+}
+
+impl<T: FloatNum> FloatNum for Dual<T> {}
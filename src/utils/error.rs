@@ -0,0 +1,33 @@
+use thiserror::Error;
+
+use crate::utils::checkpoint::CheckpointError;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum OptError {
+    #[error("{alg} requires the objective to provide a gradient")]
+    MissingGradient { alg: String },
+
+    #[error("Invalid bounds: {0}")]
+    InvalidBounds(String),
+
+    #[error("Invalid linear constraints: {0}")]
+    InvalidLinearConstraints(String),
+
+    #[error("Invalid variable types: {0}")]
+    InvalidVariables(String),
+
+    #[error("Invalid initial population for {alg}: {reason}")]
+    InvalidPopulation { alg: String, reason: String },
+
+    #[error("Invalid config for {alg}: {reason}")]
+    InvalidConfig { alg: String, reason: String },
+
+    #[error("Analytic gradient {analytic} differs from finite differences {numeric} in coordinate {coord} (relative error {rel_error})")]
+    GradientMismatch { coord: usize, analytic: f64, numeric: f64, rel_error: f64 },
+
+    #[error("Objective returned {value} at {x:?}")]
+    NonFiniteObjective { x: Vec<f64>, value: f64 },
+
+    #[error(transparent)]
+    Checkpoint(#[from] CheckpointError),
+}
@@ -0,0 +1,222 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use nalgebra::{
+    allocator::Allocator,
+    Complex,
+    DefaultAllocator,
+    Dim,
+    OVector,
+    U1
+};
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, ObjectiveFunction};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FiniteDiffMethod {
+    Forward, // n + 1 evaluations, error O(h)
+    #[default]
+    Central, // 2n evaluations, error O(h^2)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum StepSize {
+    #[default]
+    Adaptive, // Scaled to |x_i| and the machine precision of T
+    Fixed { h: f64 },
+}
+
+// How gradient-based algorithms get their gradients
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum GradientConf {
+    #[default]
+    Analytic, // ObjectiveFunction::gradient
+    FiniteDifference {
+        #[serde(default)]
+        method: FiniteDiffMethod,
+        #[serde(default)]
+        step: StepSize,
+    },
+    // Needs an objective that returns itself from ObjectiveFunction::as_complex
+    ComplexStep {
+        #[serde(default = "default_complex_step")]
+        h: f64,
+    },
+}
+
+fn default_complex_step() -> f64 { 1e-20 }
+
+impl StepSize {
+    fn h<T: FloatNum>(&self, xi: T, method: FiniteDiffMethod) -> T {
+        let h = match self {
+            StepSize::Fixed { h } => T::from_f64(*h).unwrap(),
+            StepSize::Adaptive => {
+                // Balances truncation against round-off error
+                let rel = match method {
+                    FiniteDiffMethod::Forward => T::epsilon().sqrt(),
+                    FiniteDiffMethod::Central => T::epsilon().cbrt(),
+                };
+                rel * xi.abs().max(T::one())
+            },
+        };
+
+        // Makes h exactly representable as a difference of x values
+        (xi + h) - xi
+    }
+}
+
+fn perturbed<T, D>(x: &[T], d: D, i: usize, h: T) -> OVector<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    let mut xh = OVector::<T, D>::from_iterator_generic(d, U1, x.iter().cloned());
+    xh[i] += h;
+    xh
+}
+
+// Wraps a black-box objective and provides its gradient numerically, one partial derivative per rayon task.
+// Each gradient counts as one gradient evaluation plus its n + 1 (forward) or 2n (central) objective calls
+#[derive(Debug, Clone)]
+pub struct FiniteDifference<F> {
+    pub objective: F,
+    pub method: FiniteDiffMethod,
+    pub step: StepSize,
+}
+
+impl<F> FiniteDifference<F> {
+    pub fn new(objective: F, method: FiniteDiffMethod, step: StepSize) -> Self {
+        Self { objective, method, step }
+    }
+
+    pub fn forward(objective: F) -> Self {
+        Self::new(objective, FiniteDiffMethod::Forward, StepSize::Adaptive)
+    }
+
+    pub fn central(objective: F) -> Self {
+        Self::new(objective, FiniteDiffMethod::Central, StepSize::Adaptive)
+    }
+}
+
+impl<T, D, F> ObjectiveFunction<T, D> for FiniteDifference<F>
+where
+    T: FloatNum,
+    D: Dim,
+    F: ObjectiveFunction<T, D> + Clone + 'static,
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<T, D>) -> T {
+        self.objective.f(x)
+    }
+
+    fn gradient(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        let d = x.shape_generic().0;
+        let xs: Vec<T> = x.iter().cloned().collect();
+        let f0 = match self.method {
+            FiniteDiffMethod::Forward => self.objective.f(x),
+            FiniteDiffMethod::Central => T::zero(),
+        };
+
+        let grad: Vec<T> = (0..xs.len())
+            .into_par_iter()
+            .map(|i| {
+                let h = self.step.h(xs[i], self.method);
+                match self.method {
+                    FiniteDiffMethod::Forward => (self.objective.f(&perturbed(&xs, d, i, h)) - f0) / h,
+                    FiniteDiffMethod::Central => {
+                        let f_plus = self.objective.f(&perturbed(&xs, d, i, h));
+                        let f_minus = self.objective.f(&perturbed(&xs, d, i, -h));
+                        (f_plus - f_minus) / (h + h)
+                    },
+                }
+            })
+            .collect();
+
+        Some(OVector::<T, D>::from_iterator_generic(d, U1, grad))
+    }
+
+    fn gradient_evaluations(&self, dim: usize) -> usize {
+        match self.method {
+            FiniteDiffMethod::Forward => dim + 1,
+            FiniteDiffMethod::Central => 2 * dim,
+        }
+    }
+
+    fn x_lower_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        self.objective.x_lower_bound(x)
+    }
+
+    fn x_upper_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        self.objective.x_upper_bound(x)
+    }
+}
+
+// Objectives that can also be evaluated at complex points, written with the same operations as `f`.
+// Implementations also override ObjectiveFunction::as_complex to return Some(self)
+pub trait ComplexObjectiveFunction<T: FloatNum, D: Dim>: ObjectiveFunction<T, D>
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f_complex(&self, x: &OVector<Complex<T>, D>) -> Complex<T>;
+}
+
+// Complex-step derivative Im(f(x + ih e_i)) / h. Free of subtractive cancellation, so h can be tiny
+// and the gradient is exact to machine precision. No gradient when the objective has no complex form.
+// Each gradient counts its n complex calls as objective evaluations
+#[derive(Debug, Clone)]
+pub struct ComplexStep<F> {
+    pub objective: F,
+    pub h: f64,
+}
+
+impl<F> ComplexStep<F> {
+    pub fn new(objective: F) -> Self {
+        Self { objective, h: 1e-20 }
+    }
+
+    pub fn with_step(objective: F, h: f64) -> Self {
+        Self { objective, h }
+    }
+}
+
+impl<T, D, F> ObjectiveFunction<T, D> for ComplexStep<F>
+where
+    T: FloatNum,
+    D: Dim,
+    F: ObjectiveFunction<T, D> + Clone + 'static,
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<T, D>) -> T {
+        self.objective.f(x)
+    }
+
+    fn gradient(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        let objective = self.objective.as_complex()?;
+        let d = x.shape_generic().0;
+        let xs: Vec<Complex<T>> = x.iter().map(|&xi| Complex::new(xi, T::zero())).collect();
+        let h = T::from_f64(self.h).unwrap();
+
+        let grad: Vec<T> = (0..xs.len())
+            .into_par_iter()
+            .map(|i| {
+                let mut xh = OVector::<Complex<T>, D>::from_iterator_generic(d, U1, xs.iter().cloned());
+                xh[i].im = h;
+                objective.f_complex(&xh).im / h
+            })
+            .collect();
+
+        Some(OVector::<T, D>::from_iterator_generic(d, U1, grad))
+    }
+
+    fn gradient_evaluations(&self, dim: usize) -> usize {
+        if self.objective.as_complex().is_some() { dim } else { 0 }
+    }
+
+    fn x_lower_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        self.objective.x_lower_bound(x)
+    }
+
+    fn x_upper_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        self.objective.x_upper_bound(x)
+    }
+}
@@ -0,0 +1,140 @@
+use std::cmp::Ordering;
+use serde::{Deserialize, Serialize};
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    OVector
+};
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, ObjectiveFunction};
+use crate::utils::finite_diff::{FiniteDifference, FiniteDiffMethod, StepSize};
+use crate::utils::error::OptError;
+
+// A coordinate passes if either its absolute or its relative error is within tolerance
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GradientCheckConf {
+    #[serde(default = "default_rtol")]
+    pub rtol: f64,
+    #[serde(default = "default_atol")]
+    pub atol: f64,
+    #[serde(default)]
+    pub step: StepSize,
+    #[serde(default = "default_num_worst")]
+    pub num_worst: usize, // Entries kept in GradientCheck::worst
+}
+
+fn default_rtol() -> f64 { 1e-4 }
+fn default_atol() -> f64 { 1e-6 }
+fn default_num_worst() -> usize { 5 }
+
+impl Default for GradientCheckConf {
+    fn default() -> Self {
+        Self {
+            rtol: default_rtol(),
+            atol: default_atol(),
+            step: StepSize::default(),
+            num_worst: default_num_worst(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CoordinateError {
+    pub point: usize, // Index into the sample points
+    pub coord: usize,
+    pub analytic: f64,
+    pub numeric: f64,
+    pub abs_error: f64,
+    pub rel_error: f64,
+    pub passed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GradientCheck {
+    pub errors: Vec<CoordinateError>, // Every coordinate of every point, in order
+    pub worst: Vec<CoordinateError>, // Largest relative errors first
+    pub max_abs_error: f64,
+    pub max_rel_error: f64,
+    pub passed: bool,
+}
+
+impl GradientCheck {
+    // The worst failing coordinate as an error, for callers that want to bail out. Picked from every
+    // coordinate, however few are kept in worst
+    pub fn into_result(self) -> Result<Self, OptError> {
+        if self.passed {
+            return Ok(self);
+        }
+
+        let e = self.errors.iter()
+            .filter(|e| !e.passed)
+            .min_by(|a, b| by_rel_error(a, b))
+            .unwrap();
+        Err(OptError::GradientMismatch {
+            coord: e.coord,
+            analytic: e.analytic,
+            numeric: e.numeric,
+            rel_error: e.rel_error,
+        })
+    }
+}
+
+// Largest relative error first, NaN errors count as the worst
+fn by_rel_error(a: &CoordinateError, b: &CoordinateError) -> Ordering {
+    match (a.rel_error.is_nan(), b.rel_error.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => b.rel_error.partial_cmp(&a.rel_error).unwrap(),
+    }
+}
+
+// Compares ObjectiveFunction::gradient with central differences at each sample point.
+// Calls the objective directly, so nothing here counts towards evaluation budgets
+pub fn check_gradient<T, D, F>(obj: &F, x: &[OVector<T, D>], opts: &GradientCheckConf) -> Result<GradientCheck, OptError>
+where
+    T: FloatNum,
+    D: Dim,
+    F: ObjectiveFunction<T, D> + ?Sized,
+    DefaultAllocator: Allocator<D>
+{
+    let numeric_obj = FiniteDifference::new(obj.clone_box(), FiniteDiffMethod::Central, opts.step);
+
+    let mut errors = Vec::new();
+    for (point, xi) in x.iter().enumerate() {
+        let analytic = obj.gradient(xi).ok_or_else(|| OptError::MissingGradient { alg: "check_gradient".to_string() })?;
+        let numeric = numeric_obj.gradient(xi).unwrap();
+
+        for coord in 0..xi.len() {
+            let a = analytic[coord].to_f64().unwrap();
+            let n = numeric[coord].to_f64().unwrap();
+            let abs_error = (a - n).abs();
+            let scale = a.abs().max(n.abs());
+            let rel_error = if abs_error == 0.0 { 0.0 } else { abs_error / scale };
+
+            errors.push(CoordinateError {
+                point,
+                coord,
+                analytic: a,
+                numeric: n,
+                abs_error,
+                rel_error,
+                passed: abs_error <= opts.atol || rel_error <= opts.rtol,
+            });
+        }
+    }
+
+    // Failing coordinates first, then by relative error
+    let mut worst = errors.clone();
+    worst.sort_by(|a, b| a.passed.cmp(&b.passed).then_with(|| by_rel_error(a, b)));
+    worst.truncate(opts.num_worst);
+
+    Ok(GradientCheck {
+        max_abs_error: errors.iter().map(|e| e.abs_error).fold(0.0, f64::max),
+        max_rel_error: errors.iter().map(|e| e.rel_error).fold(0.0, f64::max),
+        passed: errors.iter().all(|e| e.passed),
+        errors,
+        worst,
+    })
+}
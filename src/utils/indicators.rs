@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::opt_prob::ObjectiveSense;
+
+// Quality indicators over plain sets of objective vectors, such as ParetoFront::f or a front produced
+// elsewhere. All of them assume every objective is minimized, Indicator::evaluate flips maximized fronts
+
+// Whether a is no worse than b in every objective and strictly better in one
+fn dominates(a: &[f64], b: &[f64]) -> bool {
+    a.iter().zip(b.iter()).all(|(ai, bi)| ai <= bi) && a.iter().zip(b.iter()).any(|(ai, bi)| ai < bi)
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(ai, bi)| (ai - bi).powi(2)).sum::<f64>().sqrt()
+}
+
+// Non-dominated points of a set, duplicates kept once
+fn non_dominated(points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    points.iter().enumerate()
+        .filter(|&(i, p)| !points.iter().enumerate().any(|(j, q)| dominates(q, p) || (j < i && q == p)))
+        .map(|(_, p)| p.clone())
+        .collect()
+}
+
+// Volume dominated by front and bounded by reference. Exact sweeps for up to 3 objectives, WFG beyond
+pub fn hypervolume(front: &[Vec<f64>], reference: &[f64]) -> f64 {
+    exact_or_wfg(inside(front, reference), reference)
+}
+
+// The WFG algorithm for any number of objectives, summing the volume each point adds over the ones after it
+pub fn wfg_hypervolume(front: &[Vec<f64>], reference: &[f64]) -> f64 {
+    let points = inside(front, reference);
+    if points.is_empty() || reference.is_empty() { 0.0 } else { wfg(points, reference) }
+}
+
+// Non-dominated points strictly better than reference, the others add no volume
+fn inside(front: &[Vec<f64>], reference: &[f64]) -> Vec<Vec<f64>> {
+    let points: Vec<Vec<f64>> = front.iter()
+        .filter(|p| p.len() == reference.len() && p.iter().zip(reference.iter()).all(|(pi, ri)| pi < ri))
+        .cloned()
+        .collect();
+    non_dominated(&points)
+}
+
+fn exact_or_wfg(points: Vec<Vec<f64>>, reference: &[f64]) -> f64 {
+    match reference.len() {
+        0 => 0.0,
+        1 => points.iter().map(|p| reference[0] - p[0]).fold(0.0, f64::max),
+        2 => hypervolume_2d(points, reference),
+        3 => hypervolume_3d(points, reference),
+        _ => wfg(points, reference),
+    }
+}
+
+// Sweep along the first objective, each point adds the strip below the previous one
+fn hypervolume_2d(mut points: Vec<Vec<f64>>, reference: &[f64]) -> f64 {
+    points.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    let mut volume = 0.0;
+    let mut ceiling = reference[1];
+    for p in points {
+        if p[1] < ceiling {
+            volume += (reference[0] - p[0]) * (ceiling - p[1]);
+            ceiling = p[1];
+        }
+    }
+    volume
+}
+
+// Slices along the third objective, each slab is the area of the points below it times its height
+fn hypervolume_3d(mut points: Vec<Vec<f64>>, reference: &[f64]) -> f64 {
+    points.sort_by(|a, b| a[2].total_cmp(&b[2]));
+    let mut volume = 0.0;
+    for k in 0..points.len() {
+        let top = points.get(k + 1).map_or(reference[2], |p| p[2]);
+        if top > points[k][2] {
+            volume += hypervolume_2d(points[..=k].to_vec(), reference) * (top - points[k][2]);
+        }
+    }
+    volume
+}
+
+fn wfg(mut points: Vec<Vec<f64>>, reference: &[f64]) -> f64 {
+    // Sorting on the last objective keeps the limited sets small
+    let last = reference.len() - 1;
+    points.sort_by(|a, b| b[last].total_cmp(&a[last]));
+
+    let mut volume = 0.0;
+    for (k, p) in points.iter().enumerate() {
+        let inclusive: f64 = p.iter().zip(reference.iter()).map(|(pi, ri)| ri - pi).product();
+        // The rest of the set, limited to the part p also dominates
+        let limited: Vec<Vec<f64>> = points[k + 1..].iter()
+            .map(|q| p.iter().zip(q.iter()).map(|(&pi, &qi)| pi.max(qi)).collect())
+            .collect();
+        volume += inclusive - wfg_or_exact(non_dominated(&limited), reference);
+    }
+    volume
+}
+
+fn wfg_or_exact(points: Vec<Vec<f64>>, reference: &[f64]) -> f64 {
+    match points.len() {
+        0 => 0.0,
+        1 => points[0].iter().zip(reference.iter()).map(|(pi, ri)| ri - pi).product(),
+        _ => exact_or_wfg(points, reference),
+    }
+}
+
+// Mean distance from each reference point to the closest point of front
+pub fn igd(front: &[Vec<f64>], reference_front: &[Vec<f64>]) -> f64 {
+    mean_closest(front, reference_front, distance)
+}
+
+// IGD counting only the objectives where the front is worse than the reference point, so a front
+// that dominates the reference scores zero
+pub fn igd_plus(front: &[Vec<f64>], reference_front: &[Vec<f64>]) -> f64 {
+    let modified = |a: &[f64], r: &[f64]| a.iter().zip(r.iter()).map(|(ai, ri)| (ai - ri).max(0.0).powi(2)).sum::<f64>().sqrt();
+    mean_closest(front, reference_front, modified)
+}
+
+fn mean_closest(front: &[Vec<f64>], reference_front: &[Vec<f64>], d: impl Fn(&[f64], &[f64]) -> f64) -> f64 {
+    if reference_front.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = reference_front.iter()
+        .map(|r| front.iter().map(|a| d(a, r)).fold(f64::INFINITY, f64::min))
+        .sum();
+    total / reference_front.len() as f64
+}
+
+// Generalized spread: 0 for points evenly spaced out to the extremes of the reference front, larger
+// when they cluster or miss the extremes. Objectives aren't normalized, so scale them first if their
+// ranges differ widely
+pub fn spread(front: &[Vec<f64>], reference_front: &[Vec<f64>]) -> f64 {
+    if front.len() < 2 || reference_front.is_empty() {
+        return 1.0;
+    }
+
+    // The reference point with the largest value in each objective
+    let num_objectives = reference_front[0].len();
+    let extremes: f64 = (0..num_objectives)
+        .map(|m| reference_front.iter().max_by(|a, b| a[m].total_cmp(&b[m])).unwrap())
+        .map(|e| front.iter().map(|a| distance(a, e)).fold(f64::INFINITY, f64::min))
+        .sum();
+
+    let nearest: Vec<f64> = front.iter().enumerate()
+        .map(|(i, a)| front.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, b)| distance(a, b)).fold(f64::INFINITY, f64::min))
+        .collect();
+    let mean = nearest.iter().sum::<f64>() / nearest.len() as f64;
+    let deviation: f64 = nearest.iter().map(|d| (d - mean).abs()).sum();
+
+    let denominator = extremes + front.len() as f64 * mean;
+    if denominator > 0.0 { (extremes + deviation) / denominator } else { 1.0 }
+}
+
+// Smallest amount every objective of front has to be shifted by for it to weakly dominate the reference
+// front, negative when it already dominates it with room to spare
+pub fn additive_epsilon(front: &[Vec<f64>], reference_front: &[Vec<f64>]) -> f64 {
+    reference_front.iter()
+        .map(|r| front.iter()
+            .map(|a| a.iter().zip(r.iter()).map(|(ai, ri)| ai - ri).fold(f64::NEG_INFINITY, f64::max))
+            .fold(f64::INFINITY, f64::min))
+        .fold(f64::NEG_INFINITY, f64::max)
+}
+
+// An indicator to track during a run with NonConvexOpt::add_indicator, reported in the diagnostics
+// under name(). Reference points and fronts are in the objectives' own units
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum Indicator {
+    Hypervolume { reference: Vec<f64> },
+    IGD { reference_front: Vec<Vec<f64>> },
+    IGDPlus { reference_front: Vec<Vec<f64>> },
+    Spread { reference_front: Vec<Vec<f64>> },
+    Epsilon { reference_front: Vec<Vec<f64>> },
+}
+
+impl Indicator {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Indicator::Hypervolume { .. } => "hypervolume",
+            Indicator::IGD { .. } => "igd",
+            Indicator::IGDPlus { .. } => "igd_plus",
+            Indicator::Spread { .. } => "spread",
+            Indicator::Epsilon { .. } => "epsilon",
+        }
+    }
+
+    // Maximized objectives are negated, along with the references, so the minimizing definitions apply
+    pub fn evaluate(&self, front: &[Vec<f64>], sense: ObjectiveSense) -> f64 {
+        let to_min = |p: &Vec<f64>| -> Vec<f64> { p.iter().map(|&v| -sense.apply(v)).collect() };
+        let flip = |points: &[Vec<f64>]| -> Vec<Vec<f64>> { points.iter().map(to_min).collect() };
+        let front = flip(front);
+        match self {
+            Indicator::Hypervolume { reference } => hypervolume(&front, &to_min(reference)),
+            Indicator::IGD { reference_front } => igd(&front, &flip(reference_front)),
+            Indicator::IGDPlus { reference_front } => igd_plus(&front, &flip(reference_front)),
+            Indicator::Spread { reference_front } => spread(&front, &flip(reference_front)),
+            Indicator::Epsilon { reference_front } => additive_epsilon(&front, &flip(reference_front)),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use rand::Rng;
+use rand_distr::StandardNormal;
+use serde::{Deserialize, Serialize};
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    DMatrix,
+    DVector,
+    Dyn,
+    OMatrix,
+    OVector,
+    U1
+};
+
+use crate::utils::opt_prob::FloatNumber as FloatNum;
+use crate::utils::bounds::Bounds;
+use crate::utils::error::OptError;
+use crate::utils::rng::OptRng;
+
+// Linear constraints A x <= b and C x = d, kept apart from the black-box ones so points can be
+// projected onto the polytope and sampled from it instead of only being scored
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearConstraints<T: FloatNum> {
+    pub a: DMatrix<T>,
+    pub b: DVector<T>,
+    pub c: DMatrix<T>,
+    pub d: DVector<T>,
+    pub tol: T, // Residuals up to tol count as satisfied
+    pub project: bool, // Whether OptProb::repair projects candidates onto the polytope
+    q: DMatrix<T>, // Orthonormal rows spanning the rows of C, with q x = e on the same affine set
+    e: DVector<T>,
+}
+
+const MAX_PROJECTION_ITERS: usize = 1000;
+
+impl<T: FloatNum> LinearConstraints<T> {
+    pub fn new(a: DMatrix<T>, b: DVector<T>, c: DMatrix<T>, d: DVector<T>) -> Result<Self, OptError> {
+        let invalid = |reason: String| Err(OptError::InvalidLinearConstraints(reason));
+
+        if a.nrows() != b.len() || c.nrows() != d.len() {
+            return invalid(format!("A has {} rows for {} values of b, C has {} rows for {} values of d", a.nrows(), b.len(), c.nrows(), d.len()));
+        }
+        if a.nrows() > 0 && c.nrows() > 0 && a.ncols() != c.ncols() {
+            return invalid(format!("A has {} columns but C has {}", a.ncols(), c.ncols()));
+        }
+        if a.iter().chain(b.iter()).chain(c.iter()).chain(d.iter()).any(|v| !v.is_finite()) {
+            return invalid("coefficients contain NaN or inf".to_string());
+        }
+
+        let tol = T::from_f64(1e-8).unwrap();
+        let (q, e) = orthonormalize(&c, &d, tol)?;
+        Ok(Self { a, b, c, d, tol, project: true, q, e })
+    }
+
+    pub fn inequalities(a: DMatrix<T>, b: DVector<T>) -> Result<Self, OptError> {
+        let n = a.ncols();
+        Self::new(a, b, DMatrix::zeros(0, n), DVector::zeros(0))
+    }
+
+    pub fn equalities(c: DMatrix<T>, d: DVector<T>) -> Result<Self, OptError> {
+        let n = c.ncols();
+        Self::new(DMatrix::zeros(0, n), DVector::zeros(0), c, d)
+    }
+
+    pub fn with_tol(mut self, tol: T) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    pub fn with_project(mut self, project: bool) -> Self {
+        self.project = project;
+        self
+    }
+
+    pub fn dim(&self) -> usize {
+        if self.a.nrows() > 0 { self.a.ncols() } else { self.c.ncols() }
+    }
+
+    // A x - b and C x - d
+    pub fn residuals<D: Dim>(&self, x: &OVector<T, D>) -> (DVector<T>, DVector<T>)
+    where
+        DefaultAllocator: Allocator<D>
+    {
+        let x = to_dvector(x);
+        (&self.a * &x - &self.b, &self.c * &x - &self.d)
+    }
+
+    // Total amount beyond tol, zero when x is feasible
+    pub fn violation<D: Dim>(&self, x: &OVector<T, D>) -> T
+    where
+        DefaultAllocator: Allocator<D>
+    {
+        let (g, h) = self.residuals(x);
+        let ineq = g.iter().fold(T::zero(), |acc, &gi| acc + (gi - self.tol).max(T::zero()));
+        let eq = h.iter().fold(T::zero(), |acc, &hj| acc + (hj.abs() - self.tol).max(T::zero()));
+        ineq + eq
+    }
+
+    pub fn is_feasible<D: Dim>(&self, x: &OVector<T, D>) -> bool
+    where
+        DefaultAllocator: Allocator<D>
+    {
+        self.violation(x) == T::zero()
+    }
+
+    // Closest point of the polytope, intersected with the box when bounds are given, found with Dykstra's
+    // alternating projections. An empty region leaves the result infeasible, so check it when that can happen
+    pub fn project<D: Dim>(&self, x: &OVector<T, D>, bounds: Option<&Bounds<T, D>>) -> OVector<T, D>
+    where
+        DefaultAllocator: Allocator<D>
+    {
+        let n = x.len();
+        let mut y = to_dvector(x);
+        let mut increments = vec![DVector::<T>::zeros(n); self.a.nrows() + 1];
+        let row_norms: Vec<T> = (0..self.a.nrows()).map(|i| self.a.row(i).dot(&self.a.row(i))).collect();
+
+        y = self.project_affine(&y);
+        for _ in 0..MAX_PROJECTION_ITERS {
+            let previous = y.clone();
+
+            for i in 0..self.a.nrows() {
+                let shifted = &y + &increments[i];
+                let excess = self.a.row(i).transpose().dot(&shifted) - self.b[i];
+                let projected = if excess > T::zero() && row_norms[i] > T::zero() {
+                    &shifted - self.a.row(i).transpose() * (excess / row_norms[i])
+                } else {
+                    shifted.clone()
+                };
+                increments[i] = shifted - &projected;
+                y = projected;
+            }
+
+            if let Some(bounds) = bounds {
+                let shifted = &y + &increments[self.a.nrows()];
+                let projected = DVector::from_iterator(n, (0..n).map(|i| shifted[i].max(bounds.lower[i]).min(bounds.upper[i])));
+                increments[self.a.nrows()] = shifted - &projected;
+                y = projected;
+            }
+
+            // Equalities last, so they hold to rounding however early the loop stops
+            y = self.project_affine(&y);
+
+            // Well inside tol, so the final clamp below can't undo the equalities
+            let step = (&y - &previous).iter().fold(T::zero(), |acc, &v| acc.max(v.abs()));
+            if step <= self.tol * T::from_f64(1e-2).unwrap() && self.violation::<Dyn>(&y) == T::zero() {
+                break;
+            }
+        }
+
+        // The box holds exactly, since algorithms test it with Bounds::contains
+        if let Some(bounds) = bounds {
+            for i in 0..n {
+                y[i] = y[i].max(bounds.lower[i]).min(bounds.upper[i]);
+            }
+        }
+        from_dvector(&y)
+    }
+
+    // Uniform samples from the polytope (within the box, if given) by hit-and-run. Starts from the projection
+    // of start and takes thin steps between samples. Every direction has to be bounded, by the
+    // constraints or the box
+    pub fn sample<D: Dim>(&self, start: &OVector<T, D>, n: usize, thin: usize, bounds: Option<&Bounds<T, D>>, rng: &mut OptRng) -> Result<Vec<OVector<T, D>>, OptError>
+    where
+        DefaultAllocator: Allocator<D>
+    {
+        let dim = start.len();
+        let x = self.project(start, bounds);
+        if !self.is_feasible(&x) || bounds.is_some_and(|b| !b.contains(&x)) {
+            return Err(OptError::InvalidLinearConstraints("no feasible point was found to start sampling from".to_string()));
+        }
+        let mut x = to_dvector(&x);
+
+        let mut samples = Vec::with_capacity(n);
+        for _ in 0..n {
+            for _ in 0..thin.max(1) {
+                // A random direction within the equality constraints' null space
+                let z = DVector::<T>::from_fn(dim, |_, _| T::from_f64(rng.sample(StandardNormal)).unwrap());
+                let dir = &z - self.q.transpose() * (&self.q * &z);
+                let norm = dir.dot(&dir).sqrt();
+                if norm <= T::epsilon() {
+                    break;
+                }
+                let dir = dir / norm;
+
+                // Chord of the polytope through x along dir
+                let (mut lo, mut hi) = (T::neg_infinity(), T::infinity());
+                for i in 0..self.a.nrows() {
+                    let rate = self.a.row(i).transpose().dot(&dir);
+                    let slack = (self.b[i] - self.a.row(i).transpose().dot(&x)).max(T::zero());
+                    if rate > T::epsilon() {
+                        hi = hi.min(slack / rate);
+                    } else if rate < -T::epsilon() {
+                        lo = lo.max(slack / rate);
+                    }
+                }
+                if let Some(bounds) = bounds {
+                    for i in 0..dim {
+                        if dir[i] != T::zero() {
+                            let (t1, t2) = ((bounds.lower[i] - x[i]) / dir[i], (bounds.upper[i] - x[i]) / dir[i]);
+                            lo = lo.max(t1.min(t2));
+                            hi = hi.min(t1.max(t2));
+                        }
+                    }
+                }
+                if !lo.is_finite() || !hi.is_finite() {
+                    return Err(OptError::InvalidLinearConstraints("hit-and-run needs a bounded region, add bounds".to_string()));
+                }
+
+                let u = T::from_f64(rng.random::<f64>()).unwrap();
+                x += dir * (lo + (hi - lo) * u);
+            }
+            samples.push(from_dvector(&x));
+        }
+        Ok(samples)
+    }
+
+    // An initial population of n feasible points drawn by sample
+    pub fn population<N: Dim, D: Dim>(&self, start: &OVector<T, D>, n: usize, thin: usize, bounds: Option<&Bounds<T, D>>, rng: &mut OptRng) -> Result<OMatrix<T, N, D>, OptError>
+    where
+        DefaultAllocator: Allocator<D> + Allocator<N, D>
+    {
+        let samples = self.sample(start, n, thin, bounds, rng)?;
+        Ok(OMatrix::<T, N, D>::from_fn_generic(N::from_usize(n), D::from_usize(start.len()), |i, j| samples[i][j]))
+    }
+
+    fn project_affine(&self, x: &DVector<T>) -> DVector<T> {
+        if self.q.nrows() == 0 {
+            return x.clone();
+        }
+        x - self.q.transpose() * (&self.q * x - &self.e)
+    }
+}
+
+// Gram-Schmidt on the rows of C, applying the same row operations to d. Dependent rows are dropped,
+// unless they contradict the others
+fn orthonormalize<T: FloatNum>(c: &DMatrix<T>, d: &DVector<T>, tol: T) -> Result<(DMatrix<T>, DVector<T>), OptError> {
+    let mut rows: Vec<(DVector<T>, T)> = Vec::new();
+    for j in 0..c.nrows() {
+        let original = c.row(j).transpose();
+        let (mut v, mut e) = (original.clone(), d[j]);
+        // Twice, so the rows stay orthogonal to rounding
+        for _ in 0..2 {
+            for (q, qe) in &rows {
+                let p = q.dot(&v);
+                v -= q * p;
+                e -= *qe * p;
+            }
+        }
+
+        let norm = v.dot(&v).sqrt();
+        let scale = original.dot(&original).sqrt();
+        if norm <= T::from_f64(1e-10).unwrap() * scale.max(T::one()) {
+            if e.abs() > tol * d[j].abs().max(T::one()) {
+                return Err(OptError::InvalidLinearConstraints(format!("equality {} contradicts the ones before it", j)));
+            }
+            continue;
+        }
+        rows.push((v / norm, e / norm));
+    }
+
+    let n = c.ncols();
+    let q = DMatrix::from_fn(rows.len(), n, |i, k| rows[i].0[k]);
+    let e = DVector::from_iterator(rows.len(), rows.iter().map(|(_, e)| *e));
+    Ok((q, e))
+}
+
+fn to_dvector<T: FloatNum, D: Dim>(x: &OVector<T, D>) -> DVector<T>
+where
+    DefaultAllocator: Allocator<D>
+{
+    DVector::from_iterator(x.len(), x.iter().cloned())
+}
+
+fn from_dvector<T: FloatNum, D: Dim>(x: &DVector<T>) -> OVector<T, D>
+where
+    DefaultAllocator: Allocator<D>
+{
+    OVector::<T, D>::from_iterator_generic(D::from_usize(x.len()), U1, x.iter().cloned())
+}
+
+// Linear constraints as written in OptConf, one inner list per row of A or C
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LinearConstraintsConf {
+    #[serde(default)]
+    pub a: Vec<Vec<f64>>,
+    #[serde(default)]
+    pub b: Vec<f64>,
+    #[serde(default)]
+    pub c: Vec<Vec<f64>>,
+    #[serde(default)]
+    pub d: Vec<f64>,
+    #[serde(default = "default_tol")]
+    pub tol: f64,
+    #[serde(default = "default_project")]
+    pub project: bool, // Project repaired candidates and the initial population onto the polytope
+}
+
+fn default_tol() -> f64 { 1e-8 }
+fn default_project() -> bool { true }
+
+impl LinearConstraintsConf {
+    pub fn to_linear<T: FloatNum>(&self) -> Result<LinearConstraints<T>, OptError> {
+        let dim = self.a.first().or(self.c.first()).map_or(0, |row| row.len());
+        let matrix = |rows: &[Vec<f64>], name: &str| -> Result<DMatrix<T>, OptError> {
+            if let Some(row) = rows.iter().find(|row| row.len() != dim) {
+                return Err(OptError::InvalidLinearConstraints(format!("a row of {} has {} values where {} were expected", name, row.len(), dim)));
+            }
+            Ok(DMatrix::from_fn(rows.len(), dim, |i, j| T::from_f64(rows[i][j]).unwrap()))
+        };
+        let vector = |values: &[f64]| DVector::from_iterator(values.len(), values.iter().map(|&v| T::from_f64(v).unwrap()));
+
+        Ok(LinearConstraints::new(matrix(&self.a, "A")?, vector(&self.b), matrix(&self.c, "C")?, vector(&self.d))?
+            .with_tol(T::from_f64(self.tol).unwrap())
+            .with_project(self.project))
+    }
+}
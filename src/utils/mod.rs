@@ -1,3 +1,22 @@
 pub mod config;
 pub mod opt_prob;
 pub mod alg_conf;
+pub mod rng;
+pub mod observer;
+pub mod checkpoint;
+pub mod stopping;
+pub mod result;
+pub mod error;
+pub mod validation;
+pub mod finite_diff;
+pub mod dual;
+pub mod autodiff;
+pub mod gradient_check;
+pub mod bounds;
+pub mod constraint_handling;
+pub mod linear_constraints;
+pub mod pareto;
+pub mod indicators;
+pub mod variables;
+pub mod permutation;
+pub mod sampling;
\ No newline at end of file
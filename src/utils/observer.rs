@@ -0,0 +1,42 @@
+use nalgebra::{
+    allocator::Allocator, 
+    DefaultAllocator, 
+    Dim
+};
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, State, Diagnostics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObserverAction {
+    Continue,
+    Stop,
+}
+
+// Called by NonConvexOpt after every step, the state is in the objective's own units
+pub trait Observer<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D> 
+                    + Allocator<N> 
+                    + Allocator<N, D> 
+{
+    fn notify(&mut self, iter: usize, st: &State<T, N, D>, diagnostics: &Diagnostics) -> ObserverAction;
+}
+
+// Closures can be registered directly
+impl<T, N, D, F> Observer<T, N, D> for F
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    F: FnMut(usize, &State<T, N, D>, &Diagnostics) -> ObserverAction,
+    DefaultAllocator: Allocator<D> 
+                    + Allocator<N> 
+                    + Allocator<N, D> 
+{
+    fn notify(&mut self, iter: usize, st: &State<T, N, D>, diagnostics: &Diagnostics) -> ObserverAction {
+        self(iter, st, diagnostics)
+    }
+}
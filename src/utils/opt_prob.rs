@@ -1,9 +1,15 @@
+use std::collections::BTreeMap;
+use std::cmp::Ordering as CmpOrdering;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use serde::{Deserialize, Serialize};
 use num_traits::{Float, FromPrimitive, NumCast, One, Zero};
 use nalgebra::{
     Scalar,
     allocator::Allocator, 
     DefaultAllocator, 
     Dim, 
+    DVector,
     OVector, 
     OMatrix
 };
@@ -13,6 +19,16 @@ use simba::scalar::{
     ClosedNeg, ClosedSub, ClosedSubAssign, SubsetOf,
 };
 
+use crate::utils::checkpoint::AlgCheckpoint;
+use crate::utils::error::OptError;
+use crate::utils::bounds::{Bounds, BoundHandling};
+use crate::utils::constraint_handling::ConstraintHandling;
+use crate::utils::linear_constraints::LinearConstraints;
+use crate::utils::variables::Variables;
+use crate::utils::rng::OptRng;
+use crate::utils::result::{LagrangeReport, ParetoFront};
+use crate::utils::finite_diff::ComplexObjectiveFunction;
+
 // More general trait for float numbers 
 pub trait FloatNumber:
     Copy
@@ -52,6 +68,14 @@ pub trait CloneBoxConstraint<T: FloatNumber, D: Dim> {
     fn clone_box_constraint(&self) -> Box<dyn BooleanConstraintFunction<T, D>>;
 }
 
+pub trait CloneBoxConstraintFunction<T: FloatNumber, D: Dim> {
+    fn clone_box_constraint_function(&self) -> Box<dyn ConstraintFunction<T, D>>;
+}
+
+pub trait CloneBoxMulti<T: FloatNumber, D: Dim> {
+    fn clone_box_multi(&self) -> Box<dyn MultiObjectiveFunction<T, D>>;
+}
+
 impl<T: FloatNumber, D: Dim, F: ObjectiveFunction<T, D> + Clone + 'static> CloneBox<T, D> for F 
 where 
     DefaultAllocator: Allocator<D>
@@ -70,6 +94,24 @@ where
     }
 }
 
+impl<T: FloatNumber, D: Dim, F: ConstraintFunction<T, D> + Clone + 'static> CloneBoxConstraintFunction<T, D> for F 
+where 
+    DefaultAllocator: Allocator<D>
+{
+    fn clone_box_constraint_function(&self) -> Box<dyn ConstraintFunction<T, D>> {
+        Box::new(self.clone())
+    }
+}
+
+impl<T: FloatNumber, D: Dim, F: MultiObjectiveFunction<T, D> + Clone + 'static> CloneBoxMulti<T, D> for F 
+where 
+    DefaultAllocator: Allocator<D>
+{
+    fn clone_box_multi(&self) -> Box<dyn MultiObjectiveFunction<T, D>> {
+        Box::new(self.clone())
+    }
+}
+
 pub trait ObjectiveFunction<T: FloatNumber, D: Dim>: CloneBox<T, D> + Send + Sync
 where
     DefaultAllocator: Allocator<D>,
@@ -80,6 +122,77 @@ where
         None
     }
 
+    // Calls to f made by each `gradient` in dim dimensions, e.g. 2n for central differences.
+    // OptProb charges them to the objective count and max_evals
+    fn gradient_evaluations(&self, _dim: usize) -> usize {
+        0
+    }
+
+    // Objectives that implement ComplexObjectiveFunction return Some(self), which lets
+    // GradientConf::ComplexStep differentiate them
+    fn as_complex(&self) -> Option<&dyn ComplexObjectiveFunction<T, D>> {
+        None
+    }
+
+    // Read once at the first point of the initial population by NonConvexOpt::new, unless OptConf::bounds is set
+    fn x_lower_bound(&self, _x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        None
+    }
+
+    fn x_upper_bound(&self, _x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        None
+    }
+}
+
+impl<T: FloatNumber, D: Dim> Clone for Box<dyn ObjectiveFunction<T, D>>
+where
+    DefaultAllocator: Allocator<D>,
+{
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+// Lets a boxed objective be wrapped again, e.g. by utils::finite_diff::FiniteDifference
+impl<T: FloatNumber, D: Dim> ObjectiveFunction<T, D> for Box<dyn ObjectiveFunction<T, D>>
+where
+    DefaultAllocator: Allocator<D>,
+{
+    fn f(&self, x: &OVector<T, D>) -> T {
+        (**self).f(x)
+    }
+
+    fn gradient(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        (**self).gradient(x)
+    }
+
+    fn gradient_evaluations(&self, dim: usize) -> usize {
+        (**self).gradient_evaluations(dim)
+    }
+
+    fn as_complex(&self) -> Option<&dyn ComplexObjectiveFunction<T, D>> {
+        (**self).as_complex()
+    }
+
+    fn x_lower_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        (**self).x_lower_bound(x)
+    }
+
+    fn x_upper_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        (**self).x_upper_bound(x)
+    }
+}
+
+// Several objectives traded off against each other, all maximized or all minimized per ObjectiveSense
+pub trait MultiObjectiveFunction<T: FloatNumber, D: Dim>: CloneBoxMulti<T, D> + Send + Sync
+where
+    DefaultAllocator: Allocator<D>,
+{
+    fn f(&self, x: &OVector<T, D>) -> DVector<T>;
+
+    // Length of the vector returned by f
+    fn num_objectives(&self) -> usize;
+
     fn x_lower_bound(&self, _x: &OVector<T, D>) -> Option<OVector<T, D>> {
         None
     }
@@ -89,6 +202,60 @@ where
     }
 }
 
+impl<T: FloatNumber, D: Dim> Clone for Box<dyn MultiObjectiveFunction<T, D>>
+where
+    DefaultAllocator: Allocator<D>,
+{
+    fn clone(&self) -> Self {
+        (**self).clone_box_multi()
+    }
+}
+
+impl<T: FloatNumber, D: Dim> MultiObjectiveFunction<T, D> for Box<dyn MultiObjectiveFunction<T, D>>
+where
+    DefaultAllocator: Allocator<D>,
+{
+    fn f(&self, x: &OVector<T, D>) -> DVector<T> {
+        (**self).f(x)
+    }
+
+    fn num_objectives(&self) -> usize {
+        (**self).num_objectives()
+    }
+
+    fn x_lower_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        (**self).x_lower_bound(x)
+    }
+
+    fn x_upper_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        (**self).x_upper_bound(x)
+    }
+}
+
+// The scalar view of a multi-objective problem, what single-objective algorithms and the stopping criteria see
+#[derive(Clone)]
+pub struct SumOfObjectives<M>(pub M);
+
+impl<T, D, M> ObjectiveFunction<T, D> for SumOfObjectives<M>
+where
+    T: FloatNumber,
+    D: Dim,
+    M: MultiObjectiveFunction<T, D> + Clone + 'static,
+    DefaultAllocator: Allocator<D>,
+{
+    fn f(&self, x: &OVector<T, D>) -> T {
+        self.0.f(x).sum()
+    }
+
+    fn x_lower_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        self.0.x_lower_bound(x)
+    }
+
+    fn x_upper_bound(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        self.0.x_upper_bound(x)
+    }
+}
+
 pub trait BooleanConstraintFunction<T: FloatNumber, D: Dim>: CloneBoxConstraint<T, D> + Send + Sync
 where
     DefaultAllocator: Allocator<D>,
@@ -96,12 +263,139 @@ where
     fn g(&self, x: &OVector<T, D>) -> bool;
 }
 
+impl<T: FloatNumber, D: Dim> Clone for Box<dyn BooleanConstraintFunction<T, D>>
+where
+    DefaultAllocator: Allocator<D>,
+{
+    fn clone(&self) -> Self {
+        (**self).clone_box_constraint()
+    }
+}
+
+impl<T: FloatNumber, D: Dim> BooleanConstraintFunction<T, D> for Box<dyn BooleanConstraintFunction<T, D>>
+where
+    DefaultAllocator: Allocator<D>,
+{
+    fn g(&self, x: &OVector<T, D>) -> bool {
+        (**self).g(x)
+    }
+}
+
+// Real-valued constraints, feasible when g_i(x) <= ineq_tol and |h_j(x)| <= eq_tol for every i and j
+pub trait ConstraintFunction<T: FloatNumber, D: Dim>: CloneBoxConstraintFunction<T, D> + Send + Sync
+where
+    DefaultAllocator: Allocator<D>,
+{
+    // Inequalities g_i(x) <= 0
+    fn g(&self, _x: &OVector<T, D>) -> DVector<T> {
+        DVector::zeros(0)
+    }
+
+    // Equalities h_j(x) = 0
+    fn h(&self, _x: &OVector<T, D>) -> DVector<T> {
+        DVector::zeros(0)
+    }
+
+    fn ineq_tol(&self) -> T {
+        T::zero()
+    }
+
+    fn eq_tol(&self) -> T {
+        T::from_f64(1e-6).unwrap()
+    }
+
+    // Total amount by which x misses the constraints, zero exactly when it is feasible
+    fn violation(&self, x: &OVector<T, D>) -> T {
+        let ineq = self.g(x).iter().fold(T::zero(), |acc, &gi| acc + (gi - self.ineq_tol()).max(T::zero()));
+        let eq = self.h(x).iter().fold(T::zero(), |acc, &hj| acc + (hj.abs() - self.eq_tol()).max(T::zero()));
+        ineq + eq
+    }
+}
+
+impl<T: FloatNumber, D: Dim> Clone for Box<dyn ConstraintFunction<T, D>>
+where
+    DefaultAllocator: Allocator<D>,
+{
+    fn clone(&self) -> Self {
+        (**self).clone_box_constraint_function()
+    }
+}
+
+// Lets a BooleanConstraintFunction be used where a ConstraintFunction is expected.
+// A rejected point has a single inequality of 1, so every infeasible point violates equally
+#[derive(Debug, Clone)]
+pub struct BooleanConstraint<G>(pub G);
+
+impl<T, D, G> ConstraintFunction<T, D> for BooleanConstraint<G>
+where
+    T: FloatNumber,
+    D: Dim,
+    G: BooleanConstraintFunction<T, D> + Clone + 'static,
+    DefaultAllocator: Allocator<D>,
+{
+    fn g(&self, x: &OVector<T, D>) -> DVector<T> {
+        DVector::from_element(1, if self.0.g(x) { T::zero() } else { T::one() })
+    }
+}
+
+// Whether the objective is a fitness to maximize or a cost to minimize
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObjectiveSense {
+    #[default]
+    Maximize,
+    Minimize,
+}
+
+impl ObjectiveSense {
+    // Maps between user units and the internal fitness, which is always maximized
+    pub fn apply<T: FloatNumber>(&self, value: T) -> T {
+        match self {
+            ObjectiveSense::Maximize => value,
+            ObjectiveSense::Minimize => -value,
+        }
+    }
+}
+
+// Number of calls made to the user's functions
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EvalCounts {
+    pub objective: usize,
+    pub gradient: usize,
+    pub constraints: usize,
+}
+
+#[derive(Debug, Default)]
+struct EvalCounters {
+    objective: AtomicUsize,
+    gradient: AtomicUsize,
+    constraints: AtomicUsize,
+    error: Mutex<Option<OptError>>, // First problem hit during a step, picked up by NonConvexOpt
+}
+
+// Orders NaN below everything else so sorting and max_by never panic
+pub fn fitness_cmp<T: FloatNumber>(a: &T, b: &T) -> CmpOrdering {
+    match (a.is_nan(), b.is_nan()) {
+        (false, false) => a.partial_cmp(b).unwrap(),
+        (true, false) => CmpOrdering::Less,
+        (false, true) => CmpOrdering::Greater,
+        (true, true) => CmpOrdering::Equal,
+    }
+}
+
 pub struct OptProb<T: FloatNumber, D: Dim>
 where
     DefaultAllocator: Allocator<D>,
 {
     pub objective: Box<dyn ObjectiveFunction<T, D>>,
-    pub constraints: Option<Box<dyn BooleanConstraintFunction<T, D>>>,
+    pub constraints: Option<Box<dyn ConstraintFunction<T, D>>>,
+    pub sense: ObjectiveSense,
+    pub max_evals: Option<usize>, // Budget on objective evaluations
+    pub bounds: Option<Bounds<T, D>>, // The one source of box bounds for every algorithm, None is unbounded
+    pub constraint_handling: ConstraintHandling,
+    pub linear: Option<LinearConstraints<T>>, // Checked alongside constraints, but known in closed form
+    pub multi: Option<Box<dyn MultiObjectiveFunction<T, D>>>, // Set for multi-objective problems, objective is then their sum
+    pub variables: Option<Variables>, // Integer and discrete dimensions, None when all are continuous
+    counters: Arc<EvalCounters>, // Shared by all clones, so copies held inside an algorithm are counted too
 }
 
 impl<T: FloatNumber, D: Dim> OptProb<T, D>
@@ -114,19 +408,301 @@ where
     ) -> Self {
         Self {
             objective,
-            constraints,
+            constraints: constraints.map(|c| Box::new(BooleanConstraint(c)) as Box<dyn ConstraintFunction<T, D>>),
+            sense: ObjectiveSense::Maximize,
+            max_evals: None,
+            bounds: None,
+            constraint_handling: ConstraintHandling::default(),
+            linear: None,
+            multi: None,
+            variables: None,
+            counters: Arc::new(EvalCounters::default()),
+        }
+    }
+
+    pub fn with_sense(mut self, sense: ObjectiveSense) -> Self {
+        self.sense = sense;
+        self
+    }
+
+    // Real-valued constraints, replacing any given to new
+    pub fn with_constraints(mut self, constraints: Option<Box<dyn ConstraintFunction<T, D>>>) -> Self {
+        self.constraints = constraints;
+        self
+    }
+
+    pub fn with_max_evals(mut self, max_evals: Option<usize>) -> Self {
+        self.max_evals = max_evals;
+        self
+    }
+
+    pub fn with_bounds(mut self, bounds: Bounds<T, D>) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub fn with_constraint_handling(mut self, constraint_handling: ConstraintHandling) -> Self {
+        self.constraint_handling = constraint_handling;
+        self
+    }
+
+    pub fn with_linear_constraints(mut self, linear: LinearConstraints<T>) -> Self {
+        self.linear = Some(linear);
+        self
+    }
+
+    pub fn with_multi_objective(mut self, multi: Box<dyn MultiObjectiveFunction<T, D>>) -> Self {
+        self.multi = Some(multi);
+        self
+    }
+
+    // Also limits the bounds of integer and discrete dimensions to their range
+    pub fn with_variables(mut self, variables: Variables) -> Result<Self, OptError> {
+        self.bounds = Some(variables.restrict(self.bounds.take())?);
+        self.variables = Some(variables);
+        Ok(self)
+    }
+
+    // Onto the integer, discrete and categorical levels, unchanged when every dimension is continuous
+    pub fn snap(&self, x: &OVector<T, D>) -> OVector<T, D> {
+        match &self.variables {
+            Some(variables) => variables.snap(x),
+            None => x.clone(),
+        }
+    }
+
+    // Integer neighbourhoods for local moves: a perturbed integer, discrete or categorical dimension moves at least one level
+    pub fn step_away(&self, x: &OVector<T, D>, parent: &OVector<T, D>, rng: &mut OptRng) -> OVector<T, D> {
+        match &self.variables {
+            Some(variables) => variables.step_away(x, parent, rng),
+            None => x.clone(),
+        }
+    }
+
+    pub fn num_objectives(&self) -> usize {
+        self.multi.as_ref().map_or(1, |multi| multi.num_objectives())
+    }
+
+    // Bounds for a dim-dimensional point, infinite where none were given
+    pub fn bounds_or_unbounded(&self, dim: usize) -> Bounds<T, D> {
+        self.bounds.clone().unwrap_or_else(|| Bounds::unbounded(dim))
+    }
+
+    // Repairs a point generated from parent, unchanged when there are no bounds. With linear constraints
+    // that project, the point is then moved onto the polytope within the box. Integer and discrete
+    // dimensions are snapped last, which can leave the polytope again
+    pub fn repair(&self, x: &OVector<T, D>, parent: &OVector<T, D>, handling: BoundHandling, rng: &mut OptRng) -> OVector<T, D> {
+        let repaired = match &self.bounds {
+            Some(bounds) => bounds.repair(x, parent, handling, rng),
+            None => x.clone(),
+        };
+        let repaired = match &self.linear {
+            Some(linear) if linear.project && handling != BoundHandling::DeathPenalty => linear.project(&repaired, self.bounds.as_ref()),
+            _ => repaired,
+        };
+        self.snap(&repaired)
+    }
+
+    // Like evaluate, but points left outside the box by the death penalty score the worst
+    // fitness and are not counted towards the budget
+    pub fn evaluate_bounded(&self, x: &OVector<T, D>, handling: BoundHandling) -> T {
+        match &self.bounds {
+            Some(bounds) if handling == BoundHandling::DeathPenalty && !bounds.contains(x) => T::neg_infinity(),
+            _ => self.evaluate(x),
         }
     }
 
     pub fn is_feasible(&self, x: &OVector<T, D>) -> bool {
+        self.violation(x) == T::zero()
+    }
+
+    // Total constraint violation, zero for feasible points and when there are no constraints.
+    // Linear constraints aren't counted as constraint evaluations
+    pub fn violation(&self, x: &OVector<T, D>) -> T {
+        let linear = self.linear.as_ref().map_or(T::zero(), |linear| linear.violation(x));
         match &self.constraints {
-            Some(constraints) => constraints.g(x),
-            None => true,
+            Some(constraints) => {
+                self.counters.constraints.fetch_add(1, Ordering::Relaxed);
+                constraints.violation(x) + linear
+            },
+            None => linear,
         }
     }
 
+    // Raw inequality and equality values, black-box ones first and then A x - b and C x - d
+    pub fn constraint_values(&self, x: &OVector<T, D>) -> (DVector<T>, DVector<T>) {
+        let (g, h) = match &self.constraints {
+            Some(constraints) => {
+                self.counters.constraints.fetch_add(1, Ordering::Relaxed);
+                (constraints.g(x), constraints.h(x))
+            },
+            None => (DVector::zeros(0), DVector::zeros(0)),
+        };
+        match &self.linear {
+            Some(linear) => {
+                let (lg, lh) = linear.residuals(x);
+                (concat(&g, &lg), concat(&h, &lh))
+            },
+            None => (g, h),
+        }
+    }
+
+    // Total violation from values constraint_values already returned, without calling the constraints
+    // again. The same as violation unless ConstraintFunction::violation is overridden
+    pub fn violation_from(&self, g: &DVector<T>, h: &DVector<T>) -> T {
+        let (ineq_tol, eq_tol) = self.constraints.as_ref().map_or((T::zero(), T::zero()), |c| (c.ineq_tol(), c.eq_tol()));
+        let (n_ineq, n_eq) = self.linear.as_ref().map_or((0, 0), |linear| (linear.b.len(), linear.d.len()));
+        let (black_box_ineq, black_box_eq) = (g.len() - n_ineq, h.len() - n_eq);
+        let linear_tol = self.linear.as_ref().map_or(T::zero(), |linear| linear.tol);
+
+        let ineq = g.iter().enumerate().fold(T::zero(), |acc, (i, &gi)| {
+            let tol = if i < black_box_ineq { ineq_tol } else { linear_tol };
+            acc + (gi - tol).max(T::zero())
+        });
+        let eq = h.iter().enumerate().fold(T::zero(), |acc, (j, &hj)| {
+            let tol = if j < black_box_eq { eq_tol } else { linear_tol };
+            acc + (hj.abs() - tol).max(T::zero())
+        });
+        ineq + eq
+    }
+
+    // An unconstrained problem over the same box with another objective, already in fitness units.
+    // Shares the counters and budget, so evaluations made on it count towards this problem
+    pub fn subproblem(&self, objective: Box<dyn ObjectiveFunction<T, D>>) -> Self {
+        Self {
+            objective,
+            constraints: None,
+            sense: ObjectiveSense::Maximize,
+            max_evals: self.max_evals,
+            bounds: self.bounds.clone(),
+            constraint_handling: self.constraint_handling.clone(),
+            linear: None,
+            multi: None,
+            variables: self.variables.clone(),
+            counters: self.counters.clone(),
+        }
+    }
+
+    // Fitness seen by the algorithms - larger is always better.
+    // Once the budget is spent the objective is no longer called and points get the worst possible fitness
     pub fn evaluate(&self, x: &OVector<T, D>) -> T {
-        self.objective.f(x)
+        if !self.count_evaluation() {
+            return T::neg_infinity();
+        }
+
+        // NaN/inf is reported as an error and the point gets the worst fitness so the step can finish
+        let f = self.objective.f(x);
+        if !f.is_finite() {
+            self.report(OptError::NonFiniteObjective {
+                x: x.iter().map(|xi| xi.to_f64().unwrap()).collect(),
+                value: f.to_f64().unwrap(),
+            });
+            return T::neg_infinity();
+        }
+        self.sense.apply(f)
+    }
+
+    // Every objective in fitness units, one call to the multi-objective function counted as one evaluation.
+    // Like evaluate, a spent budget or a non-finite value gives -inf in every objective. Without a
+    // multi-objective function the error is reported and the one objective is -inf
+    pub fn evaluate_multi(&self, x: &OVector<T, D>) -> DVector<T> {
+        let Some(multi) = self.multi.as_ref() else {
+            self.report(OptError::InvalidConfig {
+                alg: "multi-objective evaluation".to_string(),
+                reason: "needs a MultiObjectiveFunction, see NonConvexOpt::new_multi_objective".to_string(),
+            });
+            return DVector::from_element(1, T::neg_infinity());
+        };
+        let worst = DVector::from_element(multi.num_objectives(), T::neg_infinity());
+        if !self.count_evaluation() {
+            return worst;
+        }
+
+        let f = multi.f(x);
+        if let Some(value) = f.iter().find(|fi| !fi.is_finite()) {
+            self.report(OptError::NonFiniteObjective {
+                x: x.iter().map(|xi| xi.to_f64().unwrap()).collect(),
+                value: value.to_f64().unwrap(),
+            });
+            return worst;
+        }
+        f.map(|fi| self.sense.apply(fi))
+    }
+
+    // Takes one evaluation from the budget, false once it is spent
+    fn count_evaluation(&self) -> bool {
+        self.count_evaluations(1)
+    }
+
+    // Takes k evaluations at once. When fewer are left, none are made and the budget is marked as spent
+    fn count_evaluations(&self, k: usize) -> bool {
+        let max_evals = self.max_evals;
+        let mut covered = true;
+        let _ = self.counters.objective.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| match max_evals {
+            Some(max) if n + k > max => {
+                covered = false;
+                (n < max).then_some(max)
+            },
+            _ => Some(n + k),
+        });
+        covered
+    }
+
+    // Gradient of the fitness returned by `evaluate`. Numerical gradients also use up objective evaluations,
+    // and once the budget can't cover them there is none, leaving the run to stop on max_evals
+    pub fn gradient(&self, x: &OVector<T, D>) -> Option<OVector<T, D>> {
+        let cost = self.objective.gradient_evaluations(x.len());
+        if cost > 0 && !self.count_evaluations(cost) {
+            return None;
+        }
+
+        let grad = self.objective.gradient(x)?;
+        self.counters.gradient.fetch_add(1, Ordering::Relaxed);
+        Some(match self.sense {
+            ObjectiveSense::Maximize => grad,
+            ObjectiveSense::Minimize => -grad,
+        })
+    }
+
+    // For algorithms that can't continue without a gradient, a missing one is reported as an error
+    // unless the budget ran out
+    pub fn require_gradient(&self, x: &OVector<T, D>, alg: &str) -> Option<OVector<T, D>> {
+        let grad = self.gradient(x);
+        if grad.is_none() && !self.budget_exhausted() {
+            self.report(OptError::MissingGradient { alg: alg.to_string() });
+        }
+        grad
+    }
+
+    // Only the first error is kept until it is taken
+    pub fn report(&self, err: OptError) {
+        let mut slot = self.counters.error.lock().unwrap();
+        if slot.is_none() {
+            *slot = Some(err);
+        }
+    }
+
+    pub fn take_error(&self) -> Option<OptError> {
+        self.counters.error.lock().unwrap().take()
+    }
+
+    pub fn budget_exhausted(&self) -> bool {
+        self.max_evals.is_some_and(|max| self.counters.objective.load(Ordering::Relaxed) >= max)
+    }
+
+    pub fn evaluations(&self) -> EvalCounts {
+        EvalCounts {
+            objective: self.counters.objective.load(Ordering::Relaxed),
+            gradient: self.counters.gradient.load(Ordering::Relaxed),
+            constraints: self.counters.constraints.load(Ordering::Relaxed),
+        }
+    }
+
+    // Carry counts over when resuming from a checkpoint
+    pub fn restore_evaluations(&self, counts: EvalCounts) {
+        self.counters.objective.store(counts.objective, Ordering::Relaxed);
+        self.counters.gradient.store(counts.gradient, Ordering::Relaxed);
+        self.counters.constraints.store(counts.constraints, Ordering::Relaxed);
     }
 }
 
@@ -139,11 +715,24 @@ where
     fn clone(&self) -> Self {
         Self {
             objective: self.objective.clone_box(), 
-            constraints: self.constraints.as_ref().map(|c| c.clone_box_constraint()),
+            constraints: self.constraints.clone(),
+            sense: self.sense,
+            max_evals: self.max_evals,
+            bounds: self.bounds.clone(),
+            constraint_handling: self.constraint_handling.clone(),
+            linear: self.linear.clone(),
+            multi: self.multi.clone(),
+            variables: self.variables.clone(),
+            counters: self.counters.clone(),
         }
     }
 }
 
+fn concat<T: FloatNumber>(a: &DVector<T>, b: &DVector<T>) -> DVector<T> {
+    DVector::from_iterator(a.len() + b.len(), a.iter().chain(b.iter()).cloned())
+}
+
+#[derive(Clone, Debug)]
 pub struct State<T, N, D>
 where
     T: FloatNumber,
@@ -158,9 +747,13 @@ where
     pub pop: OMatrix<T, N, D>,
     pub fitness: OVector<T, N>,
     pub constraints: OVector<bool, N>,
+    pub violation: OVector<T, N>, // Total constraint violation of each member, zero when feasible
     pub iter: usize,
 }
 
+// Algorithm-specific quantities (temperature, step size, ...) keyed by name
+pub type Diagnostics = BTreeMap<String, f64>;
+
 pub trait OptimizationAlgorithm<T: FloatNumber, N: Dim, D: Dim>
 where
     DefaultAllocator: Allocator<D> 
@@ -169,7 +762,19 @@ where
 {
     fn step(&mut self);
     fn state(&self) -> &State<T, N, D>;
+    fn checkpoint(&self) -> AlgCheckpoint;
     fn get_simplex(&self) -> Option<&Vec<OVector<T, D>>> {
         None
     }
+    fn diagnostics(&self) -> Diagnostics {
+        Diagnostics::new()
+    }
+    // Multipliers and residuals, for algorithms that estimate them
+    fn lagrange(&self) -> Option<LagrangeReport> {
+        None
+    }
+    // Non-dominated points, for multi-objective algorithms
+    fn pareto_front(&self) -> Option<ParetoFront> {
+        None
+    }
 }
\ No newline at end of file
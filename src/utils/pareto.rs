@@ -0,0 +1,162 @@
+use std::cmp::Ordering;
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    DVector,
+    OMatrix,
+};
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, ObjectiveSense, fitness_cmp};
+use crate::utils::result::ParetoFront;
+
+// Whether a is at least as good as b in every objective and strictly better in one, all maximized
+pub fn dominates<T: FloatNum>(a: &DVector<T>, b: &DVector<T>) -> bool {
+    let mut strictly = false;
+    for (&ai, &bi) in a.iter().zip(b.iter()) {
+        if ai < bi {
+            return false;
+        }
+        if ai > bi {
+            strictly = true;
+        }
+    }
+    strictly
+}
+
+// Deb's constrained domination: feasible beats infeasible, the smaller violation wins between
+// infeasible points, and Pareto dominance decides between feasible ones
+pub fn constrained_dominates<T: FloatNum>(a: &DVector<T>, va: T, b: &DVector<T>, vb: T) -> bool {
+    match (va == T::zero(), vb == T::zero()) {
+        (true, true) => dominates(a, b),
+        (true, false) => true,
+        (false, true) => false,
+        (false, false) => va < vb,
+    }
+}
+
+// Fast non-dominated sorting, fronts of indices from best to worst
+pub fn non_dominated_sort<T: FloatNum>(objectives: &[DVector<T>], violation: &[T]) -> Vec<Vec<usize>> {
+    let n = objectives.len();
+    let mut dominated_by: Vec<Vec<usize>> = vec![Vec::new(); n]; // Points each one dominates
+    let mut count = vec![0usize; n]; // Number of points dominating each one
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if constrained_dominates(&objectives[i], violation[i], &objectives[j], violation[j]) {
+                dominated_by[i].push(j);
+                count[j] += 1;
+            } else if constrained_dominates(&objectives[j], violation[j], &objectives[i], violation[i]) {
+                dominated_by[j].push(i);
+                count[i] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut current: Vec<usize> = (0..n).filter(|&i| count[i] == 0).collect();
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        for &i in &current {
+            for &j in &dominated_by[i] {
+                count[j] -= 1;
+                if count[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        next.sort_unstable();
+        fronts.push(current);
+        current = next;
+    }
+    fronts
+}
+
+// Index of the front each point belongs to, 0 for the non-dominated ones
+pub fn front_ranks(fronts: &[Vec<usize>], n: usize) -> Vec<usize> {
+    let mut rank = vec![0; n];
+    for (r, front) in fronts.iter().enumerate() {
+        for &i in front {
+            rank[i] = r;
+        }
+    }
+    rank
+}
+
+// Crowding distance of each member of front, in the same order. The extremes of every objective get
+// infinity, objectives that are constant or not finite across the front are skipped
+pub fn crowding_distance<T: FloatNum>(objectives: &[DVector<T>], front: &[usize]) -> Vec<T> {
+    let mut distance = vec![T::zero(); front.len()];
+    if front.len() <= 2 {
+        return vec![T::infinity(); front.len()];
+    }
+
+    let num_objectives = objectives[front[0]].len();
+    let mut order: Vec<usize> = (0..front.len()).collect();
+    for values in (0..num_objectives).map(|m| front.iter().map(|&i| objectives[i][m]).collect::<Vec<T>>()) {
+        order.sort_by(|&a, &b| fitness_cmp(&values[a], &values[b]));
+        let range = values[order[front.len() - 1]] - values[order[0]];
+        if !range.is_finite() || range <= T::zero() {
+            continue;
+        }
+
+        distance[order[0]] = T::infinity();
+        distance[order[front.len() - 1]] = T::infinity();
+        for k in 1..front.len() - 1 {
+            distance[order[k]] += (values[order[k + 1]] - values[order[k - 1]]) / range;
+        }
+    }
+    distance
+}
+
+// Crowded comparison: lower rank first, then the less crowded point
+pub fn crowded_cmp<T: FloatNum>(rank_a: usize, crowding_a: T, rank_b: usize, crowding_b: T) -> Ordering {
+    rank_b.cmp(&rank_a).then_with(|| fitness_cmp(&crowding_a, &crowding_b))
+}
+
+// Members of a population as reported in OptimizationResult::pareto_front, objectives back in the user's units
+pub fn pareto_front<T, N, D>(pop: &OMatrix<T, N, D>, objectives: &[DVector<T>], members: &[usize], sense: ObjectiveSense) -> ParetoFront
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<N, D>
+{
+    ParetoFront {
+        x: members.iter().map(|&i| pop.row(i).iter().map(|xi| xi.to_f64().unwrap()).collect()).collect(),
+        f: members.iter().map(|&i| objectives[i].iter().map(|&fi| sense.apply(fi).to_f64().unwrap()).collect()).collect(),
+    }
+}
+
+// Number of Das-Dennis weight vectors for num_objectives and divisions, binomial(divisions + num_objectives - 1, num_objectives - 1)
+pub fn num_weights(num_objectives: usize, divisions: usize) -> usize {
+    if num_objectives == 0 {
+        return 0;
+    }
+    let k = (num_objectives - 1).min(divisions);
+    let n = divisions + num_objectives - 1;
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+// Das-Dennis weights, every vector of non-negative multiples of 1 / divisions summing to one
+pub fn uniform_weights(num_objectives: usize, divisions: usize) -> Vec<Vec<f64>> {
+    fn fill(left: usize, m: usize, current: &mut Vec<usize>, divisions: usize, out: &mut Vec<Vec<f64>>) {
+        if m == 1 {
+            current.push(left);
+            out.push(current.iter().map(|&k| k as f64 / divisions.max(1) as f64).collect());
+            current.pop();
+            return;
+        }
+        for k in 0..=left {
+            current.push(k);
+            fill(left - k, m - 1, current, divisions, out);
+            current.pop();
+        }
+    }
+
+    let mut weights = Vec::with_capacity(num_weights(num_objectives, divisions));
+    if num_objectives > 0 {
+        fill(divisions, num_objectives, &mut Vec::new(), divisions, &mut weights);
+    }
+    weights
+}
@@ -0,0 +1,178 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    OVector,
+    U1
+};
+
+use crate::utils::opt_prob::FloatNumber as FloatNum;
+use crate::utils::rng::OptRng;
+
+// Orders of 0..n stored as real vectors, for scheduling and routing problems. The objective sees the
+// order itself, x[k] being the item visited k-th. Moves on orders are written over plain index slices
+
+// Whether x holds every index 0..n exactly once
+pub fn is_permutation(x: &[f64]) -> bool {
+    let mut seen = vec![false; x.len()];
+    x.iter().all(|&v| {
+        let ok = v.fract() == 0.0 && v >= 0.0 && (v as usize) < seen.len() && !seen[v as usize];
+        if ok {
+            seen[v as usize] = true;
+        }
+        ok
+    })
+}
+
+pub fn to_order<T, D>(x: &OVector<T, D>) -> Vec<usize>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    x.iter().map(|v| v.to_usize().unwrap()).collect()
+}
+
+pub fn from_order<T, D>(order: &[usize]) -> OVector<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    OVector::<T, D>::from_iterator_generic(D::from_usize(order.len()), U1, order.iter().map(|&k| T::from_usize(k).unwrap()))
+}
+
+// Two cut points i < j, or 0 and 0 for a single item
+fn segment(n: usize, rng: &mut OptRng) -> (usize, usize) {
+    if n < 2 {
+        return (0, 0);
+    }
+    let a = rng.random_range(0..n);
+    let b = (a + rng.random_range(1..n)) % n;
+    (a.min(b), a.max(b))
+}
+
+// Partially mapped crossover: p1 between the cuts, p2 elsewhere with clashes mapped through the segment
+pub fn pmx(p1: &[usize], p2: &[usize], i: usize, j: usize) -> Vec<usize> {
+    let mut child = p2.to_vec();
+    let mut position = vec![0; p1.len()];
+    for (k, &v) in p1.iter().enumerate() {
+        position[v] = k;
+    }
+    child[i..=j].copy_from_slice(&p1[i..=j]);
+    for k in (0..p1.len()).filter(|k| !(i..=j).contains(k)) {
+        let mut v = p2[k];
+        while (i..=j).contains(&position[v]) {
+            v = p2[position[v]];
+        }
+        child[k] = v;
+    }
+    child
+}
+
+// Order crossover: p1 between the cuts, the rest in the order they come in p2 after the second cut
+pub fn ox(p1: &[usize], p2: &[usize], i: usize, j: usize) -> Vec<usize> {
+    let n = p1.len();
+    let mut used = vec![false; n];
+    let mut child = vec![0; n];
+    for k in i..=j {
+        child[k] = p1[k];
+        used[p1[k]] = true;
+    }
+    let mut rest = (1..=n).map(|s| p2[(j + s) % n]).filter(|&v| !used[v]);
+    for s in 1..=n - (j - i + 1) {
+        child[(j + s) % n] = rest.next().unwrap();
+    }
+    child
+}
+
+// Cycle crossover: each position keeps the value of one parent, alternating parents cycle by cycle
+pub fn cycle(p1: &[usize], p2: &[usize]) -> Vec<usize> {
+    let n = p1.len();
+    let mut position = vec![0; n];
+    for (k, &v) in p1.iter().enumerate() {
+        position[v] = k;
+    }
+    let mut child = vec![usize::MAX; n];
+    let mut from_p1 = true;
+    for start in 0..n {
+        if child[start] != usize::MAX {
+            continue;
+        }
+        let mut k = start;
+        loop {
+            child[k] = if from_p1 { p1[k] } else { p2[k] };
+            k = position[p2[k]];
+            if k == start {
+                break;
+            }
+        }
+        from_p1 = !from_p1;
+    }
+    child
+}
+
+// Swaps two random positions
+pub fn swap(order: &mut [usize], rng: &mut OptRng) {
+    if order.len() > 1 {
+        let i = rng.random_range(0..order.len());
+        let j = (i + rng.random_range(1..order.len())) % order.len();
+        order.swap(i, j);
+    }
+}
+
+// Moves a random item to a random other position
+pub fn insert(order: &mut Vec<usize>, rng: &mut OptRng) {
+    let n = order.len();
+    if n > 1 {
+        let item = order.remove(rng.random_range(0..n));
+        order.insert(rng.random_range(0..n), item);
+    }
+}
+
+// Reverses a random segment, a 2-opt move when the order is a tour
+pub fn inversion(order: &mut [usize], rng: &mut OptRng) {
+    let (i, j) = segment(order.len(), rng);
+    order[i..=j].reverse();
+}
+
+// Shuffles a random segment
+pub fn scramble(order: &mut [usize], rng: &mut OptRng) {
+    let (i, j) = segment(order.len(), rng);
+    order[i..=j].shuffle(rng);
+}
+
+// Neighbours for Tabu Search and SA. Real perturbs the coordinates as for any continuous problem, Swap
+// and TwoOpt move through the orders of a permutation problem
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Neighbourhood {
+    #[default]
+    Real,
+    Swap,
+    TwoOpt,
+}
+
+impl Neighbourhood {
+    pub fn is_permutation(&self) -> bool {
+        *self != Neighbourhood::Real
+    }
+
+    // A neighbouring order of x, x itself for Real
+    pub fn neighbour<T, D>(&self, x: &OVector<T, D>, rng: &mut OptRng) -> OVector<T, D>
+    where
+        T: FloatNum,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+    {
+        let mut order = to_order(x);
+        match self {
+            Neighbourhood::Real => return x.clone(),
+            Neighbourhood::Swap => swap(&mut order, rng),
+            Neighbourhood::TwoOpt => inversion(&mut order, rng),
+        }
+        from_order(&order)
+    }
+}
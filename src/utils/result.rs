@@ -0,0 +1,184 @@
+use std::fmt::Write as _;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use thiserror::Error;
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    OVector
+};
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, State, EvalCounts};
+use crate::utils::stopping::TerminationReason;
+use crate::utils::checkpoint::NonFinite;
+
+#[derive(Error, Debug)]
+pub enum ResultError {
+    #[error("Failed to serialize result: {0}")]
+    SerializationError(String),
+
+    #[error("Failed to deserialize result: {0}")]
+    DeserializationError(String),
+}
+
+// Population statistics after one iteration, in the objective's own units
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub iter: usize,
+    #[serde_as(as = "NonFinite")]
+    pub best_f: f64,
+    #[serde_as(as = "NonFinite")]
+    pub mean_fitness: f64,
+    #[serde_as(as = "NonFinite")]
+    pub std_fitness: f64,
+    pub feasible_fraction: f64,
+}
+
+impl HistoryEntry {
+    // Mean and std only cover finite fitness values, infeasible points are often scored as -inf
+    pub fn from_state<T, N, D>(st: &State<T, N, D>) -> Self
+    where
+        T: FloatNum,
+        N: Dim,
+        D: Dim,
+        DefaultAllocator: Allocator<D> + Allocator<N> + Allocator<N, D>
+    {
+        let finite: Vec<f64> = st.fitness.iter()
+            .map(|f| f.to_f64().unwrap())
+            .filter(|f| f.is_finite())
+            .collect();
+
+        let (mean_fitness, std_fitness) = if finite.is_empty() {
+            (f64::NAN, f64::NAN)
+        } else {
+            let n = finite.len() as f64;
+            let mean = finite.iter().sum::<f64>() / n;
+            let var = finite.iter().map(|f| (f - mean).powi(2)).sum::<f64>() / n;
+            (mean, var.sqrt())
+        };
+
+        let n_feasible = st.constraints.iter().filter(|&&c| c).count();
+
+        Self {
+            iter: st.iter,
+            best_f: st.best_f.to_f64().unwrap(),
+            mean_fitness,
+            std_fitness,
+            feasible_fraction: n_feasible as f64 / st.constraints.len().max(1) as f64,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn to_json(&self) -> Result<String, ResultError> {
+        serde_json::to_string(self)
+            .map_err(|e| ResultError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ResultError> {
+        serde_json::from_str(json)
+            .map_err(|e| ResultError::DeserializationError(e.to_string()))
+    }
+
+    // One row per iteration with a header, non-finite values are written as inf/NaN
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("iter,best_f,mean_fitness,std_fitness,feasible_fraction\n");
+        for e in &self.entries {
+            writeln!(csv, "{},{},{},{},{}", e.iter, e.best_f, e.mean_fitness, e.std_fitness, e.feasible_fraction).unwrap();
+        }
+        csv
+    }
+}
+
+// Final multipliers of the augmented Lagrangian solver, and the constraint values g(x) and h(x) at best_x
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct LagrangeReport {
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub ineq_multipliers: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub eq_multipliers: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub ineq_residuals: Vec<f64>,
+    #[serde_as(as = "Vec<NonFinite>")]
+    pub eq_residuals: Vec<f64>,
+    #[serde_as(as = "NonFinite")]
+    pub penalty: f64,
+}
+
+// Non-dominated members of the final population, objectives in their own units. x[i] scores f[i]
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ParetoFront {
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub x: Vec<Vec<f64>>,
+    #[serde_as(as = "Vec<Vec<NonFinite>>")]
+    pub f: Vec<Vec<f64>>,
+}
+
+impl ParetoFront {
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+}
+
+// Returned by NonConvexOpt::run, best_f is in the objective's own units
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(bound(
+    serialize = "OVector<T, D>: Serialize",
+    deserialize = "OVector<T, D>: Deserialize<'de>"
+))]
+pub struct OptimizationResult<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    pub best_x: OVector<T, D>,
+    #[serde_as(as = "NonFinite")]
+    pub best_f: T,
+    pub termination: TerminationReason,
+    pub iterations: usize,
+    pub evals: EvalCounts,
+    pub elapsed: Duration,
+    pub history: Option<History>, // Only kept when OptConf::record_history is set
+    #[serde(default)]
+    pub lagrange: Option<LagrangeReport>, // Only set by AugLag
+    #[serde(default)]
+    pub pareto_front: Option<ParetoFront>, // Only set by multi-objective algorithms
+}
+
+impl<T, D> OptimizationResult<T, D>
+where
+    T: FloatNum,
+    D: Dim,
+    OVector<T, D>: Serialize + for<'de> Deserialize<'de>,
+    DefaultAllocator: Allocator<D>
+{
+    pub fn to_json(&self) -> Result<String, ResultError> {
+        serde_json::to_string(self)
+            .map_err(|e| ResultError::SerializationError(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ResultError> {
+        serde_json::from_str(json)
+            .map_err(|e| ResultError::DeserializationError(e.to_string()))
+    }
+}
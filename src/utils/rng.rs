@@ -0,0 +1,26 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// All randomness in the crate is drawn from this generator so that runs can be reproduced
+pub type OptRng = ChaCha8Rng;
+
+// Seeded generator if a seed is given, otherwise seeded from the thread-local entropy source
+pub fn new_rng(seed: Option<u64>) -> OptRng {
+    match seed {
+        Some(seed) => OptRng::seed_from_u64(seed),
+        None => OptRng::from_rng(&mut rand::rng()),
+    }
+}
+
+// Independent streams for parallel loops, one per item (not per thread),
+// so the result does not depend on how rayon schedules the work
+pub fn stream_rngs(rng: &mut OptRng, n: usize) -> Vec<OptRng> {
+    let base = rng.random::<u64>();
+    (0..n)
+        .map(|i| {
+            let mut stream = OptRng::seed_from_u64(base);
+            stream.set_stream(i as u64);
+            stream
+        })
+        .collect()
+}
@@ -0,0 +1,282 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    OMatrix,
+    OVector,
+    U1
+};
+
+use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb};
+use crate::utils::bounds::Bounds;
+use crate::utils::error::OptError;
+use crate::utils::rng::OptRng;
+
+// How to spread an initial population over the bounds
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub enum Sampler {
+    #[default]
+    Uniform,
+    // One point in each of n equal slices of every dimension
+    LatinHypercube,
+    // The Latin hypercube with the largest smallest distance between points out of candidates
+    MaximinLatinHypercube {
+        #[serde(default = "default_candidates")]
+        candidates: usize,
+    },
+    // Up to 21 dimensions. Populations of 2^k points are the most even
+    Sobol {
+        #[serde(default = "default_scramble")]
+        scramble: bool,
+    },
+    Halton {
+        #[serde(default = "default_scramble")]
+        scramble: bool,
+    },
+    // Points from base and their reflections through the centre of the box, keeping the better half.
+    // Evaluates the objective on all of them
+    Opposition {
+        #[serde(default)]
+        base: Box<Sampler>,
+    },
+}
+
+// A population drawn with sampler, set in OptConf for NonConvexOpt::new_sampled. dim is only needed
+// when the problem dimension isn't fixed by its type
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SamplingConf {
+    #[serde(default)]
+    pub sampler: Sampler,
+    pub pop_size: usize,
+    #[serde(default)]
+    pub dim: Option<usize>,
+}
+
+fn default_candidates() -> usize { 20 }
+fn default_scramble() -> bool { true }
+
+impl Sampler {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sampler::Uniform => "Uniform",
+            Sampler::LatinHypercube => "LatinHypercube",
+            Sampler::MaximinLatinHypercube { .. } => "MaximinLatinHypercube",
+            Sampler::Sobol { .. } => "Sobol",
+            Sampler::Halton { .. } => "Halton",
+            Sampler::Opposition { .. } => "Opposition",
+        }
+    }
+
+    // n points inside finite bounds, one per row. Opposition needs the objective, see population
+    pub fn sample<T, N, D>(&self, n: usize, bounds: &Bounds<T, D>, rng: &mut OptRng) -> Result<OMatrix<T, N, D>, OptError>
+    where
+        T: FloatNum,
+        N: Dim,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+                        + Allocator<N, D>
+    {
+        if !bounds.is_finite() {
+            return Err(OptError::InvalidBounds(format!("{} sampling needs finite bounds in every dimension", self.name())));
+        }
+        if N::try_to_usize().is_some_and(|rows| rows != n) {
+            return Err(OptError::InvalidConfig { alg: self.name().to_string(), reason: format!("can't put {} points in a {}-row population", n, N::try_to_usize().unwrap()) });
+        }
+        let unit = self.unit_points(n, bounds.dim(), rng)?;
+        Ok(OMatrix::<T, N, D>::from_fn_generic(N::from_usize(n), D::from_usize(bounds.dim()), |i, j| {
+            bounds.lower[j] + T::from_f64(unit[i][j]).unwrap() * (bounds.upper[j] - bounds.lower[j])
+        }))
+    }
+
+    // n points inside the problem's bounds. Opposition scores the candidates with the problem,
+    // feasible ones first, and those evaluations count towards max_evals
+    pub fn population<T, N, D>(&self, n: usize, opt_prob: &OptProb<T, D>, rng: &mut OptRng) -> Result<OMatrix<T, N, D>, OptError>
+    where
+        T: FloatNum,
+        N: Dim,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+                        + Allocator<N, D>
+    {
+        let Some(bounds) = &opt_prob.bounds else {
+            return Err(OptError::InvalidBounds(format!("{} sampling needs finite bounds in every dimension", self.name())));
+        };
+        let Sampler::Opposition { base } = self else {
+            return self.sample(n, bounds, rng);
+        };
+        if matches!(**base, Sampler::Opposition { .. }) {
+            return Err(OptError::InvalidConfig { alg: self.name().to_string(), reason: "the base sampler can't be Opposition itself".to_string() });
+        }
+
+        let points: OMatrix<T, N, D> = base.sample(n, bounds, rng)?;
+        let mut candidates: Vec<(OVector<T, D>, T, T)> = Vec::with_capacity(2 * n);
+        for row in points.row_iter() {
+            let x = row.transpose();
+            let opposite = OVector::<T, D>::from_fn_generic(D::from_usize(x.len()), U1, |j, _| bounds.lower[j] + bounds.upper[j] - x[j]);
+            for x in [opt_prob.snap(&x), opt_prob.snap(&opposite)] {
+                let (f, violation) = (opt_prob.evaluate(&x), opt_prob.violation(&x));
+                candidates.push((x, f, violation));
+            }
+        }
+        candidates.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap().then(b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)));
+        Ok(OMatrix::<T, N, D>::from_fn_generic(N::from_usize(n), D::from_usize(bounds.dim()), |i, j| candidates[i].0[j]))
+    }
+
+    // Points of the unit cube
+    fn unit_points(&self, n: usize, dim: usize, rng: &mut OptRng) -> Result<Vec<Vec<f64>>, OptError> {
+        match self {
+            Sampler::Uniform => Ok((0..n).map(|_| (0..dim).map(|_| rng.random::<f64>()).collect()).collect()),
+            Sampler::LatinHypercube => Ok(latin_hypercube(n, dim, rng)),
+            Sampler::MaximinLatinHypercube { candidates } => {
+                let best = (0..(*candidates).max(1))
+                    .map(|_| latin_hypercube(n, dim, rng))
+                    .map(|points| (min_distance(&points), points))
+                    .max_by(|a, b| a.0.total_cmp(&b.0))
+                    .unwrap();
+                Ok(best.1)
+            },
+            Sampler::Sobol { scramble } => sobol(n, dim, *scramble, rng),
+            Sampler::Halton { scramble } => Ok(halton(n, dim, *scramble, rng)),
+            Sampler::Opposition { .. } => Err(OptError::InvalidConfig { alg: self.name().to_string(), reason: "needs the objective, use Sampler::population".to_string() }),
+        }
+    }
+}
+
+fn latin_hypercube(n: usize, dim: usize, rng: &mut OptRng) -> Vec<Vec<f64>> {
+    let columns: Vec<Vec<f64>> = (0..dim).map(|_| {
+        let mut slices: Vec<usize> = (0..n).collect();
+        slices.shuffle(rng);
+        slices.iter().map(|&slice| (slice as f64 + rng.random::<f64>()) / n as f64).collect()
+    }).collect();
+    (0..n).map(|i| columns.iter().map(|column| column[i]).collect()).collect()
+}
+
+fn min_distance(points: &[Vec<f64>]) -> f64 {
+    let mut min = f64::INFINITY;
+    for (i, a) in points.iter().enumerate() {
+        for b in &points[i + 1..] {
+            min = min.min(a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum::<f64>());
+        }
+    }
+    min.sqrt()
+}
+
+// Degree, coefficients and initial direction numbers of dimensions 2 to 21, from Joe and Kuo
+const SOBOL_POLYNOMIALS: [(u32, u32, &[u32]); 20] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+];
+
+const BITS: usize = 32;
+
+fn sobol_directions(j: usize) -> [u32; BITS] {
+    let mut v = [0u32; BITS];
+    if j == 0 {
+        for (k, vk) in v.iter_mut().enumerate() {
+            *vk = 1 << (BITS - 1 - k);
+        }
+        return v;
+    }
+    let (s, a, m) = SOBOL_POLYNOMIALS[j - 1];
+    let s = s as usize;
+    for k in 0..BITS {
+        v[k] = if k < s {
+            m[k] << (BITS - 1 - k)
+        } else {
+            let mut vk = v[k - s] ^ (v[k - s] >> s);
+            for l in 1..s {
+                if (a >> (s - 1 - l)) & 1 == 1 {
+                    vk ^= v[k - l];
+                }
+            }
+            vk
+        };
+    }
+    v
+}
+
+// Owen scrambling through a hash on the reversed bits, from Burley's "Practical Hash-based Owen
+// Scrambling". Keeps the points of every elementary interval in it
+fn owen_scramble(x: u32, seed: u32) -> u32 {
+    let mut x = x.reverse_bits().wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50b47c);
+    x ^= x.wrapping_mul(0xb82f1e52);
+    x ^= x.wrapping_mul(0xc7afe638);
+    x ^= x.wrapping_mul(0x8d22f6e6);
+    x.reverse_bits()
+}
+
+// Points 0 to n - 1, so the unscrambled sequence starts at the lower corner
+fn sobol(n: usize, dim: usize, scramble: bool, rng: &mut OptRng) -> Result<Vec<Vec<f64>>, OptError> {
+    if dim > SOBOL_POLYNOMIALS.len() + 1 {
+        return Err(OptError::InvalidConfig { alg: "Sobol".to_string(), reason: format!("supports up to {} dimensions, got {}, Halton has no limit", SOBOL_POLYNOMIALS.len() + 1, dim) });
+    }
+    let directions: Vec<[u32; BITS]> = (0..dim).map(sobol_directions).collect();
+    let seeds: Vec<u32> = (0..dim).map(|_| rng.random()).collect();
+    Ok((0..n).map(|i| {
+        let gray = (i ^ (i >> 1)) as u64;
+        (0..dim).map(|j| {
+            let x = (0..BITS).filter(|&k| (gray >> k) & 1 == 1).fold(0u32, |x, k| x ^ directions[j][k]);
+            let x = if scramble { owen_scramble(x, seeds[j]) } else { x };
+            (x as f64 + 0.5) / 2f64.powi(BITS as i32)
+        }).collect()
+    }).collect())
+}
+
+fn primes(count: usize) -> Vec<u64> {
+    let mut primes: Vec<u64> = Vec::with_capacity(count);
+    let mut candidate = 2;
+    while primes.len() < count {
+        if primes.iter().take_while(|&&p| p * p <= candidate).all(|&p| candidate % p != 0) {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+// Radical inverses in the first dim primes, from index 1 so no point sits on the lower corner. Scrambling
+// permutes the nonzero digits of each base at random
+fn halton(n: usize, dim: usize, scramble: bool, rng: &mut OptRng) -> Vec<Vec<f64>> {
+    let bases = primes(dim);
+    let permutations: Vec<Vec<u64>> = bases.iter().map(|&b| {
+        let mut digits: Vec<u64> = (1..b).collect();
+        if scramble {
+            digits.shuffle(rng);
+        }
+        std::iter::once(0).chain(digits).collect()
+    }).collect();
+
+    (1..=n as u64).map(|i| {
+        bases.iter().zip(permutations.iter()).map(|(&b, perm)| {
+            let (mut k, mut f, mut x) = (i, 1.0 / b as f64, 0.0);
+            while k > 0 {
+                x += perm[(k % b) as usize] as f64 * f;
+                k /= b;
+                f /= b as f64;
+            }
+            x
+        }).collect()
+    }).collect()
+}
@@ -0,0 +1,387 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim
+};
+
+use crate::utils::config::OptConf;
+use crate::utils::opt_prob::{
+    FloatNumber as FloatNum,
+    OptProb,
+    ObjectiveSense,
+    State,
+    Diagnostics
+};
+
+// Why a run ended
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum TerminationReason {
+    Criterion(String), // Name of the stopping criterion that fired
+    Observer,
+    MaxIter,
+    MaxEvals,
+}
+
+// Everything a criterion can look at after a step, best_f and fitness are in the objective's own units
+pub struct StopContext<'a, T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    pub st: &'a State<T, N, D>,
+    pub previous_best: T,
+    pub sense: ObjectiveSense,
+    pub diagnostics: &'a Diagnostics,
+    pub elapsed: Duration,
+    pub opt_prob: &'a OptProb<T, D>,
+}
+
+impl<T, N, D> StopContext<'_, T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    // Positive when the last step improved the best value, whatever the sense
+    pub fn improvement(&self) -> T {
+        self.sense.apply(self.st.best_f) - self.sense.apply(self.previous_best)
+    }
+}
+
+pub trait StoppingCriterion<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String;
+
+    // Called once after every step, so stateful criteria can count steps
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool;
+
+    // Name of whatever fired, combinators override this to name their children
+    fn fired(&mut self, ctx: &StopContext<T, N, D>) -> Option<String> {
+        self.should_stop(ctx).then(|| self.name())
+    }
+}
+
+// The original atol/rtol test. A maximization stops once exp(-f) <= atol, as before, and a minimization once
+// its cost is within atol of 0, so a negative cost doesn't stop it
+pub struct Tolerance {
+    pub atol: f64,
+    pub rtol: f64,
+    pub min_iter: usize, // rtol only counts after this many iterations
+}
+
+impl<T, N, D> StoppingCriterion<T, N, D> for Tolerance
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String {
+        "Tolerance".to_string()
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool {
+        let best = ctx.st.best_f;
+        let atol = T::from_f64(self.atol).unwrap();
+        let reached_atol = match ctx.sense {
+            ObjectiveSense::Maximize => (-best).exp() <= atol,
+            ObjectiveSense::Minimize => best.abs() <= atol,
+        };
+
+        reached_atol
+            || ((best - ctx.previous_best).abs() <= T::from_f64(self.rtol).unwrap() && ctx.st.iter > self.min_iter)
+    }
+}
+
+// Best value at least as good as the target
+pub struct TargetValue {
+    pub target: f64,
+}
+
+impl<T, N, D> StoppingCriterion<T, N, D> for TargetValue
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String {
+        "TargetValue".to_string()
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool {
+        let target = T::from_f64(self.target).unwrap();
+        ctx.sense.apply(ctx.st.best_f) >= ctx.sense.apply(target)
+    }
+}
+
+// No improvement larger than tol for iters consecutive steps
+pub struct Stagnation {
+    pub iters: usize,
+    pub tol: f64,
+    count: usize,
+}
+
+impl Stagnation {
+    pub fn new(iters: usize, tol: f64) -> Self {
+        Self { iters, tol, count: 0 }
+    }
+}
+
+impl<T, N, D> StoppingCriterion<T, N, D> for Stagnation
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String {
+        "Stagnation".to_string()
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool {
+        if ctx.improvement() > T::from_f64(self.tol).unwrap() {
+            self.count = 0;
+        } else {
+            self.count += 1;
+        }
+        self.count >= self.iters
+    }
+}
+
+// Time since the optimizer was created (or resumed)
+pub struct WallClock {
+    pub limit: Duration,
+}
+
+impl<T, N, D> StoppingCriterion<T, N, D> for WallClock
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String {
+        "WallClock".to_string()
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool {
+        ctx.elapsed >= self.limit
+    }
+}
+
+// Norm of the objective's gradient at the best point, never fires without a gradient
+pub struct GradientNorm {
+    pub tol: f64,
+}
+
+impl<T, N, D> StoppingCriterion<T, N, D> for GradientNorm
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String {
+        "GradientNorm".to_string()
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool {
+        // Straight from the objective so the check doesn't use up the evaluation budget
+        ctx.opt_prob.objective.gradient(&ctx.st.best_x)
+            .is_some_and(|g| g.dot(&g).sqrt() <= T::from_f64(self.tol).unwrap())
+    }
+}
+
+// Mean distance of the population from its centroid, never fires for single-point algorithms
+pub struct Diversity {
+    pub tol: f64,
+}
+
+impl<T, N, D> StoppingCriterion<T, N, D> for Diversity
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String {
+        "Diversity".to_string()
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool {
+        let pop = &ctx.st.pop;
+        if pop.nrows() < 2 {
+            return false;
+        }
+
+        let n = T::from_usize(pop.nrows()).unwrap();
+        let centroid: Vec<T> = pop.column_iter().map(|col| col.sum() / n).collect();
+        let spread = pop.row_iter()
+            .map(|row| row.iter().zip(&centroid).map(|(&x, &c)| (x - c) * (x - c)).fold(T::zero(), |acc, d| acc + d).sqrt())
+            .fold(T::zero(), |acc, d| acc + d) / n;
+        spread <= T::from_f64(self.tol).unwrap()
+    }
+}
+
+// Fires when an algorithm reports a diagnostic at or below tol, e.g. CMA-ES sigma or NM simplex size
+pub struct DiagnosticBelow {
+    pub key: String,
+    pub tol: f64,
+}
+
+impl<T, N, D> StoppingCriterion<T, N, D> for DiagnosticBelow
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String {
+        self.key.clone()
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool {
+        ctx.diagnostics.get(&self.key).is_some_and(|&v| v <= self.tol)
+    }
+}
+
+type BoxedCriterion<T, N, D> = Box<dyn StoppingCriterion<T, N, D>>;
+
+// Stops when any child does
+pub struct Any<T, N, D>(pub Vec<BoxedCriterion<T, N, D>>);
+
+impl<T, N, D> StoppingCriterion<T, N, D> for Any<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String {
+        format!("Any({})", self.0.iter().map(|c| c.name()).collect::<Vec<_>>().join(", "))
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool {
+        self.fired(ctx).is_some()
+    }
+
+    fn fired(&mut self, ctx: &StopContext<T, N, D>) -> Option<String> {
+        // Every child is checked each step so stateful ones keep count
+        let fired: Vec<String> = self.0.iter_mut().filter_map(|c| c.fired(ctx)).collect();
+        fired.into_iter().next()
+    }
+}
+
+// Stops only when every child does
+pub struct All<T, N, D>(pub Vec<BoxedCriterion<T, N, D>>);
+
+impl<T, N, D> StoppingCriterion<T, N, D> for All<T, N, D>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N>
+                    + Allocator<N, D>
+{
+    fn name(&self) -> String {
+        format!("All({})", self.0.iter().map(|c| c.name()).collect::<Vec<_>>().join(", "))
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<T, N, D>) -> bool {
+        self.fired(ctx).is_some()
+    }
+
+    fn fired(&mut self, ctx: &StopContext<T, N, D>) -> Option<String> {
+        let fired: Vec<Option<String>> = self.0.iter_mut().map(|c| c.fired(ctx)).collect();
+        if self.0.is_empty() || fired.iter().any(|f| f.is_none()) {
+            return None;
+        }
+        Some(format!("All({})", fired.into_iter().flatten().collect::<Vec<_>>().join(", ")))
+    }
+}
+
+// JSON form of the criteria, e.g. {"Any": [{"TargetValue": {"target": 0.0}}, {"Stagnation": {"iters": 50}}]}
+// Without a stopping config, runs use the atol/rtol test
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub enum StoppingConf {
+    #[default]
+    Tolerance,
+    TargetValue { target: f64 },
+    Stagnation {
+        iters: usize,
+        #[serde(default)]
+        tol: f64,
+    },
+    WallClock { seconds: f64 },
+    GradientNorm { tol: f64 },
+    Diversity { tol: f64 },
+    Sigma { tol: f64 },       // CMA-ES step size
+    TolX { tol: f64 },        // CMA-ES sigma times the largest coordinate std
+    SimplexSize { tol: f64 }, // Nelder-Mead
+    Any(Vec<StoppingConf>),
+    All(Vec<StoppingConf>),
+}
+
+impl StoppingConf {
+    pub fn build<T, N, D>(&self, opt_conf: &OptConf) -> BoxedCriterion<T, N, D>
+    where
+        T: FloatNum,
+        N: Dim,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+                        + Allocator<N>
+                        + Allocator<N, D>
+    {
+        match self {
+            StoppingConf::Tolerance => Box::new(Tolerance {
+                atol: opt_conf.atol,
+                rtol: opt_conf.rtol,
+                min_iter: (opt_conf.max_iter as f64 * opt_conf.rtol_max_iter_fraction).floor() as usize,
+            }),
+            StoppingConf::TargetValue { target } => Box::new(TargetValue { target: *target }),
+            StoppingConf::Stagnation { iters, tol } => Box::new(Stagnation::new(*iters, *tol)),
+            StoppingConf::WallClock { seconds } => Box::new(WallClock { limit: Duration::from_secs_f64(*seconds) }),
+            StoppingConf::GradientNorm { tol } => Box::new(GradientNorm { tol: *tol }),
+            StoppingConf::Diversity { tol } => Box::new(Diversity { tol: *tol }),
+            StoppingConf::Sigma { tol } => Box::new(DiagnosticBelow { key: "sigma".to_string(), tol: *tol }),
+            StoppingConf::TolX { tol } => Box::new(DiagnosticBelow { key: "tol_x".to_string(), tol: *tol }),
+            StoppingConf::SimplexSize { tol } => Box::new(DiagnosticBelow { key: "simplex_size".to_string(), tol: *tol }),
+            StoppingConf::Any(confs) => Box::new(Any(confs.iter().map(|c| c.build(opt_conf)).collect())),
+            StoppingConf::All(confs) => Box::new(All(confs.iter().map(|c| c.build(opt_conf)).collect())),
+        }
+    }
+}
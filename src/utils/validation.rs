@@ -0,0 +1,325 @@
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    OMatrix,
+    OVector
+};
+
+use crate::utils::config::{Config, AlgConf, SelectionConf, CrossoverConf, MutationConf, SwapConf, Scalarization};
+use crate::utils::alg_conf::de_conf::MutationType;
+use crate::utils::opt_prob::{FloatNumber as FloatNum, OptProb};
+use crate::utils::error::OptError;
+use crate::utils::gradient_check::check_gradient;
+use crate::utils::pareto::num_weights;
+use crate::utils::permutation::is_permutation;
+
+// Checks run by NonConvexOpt::new so mismatches fail early instead of panicking mid-run
+pub fn validate<T, N, D>(conf: &Config, init_pop: &OMatrix<T, N, D>, opt_prob: &OptProb<T, D>) -> Result<(), OptError>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N, D>
+{
+    let alg_conf = &conf.alg_conf;
+    let alg = alg_conf.name();
+    check_population(alg, init_pop)?;
+    check_config(alg_conf, init_pop.nrows(), init_pop.ncols())?;
+
+    let x0 = init_pop.row(0).transpose();
+    check_bounds(alg_conf, opt_prob, &x0)?;
+    check_variables(opt_prob, x0.len())?;
+    check_permutation(alg_conf, init_pop, opt_prob)?;
+
+    if alg_conf.is_multi_objective() {
+        check_objectives(alg_conf, opt_prob, init_pop.nrows())?;
+    }
+
+    // Straight from the objective so the checks don't count as evaluations
+    if alg_conf.requires_gradient() {
+        if opt_prob.objective.gradient(&x0).is_none() {
+            return Err(OptError::MissingGradient { alg: alg.to_string() });
+        }
+        if let Some(check) = &conf.opt_conf.check_gradient {
+            let points: Vec<OVector<T, D>> = init_pop.row_iter().map(|row| row.transpose()).collect();
+            check_gradient(opt_prob.objective.as_ref(), &points, check)?.into_result()?;
+        }
+    }
+    Ok(())
+}
+
+fn check_population<T, N, D>(alg: &str, init_pop: &OMatrix<T, N, D>) -> Result<(), OptError>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<N, D>
+{
+    let invalid = |reason: String| Err(OptError::InvalidPopulation { alg: alg.to_string(), reason });
+
+    if init_pop.nrows() == 0 || init_pop.ncols() == 0 {
+        return invalid(format!("got a {}x{} population", init_pop.nrows(), init_pop.ncols()));
+    }
+    if init_pop.iter().any(|x| !x.is_finite()) {
+        return invalid("population contains NaN or inf".to_string());
+    }
+    Ok(())
+}
+
+fn check_bounds<T, D>(alg_conf: &AlgConf, opt_prob: &OptProb<T, D>, x0: &OVector<T, D>) -> Result<(), OptError>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    if let Some(bounds) = &opt_prob.bounds {
+        if bounds.dim() != x0.len() {
+            return Err(OptError::InvalidBounds(format!("bounds have {} values for a {}-dimensional problem", bounds.dim(), x0.len())));
+        }
+    }
+    if let Some(linear) = &opt_prob.linear {
+        if linear.dim() != x0.len() {
+            return Err(OptError::InvalidLinearConstraints(format!("constraints have {} columns for a {}-dimensional problem", linear.dim(), x0.len())));
+        }
+    }
+
+    // These sample uniformly from the box, so every side has to be finite
+    let samples_box = match alg_conf {
+        AlgConf::GRASP(_) | AlgConf::MSPO(_) => true,
+        AlgConf::CGA(conf) => !matches!(conf.mutation, MutationConf::Gaussian(_)),
+        AlgConf::NSGA2(conf) => !matches!(conf.mutation, MutationConf::Gaussian(_)),
+        AlgConf::AugLag(conf) => return check_bounds(&conf.inner, opt_prob, x0),
+        _ => false,
+    };
+    if samples_box && !opt_prob.bounds.as_ref().is_some_and(|b| b.is_finite()) {
+        return Err(OptError::InvalidBounds(format!("{} needs finite bounds in every dimension", alg_conf.name())));
+    }
+    Ok(())
+}
+
+// Every algorithm snaps its candidates. The gradient methods and Nelder-Mead step in the continuous
+// relaxation and snap afterwards, a heuristic that only moves a dimension once a step crosses a rounding boundary
+fn check_variables<T, D>(opt_prob: &OptProb<T, D>, dim: usize) -> Result<(), OptError>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    let Some(variables) = &opt_prob.variables else { return Ok(()) };
+    if variables.dim() != dim {
+        return Err(OptError::InvalidVariables(format!("{} variable types for a {}-dimensional problem", variables.dim(), dim)));
+    }
+    Ok(())
+}
+
+// Permutation operators only produce orders from orders, and nothing else may move them off
+fn check_permutation<T, N, D>(alg_conf: &AlgConf, init_pop: &OMatrix<T, N, D>, opt_prob: &OptProb<T, D>) -> Result<(), OptError>
+where
+    T: FloatNum,
+    N: Dim,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+                    + Allocator<N, D>
+{
+    if !alg_conf.is_permutation() {
+        return Ok(());
+    }
+    let alg = alg_conf.name();
+    if opt_prob.variables.is_some() || opt_prob.linear.is_some() {
+        return Err(OptError::InvalidConfig { alg: alg.to_string(), reason: "permutation problems take no variable types or linear constraints".to_string() });
+    }
+    for (i, row) in init_pop.row_iter().enumerate() {
+        let x: Vec<f64> = row.iter().map(|v| v.to_f64().unwrap()).collect();
+        if !is_permutation(&x) {
+            return Err(OptError::InvalidPopulation { alg: alg.to_string(), reason: format!("row {} isn't an order of 0..{}", i, x.len()) });
+        }
+    }
+    Ok(())
+}
+
+// Multi-objective algorithms rank by the objective vector, which only problems from
+// NonConvexOpt::new_multi_objective have
+fn check_objectives<T, D>(alg_conf: &AlgConf, opt_prob: &OptProb<T, D>, pop_size: usize) -> Result<(), OptError>
+where
+    T: FloatNum,
+    D: Dim,
+    DefaultAllocator: Allocator<D>
+{
+    let alg = alg_conf.name();
+    let num_objectives = match &opt_prob.multi {
+        Some(multi) => multi.num_objectives(),
+        None => return Err(OptError::InvalidConfig { alg: alg.to_string(), reason: "needs a MultiObjectiveFunction, see NonConvexOpt::new_multi_objective".to_string() }),
+    };
+    if num_objectives == 0 {
+        return Err(OptError::InvalidConfig { alg: alg.to_string(), reason: "needs at least one objective".to_string() });
+    }
+
+    // One member per weight vector
+    if let AlgConf::MOEAD(conf) = alg_conf {
+        let expected = num_weights(num_objectives, conf.divisions);
+        if pop_size != expected {
+            return Err(OptError::InvalidPopulation {
+                alg: alg.to_string(),
+                reason: format!("{} divisions give {} weight vectors for {} objectives, got {} individuals", conf.divisions, expected, num_objectives, pop_size),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_config(alg_conf: &AlgConf, pop_size: usize, dim: usize) -> Result<(), OptError> {
+    let alg = alg_conf.name();
+    let population = |reason: String| Err(OptError::InvalidPopulation { alg: alg.to_string(), reason });
+    let config = |reason: String| Err(OptError::InvalidConfig { alg: alg.to_string(), reason });
+    let probability = |name: &str, p: f64| {
+        if (0.0..=1.0).contains(&p) {
+            Ok(())
+        } else {
+            Err(OptError::InvalidConfig { alg: alg.to_string(), reason: format!("{} must be in [0, 1], got {}", name, p) })
+        }
+    };
+
+    match alg_conf {
+        AlgConf::CGA(conf) => {
+            if conf.common.num_parents == 0 {
+                return config("num_parents must be at least 1".to_string());
+            }
+            if let SelectionConf::Tournament(t) = &conf.selection {
+                if t.tournament_size == 0 || t.tournament_size > pop_size {
+                    return population(format!("tournament_size {} needs between 1 and {} individuals", t.tournament_size, pop_size));
+                }
+            }
+            check_operators(alg, &conf.crossover, &conf.mutation, probability)?;
+        },
+        AlgConf::NSGA2(conf) => {
+            // Crossover needs two distinct parents
+            if pop_size < 2 {
+                return population(format!("needs at least 2 individuals, got {}", pop_size));
+            }
+            if conf.tournament_size == 0 || conf.tournament_size > pop_size {
+                return population(format!("tournament_size {} needs between 1 and {} individuals", conf.tournament_size, pop_size));
+            }
+            check_operators(alg, &conf.crossover, &conf.mutation, probability)?;
+        },
+        AlgConf::PT(conf) => {
+            if conf.common.num_replicas == 0 {
+                return config("num_replicas must be at least 1".to_string());
+            }
+            if let SwapConf::Stochastic(s) = &conf.swap_conf {
+                probability("swap_probability", s.swap_probability)?;
+            }
+        },
+        AlgConf::TS(conf) => {
+            probability("perturbation_prob", conf.common.perturbation_prob)?;
+            if conf.common.num_neighbors == 0 {
+                return config("num_neighbors must be at least 1".to_string());
+            }
+        },
+        AlgConf::GRASP(conf) => {
+            probability("perturbation_prob", conf.perturbation_prob)?;
+            // The restricted candidate list is empty from alpha = 0.5 on
+            if !(0.0..0.5).contains(&conf.alpha) {
+                return config(format!("alpha must be in [0, 0.5), got {}", conf.alpha));
+            }
+            if conf.step_size <= 0.0 {
+                return config(format!("step_size must be positive, got {}", conf.step_size));
+            }
+        },
+        AlgConf::NM(_) => {
+            if pop_size != dim + 1 {
+                return population(format!("the simplex needs {} vertices for {} dimensions, got {}", dim + 1, dim, pop_size));
+            }
+        },
+        AlgConf::MSPO(conf) => {
+            if conf.num_swarms == 0 || conf.swarm_size == 0 {
+                return config("num_swarms and swarm_size must be at least 1".to_string());
+            }
+            if pop_size < conf.num_swarms * conf.swarm_size {
+                return population(format!("needs at least num_swarms * swarm_size = {} individuals, got {}", conf.num_swarms * conf.swarm_size, pop_size));
+            }
+        },
+        AlgConf::DE(conf) => {
+            let strategy = match &conf.mutation_type {
+                MutationType::Standard(s) => &s.strategy,
+                MutationType::Adaptive(a) => &a.strategy,
+            };
+            if pop_size < strategy.min_population() {
+                return population(format!("{:?} needs at least {} individuals, got {}", strategy, strategy.min_population(), pop_size));
+            }
+        },
+        AlgConf::CMAES(conf) => {
+            if conf.num_parents == 0 || conf.num_parents > pop_size {
+                return population(format!("num_parents {} needs between 1 and {} individuals", conf.num_parents, pop_size));
+            }
+            if conf.initial_sigma <= 0.0 {
+                return config(format!("initial_sigma must be positive, got {}", conf.initial_sigma));
+            }
+        },
+        AlgConf::MOEAD(conf) => {
+            if conf.divisions == 0 {
+                return config("divisions must be at least 1".to_string());
+            }
+            if conf.max_replacements == 0 {
+                return config("max_replacements must be at least 1".to_string());
+            }
+            probability("neighbourhood_prob", conf.neighbourhood_prob)?;
+            probability("cr", conf.cr)?;
+            if let Scalarization::PBI { theta } = conf.scalarization {
+                if theta < 0.0 {
+                    return config(format!("theta must be non-negative, got {}", theta));
+                }
+            }
+            // Mating pools are neighbourhoods, so each has to hold the DE donors
+            let neighbourhood = conf.neighbourhood_size.min(pop_size);
+            if neighbourhood < conf.strategy.min_population() {
+                return config(format!("{:?} needs neighbourhoods of at least {} members, got {}", conf.strategy, conf.strategy.min_population(), neighbourhood));
+            }
+        },
+        AlgConf::AugLag(conf) => {
+            if matches!(*conf.inner, AlgConf::AugLag(_)) {
+                return config("the inner solver can't be AugLag itself".to_string());
+            }
+            if conf.inner.is_multi_objective() {
+                return config(format!("the inner solver has a single objective, so it can't be {}", conf.inner.name()));
+            }
+            if conf.inner_iters == 0 {
+                return config("inner_iters must be at least 1".to_string());
+            }
+            if conf.initial_penalty <= 0.0 || conf.max_penalty < conf.initial_penalty {
+                return config(format!("needs 0 < initial_penalty <= max_penalty, got {} and {}", conf.initial_penalty, conf.max_penalty));
+            }
+            if conf.penalty_growth < 1.0 {
+                return config(format!("penalty_growth must be at least 1, got {}", conf.penalty_growth));
+            }
+            if conf.residual_reduction <= 0.0 || conf.residual_reduction > 1.0 {
+                return config(format!("residual_reduction must be in (0, 1], got {}", conf.residual_reduction));
+            }
+            check_config(&conf.inner, pop_size, dim)?;
+        },
+        AlgConf::SA(_) | AlgConf::Adam(_) | AlgConf::SGA(_) | AlgConf::LBFGS(_) => {},
+    }
+    Ok(())
+}
+
+// Shared by CGA and NSGA2
+fn check_operators(alg: &str, crossover: &CrossoverConf, mutation: &MutationConf, probability: impl Fn(&str, f64) -> Result<(), OptError>) -> Result<(), OptError> {
+    match crossover {
+        CrossoverConf::Random(c) => probability("crossover_prob", c.crossover_prob)?,
+        CrossoverConf::Heuristic(c) => probability("crossover_prob", c.crossover_prob)?,
+        CrossoverConf::PMX(c) | CrossoverConf::OX(c) | CrossoverConf::Cycle(c) => probability("crossover_prob", c.crossover_prob)?,
+    }
+    match mutation {
+        MutationConf::Gaussian(m) => probability("mutation_rate", m.mutation_rate)?,
+        MutationConf::Uniform(m) => probability("mutation_rate", m.mutation_rate)?,
+        MutationConf::NonUniform(m) => probability("mutation_rate", m.mutation_rate)?,
+        MutationConf::Polynomial(m) => probability("mutation_rate", m.mutation_rate)?,
+        MutationConf::Swap(m) | MutationConf::Insert(m) | MutationConf::Inversion(m) | MutationConf::Scramble(m) => probability("mutation_rate", m.mutation_rate)?,
+    }
+    // Real-valued operators would turn orders into arbitrary vectors
+    if crossover.is_permutation() != mutation.is_permutation() {
+        return Err(OptError::InvalidConfig { alg: alg.to_string(), reason: "permutation crossovers and mutations only work with each other".to_string() });
+    }
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use rand::Rng;
+use nalgebra::{
+    allocator::Allocator,
+    DefaultAllocator,
+    Dim,
+    OVector
+};
+
+use crate::utils::opt_prob::FloatNumber as FloatNum;
+use crate::utils::bounds::Bounds;
+use crate::utils::error::OptError;
+use crate::utils::rng::OptRng;
+
+// What values a dimension can take. Integer, discrete and categorical dimensions replace the box
+// bounds of their dimension with their own range
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub enum VarType {
+    #[default]
+    Continuous,
+    Integer { lower: i64, upper: i64 },
+    Discrete { values: Vec<f64> }, // Any order, sorted when the spec is built
+    // The objective sees the index of the category, 0 to categories.len() - 1
+    Categorical {
+        categories: Vec<String>,
+        #[serde(default)]
+        encoding: CategoricalEncoding,
+    },
+}
+
+// How moves go through the categories of a dimension
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CategoricalEncoding {
+    // Ordered like an integer, a move goes to the next or previous category
+    #[default]
+    Ordinal,
+    // Unordered, every category as far from the others as in a one-hot encoding, so a move goes to
+    // any other category with equal probability
+    OneHot,
+}
+
+// Per-dimension variable types, set from OptConf::variables. Candidates are snapped onto them by
+// OptProb::repair, so the objective only sees integral values and listed levels
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variables {
+    types: Vec<VarType>,
+}
+
+impl Variables {
+    pub fn new(types: Vec<VarType>) -> Result<Self, OptError> {
+        let mut types = types;
+        for (i, var) in types.iter_mut().enumerate() {
+            match var {
+                VarType::Continuous => {},
+                VarType::Integer { lower, upper } => {
+                    if lower > upper {
+                        return Err(OptError::InvalidVariables(format!("integer range {}..={} is empty in dimension {}", lower, upper, i)));
+                    }
+                },
+                VarType::Discrete { values } => {
+                    if values.is_empty() || values.iter().any(|v| !v.is_finite()) {
+                        return Err(OptError::InvalidVariables(format!("dimension {} needs at least one finite value", i)));
+                    }
+                    values.sort_by(f64::total_cmp);
+                    values.dedup();
+                },
+                VarType::Categorical { categories, .. } => {
+                    if categories.is_empty() {
+                        return Err(OptError::InvalidVariables(format!("dimension {} needs at least one category", i)));
+                    }
+                    if let Some(name) = categories.iter().enumerate().find(|&(k, c)| categories[..k].contains(c)).map(|(_, c)| c) {
+                        return Err(OptError::InvalidVariables(format!("category {} appears twice in dimension {}", name, i)));
+                    }
+                },
+            }
+        }
+        Ok(Self { types })
+    }
+
+    // As written in OptConf, a single type applies to every dimension
+    pub fn from_conf(types: &[VarType], dim: usize) -> Result<Self, OptError> {
+        match types.len() {
+            1 => Self::new(vec![types[0].clone(); dim]),
+            n if n == dim => Self::new(types.to_vec()),
+            n => Err(OptError::InvalidVariables(format!("{} variable types for a {}-dimensional problem", n, dim))),
+        }
+    }
+
+    pub fn types(&self) -> &[VarType] {
+        &self.types
+    }
+
+    pub fn dim(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_continuous(&self) -> bool {
+        self.types.iter().all(|var| *var == VarType::Continuous)
+    }
+
+    // Name of the category at index v of dimension i, None when it isn't categorical or v isn't an index
+    pub fn category(&self, i: usize, v: f64) -> Option<&str> {
+        match &self.types[i] {
+            VarType::Categorical { categories, .. } if v.fract() == 0.0 && v >= 0.0 => categories.get(v as usize).map(String::as_str),
+            _ => None,
+        }
+    }
+
+    // Range of the levels of dimension i, None when it is continuous
+    fn range(&self, i: usize) -> Option<(f64, f64)> {
+        match &self.types[i] {
+            VarType::Continuous => None,
+            VarType::Integer { lower, upper } => Some((*lower as f64, *upper as f64)),
+            VarType::Discrete { values } => Some((values[0], values[values.len() - 1])),
+            VarType::Categorical { categories, .. } => Some((0.0, (categories.len() - 1) as f64)),
+        }
+    }
+
+    // Whether v is one of the values dimension i can take
+    fn on_level(&self, i: usize, v: f64) -> bool {
+        match &self.types[i] {
+            VarType::Continuous => true,
+            VarType::Integer { .. } | VarType::Categorical { .. } => {
+                let (lower, upper) = self.range(i).unwrap();
+                v.fract() == 0.0 && v >= lower && v <= upper
+            },
+            VarType::Discrete { values } => values.contains(&v),
+        }
+    }
+
+    // The level of dimension i just above or below current, None at the end of the range or when continuous.
+    // One-hot categories have no order, any other one is next
+    fn next_level(&self, i: usize, current: f64, up: bool, rng: &mut OptRng) -> Option<f64> {
+        match &self.types[i] {
+            VarType::Continuous => None,
+            VarType::Categorical { categories, encoding: CategoricalEncoding::OneHot } => {
+                let n = categories.len();
+                (n > 1).then(|| {
+                    let k = rng.random_range(0..n - 1);
+                    if k as f64 >= current { (k + 1) as f64 } else { k as f64 }
+                })
+            },
+            VarType::Integer { .. } | VarType::Categorical { .. } => {
+                let (lower, upper) = self.range(i).unwrap();
+                let v = if up { current + 1.0 } else { current - 1.0 };
+                (v >= lower && v <= upper).then_some(v)
+            },
+            VarType::Discrete { values } => if up {
+                values.iter().find(|&&v| v > current).copied()
+            } else {
+                values.iter().rev().find(|&&v| v < current).copied()
+            },
+        }
+    }
+
+    // Box with integer, discrete and categorical dimensions limited to their range, other dimensions as given
+    pub fn restrict<T, D>(&self, bounds: Option<Bounds<T, D>>) -> Result<Bounds<T, D>, OptError>
+    where
+        T: FloatNum,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+    {
+        let mut bounds = bounds.unwrap_or_else(|| Bounds::unbounded(self.dim()));
+        for i in 0..self.dim() {
+            let Some((lo, hi)) = self.range(i) else { continue };
+            bounds.lower[i] = T::from_f64(lo).unwrap();
+            bounds.upper[i] = T::from_f64(hi).unwrap();
+        }
+        Bounds::new(bounds.lower, bounds.upper)
+    }
+
+    // Rounds integer and categorical dimensions and moves discrete ones to their closest value. Points outside a
+    // discrete range are left there, so the death penalty still sees them
+    pub fn snap<T, D>(&self, x: &OVector<T, D>) -> OVector<T, D>
+    where
+        T: FloatNum,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+    {
+        let mut x = x.clone();
+        for (i, var) in self.types.iter().enumerate() {
+            match var {
+                VarType::Continuous => {},
+                VarType::Integer { .. } | VarType::Categorical { .. } => x[i] = x[i].round(),
+                VarType::Discrete { values } => {
+                    let v = x[i].to_f64().unwrap();
+                    if v >= values[0] && v <= values[values.len() - 1] {
+                        x[i] = T::from_f64(closest(values, v)).unwrap();
+                    }
+                },
+            }
+        }
+        x
+    }
+
+    // Whether every integer, discrete and categorical dimension of x is on one of its levels
+    pub fn contains<T, D>(&self, x: &OVector<T, D>) -> bool
+    where
+        T: FloatNum,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+    {
+        (0..self.dim()).all(|i| self.on_level(i, x[i].to_f64().unwrap()))
+    }
+
+    // Small moves in an integer or discrete dimension would round straight back to where they started,
+    // so a dimension that moved away from parent goes at least one level in that direction. A one-hot
+    // categorical dimension that moved at all goes to a random other category instead
+    pub fn step_away<T, D>(&self, x: &OVector<T, D>, parent: &OVector<T, D>, rng: &mut OptRng) -> OVector<T, D>
+    where
+        T: FloatNum,
+        D: Dim,
+        DefaultAllocator: Allocator<D>
+    {
+        let snapped = self.snap(x);
+        let start = self.snap(parent);
+        let mut x = x.clone();
+        for i in 0..self.dim() {
+            let unordered = matches!(self.types[i], VarType::Categorical { encoding: CategoricalEncoding::OneHot, .. });
+            if self.types[i] == VarType::Continuous || x[i] == parent[i] || (snapped[i] != start[i] && !unordered) {
+                continue;
+            }
+            if let Some(v) = self.next_level(i, start[i].to_f64().unwrap(), x[i] > parent[i], rng) {
+                x[i] = T::from_f64(v).unwrap();
+            }
+        }
+        x
+    }
+}
+
+// Closest of the sorted values to v, the lower one on a tie
+fn closest(values: &[f64], v: f64) -> f64 {
+    let k = values.partition_point(|&level| level < v);
+    match (k.checked_sub(1).map(|j| values[j]), values.get(k)) {
+        (Some(below), Some(&above)) => if v - below <= above - v { below } else { above },
+        (Some(below), None) => below,
+        (None, Some(&above)) => above,
+        (None, None) => v,
+    }
+}
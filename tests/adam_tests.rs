@@ -1,11 +1,12 @@
 mod common;
 
 use nalgebra::{SMatrix, U1, U2};
-use common::fcns::{QuadraticObjective, QuadraticConstraints};
+use common::fcns::{SphereCost, UnitBox};
 use non_convex_opt::algorithms::adam::adam::Adam;
 use non_convex_opt::utils::{
-    config::AdamConf,
+    config::{AdamConf, BoundHandling, ObjectiveSense},
     opt_prob::{OptProb, OptimizationAlgorithm},
+    bounds::Bounds,
 };
 
 #[test]
@@ -15,14 +16,17 @@ fn test_adam() {
         beta1: 0.9,
         beta2: 0.999,
         epsilon: 1e-8,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
-    let mut adam = Adam::<f64, U1, U2>::new(conf, init_x.clone(), opt_prob);
+    let mut adam = Adam::<f64, U1, U2>::new(conf, init_x.clone(), opt_prob, Some(0));
     
     let initial_fitness = adam.st.best_f;
     
@@ -0,0 +1,189 @@
+mod common;
+
+use nalgebra::{DMatrix, DVector, Dim, OVector, DefaultAllocator, allocator::Allocator};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::{ObjectiveFunction, ConstraintFunction},
+    checkpoint::AlgCheckpoint,
+    stopping::TerminationReason,
+    error::OptError,
+};
+
+// (x0 - c0)^2 + (x1 - c1)^2, minimized
+#[derive(Debug, Clone)]
+struct ShiftedSphere {
+    c: [f64; 2],
+    with_gradient: bool,
+}
+
+impl<D: Dim> ObjectiveFunction<f64, D> for ShiftedSphere
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> f64 {
+        (x[0] - self.c[0]).powi(2) + (x[1] - self.c[1]).powi(2)
+    }
+
+    fn gradient(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
+        if !self.with_gradient {
+            return None;
+        }
+        let mut grad = x.clone();
+        grad[0] = 2.0 * (x[0] - self.c[0]);
+        grad[1] = 2.0 * (x[1] - self.c[1]);
+        Some(grad)
+    }
+}
+
+// x0 + x1 = 1
+#[derive(Debug, Clone)]
+struct Line;
+
+impl<D: Dim> ConstraintFunction<f64, D> for Line
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn h(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(vec![x[0] + x[1] - 1.0])
+    }
+}
+
+// x0 + x1 <= 2
+#[derive(Debug, Clone)]
+struct HalfPlane;
+
+impl<D: Dim> ConstraintFunction<f64, D> for HalfPlane
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(vec![x[0] + x[1] - 2.0])
+    }
+}
+
+fn conf(max_iter: usize, alg_conf: &str) -> Config {
+    common::conf(max_iter, r#""seed": 3, "objective_sense": "Minimize""#, alg_conf)
+}
+
+#[test]
+fn test_equality_with_lbfgs() {
+    let alg_conf = r#"{ "AugLag": { "inner": { "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } }, "inner_iters": 20 } }"#;
+    let init_pop = DMatrix::from_row_slice(1, 2, &[3.0, -2.0]);
+    let objective = ShiftedSphere { c: [1.0, 2.0], with_gradient: true };
+    let mut opt = NonConvexOpt::new_with_constraints(conf(200, alg_conf), init_pop, objective, Some(Line)).unwrap();
+    let result = opt.run().unwrap();
+
+    // Minimum at (0, 1) with f = 2 and lambda = 2
+    assert!((result.best_x[0] - 0.0).abs() < 1e-4, "{:?}", result.best_x.as_slice());
+    assert!((result.best_x[1] - 1.0).abs() < 1e-4, "{:?}", result.best_x.as_slice());
+    assert!((result.best_f - 2.0).abs() < 1e-4);
+
+    let lagrange = result.lagrange.unwrap();
+    assert!((lagrange.eq_multipliers[0] - 2.0).abs() < 1e-3, "{:?}", lagrange);
+    assert!(lagrange.eq_residuals[0].abs() < 1e-5);
+    assert!(lagrange.ineq_multipliers.is_empty() && lagrange.ineq_residuals.is_empty());
+}
+
+#[test]
+fn test_inequality_without_gradients() {
+    let inner_confs = [
+        r#"{ "NM": {} }"#,
+        r#"{ "CMAES": { "num_parents": 5, "initial_sigma": 0.5 } }"#,
+    ];
+
+    for inner in inner_confs {
+        let alg_conf = format!(r#"{{ "AugLag": {{ "inner": {}, "inner_iters": 60 }} }}"#, inner);
+        let n_rows = if inner.contains("\"NM\"") { 3 } else { 10 };
+        let init_pop = DMatrix::from_fn(n_rows, 2, |i, j| 0.1 * (i as f64) + 0.3 * ((i + j) % 2) as f64);
+        let objective = ShiftedSphere { c: [2.0, 2.0], with_gradient: false };
+        let mut opt = NonConvexOpt::new_with_constraints(conf(600, &alg_conf), init_pop, objective, Some(HalfPlane)).unwrap();
+        let result = opt.run().unwrap();
+
+        // Minimum at (1, 1) on the boundary, with mu = 2
+        assert!((result.best_x[0] - 1.0).abs() < 1e-3, "{}: {:?}", inner, result.best_x.as_slice());
+        assert!((result.best_x[1] - 1.0).abs() < 1e-3, "{}: {:?}", inner, result.best_x.as_slice());
+
+        let lagrange = result.lagrange.unwrap();
+        assert!((lagrange.ineq_multipliers[0] - 2.0).abs() < 1e-2, "{}: {:?}", inner, lagrange);
+        assert!(lagrange.ineq_residuals[0] < 1e-3);
+        assert!(lagrange.eq_multipliers.is_empty());
+    }
+}
+
+#[test]
+fn test_constraints_called_once_per_point() {
+    let alg_conf = r#"{ "AugLag": { "inner": { "CMAES": { "num_parents": 5, "initial_sigma": 0.5 } }, "inner_iters": 10 } }"#;
+    let init_pop = DMatrix::from_fn(10, 2, |i, j| 0.1 * (i as f64) + 0.3 * ((i + j) % 2) as f64);
+    let objective = ShiftedSphere { c: [2.0, 2.0], with_gradient: false };
+    let mut opt = NonConvexOpt::new_with_constraints(conf(30, alg_conf), init_pop, objective, Some(HalfPlane)).unwrap();
+    for _ in 0..30 {
+        opt.step().unwrap();
+    }
+
+    // The state and the multiplier updates reuse the values from the inner solver's evaluations,
+    // the one extra call sizes the multipliers at the first point
+    let evals = opt.evaluations();
+    assert!(evals.constraints <= evals.objective + 1, "{:?}", evals);
+}
+
+#[test]
+fn test_penalty_gradient_uses_the_budget() {
+    let alg_conf = r#"{ "AugLag": { "inner": { "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } }, "inner_iters": 20 } }"#;
+    let conf = common::conf(200, r#""seed": 3, "objective_sense": "Minimize", "max_evals": 60"#, alg_conf);
+    let init_pop = DMatrix::from_row_slice(1, 2, &[3.0, -2.0]);
+    let objective = ShiftedSphere { c: [1.0, 2.0], with_gradient: true };
+    let mut opt = NonConvexOpt::new_with_constraints(conf, init_pop, objective, Some(Line)).unwrap();
+    let result = opt.run().unwrap();
+
+    // Every gradient differentiates the penalty with 2 dim constraint calls, charged to the budget
+    assert_eq!(result.termination, TerminationReason::MaxEvals);
+    assert_eq!(result.evals.objective, 60);
+    assert!(result.evals.gradient > 0);
+    assert!(result.evals.objective >= 4 * result.evals.gradient, "{:?}", result.evals);
+    assert!(result.evals.constraints >= 4 * result.evals.gradient, "{:?}", result.evals);
+}
+
+#[test]
+fn test_checkpoint_resume() {
+    let alg_conf = r#"{ "AugLag": { "inner": { "NM": {} }, "inner_iters": 10 } }"#;
+    let init_pop = DMatrix::from_fn(3, 2, |i, j| 0.1 * (i as f64) + 0.3 * ((i + j) % 2) as f64);
+    let objective = ShiftedSphere { c: [2.0, 2.0], with_gradient: false };
+
+    let mut opt = NonConvexOpt::new_with_constraints(conf(100, alg_conf), init_pop, objective.clone(), Some(HalfPlane)).unwrap();
+    for _ in 0..25 {
+        opt.step().unwrap();
+    }
+
+    let (checkpoint, resumed) = common::assert_resumes_exactly(&mut opt, 25, |checkpoint| {
+        NonConvexOpt::resume_with_constraints(conf(100, alg_conf), checkpoint, objective, Some(HalfPlane)).unwrap()
+    });
+    match &checkpoint.alg {
+        AlgCheckpoint::AugLag(c) => {
+            assert_eq!(c.outer_iter, 2);
+            assert_eq!(c.inner_iter, 5);
+            assert!(matches!(*c.inner, AlgCheckpoint::NM(_)));
+        },
+        _ => panic!("Expected AugLagCheckpoint"),
+    }
+    assert_eq!(resumed.alg.lagrange(), opt.alg.lagrange());
+}
+
+#[test]
+fn test_invalid_configs() {
+    let init_pop = DMatrix::from_row_slice(1, 2, &[0.0, 0.0]);
+    let objective = ShiftedSphere { c: [1.0, 2.0], with_gradient: false };
+
+    let nested = r#"{ "AugLag": { "inner": { "AugLag": { "inner": { "SA": {} } } } } }"#;
+    let err = NonConvexOpt::new_with_constraints(conf(10, nested), init_pop.clone(), objective.clone(), Some(Line)).err().unwrap();
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+
+    let growth = r#"{ "AugLag": { "inner": { "SA": {} }, "penalty_growth": 0.5 } }"#;
+    let err = NonConvexOpt::new_with_constraints(conf(10, growth), init_pop.clone(), objective.clone(), Some(Line)).err().unwrap();
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+
+    // L-BFGS inside still needs the objective's gradient
+    let lbfgs = r#"{ "AugLag": { "inner": { "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } } } }"#;
+    let err = NonConvexOpt::new_with_constraints(conf(10, lbfgs), init_pop, objective, Some(Line)).err().unwrap();
+    assert!(matches!(err, OptError::MissingGradient { .. }), "{}", err);
+}
@@ -0,0 +1,120 @@
+use nalgebra::{DMatrix, DVector, Dim, OVector, SVector, DefaultAllocator, allocator::Allocator};
+use num_traits::Float;
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::{FloatNumber, ObjectiveFunction, BooleanConstraintFunction},
+    dual::{Dual, HyperDual},
+    autodiff::{AutoDiff, AutoDiffObjective},
+};
+
+#[derive(Debug, Clone)]
+struct Rosenbrock {
+    a: f64,
+    b: f64,
+}
+
+impl<D: Dim> AutoDiffObjective<D> for Rosenbrock
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f<T: FloatNumber>(&self, x: &OVector<T, D>) -> T {
+        let a = T::from_f64(self.a).unwrap();
+        let b = T::from_f64(self.b).unwrap();
+        let mut sum = T::zero();
+        for i in 0..x.len() - 1 {
+            sum += b * (x[i+1] - x[i].powi(2)).powi(2) + (a - x[i]).powi(2);
+        }
+        sum
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NoConstraints;
+
+impl<D: Dim> BooleanConstraintFunction<f64, D> for NoConstraints
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, _x: &OVector<f64, D>) -> bool {
+        true
+    }
+}
+
+fn rosenbrock_gradient(x: &DVector<f64>) -> DVector<f64> {
+    let mut grad = DVector::zeros(x.len());
+    for i in 0..x.len() - 1 {
+        grad[i] += -400.0 * x[i] * (x[i+1] - x[i].powi(2)) - 2.0 * (1.0 - x[i]);
+        grad[i+1] += 200.0 * (x[i+1] - x[i].powi(2));
+    }
+    grad
+}
+
+#[test]
+fn test_dual_derivatives() {
+    let x: f64 = 0.7;
+    let cases: Vec<(fn(Dual<f64>) -> Dual<f64>, f64, f64)> = vec![
+        (|x| x.sin() * x.cos(), (2.0 * x).sin() / 2.0, (2.0 * x).cos()),
+        (|x| x.exp() / x, x.exp() / x, x.exp() * (x - 1.0) / (x * x)),
+        (|x| x.ln() + x.sqrt(), x.ln() + x.sqrt(), 1.0 / x + 0.5 / x.sqrt()),
+        (|x| x.powf(Dual::constant(2.5)), x.powf(2.5), 2.5 * x.powf(1.5)),
+        (|x| x.powf(x), x.powf(x), x.powf(x) * (x.ln() + 1.0)),
+        (|x| x.tanh().atan(), x.tanh().atan(), (1.0 - x.tanh().powi(2)) / (1.0 + x.tanh().powi(2))),
+        (|x| x.atan2(Dual::constant(2.0)), x.atan2(2.0), 2.0 / (x * x + 4.0)),
+        (|x| (-x).abs().max(Dual::constant(0.1)), x, 1.0),
+    ];
+
+    for (i, (f, value, derivative)) in cases.into_iter().enumerate() {
+        let y = f(Dual::variable(x));
+        assert!((y.re - value).abs() < 1e-12, "case {}: {} vs {}", i, y.re, value);
+        assert!((y.eps - derivative).abs() < 1e-12, "case {}: {} vs {}", i, y.eps, derivative);
+    }
+
+    // Second derivative of x^3 sin(x) from a hyper-dual
+    let y: HyperDual<f64> = {
+        let x = Dual::new(Dual::variable(x), Dual::constant(1.0));
+        x.powi(3) * x.sin()
+    };
+    let exact = 6.0 * x * x.sin() + 6.0 * x * x * x.cos() - x.powi(3) * x.sin();
+    assert!((y.eps.eps - exact).abs() < 1e-12);
+}
+
+#[test]
+fn test_autodiff_gradient_and_hessian() {
+    let obj = AutoDiff::new(Rosenbrock { a: 1.0, b: 100.0 });
+
+    let x = DVector::from_vec(vec![-1.2, 1.0, 0.5, 2.0]);
+    let grad = obj.gradient(&x).unwrap();
+    assert!((grad - rosenbrock_gradient(&x)).abs().max() < 1e-10);
+    assert_eq!(ObjectiveFunction::<f64, _>::f(&obj, &x), AutoDiffObjective::f(&obj.objective, &x));
+
+    let x = SVector::<f64, 2>::new(-1.2, 1.0);
+    let hessian = obj.hessian(&x);
+    let exact = nalgebra::Matrix2::new(
+        1200.0 * x[0] * x[0] - 400.0 * x[1] + 2.0, -400.0 * x[0],
+        -400.0 * x[0], 200.0,
+    );
+    assert!((hessian - exact).abs().max() < 1e-10);
+}
+
+#[test]
+fn test_gradient_algorithms_with_autodiff() {
+    let alg_confs = [
+        (r#"{ "Adam": { "learning_rate": 0.05 } }"#, 3000),
+        (r#"{ "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } }"#, 50),
+    ];
+
+    for (alg_conf, max_iter) in alg_confs {
+        let conf = Config::new(&format!(r#"{{
+            "opt_conf": {{ "max_iter": {}, "seed": 3, "objective_sense": "Minimize" }},
+            "alg_conf": {}
+        }}"#, max_iter, alg_conf)).unwrap();
+
+        let init_pop = DMatrix::from_row_slice(1, 2, &[-1.2, 1.0]);
+        let mut opt = NonConvexOpt::new(conf, init_pop, AutoDiff::new(Rosenbrock { a: 1.0, b: 100.0 }), None::<NoConstraints>).unwrap();
+        let f0 = opt.state().best_f;
+        let result = opt.run().unwrap();
+
+        assert!(result.best_f < 0.2 * f0, "{}: {} -> {}", alg_conf, f0, result.best_f);
+    }
+}
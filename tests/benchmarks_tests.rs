@@ -0,0 +1,245 @@
+use nalgebra::{DMatrix, DVector, Dyn, Dim, U2, OVector, SVector, DefaultAllocator, allocator::Allocator};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::benchmarks::*;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::{ObjectiveFunction, BooleanConstraintFunction, ConstraintFunction, MultiObjectiveFunction},
+    gradient_check::{check_gradient, GradientCheckConf},
+    indicators::igd,
+    rng::new_rng,
+};
+
+#[derive(Debug, Clone)]
+struct NoConstraints;
+
+impl<D: Dim> BooleanConstraintFunction<f64, D> for NoConstraints
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, _x: &OVector<f64, D>) -> bool {
+        true
+    }
+}
+
+fn scalable() -> Vec<Box<dyn ObjectiveFunction<f64, Dyn>>> {
+    vec![
+        Box::new(Sphere),
+        Box::new(Rosenbrock),
+        Box::new(Rastrigin),
+        Box::new(Ackley),
+        Box::new(Griewank),
+        Box::new(Schwefel),
+        Box::new(Levy),
+        Box::new(Michalewicz::default()),
+        Box::new(StyblinskiTang),
+    ]
+}
+
+// The optimum's value at its location, inside the bounds, and no better point close by
+fn check_optimum<B: Benchmark>(benchmark: &B, dim: usize, tol: f64) {
+    let optimum = benchmark.optimum(dim).unwrap();
+    let x = optimum.x.clone().unwrap();
+    assert_eq!(x.len(), dim, "{}", benchmark.name());
+    assert!((benchmark.value(&x) - optimum.value).abs() <= tol, "{}: {} vs {}", benchmark.name(), benchmark.value(&x), optimum.value);
+
+    let bounds = benchmark.bounds(dim).to_bounds::<f64, Dyn>(dim).unwrap();
+    assert!(bounds.contains(&DVector::from_vec(x.clone())), "{}: {:?}", benchmark.name(), x);
+
+    for i in 0..dim {
+        for step in [-1e-4, 1e-4] {
+            let mut nudged = x.clone();
+            nudged[i] += step;
+            assert!(benchmark.value(&nudged) >= optimum.value - tol, "{} at {:?}", benchmark.name(), nudged);
+        }
+    }
+}
+
+#[test]
+fn test_scalable_optima() {
+    for dim in [1, 2, 5, 10] {
+        check_optimum(&Sphere, dim, 1e-12);
+        check_optimum(&Rastrigin, dim, 1e-12);
+        check_optimum(&Ackley, dim, 1e-12);
+        check_optimum(&Griewank, dim, 1e-12);
+        check_optimum(&Schwefel, dim, 1e-9);
+        check_optimum(&Levy, dim, 1e-12);
+        check_optimum(&StyblinskiTang, dim, 1e-9);
+    }
+    for dim in [2, 5, 10] {
+        check_optimum(&Rosenbrock, dim, 1e-12);
+    }
+    check_optimum(&Michalewicz::default(), 2, 1e-8);
+    assert_eq!(Michalewicz::default().optimum(5).unwrap().value, -4.687658);
+    assert!(Michalewicz::default().optimum(3).is_none());
+    assert!(Michalewicz { m: 5 }.optimum(2).is_none());
+}
+
+#[test]
+fn test_gradients() {
+    let mut rng = new_rng(Some(1));
+    let conf = GradientCheckConf { rtol: 1e-4, atol: 1e-5, ..GradientCheckConf::default() };
+    for objective in scalable() {
+        // Random points inside the bounds, away from the kinks of Ackley and Schwefel at zero
+        let lower = objective.x_lower_bound(&DVector::zeros(4)).unwrap();
+        let upper = objective.x_upper_bound(&DVector::zeros(4)).unwrap();
+        let points: Vec<DVector<f64>> = (0..5).map(|_| DVector::from_fn(4, |i, _| {
+            lower[i] + (upper[i] - lower[i]) * (0.05 + 0.9 * rand::Rng::random::<f64>(&mut rng))
+        })).collect();
+        let report = check_gradient(objective.as_ref(), &points, &conf).unwrap();
+        assert!(report.passed, "{:?}", report.worst);
+    }
+
+    // The constrained objectives too, at their optima
+    for (objective, x) in [
+        (Box::new(G06) as Box<dyn ObjectiveFunction<f64, Dyn>>, G06.optimum(2).unwrap().x.unwrap()),
+        (Box::new(G08), G08.optimum(2).unwrap().x.unwrap()),
+        (Box::new(G11), G11.optimum(2).unwrap().x.unwrap()),
+        (Box::new(G24), G24.optimum(2).unwrap().x.unwrap()),
+        (Box::new(G01), G01.optimum(13).unwrap().x.unwrap()),
+        (Box::new(KBF), KBF.optimum(2).unwrap().x.unwrap()),
+        (Box::new(KBF), vec![3.0, 0.7, 5.2]),
+    ] {
+        let report = check_gradient(objective.as_ref(), &[DVector::from_vec(x)], &conf).unwrap();
+        assert!(report.passed, "{:?}", report.worst);
+    }
+}
+
+#[test]
+fn test_shifted_and_rotated() {
+    let mut rng = new_rng(Some(2));
+    let conf = GradientCheckConf { rtol: 1e-4, atol: 1e-5, ..GradientCheckConf::default() };
+
+    let shifted = Shifted::random(Rastrigin, 4, &mut rng);
+    assert_eq!(shifted.name(), "ShiftedRastrigin");
+    assert!(shifted.shift.iter().all(|&o| o != 0.0 && o.abs() <= 0.4 * 10.24));
+    check_optimum(&shifted, 4, 1e-12);
+
+    // Shifting the optimum off the corner of Rosenbrock keeps it in the box
+    check_optimum(&Shifted::random(Rosenbrock, 6, &mut rng), 6, 1e-12);
+
+    let rotated = Rotated::random(Ackley, 4, &mut rng);
+    let identity = &rotated.rotation * rotated.rotation.transpose();
+    assert!((identity - DMatrix::identity(4, 4)).norm() < 1e-12);
+    check_optimum(&rotated, 4, 1e-12);
+
+    // The usual shifted then rotated problem, f(R (x - o))
+    let both = Shifted::random(Rotated::random(Rosenbrock, 3, &mut rng), 3, &mut rng);
+    assert_eq!(both.name(), "ShiftedRotatedRosenbrock");
+    check_optimum(&both, 3, 1e-10);
+    let points: Vec<DVector<f64>> = (0..3).map(|k| DVector::from_fn(3, |i, _| (i + k) as f64 * 0.7 - 1.0)).collect();
+    assert!(check_gradient(&both, &points, &conf).unwrap().passed);
+
+    // The optimum has moved away from the diagonal
+    let x = both.optimum(3).unwrap().x.unwrap();
+    let rosenbrock_x = Rosenbrock.optimum(3).unwrap().x.unwrap();
+    assert!(x.iter().zip(rosenbrock_x.iter()).any(|(a, b)| (a - b).abs() > 1e-3));
+}
+
+#[test]
+fn test_constrained_optima() {
+    fn check<B: ConstrainedBenchmark>(benchmark: B, tol: f64) {
+        let dim = benchmark.dim().unwrap();
+        let optimum = benchmark.optimum(dim).unwrap();
+        let x = optimum.x.clone().unwrap();
+        assert!((benchmark.value(&x) - optimum.value).abs() <= tol * optimum.value.abs(), "{}: {}", benchmark.name(), benchmark.value(&x));
+        let violation = ConstraintFunction::<f64, Dyn>::violation(&benchmark, &DVector::from_vec(x.clone()));
+        assert!(violation < 1e-6, "{}: {}", benchmark.name(), violation);
+        assert!(benchmark.bounds(dim).to_bounds::<f64, Dyn>(dim).unwrap().contains(&DVector::from_vec(x)));
+    }
+    check(G01, 1e-12);
+    check(G06, 1e-10);
+    check(G08, 1e-10);
+    check(G11, 1e-12);
+    check(G24, 1e-10);
+
+    // Keane's bump is scalable, its optimum only known in two dimensions
+    assert!(KBF.dim().is_none() && KBF.optimum(3).is_none());
+    let optimum = KBF.optimum(2).unwrap();
+    let x = optimum.x.unwrap();
+    assert!((KBF.value(&x) - optimum.value).abs() < 1e-6);
+    assert!(KBF.ineq(&x).iter().all(|&g| g < 1e-6));
+    assert_eq!(KBF.value(&[0.0, 0.0]), 0.0);
+
+    // Infeasible points are seen as such
+    let inside_circle = DVector::from_vec(vec![14.0, 5.0]);
+    assert!(ConstraintFunction::<f64, Dyn>::violation(&G06, &inside_circle) > 0.0);
+}
+
+#[test]
+fn test_solved_through_non_convex_opt() {
+    // atol is turned off so every run uses its iterations, whatever its minimum
+    let conf = |max_iter: usize, alg_conf: &str| Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": {}, "rtol": "0.0", "atol": "-inf", "seed": 3, "objective_sense": "Minimize" }},
+        "alg_conf": {}
+    }}"#, max_iter, alg_conf)).unwrap();
+    let de = r#"{ "DE": { "common": {}, "mutation_type": { "Standard": {} } } }"#;
+    let init_pop = |n: usize, dim: usize, lo: f64, hi: f64| DMatrix::from_fn(n, dim, |i, j| lo + (hi - lo) * (((i * 7 + j * 3) % n) as f64 + 0.5) / n as f64);
+
+    // Bounds come from the benchmark itself
+    let mut opt = NonConvexOpt::new(conf(300, de), init_pop(20, 3, -5.0, 5.0), StyblinskiTang, None::<NoConstraints>).unwrap();
+    let result = opt.run().unwrap();
+    let optimum = StyblinskiTang.optimum(3).unwrap();
+    assert!((result.best_f - optimum.value).abs() < 1e-3, "{}", result.best_f);
+
+    let mut opt = NonConvexOpt::new_with_constraints(conf(300, de), init_pop(20, 2, 13.0, 15.0), G06, Some(G06)).unwrap();
+    let result = opt.run().unwrap();
+    assert!((result.best_f - G06.optimum(2).unwrap().value).abs() < 1.0, "{}", result.best_f);
+}
+
+#[test]
+fn test_pareto_fronts() {
+    // Pareto-optimal decisions land on the reference front
+    let on_front = |f: &[f64], front: &[Vec<f64>]| front.iter().map(|p| p.iter().zip(f.iter()).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt()).fold(f64::INFINITY, f64::min);
+    for t in [0.0, 0.3, 0.7, 1.0] {
+        let mut x = vec![0.0; 10];
+        x[0] = t;
+        assert!(on_front(&ZDT1.values(&x), &ZDT1.reference_front(1001)) < 1e-3);
+        assert!(on_front(&ZDT2.values(&x), &ZDT2.reference_front(1001)) < 1e-3);
+        assert!(on_front(&ZDT4.values(&x), &ZDT4.reference_front(1001)) < 1e-3);
+    }
+    let zdt3 = ZDT3.reference_front(100);
+    assert!(zdt3.len() >= 100);
+    assert!(zdt3.windows(2).all(|w| w[0][0] < w[1][0] && w[0][1] > w[1][1]));
+    assert!(zdt3.iter().any(|p| p[1] < -0.7));
+    let zdt6 = ZDT6.values(&[0.0; 10]);
+    assert_eq!(zdt6, vec![1.0, 0.0]);
+
+    for m in [2, 3, 4] {
+        let mut x = vec![0.5; m + 5];
+        x[0] = 0.2;
+        let dtlz1: f64 = DTLZ1 { num_objectives: m }.values(&x).iter().sum();
+        assert!((dtlz1 - 0.5).abs() < 1e-12);
+        for f in [DTLZ2 { num_objectives: m }.values(&x), DTLZ3 { num_objectives: m }.values(&x), DTLZ4 { num_objectives: m }.values(&x)] {
+            assert!((f.iter().map(|fi| fi * fi).sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+        let front = DTLZ2 { num_objectives: m }.reference_front(50);
+        assert!(front.len() >= 50);
+        assert!(front.iter().all(|p| (p.iter().map(|fi| fi * fi).sum::<f64>() - 1.0).abs() < 1e-12));
+    }
+
+    // Too few variables give NaN objectives rather than a panic
+    for f in [DTLZ1 { num_objectives: 4 }.values(&[0.5, 0.5]), DTLZ2 { num_objectives: 4 }.values(&[0.5]), DTLZ3 { num_objectives: 0 }.values(&[0.5])] {
+        assert!(f.iter().all(|fi| fi.is_nan()));
+    }
+    assert_eq!(DTLZ4 { num_objectives: 4 }.values(&[0.5]).len(), 4);
+    assert!(Levy.value(&[]).is_nan());
+    assert_eq!(Levy.grad(&[]), None);
+
+    // Through the MultiObjectiveFunction interface, with ZDT4's mixed bounds
+    let x = SVector::<f64, 2>::new(0.5, 0.0);
+    assert_eq!(MultiObjectiveFunction::<f64, U2>::num_objectives(&ZDT4), 2);
+    assert_eq!(MultiObjectiveFunction::<f64, U2>::x_lower_bound(&ZDT4, &x).unwrap(), SVector::<f64, 2>::new(0.0, -5.0));
+}
+
+#[test]
+fn test_nsga2_on_zdt1() {
+    let conf = Config::new(r#"{
+        "opt_conf": { "max_iter": 150, "rtol": "0.0", "atol": "0.0", "seed": 4, "objective_sense": "Minimize" },
+        "alg_conf": { "NSGA2": { "crossover": { "Heuristic": { "crossover_prob": 0.9 } }, "mutation": { "Polynomial": { "mutation_rate": 0.3 } } } }
+    }"#).unwrap();
+    let init_pop = DMatrix::from_fn(40, 6, |i, j| ((i * 13 + j * 5) % 40) as f64 / 40.0);
+    let mut opt = NonConvexOpt::new_multi_objective(conf, init_pop, ZDT1, None::<G06>).unwrap();
+    let result = opt.run().unwrap();
+    let front = result.pareto_front.unwrap().f;
+    assert!(igd(&front, &ZDT1.reference_front(100)) < 0.05, "{}", igd(&front, &ZDT1.reference_front(100)));
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use nalgebra::{DMatrix, DVector};
+use common::fcns::{BareRosenbrock, SphereCost, UnitBox};
+use non_convex_opt::benchmarks::Rosenbrock;
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::{OptProb, BooleanConstraintFunction},
+    bounds::{Bounds, BoundsConf, BoundHandling},
+    rng::new_rng,
+    checkpoint::Checkpoint,
+    error::OptError,
+};
+
+fn conf(alg_conf: &str, bounds: &str) -> Config {
+    Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 30, "seed": 9, "objective_sense": "Minimize", "bounds": {} }},
+        "alg_conf": {}
+    }}"#, bounds, alg_conf)).unwrap()
+}
+
+const DE: &str = r#"{ "DE": { "common": {}, "mutation_type": { "Standard": {} } } }"#;
+const GRASP: &str = r#"{ "GRASP": {} }"#;
+
+fn init_pop(dim: usize) -> DMatrix<f64> {
+    DMatrix::from_fn(10, dim, |i, j| 0.1 + 0.8 * ((i * dim + j) as f64 * 0.37).sin().abs())
+}
+
+#[test]
+fn test_bounds_new_validates() {
+    let v = |x: &[f64]| DVector::from_vec(x.to_vec());
+
+    assert!(Bounds::new(v(&[0.0, -1.0]), v(&[1.0, f64::INFINITY])).is_ok());
+    assert!(matches!(Bounds::new(v(&[0.0, 2.0]), v(&[1.0, 1.0])), Err(OptError::InvalidBounds(_))));
+    assert!(matches!(Bounds::new(v(&[0.0]), v(&[1.0, 1.0])), Err(OptError::InvalidBounds(_))));
+    assert!(matches!(Bounds::new(v(&[f64::NAN]), v(&[1.0])), Err(OptError::InvalidBounds(_))));
+
+    let bounds = Bounds::new(v(&[0.0, -1.0]), v(&[1.0, f64::INFINITY])).unwrap();
+    assert!(!bounds.is_finite());
+    assert_eq!(bounds.clamp(&v(&[2.0, -3.0])), v(&[1.0, -1.0]));
+    assert!(bounds.contains(&v(&[0.5, 1e9])));
+}
+
+#[test]
+fn test_config_bounds_override_objective() {
+    // SphereCost declares [0, 1] in every dimension
+    let opt = NonConvexOpt::new(conf(DE, r#"{ "lower": [-1.0, 0.0, -2.0], "upper": [2.0, 3.0, 0.5] }"#), init_pop(3), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    assert_eq!(opt.checkpoint().bounds, Some(BoundsConf { lower: vec![-1.0, 0.0, -2.0], upper: vec![2.0, 3.0, 0.5] }));
+
+    let opt = NonConvexOpt::new(conf(DE, "null"), init_pop(3), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    assert_eq!(opt.checkpoint().bounds, Some(BoundsConf { lower: vec![0.0; 3], upper: vec![1.0; 3] }));
+
+    // Neither side declared
+    let opt = NonConvexOpt::new(conf(DE, "null"), init_pop(3), BareRosenbrock, None::<UnitBox>).unwrap();
+    assert_eq!(opt.checkpoint().bounds, None);
+}
+
+#[test]
+fn test_single_value_broadcasts() {
+    let opt = NonConvexOpt::new(conf(DE, r#"{ "lower": [0.0], "upper": ["inf"] }"#), init_pop(4), Rosenbrock, None::<UnitBox>).unwrap();
+    assert_eq!(opt.checkpoint().bounds, Some(BoundsConf { lower: vec![0.0; 4], upper: vec![f64::INFINITY; 4] }));
+}
+
+#[test]
+fn test_bad_bounds_are_rejected() {
+    let err = |bounds: &str| NonConvexOpt::new(conf(DE, bounds), init_pop(3), Rosenbrock, None::<UnitBox>).err().unwrap();
+
+    assert!(matches!(err(r#"{ "lower": [0.0, 0.0], "upper": [1.0] }"#), OptError::InvalidBounds(_)));
+    assert!(matches!(err(r#"{ "lower": [2.0], "upper": [1.0] }"#), OptError::InvalidBounds(_)));
+}
+
+#[test]
+fn test_sampling_algorithms_need_finite_bounds() {
+    let new = |alg_conf: &str, bounds: &str| NonConvexOpt::new(conf(alg_conf, bounds), init_pop(2), BareRosenbrock, None::<UnitBox>);
+    let uniform_cga = r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Heuristic": {} }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Uniform": {} } } }"#;
+    let mspo = r#"{ "MSPO": { "num_swarms": 2, "swarm_size": 5 } }"#;
+
+    for alg_conf in [GRASP, uniform_cga, mspo] {
+        assert!(matches!(new(alg_conf, "null").err().unwrap(), OptError::InvalidBounds(_)), "{}", alg_conf);
+        assert!(matches!(new(alg_conf, r#"{ "lower": [-5.0], "upper": ["inf"] }"#).err().unwrap(), OptError::InvalidBounds(_)), "{}", alg_conf);
+        assert!(new(alg_conf, r#"{ "lower": [-5.0], "upper": [5.0] }"#).is_ok(), "{}", alg_conf);
+    }
+}
+
+// Every population and local-search algorithm, HANDLING stands for the bound_handling value
+const BOUNDED_ALG_CONFS: [&str; 8] = [
+    r#"{ "CGA": { "common": { "num_parents": 4, "bound_handling": HANDLING }, "crossover": { "Heuristic": {} }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Polynomial": { "mutation_rate": 0.5 } } } }"#,
+    r#"{ "PT": { "common": { "num_replicas": 3, "bound_handling": HANDLING }, "swap_conf": { "Always": {} } } }"#,
+    r#"{ "DE": { "common": { "bound_handling": HANDLING }, "mutation_type": { "Standard": { "f": 1.5 } } } }"#,
+    r#"{ "CMAES": { "num_parents": 5, "initial_sigma": 2.0, "bound_handling": HANDLING } }"#,
+    r#"{ "MSPO": { "num_swarms": 2, "swarm_size": 5, "bound_handling": HANDLING } }"#,
+    r#"{ "TS": { "common": { "step_size": 1.0, "bound_handling": HANDLING }, "list_type": { "Standard": {} } } }"#,
+    r#"{ "GRASP": { "step_size": 1.0, "bound_handling": HANDLING } }"#,
+    r#"{ "SA": { "step_size": 2.0, "bound_handling": HANDLING } }"#,
+];
+
+#[test]
+fn test_solutions_stay_within_per_dimension_bounds() {
+    let lower = [0.0, -0.5];
+    let upper = [0.4, 0.9];
+    let bounds = r#"{ "lower": [0.0, -0.5], "upper": [0.4, 0.9] }"#;
+    let inside = |x: &[f64]| (0..2).all(|j| x[j] >= lower[j] && x[j] <= upper[j]);
+
+    for template in BOUNDED_ALG_CONFS {
+        for handling in ["Clamp", "Reflect", "Wrap", "Midpoint", "Resample", "DeathPenalty"] {
+            let alg_conf = template.replace("HANDLING", &format!("\"{}\"", handling));
+            let init_pop = DMatrix::from_fn(10, 2, |i, j| lower[j] + (upper[j] - lower[j]) * (i as f64 / 9.0));
+            // Without a gradient, so PT takes plain Metropolis steps rather than Langevin ones
+            let mut opt = NonConvexOpt::new(conf(&alg_conf, bounds), init_pop, BareRosenbrock, None::<UnitBox>).unwrap();
+
+            for _ in 0..20 {
+                opt.step().unwrap();
+                let st = opt.alg.state();
+                assert!(inside(st.best_x.as_slice()), "{}: best {:?}", alg_conf, st.best_x.as_slice());
+
+                // The death penalty leaves points where they are, they just never win
+                if handling != "DeathPenalty" {
+                    for row in st.pop.row_iter() {
+                        let row: Vec<f64> = row.iter().cloned().collect();
+                        assert!(inside(&row), "{}: {:?}", alg_conf, row);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The gradient and simplex methods, walking towards an optimum at x = 5 outside the box
+const DIRECT_ALG_CONFS: [&str; 3] = [
+    r#"{ "Adam": { "learning_rate": 0.1, "bound_handling": HANDLING } }"#,
+    r#"{ "SGA": { "learning_rate": 0.1, "bound_handling": HANDLING } }"#,
+    r#"{ "NM": { "bound_handling": HANDLING } }"#,
+];
+
+#[test]
+fn test_direct_methods_stay_within_bounds() {
+    let lower = [0.0, -0.5];
+    let upper = [0.4, 0.9];
+    let bounds = r#"{ "lower": [0.0, -0.5], "upper": [0.4, 0.9] }"#;
+    let inside = |x: &[f64]| (0..2).all(|j| x[j] >= lower[j] && x[j] <= upper[j]);
+
+    for template in DIRECT_ALG_CONFS {
+        for handling in ["Clamp", "Reflect", "Wrap", "Midpoint", "Resample", "DeathPenalty"] {
+            let alg_conf = template.replace("HANDLING", &format!("\"{}\"", handling));
+            let init_pop = DMatrix::from_row_slice(3, 2, &[0.3, 0.8, 0.1, 0.8, 0.3, 0.2]);
+            let mut opt = NonConvexOpt::new(conf(&alg_conf, bounds), init_pop, SphereCost{ c: 5.0 }, None::<UnitBox>).unwrap();
+
+            for _ in 0..20 {
+                opt.step().unwrap();
+                let st = opt.alg.state();
+                assert!(inside(st.best_x.as_slice()), "{}: best {:?}", alg_conf, st.best_x.as_slice());
+                for row in st.pop.row_iter() {
+                    let row: Vec<f64> = row.iter().cloned().collect();
+                    assert!(inside(&row), "{}: {:?}", alg_conf, row);
+                }
+            }
+
+            // Pushed against the upper corner, not stuck where it started
+            if handling == "Clamp" {
+                let best = opt.alg.state().best_x.clone();
+                assert!(best[0] > 0.35 && best[1] > 0.85, "{}: best {:?}", alg_conf, best.as_slice());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_repair_strategies() {
+    let bounds = Bounds::new(DVector::from_vec(vec![0.0, 0.0]), DVector::from_vec(vec![1.0, f64::INFINITY])).unwrap();
+    let parent = DVector::from_vec(vec![0.8, 2.0]);
+    let mut rng = new_rng(Some(1));
+    let mut repair = |x: &[f64], handling: BoundHandling| bounds.repair(&DVector::from_vec(x.to_vec()), &parent, handling, &mut rng);
+    let close = |a: &DVector<f64>, b: &[f64]| (0..2).all(|i| (a[i] - b[i]).abs() < 1e-12);
+
+    assert!(close(&repair(&[1.3, -0.5], BoundHandling::Clamp), &[1.0, 0.0]));
+    assert!(close(&repair(&[1.3, -0.5], BoundHandling::Reflect), &[0.7, 0.5]));
+    assert!(close(&repair(&[2.6, -0.5], BoundHandling::Reflect), &[0.6, 0.5]));
+    assert!(close(&repair(&[-0.25, 3.0], BoundHandling::Reflect), &[0.25, 3.0]));
+    assert!(close(&repair(&[1.3, -0.5], BoundHandling::Wrap), &[0.3, 0.5])); // Reflects where a side is infinite
+    assert!(close(&repair(&[-2.25, 0.5], BoundHandling::Wrap), &[0.75, 0.5]));
+    assert!(close(&repair(&[1.3, -0.5], BoundHandling::Midpoint), &[0.9, 1.0]));
+    assert!(close(&repair(&[1.3, -0.5], BoundHandling::DeathPenalty), &[1.3, -0.5]));
+
+    for _ in 0..100 {
+        let x = repair(&[1.3, -0.5], BoundHandling::Resample);
+        assert!((0.0..=1.0).contains(&x[0]));
+        assert_eq!(x[1], 1.0); // Midpoint where a side is infinite
+    }
+}
+
+#[test]
+fn test_death_penalty_skips_evaluation() {
+    let opt_prob = OptProb::new(Box::new(Rosenbrock), None::<Box<dyn BooleanConstraintFunction<f64, nalgebra::Dyn>>>)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
+
+    let outside = DVector::from_vec(vec![0.5, 1.5]);
+    assert_eq!(opt_prob.evaluate_bounded(&outside, BoundHandling::DeathPenalty), f64::NEG_INFINITY);
+    assert_eq!(opt_prob.evaluations().objective, 0);
+
+    assert!(opt_prob.evaluate_bounded(&outside, BoundHandling::Clamp).is_finite());
+    assert!(opt_prob.evaluate_bounded(&DVector::from_vec(vec![0.5, 0.5]), BoundHandling::DeathPenalty).is_finite());
+    assert_eq!(opt_prob.evaluations().objective, 2);
+}
+
+#[test]
+fn test_bounds_survive_resume() {
+    let bounded = conf(DE, r#"{ "lower": [-1.0, 0.0], "upper": ["inf", 2.0] }"#);
+    let mut opt = NonConvexOpt::new(bounded, init_pop(2), BareRosenbrock, None::<UnitBox>).unwrap();
+    opt.step().unwrap();
+
+    let checkpoint = Checkpoint::from_json(&opt.checkpoint().to_json().unwrap()).unwrap();
+    assert_eq!(checkpoint.bounds, Some(BoundsConf { lower: vec![-1.0, 0.0], upper: vec![f64::INFINITY, 2.0] }));
+
+    // The resume config no longer carries the bounds, the checkpoint does
+    let resumed = NonConvexOpt::<f64, nalgebra::Dyn, nalgebra::Dyn>::resume(conf(DE, "null"), checkpoint, BareRosenbrock, None::<UnitBox>).unwrap();
+    assert_eq!(resumed.checkpoint().bounds, opt.checkpoint().bounds);
+}
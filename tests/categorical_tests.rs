@@ -0,0 +1,129 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use nalgebra::{DMatrix, DVector, Dyn, Dim, OVector, DefaultAllocator, allocator::Allocator};
+use common::fcns::NoConstraints;
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::{Config, OptConf},
+    opt_prob::ObjectiveFunction,
+    variables::{Variables, VarType, CategoricalEncoding},
+    bounds::Bounds,
+    error::OptError,
+    rng::new_rng,
+};
+
+// Scores of the activations, in no particular order, so neighbouring indices say nothing about each other
+const SCORES: [f64; 5] = [1.0, 5.0, 0.0, 9.0, 2.0];
+
+// Picks an activation by index and a continuous learning rate, best at (3, 0.2). Counts every
+// evaluation that isn't at a category index
+#[derive(Debug, Clone)]
+struct Activation {
+    off_index: Arc<AtomicUsize>,
+}
+
+impl Activation {
+    fn new() -> Self {
+        Self { off_index: Arc::new(AtomicUsize::new(0)) }
+    }
+}
+
+impl<D: Dim> ObjectiveFunction<f64, D> for Activation
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> f64 {
+        if x[0].fract() != 0.0 || !(0.0..5.0).contains(&x[0]) {
+            self.off_index.fetch_add(1, Ordering::Relaxed);
+            return f64::NEG_INFINITY;
+        }
+        SCORES[x[0] as usize] - (x[1] - 0.2).powi(2)
+    }
+}
+
+fn activations(encoding: CategoricalEncoding) -> VarType {
+    let categories = ["relu", "tanh", "gelu", "swish", "elu"].iter().map(|c| c.to_string()).collect();
+    VarType::Categorical { categories, encoding }
+}
+
+fn conf(max_iter: usize, alg_conf: &str) -> Config {
+    let opt_conf = r#""seed": 3, "bounds": { "lower": [-1.0], "upper": [1.0] },
+        "variables": [{ "Categorical": { "categories": ["relu", "tanh", "gelu", "swish", "elu"], "encoding": "OneHot" } }, "Continuous"]"#;
+    common::conf(max_iter, opt_conf, alg_conf)
+}
+
+// Everything starts on gelu, the worst category
+fn init_pop(n: usize) -> DMatrix<f64> {
+    DMatrix::from_fn(n, 2, |i, j| if j == 0 { 2.0 } else { -0.9 + 1.8 * i as f64 / n as f64 })
+}
+
+#[test]
+fn test_categorical_spec() {
+    let variables = Variables::new(vec![activations(CategoricalEncoding::Ordinal), VarType::Continuous]).unwrap();
+    assert_eq!(variables.category(0, 3.0), Some("swish"));
+    assert_eq!(variables.category(0, 5.0), None);
+    assert_eq!(variables.category(0, 1.5), None);
+    assert_eq!(variables.category(1, 0.0), None);
+
+    // Indices 0 to 4, rounded like integers
+    let bounds: Bounds<f64, Dyn> = variables.restrict(None).unwrap();
+    assert_eq!((bounds.lower[0], bounds.upper[0]), (0.0, 4.0));
+    assert_eq!(variables.snap(&DVector::from_vec(vec![2.6, 0.3])), DVector::from_vec(vec![3.0, 0.3]));
+    assert!(variables.contains(&DVector::from_vec(vec![4.0, 0.3])));
+    assert!(!variables.contains(&DVector::from_vec(vec![2.5, 0.3])));
+
+    // Ordered categories move to the next one
+    let mut rng = new_rng(Some(1));
+    let parent = DVector::from_vec(vec![2.0, 0.0]);
+    let moved = variables.step_away(&DVector::from_vec(vec![1.9, 0.0]), &parent, &mut rng);
+    assert_eq!(variables.snap(&moved)[0], 1.0);
+
+    // The encoding defaults to ordinal
+    let conf: OptConf = serde_json::from_str(r#"{ "variables": [{ "Categorical": { "categories": ["a", "b"] } }] }"#).unwrap();
+    assert_eq!(conf.variables.unwrap()[0], VarType::Categorical { categories: vec!["a".to_string(), "b".to_string()], encoding: CategoricalEncoding::Ordinal });
+
+    assert!(matches!(Variables::new(vec![VarType::Categorical { categories: vec![], encoding: CategoricalEncoding::OneHot }]), Err(OptError::InvalidVariables(_))));
+    let twice = vec!["a".to_string(), "b".to_string(), "a".to_string()];
+    assert!(matches!(Variables::new(vec![VarType::Categorical { categories: twice, encoding: CategoricalEncoding::Ordinal }]), Err(OptError::InvalidVariables(_))));
+}
+
+#[test]
+fn test_one_hot_moves_resample() {
+    let variables = Variables::new(vec![activations(CategoricalEncoding::OneHot)]).unwrap();
+    let mut rng = new_rng(Some(2));
+    let parent = DVector::from_vec(vec![2.0]);
+
+    // Any move, small or large, lands on another category chosen uniformly
+    let mut counts = [0usize; 5];
+    for k in 0..4000 {
+        let x = DVector::from_vec(vec![if k % 2 == 0 { 2.1 } else { 3.8 }]);
+        let moved = variables.snap(&variables.step_away(&x, &parent, &mut rng));
+        counts[moved[0] as usize] += 1;
+    }
+    assert_eq!(counts[2], 0);
+    for (k, &count) in counts.iter().enumerate().filter(|&(k, _)| k != 2) {
+        assert!((900..1100).contains(&count), "category {}: {}", k, count);
+    }
+
+    // Unmoved dimensions stay where they are
+    assert_eq!(variables.step_away(&parent, &parent, &mut rng), parent);
+}
+
+#[test]
+fn test_best_category_found() {
+    // The algorithms that mutate or take neighbours, DE differences would stay at zero from here
+    for alg_conf in [
+        r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Random": {} }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Gaussian": { "mutation_rate": 0.3, "sigma": 0.1 } } } }"#,
+        r#"{ "TS": { "common": { "step_size": 0.2 }, "list_type": { "Standard": {} } } }"#,
+        r#"{ "SA": { "step_size": 0.2 } }"#,
+        r#"{ "PT": { "common": { "num_replicas": 3 }, "swap_conf": { "Always": {} } } }"#,
+    ] {
+        let objective = Activation::new();
+        let mut opt = NonConvexOpt::new(conf(100, alg_conf), init_pop(10), objective.clone(), None::<NoConstraints>).unwrap();
+        let result = opt.run().unwrap();
+        assert_eq!(objective.off_index.load(Ordering::Relaxed), 0, "{}", alg_conf);
+        assert_eq!(result.best_x[0], 3.0, "{}: {:?}", alg_conf, result.best_x);
+    }
+}
@@ -1,11 +1,13 @@
 mod common;
 
 use nalgebra::{OMatrix, OVector, U10, U5, U2, U1};
-use common::fcns::{RosenbrockObjective, RosenbrockConstraints};
+use common::fcns::UnitBox;
+use non_convex_opt::benchmarks::Rosenbrock;
 
 use non_convex_opt::utils::{
-    config::{Config, AlgConf},
+    config::{Config, AlgConf, ObjectiveSense},
     opt_prob::{OptProb, OptimizationAlgorithm},
+    bounds::Bounds,
 };
 
 use non_convex_opt::algorithms::continous_ga::{
@@ -13,6 +15,7 @@ use non_convex_opt::algorithms::continous_ga::{
     crossover::{CrossoverOperator, Random, Heuristic},
     cga::CGA,
 };
+use non_convex_opt::utils::rng::new_rng;
 
 #[test]
 fn test_roulette_wheel_selection() {
@@ -20,7 +23,8 @@ fn test_roulette_wheel_selection() {
     let population = OMatrix::<f64, U10, U5>::from_element_generic(U10, U5, 1.0);
     let fitness = OVector::<f64, U10>::from_element_generic(U10, U1, 1.0);
     let constraint = OVector::<bool, U10>::from_element_generic(U10, U1, true);
-    let selected = selection.select(&population, &fitness, &constraint);
+    let mut rng = new_rng(Some(0));
+    let selected = selection.select(&population, &fitness, &constraint, &mut rng);
     assert_eq!(selected.nrows(), 5);
     assert_eq!(selected.ncols(), 5);
 }
@@ -31,7 +35,8 @@ fn test_tournament_selection() {
     let population = OMatrix::<f64, U10, U5>::from_element_generic(U10, U5, 1.0);
     let fitness = OVector::<f64, U10>::from_element_generic(U10, U1, 1.0);
     let constraint = OVector::<bool, U10>::from_element_generic(U10, U1, true);
-    let selected = selection.select(&population, &fitness, &constraint);
+    let mut rng = new_rng(Some(0));
+    let selected = selection.select(&population, &fitness, &constraint, &mut rng);
     assert_eq!(selected.nrows(), 5);
     assert_eq!(selected.ncols(), 5);
 }
@@ -42,20 +47,37 @@ fn test_residual_selection() {
     let population = OMatrix::<f64, U10, U5>::from_element_generic(U10, U5, 1.0);
     let fitness = OVector::<f64, U10>::from_element_generic(U10, U1, 1.0);
     let constraint = OVector::<bool, U10>::from_element_generic(U10, U1, true);
-    let selected = selection.select(&population, &fitness, &constraint);
+    let mut rng = new_rng(Some(0));
+    let selected = selection.select(&population, &fitness, &constraint, &mut rng);
     assert_eq!(selected.nrows(), 5);
     assert_eq!(selected.ncols(), 5);
 }
 
+#[test]
+fn test_proportional_selection_negative_fitness() {
+    // Costs negated into fitness are all negative, the worst individual should get no weight
+    let population = OMatrix::<f64, U10, U5>::from_fn_generic(U10, U5, |i, _| i as f64);
+    let fitness = OVector::<f64, U10>::from_fn_generic(U10, U1, |i, _| -(i as f64) - 1.0);
+    let constraint = OVector::<bool, U10>::from_element_generic(U10, U1, true);
+    let mut rng = new_rng(Some(0));
+
+    let roulette = RouletteWheel::new(10, 5).select(&population, &fitness, &constraint, &mut rng);
+    let residual = Residual::new(10, 5).select(&population, &fitness, &constraint, &mut rng);
+    for selected in [roulette, residual] {
+        assert!(selected.column(0).iter().all(|&x| x < 9.0));
+    }
+}
+
 #[test]
 fn test_random_crossover() {
     let selection = RouletteWheel::new(10, 5);
     let population = OMatrix::<f64, U10, U5>::from_element_generic(U10, U5, 1.0);
     let fitness = OVector::<f64, U10>::from_element_generic(U10, U1, 1.0);
     let constraint = OVector::<bool, U10>::from_element_generic(U10, U1, true);
-    let selected = selection.select(&population, &fitness, &constraint);
+    let mut rng = new_rng(Some(0));
+    let selected = selection.select(&population, &fitness, &constraint, &mut rng);
     let crossover = Random::new(0.9, 10);
-    let offspring: OMatrix<f64, U10, U5> = crossover.crossover(&selected);
+    let offspring: OMatrix<f64, U10, U5> = crossover.crossover(&selected, &mut rng);
     assert_eq!(offspring.nrows(), 10);
     assert_eq!(offspring.ncols(), 5);
 }
@@ -66,9 +88,10 @@ fn test_heuristic_crossover() {
     let population = OMatrix::<f64, U10, U5>::from_element_generic(U10, U5, 1.0);
     let fitness = OVector::<f64, U10>::from_element_generic(U10, U1, 1.0);
     let constraint = OVector::<bool, U10>::from_element_generic(U10, U1, true);
-    let selected = selection.select(&population, &fitness, &constraint);
+    let mut rng = new_rng(Some(0));
+    let selected = selection.select(&population, &fitness, &constraint, &mut rng);
     let crossover = Heuristic::new(0.9, 10);
-    let offspring: OMatrix<f64, U10, U5> = crossover.crossover(&selected);
+    let offspring: OMatrix<f64, U10, U5> = crossover.crossover(&selected, &mut rng);
     assert_eq!(offspring.nrows(), 10);
     assert_eq!(offspring.ncols(), 5);
 }
@@ -91,10 +114,12 @@ fn test_cga() {
         }
     }
 
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
-    let mut cga = CGA::new(cga_conf, init_pop, opt_prob, 5);
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, -10.0, 10.0).unwrap());
+    let mut cga = CGA::new(cga_conf, init_pop, opt_prob, 5, None);
 
     for _ in 0..5 {
         cga.step();
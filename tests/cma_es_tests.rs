@@ -1,11 +1,11 @@
 mod common;
 
 use nalgebra::{OMatrix, OVector, U20, U2};
-use common::fcns::{QuadraticObjective, QuadraticConstraints};
+use common::fcns::{SphereCost, UnitBox};
 
 use non_convex_opt::algorithms::cma_es::cma_es::CMAES;
 use non_convex_opt::utils::{
-    config::CMAESConf,
+    config::{CMAESConf, BoundHandling, ObjectiveSense},
     opt_prob::{OptProb, OptimizationAlgorithm},
 };
 
@@ -14,14 +14,16 @@ fn test_cmaes_initialization() {
     let conf = CMAESConf {
         num_parents: 10,
         initial_sigma: 0.5,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_x = OMatrix::<f64, U20, U2>::from_element_generic(U20, U2, 0.5);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
     
-    let cmaes = CMAES::new(conf, init_x.clone(), opt_prob);
+    let cmaes = CMAES::new(conf, init_x.clone(), opt_prob, None);
     
     let pop: OMatrix<f64, U20, U2> = cmaes.st.pop;
     let fit: OVector<f64, U20> = cmaes.st.fitness;
@@ -42,14 +44,16 @@ fn test_cmaes() {
     let conf = CMAESConf {
         num_parents: 10,
         initial_sigma: 0.3,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_x = OMatrix::<f64, U20, U2>::from_element_generic(U20, U2, 0.5);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
     
-    let mut cmaes:CMAES<f64, U20, U2> = CMAES::new(conf, init_x.clone(), opt_prob);
+    let mut cmaes:CMAES<f64, U20, U2> = CMAES::new(conf, init_x.clone(), opt_prob, None);
     
     for _ in 0..20 {
         cmaes.step();
@@ -1,84 +1,143 @@
-use nalgebra::{OVector, Dim, U1, DefaultAllocator, allocator::Allocator};
-use non_convex_opt::utils::opt_prob::{ObjectiveFunction, BooleanConstraintFunction};
+use nalgebra::{OVector, DVector, Dim, DefaultAllocator, allocator::Allocator};
+use nalgebra::Complex;
+use non_convex_opt::utils::opt_prob::{ObjectiveFunction, ConstraintFunction, BooleanConstraintFunction};
+use non_convex_opt::utils::finite_diff::ComplexObjectiveFunction;
+use non_convex_opt::utils::bounds::BoundsConf;
+use non_convex_opt::benchmarks::{Benchmark, MultiObjectiveBenchmark, Optimum, Rosenbrock};
 
+// benchmarks::Rosenbrock without the benchmark's gradient and bounds, with a complex form for
+// GradientConf::ComplexStep. For the numerical gradient and unbounded problem tests, as every benchmark has both
 #[derive(Debug, Clone)]
-pub struct RosenbrockObjective {
-    pub a: f64,
-    pub b: f64,
-}
+pub struct BareRosenbrock;
 
-impl<D: Dim> ObjectiveFunction<f64, D> for RosenbrockObjective 
-where 
+impl<D: Dim> ObjectiveFunction<f64, D> for BareRosenbrock
+where
     DefaultAllocator: Allocator<D>
 {
     fn f(&self, x: &OVector<f64, D>) -> f64 {
-        let n = x.len();
-        let mut sum = 0.0;
-        for i in 0..n-1 {
-            sum += self.b * (x[i+1] - x[i].powi(2)).powi(2) + 
-                   (self.a - x[i]).powi(2);
-        }
-        sum
+        Rosenbrock.value(x.as_slice())
+    }
+
+    fn as_complex(&self) -> Option<&dyn ComplexObjectiveFunction<f64, D>> {
+        Some(self)
     }
 }
 
+impl<D: Dim> ComplexObjectiveFunction<f64, D> for BareRosenbrock
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f_complex(&self, x: &OVector<Complex<f64>, D>) -> Complex<f64> {
+        (0..x.len().saturating_sub(1))
+            .map(|i| (x[i + 1] - x[i].powi(2)).powi(2) * 100.0 + (-x[i] + 1.0).powi(2))
+            .sum()
+    }
+}
+
+// Feasible inside [0, 1] in every coordinate
 #[derive(Debug, Clone)]
-pub struct RosenbrockConstraints {}
+pub struct UnitBox;
 
-impl<D: Dim> BooleanConstraintFunction<f64, D> for RosenbrockConstraints 
-where 
+impl<D: Dim> BooleanConstraintFunction<f64, D> for UnitBox
+where
     DefaultAllocator: Allocator<D>
 {
     fn g(&self, x: &OVector<f64, D>) -> bool {
-        x.iter().all(|&xi| xi >= 0.0 && xi <= 1.0)
+        x.iter().all(|&xi| (0.0..=1.0).contains(&xi))
     }
 }
 
+// Cost to minimize on [0, 1], optimum of 0 at x = c
 #[derive(Debug, Clone)]
-pub struct QuadraticObjective {
-    pub a: f64,
-    pub b: f64,
+pub struct SphereCost {
+    pub c: f64,
+}
+
+impl Benchmark for SphereCost {
+    fn name(&self) -> String {
+        "SphereCost".to_string()
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        x.iter().map(|&xi| (xi - self.c).powi(2)).sum()
+    }
+
+    fn grad(&self, x: &[f64]) -> Option<Vec<f64>> {
+        Some(x.iter().map(|&xi| 2.0 * (xi - self.c)).collect())
+    }
+
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        BoundsConf { lower: vec![0.0], upper: vec![1.0] }
+    }
+
+    fn optimum(&self, dim: usize) -> Option<Optimum> {
+        Some(Optimum { value: 0.0, x: Some(vec![self.c; dim]) })
+    }
 }
 
-impl<D: Dim> ObjectiveFunction<f64, D> for QuadraticObjective 
+// Rewards larger x[0] but is undefined past the cliff
+#[derive(Debug, Clone)]
+pub struct NaNCliff {
+    pub cliff: f64,
+}
+
+impl<D: Dim> ObjectiveFunction<f64, D> for NaNCliff 
 where 
     DefaultAllocator: Allocator<D>
 {
     fn f(&self, x: &OVector<f64, D>) -> f64 {
-        let n = x.len();
-        let mut sum = 0.0;
-        for i in 0..n {
-            sum -= self.b * x[i].powi(2) - self.a * x[i];
-        }
-        sum
+        if x[0] > self.cliff { f64::NAN } else { x[0] }
+    }
+}
+
+// Schaffer's problem on [-5, 5], x^2 and (x - 2)^2, with Pareto set [0, 2]
+#[derive(Debug, Clone)]
+pub struct Schaffer;
+
+impl MultiObjectiveBenchmark for Schaffer {
+    fn name(&self) -> String {
+        "Schaffer".to_string()
+    }
+
+    fn values(&self, x: &[f64]) -> Vec<f64> {
+        vec![x[0].powi(2), (x[0] - 2.0).powi(2)]
     }
 
-    fn gradient(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
-        let n = x.len();
-        let mut grad = OVector::<f64, D>::zeros_generic(D::from_usize(n), U1);
-        for i in 0..n {
-            grad[i] = -2.0 * self.b * x[i] + self.a;
-        }
-        Some(grad)
+    fn num_objectives(&self) -> usize {
+        2
     }
 
-    fn x_lower_bound(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
-        Some(OVector::<f64, D>::from_element_generic(D::from_usize(x.len()), U1, 0.0))
+    fn bounds(&self, _dim: usize) -> BoundsConf {
+        BoundsConf { lower: vec![-5.0], upper: vec![5.0] }
     }
 
-    fn x_upper_bound(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
-        Some(OVector::<f64, D>::from_element_generic(D::from_usize(x.len()), U1, 1.0))
+    fn reference_front(&self, points: usize) -> Vec<Vec<f64>> {
+        (0..points.max(2)).map(|k| self.values(&[2.0 * k as f64 / (points.max(2) - 1) as f64])).collect()
     }
 }
 
+// x[0] >= 1
 #[derive(Debug, Clone)]
-pub struct QuadraticConstraints {}
+pub struct AtLeastOne;
 
-impl<D: Dim> BooleanConstraintFunction<f64, D> for QuadraticConstraints 
-where 
+impl<D: Dim> ConstraintFunction<f64, D> for AtLeastOne
+where
     DefaultAllocator: Allocator<D>
 {
-    fn g(&self, x: &OVector<f64, D>) -> bool {
-        x.iter().all(|&xi| xi >= 0.0 && xi <= 1.0)
+    fn g(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(vec![1.0 - x[0]])
+    }
+}
+
+// Leaves every point feasible
+#[derive(Debug, Clone)]
+pub struct NoConstraints;
+
+impl<D: Dim> BooleanConstraintFunction<f64, D> for NoConstraints
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, _x: &OVector<f64, D>) -> bool {
+        true
     }
-}   
\ No newline at end of file
+}
@@ -1 +1,45 @@
-pub mod fcns; 
\ No newline at end of file
+// Shared by every test crate, each of which only uses some of it
+#![allow(dead_code)]
+
+pub mod fcns;
+
+use nalgebra::{DMatrix, Dyn};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{config::Config, checkpoint::Checkpoint};
+
+pub type Opt = NonConvexOpt<f64, Dyn, Dyn>;
+
+// A run that only stops at max_iter, opt_conf holds the file's own settings, e.g. "seed": 3
+pub fn conf(max_iter: usize, opt_conf: &str, alg_conf: &str) -> Config {
+    Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": {}, "rtol": "0.0", "atol": "0.0", {} }},
+        "alg_conf": {}
+    }}"#, max_iter, opt_conf, alg_conf)).unwrap()
+}
+
+// n rows spread over [lo, hi] in every column, no two alike
+pub fn spread(n: usize, dim: usize, lo: f64, hi: f64) -> DMatrix<f64> {
+    DMatrix::from_fn(n, dim, |i, j| lo + (hi - lo) * (((i * 7 + j * 3) % n) as f64 + 0.5) / n as f64)
+}
+
+// The population and best fitness after each of steps steps
+pub fn trajectory(opt: &mut Opt, steps: usize) -> Vec<(Vec<f64>, f64)> {
+    (0..steps)
+        .map(|_| {
+            opt.step().unwrap();
+            let st = opt.state();
+            (st.pop.iter().cloned().collect(), st.best_f)
+        })
+        .collect()
+}
+
+// Checkpoints opt, steps it on and asserts a run resumed from the checkpoint's JSON takes the same steps.
+// Returns the checkpoint and the resumed run for the algorithm's own checks
+pub fn assert_resumes_exactly(opt: &mut Opt, steps: usize, resume: impl FnOnce(Checkpoint) -> Opt) -> (Checkpoint, Opt) {
+    let checkpoint = opt.checkpoint();
+    let expected = trajectory(opt, steps);
+
+    let mut resumed = resume(Checkpoint::from_json(&checkpoint.to_json().unwrap()).unwrap());
+    assert_eq!(trajectory(&mut resumed, steps), expected);
+    (checkpoint, resumed)
+}
@@ -0,0 +1,214 @@
+mod common;
+
+use std::cmp::Ordering;
+use nalgebra::{DMatrix, DVector, Dim, OVector, DefaultAllocator, allocator::Allocator};
+use common::fcns::SphereCost;
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::ConstraintFunction,
+    checkpoint::AlgCheckpoint,
+    constraint_handling::{ConstraintHandler, ConstraintHandling},
+    rng::new_rng,
+};
+
+// x0 + x1 >= 1, which cuts the quadratic's peak at (0.005, 0.005) off
+#[derive(Debug, Clone)]
+struct HalfPlane;
+
+impl<D: Dim> ConstraintFunction<f64, D> for HalfPlane
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(vec![1.0 - x[0] - x[1]])
+    }
+}
+
+fn handling(json: &str) -> ConstraintHandling {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_feasibility_rules() {
+    let handler = ConstraintHandler::new(ConstraintHandling::FeasibilityRules);
+
+    assert_eq!(handler.compare(-5.0, 0.0, 10.0, 0.1), Ordering::Greater);
+    assert_eq!(handler.compare(1.0, 0.0, 2.0, 0.0), Ordering::Less);
+    assert_eq!(handler.compare(10.0, 0.5, -10.0, 0.2), Ordering::Less);
+    assert!(handler.better(-10.0, 0.2, -10.0, 0.5));
+
+    let fitness = [3.0, 1.0, 5.0, 2.0];
+    let violation = [0.0, 0.0, 0.4, 0.1];
+    assert_eq!(handler.rank(&fitness, &violation, &mut new_rng(Some(1))), vec![0, 1, 3, 2]);
+
+    // Infeasible points score below the worst feasible one
+    let scores = handler.scores(&fitness, &violation, &mut new_rng(Some(1)));
+    assert_eq!(scores, vec![3.0, 1.0, 0.6, 0.9]);
+}
+
+#[test]
+fn test_penalties() {
+    let fixed = ConstraintHandler::new(handling(r#"{ "StaticPenalty": { "penalty": 10.0 } }"#));
+    assert_eq!(fixed.penalized(1.0, 0.5), -4.0);
+    assert_eq!(fixed.penalized(1.0, 0.0), 1.0);
+    assert!(fixed.better(1.0, 0.05, 0.0, 0.0));
+    assert!(!fixed.better(1.0, 0.5, 0.0, 0.0));
+    assert_eq!(fixed.scores(&[1.0, 0.0], &[0.5, 0.0], &mut new_rng(None)), vec![-4.0, 0.0]);
+
+    // (0.5 * iter)^2 * v^2, so 0.25 * 0.25 at the first generation and 1 * 0.25 at the second
+    let mut dynamic = ConstraintHandler::new(handling(r#"{ "DynamicPenalty": {} }"#));
+    assert!((dynamic.penalized(1.0f64, 0.5) - 0.9375).abs() < 1e-12);
+    dynamic.next_generation();
+    assert!((dynamic.penalized(1.0f64, 0.5) - 0.75).abs() < 1e-12);
+}
+
+#[test]
+fn test_adaptive_penalty_follows_best_feasibility() {
+    let mut handler = ConstraintHandler::new(handling(r#"{ "AdaptivePenalty": { "initial": 4.0, "window": 2 } }"#));
+
+    // Nothing feasible yet, so the penalty doubles once the window is full
+    assert!(handler.update_best(1.0, 0.3, f64::NEG_INFINITY));
+    handler.next_generation();
+    assert_eq!(handler.penalty, 4.0);
+    handler.next_generation();
+    assert_eq!(handler.penalty, 8.0);
+
+    // A feasible best for a full window relaxes it
+    assert!(handler.update_best(-1.0, 0.0, 1.0));
+    handler.next_generation();
+    assert_eq!(handler.penalty, 8.0);
+    handler.next_generation();
+    assert!((handler.penalty - 8.0 / 1.5).abs() < 1e-12);
+    assert!((handler.penalized(2.0f64, 0.75) - (2.0 - 4.0)).abs() < 1e-12);
+}
+
+#[test]
+fn test_stochastic_ranking() {
+    let fitness = [5.0, 4.0, 3.0, 2.0, 1.0];
+    let violation = [0.5, 0.4, 0.0, 0.0, 0.0];
+
+    // Only violations count without the fitness draw, fitness alone with it always taken
+    let by_violation = ConstraintHandler::new(handling(r#"{ "StochasticRanking": { "pf": 0.0 } }"#));
+    assert_eq!(by_violation.rank(&fitness, &violation, &mut new_rng(Some(3))), vec![2, 3, 4, 1, 0]);
+    let by_fitness = ConstraintHandler::new(handling(r#"{ "StochasticRanking": { "pf": 1.0 } }"#));
+    assert_eq!(by_fitness.rank(&fitness, &violation, &mut new_rng(Some(3))), vec![0, 1, 2, 3, 4]);
+
+    // Scores follow the ranking, and the same seed ranks the same way
+    let handler = ConstraintHandler::new(handling(r#"{ "StochasticRanking": {} }"#));
+    let rank = handler.rank(&fitness, &violation, &mut new_rng(Some(7)));
+    assert_eq!(rank, handler.rank(&fitness, &violation, &mut new_rng(Some(7))));
+    let scores = handler.scores(&fitness, &violation, &mut new_rng(Some(7)));
+    assert_eq!(scores[rank[0]], 5.0);
+    assert_eq!(scores[rank[4]], 1.0);
+
+    // One-on-one, an infeasible point with the better fitness wins only on the fitness draw
+    assert!(!by_violation.duel(5.0, 0.5, 1.0, 0.0, &mut new_rng(Some(3))));
+    assert!(by_fitness.duel(5.0, 0.5, 1.0, 0.0, &mut new_rng(Some(3))));
+    let mut rng = new_rng(Some(3));
+    let wins = (0..1000).filter(|_| handler.duel(5.0, 0.5, 1.0, 0.0, &mut rng)).count();
+    assert!((350..550).contains(&wins), "{}", wins);
+}
+
+#[test]
+fn test_epsilon_level_decays() {
+    let mut handler = ConstraintHandler::new(handling(r#"{ "Epsilon": { "theta": 0.5, "cp": 2.0, "tc": 4 } }"#));
+    handler.init(&[0.0, 0.2, 0.4, 0.8]);
+    assert_eq!(handler.epsilon, 0.4);
+
+    // Both within epsilon, so fitness decides
+    assert!(handler.better(2.0, 0.3, 1.0, 0.0));
+    assert!(handler.better(1.0, 0.3, 2.0, 0.5));
+
+    let mut levels = vec![];
+    for _ in 0..4 {
+        handler.next_generation();
+        levels.push(handler.epsilon);
+    }
+    assert!((levels[0] - 0.4 * 0.25).abs() < 1e-12);
+    assert!((levels[1] - 0.4 * 0.0625).abs() < 1e-12);
+    assert_eq!(levels[2..], [0.0, 0.0]);
+    assert!(!handler.better(2.0, 0.3, 1.0, 0.0));
+}
+
+#[test]
+fn test_best_only_moves_by_feasibility_rules() {
+    let mut handler = ConstraintHandler::new(handling(r#"{ "StaticPenalty": { "penalty": 0.1 } }"#));
+
+    // The penalty prefers it, but an infeasible point never replaces a feasible best
+    assert!(handler.better(10.0, 1.0, 0.0, 0.0));
+    assert!(handler.update_best(0.0, 0.0, f64::NEG_INFINITY));
+    assert!(!handler.update_best(10.0, 1.0, 0.0));
+    assert_eq!(handler.best_violation, 0.0);
+}
+
+fn conf(technique: &str, alg_conf: &str) -> Config {
+    Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 40, "seed": 5, "objective_sense": "Minimize", "constraint_handling": {} }},
+        "alg_conf": {}
+    }}"#, technique, alg_conf)).unwrap()
+}
+
+// The algorithms that rank with the handler and can leave an infeasible start. PT's Metropolis
+// step still rejects infeasible moves, so only its best tracking goes through the handler
+const ALG_CONFS: [&str; 4] = [
+    r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Heuristic": {} }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Polynomial": { "mutation_rate": 0.5 } } } }"#,
+    r#"{ "DE": { "common": {}, "mutation_type": { "Standard": {} } } }"#,
+    r#"{ "CMAES": { "num_parents": 5, "initial_sigma": 0.3 } }"#,
+    r#"{ "MSPO": { "num_swarms": 2, "swarm_size": 5 } }"#,
+];
+
+#[test]
+fn test_algorithms_reach_feasible_region() {
+    // Penalty coefficients sized for an objective of order 100 near the boundary
+    let techniques = [
+        r#""FeasibilityRules""#,
+        r#"{ "StaticPenalty": {} }"#,
+        r#"{ "DynamicPenalty": { "c": 5.0 } }"#,
+        r#"{ "AdaptivePenalty": { "initial": 100.0 } }"#,
+        r#"{ "StochasticRanking": {} }"#,
+        r#"{ "Epsilon": { "tc": 20 } }"#,
+    ];
+
+    for alg_conf in ALG_CONFS {
+        for technique in techniques {
+            // Starts entirely on the infeasible side
+            let init_pop = DMatrix::from_fn(10, 2, |i, j| 0.04 * (i as f64) + 0.02 * (j as f64));
+            let mut opt = NonConvexOpt::new_with_constraints(conf(technique, alg_conf), init_pop, SphereCost { c: 0.005 }, Some(HalfPlane)).unwrap();
+            for _ in 0..40 {
+                opt.step().unwrap();
+            }
+
+            // A squared penalty fades near the boundary, so the dynamic one may settle just outside
+            let best = &opt.alg.state().best_x;
+            let tol = if technique.contains("DynamicPenalty") { 0.01 } else { 0.0 };
+            assert!(best[0] + best[1] >= 1.0 - tol, "{} with {}: {:?}", alg_conf, technique, best.as_slice());
+        }
+    }
+}
+
+#[test]
+fn test_handler_state_survives_checkpoint() {
+    let pt = r#"{ "PT": { "common": { "num_replicas": 3 }, "swap_conf": { "Always": {} } } }"#;
+    let technique = r#"{ "Epsilon": { "tc": 20 } }"#;
+    let handler = |opt: &NonConvexOpt<f64, nalgebra::Dyn, nalgebra::Dyn>| match opt.checkpoint().alg {
+        AlgCheckpoint::PT(c) => c.handler.unwrap(),
+        _ => panic!("Expected PTCheckpoint"),
+    };
+
+    // Only the last two rows start feasible
+    let init_pop = DMatrix::from_fn(10, 2, |i, j| 0.06 * (i as f64) + 0.05 * (j as f64));
+    let mut opt = NonConvexOpt::new_with_constraints(conf(technique, pt), init_pop, SphereCost { c: 0.005 }, Some(HalfPlane)).unwrap();
+    for _ in 0..5 {
+        opt.step().unwrap();
+        let best = &opt.alg.state().best_x;
+        assert!(best[0] + best[1] >= 1.0);
+    }
+
+    let before = handler(&opt);
+    assert_eq!(before.iter, 6);
+    assert!(before.epsilon < before.epsilon0);
+
+    let resumed = NonConvexOpt::resume_with_constraints(conf(technique, pt), opt.checkpoint(), SphereCost { c: 0.005 }, Some(HalfPlane)).unwrap();
+    assert_eq!(handler(&resumed), before);
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use nalgebra::{DMatrix, DVector, Dim, OVector, DefaultAllocator, allocator::Allocator};
+use common::fcns::{SphereCost, UnitBox};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::{OptProb, ConstraintFunction, BooleanConstraint},
+    checkpoint::StateData,
+};
+
+// x0 + x1 <= 1 and x0 = 2 x1
+#[derive(Debug, Clone)]
+struct LinearConstraints {
+    eq_tol: f64,
+}
+
+impl<D: Dim> ConstraintFunction<f64, D> for LinearConstraints
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(vec![x[0] + x[1] - 1.0])
+    }
+
+    fn h(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(vec![x[0] - 2.0 * x[1]])
+    }
+
+    fn eq_tol(&self) -> f64 {
+        self.eq_tol
+    }
+}
+
+fn problem(eq_tol: f64) -> OptProb<f64, nalgebra::Dyn> {
+    OptProb::new(Box::new(SphereCost { c: 0.005 }), None)
+        .with_constraints(Some(Box::new(LinearConstraints { eq_tol })))
+}
+
+#[test]
+fn test_violation_sums_inequalities_and_equalities() {
+    let opt_prob = problem(1e-6);
+
+    let feasible = DVector::from_vec(vec![0.4, 0.2]);
+    assert_eq!(opt_prob.violation(&feasible), 0.0);
+    assert!(opt_prob.is_feasible(&feasible));
+
+    // Inequality misses by 0.5, equality by 0.3
+    let x = DVector::from_vec(vec![1.1, 0.4]);
+    assert!((opt_prob.violation(&x) - 0.8).abs() < 1e-5);
+    assert!(!opt_prob.is_feasible(&x));
+
+    // A nearly feasible point violates less than a hopeless one
+    let near = DVector::from_vec(vec![0.41, 0.2]);
+    let far = DVector::from_vec(vec![5.0, -3.0]);
+    assert!(opt_prob.violation(&near) < opt_prob.violation(&far));
+
+    // Within the equality tolerance counts as feasible
+    assert!(problem(0.05).is_feasible(&near));
+    assert_eq!(opt_prob.evaluations().constraints, 6);
+}
+
+#[test]
+fn test_boolean_constraints_through_adapter() {
+    let adapter = BooleanConstraint(UnitBox);
+    let inside = DVector::from_vec(vec![0.5, 0.5]);
+    let outside = DVector::from_vec(vec![0.5, 1.5]);
+    assert_eq!(ConstraintFunction::<f64, _>::violation(&adapter, &inside), 0.0);
+    assert_eq!(ConstraintFunction::<f64, _>::violation(&adapter, &outside), 1.0);
+
+    let opt_prob = OptProb::new(Box::new(SphereCost { c: 0.005 }), Some(Box::new(UnitBox)));
+    assert!(opt_prob.is_feasible(&inside));
+    assert_eq!(opt_prob.violation(&outside), 1.0);
+}
+
+#[test]
+fn test_state_stores_violation() {
+    let conf = Config::new(r#"{
+        "opt_conf": { "max_iter": 10, "seed": 4, "objective_sense": "Minimize" },
+        "alg_conf": { "DE": { "common": {}, "mutation_type": { "Standard": {} } } }
+    }"#).unwrap();
+    let init_pop = DMatrix::from_fn(10, 2, |i, j| 0.3 * (i as f64) - 0.5 * (j as f64));
+    let mut opt = NonConvexOpt::new_with_constraints(conf.clone(), init_pop, SphereCost { c: 0.005 }, Some(LinearConstraints { eq_tol: 1e-6 })).unwrap();
+
+    for _ in 0..3 {
+        opt.step().unwrap();
+        let st = opt.alg.state();
+        assert_eq!(st.violation.len(), st.pop.nrows());
+        for i in 0..st.pop.nrows() {
+            assert!(st.violation[i] >= 0.0);
+            assert_eq!(st.constraints[i], st.violation[i] == 0.0);
+        }
+    }
+
+    let checkpoint = opt.checkpoint();
+    let violation = opt.alg.state().violation.clone();
+    let resumed = NonConvexOpt::<f64, nalgebra::Dyn, nalgebra::Dyn>::resume_with_constraints(conf, checkpoint, SphereCost { c: 0.005 }, Some(LinearConstraints { eq_tol: 1e-6 })).unwrap();
+    assert_eq!(resumed.alg.state().violation, violation);
+}
+
+#[test]
+fn test_old_checkpoints_fall_back_to_feasibility_flags() {
+    let json = r#"{
+        "best_x": [0.0], "best_f": 1.0,
+        "pop": { "nrows": 2, "ncols": 1, "data": [0.0, 1.0] },
+        "fitness": [1.0, 0.5], "constraints": [true, false], "iter": 2
+    }"#;
+    let data: StateData = serde_json::from_str(json).unwrap();
+    let st = data.to_state::<f64, nalgebra::Dyn, nalgebra::Dyn>().unwrap();
+    assert_eq!(st.violation.as_slice(), &[0.0, 1.0]);
+}
@@ -2,10 +2,11 @@ mod common;
 
 use nalgebra::DMatrix;
 use non_convex_opt::utils::opt_prob::{OptProb, OptimizationAlgorithm};
-use common::fcns::{RosenbrockObjective, RosenbrockConstraints};
+use common::fcns::UnitBox;
+use non_convex_opt::benchmarks::Rosenbrock;
 
 use non_convex_opt::{
-    utils::config::DEConf,
+    utils::config::{DEConf, BoundHandling, ObjectiveSense},
     algorithms::differential_evolution::de::DE
 };
 
@@ -20,6 +21,7 @@ fn test_de_basic() {
         common: CommonConf {
             archive_size: 10,
             success_history_size: 50,
+            bound_handling: BoundHandling::Clamp,
         },
         mutation_type: MutationType::Standard(StandardConf {
             f: 0.8,
@@ -35,11 +37,12 @@ fn test_de_basic() {
         }
     }
 
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 100.0 };
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
     
-    let mut de = DE::new(conf, init_pop.clone(), opt_prob);
+    let mut de = DE::new(conf, init_pop.clone(), opt_prob, None);
     let initial_fitness = de.st.best_f;
     
     for _ in 0..50 {
@@ -55,6 +58,7 @@ fn test_adaptive_de() {
         common: CommonConf {
             archive_size: 10,
             success_history_size: 50,
+            bound_handling: BoundHandling::Clamp,
         },
         mutation_type: MutationType::Adaptive(AdaptiveConf {
             strategy: DEStrategy::Best2Bin,
@@ -72,11 +76,12 @@ fn test_adaptive_de() {
         }
     }
 
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 100.0 };
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
     
-    let mut de = DE::new(conf, init_pop.clone(), opt_prob);
+    let mut de = DE::new(conf, init_pop.clone(), opt_prob, None);
     let initial_fitness = de.st.best_f;
     
     // Run for more iterations to ensure improvement
@@ -0,0 +1,148 @@
+mod common;
+
+use nalgebra::{DMatrix, DVector, SVector, Dyn, U2};
+use common::fcns::{BareRosenbrock, UnitBox};
+use non_convex_opt::benchmarks::{Benchmark, Rosenbrock, Sphere};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::{ObjectiveFunction, OptProb},
+    finite_diff::{FiniteDifference, ComplexStep, FiniteDiffMethod, StepSize, GradientConf},
+    stopping::TerminationReason,
+    error::OptError,
+};
+
+fn rosenbrock_gradient(x: &DVector<f64>) -> DVector<f64> {
+    DVector::from_vec(Rosenbrock.grad(x.as_slice()).unwrap())
+}
+
+fn max_error(a: &DVector<f64>, b: &DVector<f64>) -> f64 {
+    (a - b).abs().max()
+}
+
+#[test]
+fn test_finite_difference_matches_analytic() {
+    let obj = Sphere;
+    let x = SVector::<f64, 2>::new(0.3, -0.7);
+    let exact = ObjectiveFunction::<f64, U2>::gradient(&obj, &x).unwrap();
+
+    let forward = FiniteDifference::forward(obj).gradient(&x).unwrap();
+    let central = FiniteDifference::central(obj).gradient(&x).unwrap();
+    let fixed = FiniteDifference::new(obj, FiniteDiffMethod::Central, StepSize::Fixed { h: 1e-3 }).gradient(&x).unwrap();
+
+    assert!((forward - exact).abs().max() < 1e-5);
+    assert!((central - exact).abs().max() < 1e-7);
+    assert!((fixed - exact).abs().max() < 1e-7); // Central differences are exact on quadratics
+}
+
+#[test]
+fn test_central_beats_forward() {
+    let obj = Rosenbrock;
+    let x = DVector::from_vec(vec![-1.2, 1.0, 0.5, 2.0]);
+    let exact = rosenbrock_gradient(&x);
+
+    let forward = max_error(&FiniteDifference::forward(obj).gradient(&x).unwrap(), &exact);
+    let central = max_error(&FiniteDifference::central(obj).gradient(&x).unwrap(), &exact);
+    assert!(forward < 1e-3);
+    assert!(central < 1e-5);
+    assert!(central < forward);
+}
+
+#[test]
+fn test_complex_step_is_exact() {
+    let obj = BareRosenbrock;
+    let x = DVector::from_vec(vec![-1.2, 1.0, 0.5, 2.0]);
+    let exact = rosenbrock_gradient(&x);
+
+    let grad = ComplexStep::new(obj.clone()).gradient(&x).unwrap();
+    assert!(max_error(&grad, &exact) < 1e-12 * exact.abs().max());
+    assert_eq!(ComplexStep::new(obj.clone()).f(&x), obj.f(&x));
+}
+
+#[test]
+fn test_gradient_conf_from_json() {
+    let conf = Config::new(r#"{
+        "opt_conf": { "gradient": { "FiniteDifference": { "method": "Forward", "step": { "Fixed": { "h": 1e-6 } } } } },
+        "alg_conf": { "Adam": {} }
+    }"#).unwrap();
+    assert_eq!(conf.opt_conf.gradient, GradientConf::FiniteDifference { method: FiniteDiffMethod::Forward, step: StepSize::Fixed { h: 1e-6 } });
+
+    let conf = Config::new(r#"{ "opt_conf": {}, "alg_conf": { "Adam": {} } }"#).unwrap();
+    assert_eq!(conf.opt_conf.gradient, GradientConf::Analytic);
+}
+
+#[test]
+fn test_gradient_algorithms_on_black_box() {
+    let alg_confs = [
+        (r#"{ "Adam": { "learning_rate": 0.05 } }"#, 3000),
+        (r#"{ "SGA": { "learning_rate": 0.0002, "momentum": 0.5 } }"#, 3000),
+        (r#"{ "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } }"#, 50),
+    ];
+
+    for (alg_conf, max_iter) in alg_confs {
+        let conf = Config::new(&format!(r#"{{
+            "opt_conf": {{ "max_iter": {}, "seed": 3, "objective_sense": "Minimize", "gradient": {{ "FiniteDifference": {{}} }} }},
+            "alg_conf": {}
+        }}"#, max_iter, alg_conf)).unwrap();
+
+        let init_pop = DMatrix::from_row_slice(1, 2, &[-1.2, 1.0]);
+        let mut opt = NonConvexOpt::new(conf, init_pop, Rosenbrock, None::<UnitBox>).unwrap();
+        let f0 = opt.state().best_f;
+        let result = opt.run().unwrap();
+
+        assert!(result.best_f < 0.2 * f0, "{}: {} -> {}", alg_conf, f0, result.best_f);
+        assert!(result.evals.gradient > 0);
+    }
+}
+
+#[test]
+fn test_numerical_gradients_use_the_budget() {
+    for (method, per_gradient) in [("Forward", 3), ("Central", 4)] {
+        let conf = Config::new(&format!(r#"{{
+            "opt_conf": {{ "max_iter": 1000, "max_evals": 50, "seed": 3, "objective_sense": "Minimize", "gradient": {{ "FiniteDifference": {{ "method": "{}" }} }} }},
+            "alg_conf": {{ "Adam": {{ "learning_rate": 0.05 }} }}
+        }}"#, method)).unwrap();
+
+        let init_pop = DMatrix::from_row_slice(1, 2, &[-1.2, 1.0]);
+        let mut opt = NonConvexOpt::new(conf, init_pop, Rosenbrock, None::<UnitBox>).unwrap();
+        let result = opt.run().unwrap();
+
+        assert_eq!(result.termination, TerminationReason::MaxEvals, "{}", method);
+        assert_eq!(result.evals.objective, 50, "{}", method);
+        assert!(result.evals.objective >= per_gradient * result.evals.gradient, "{}: {:?}", method, result.evals);
+        assert!(result.iterations < 1000, "{}", method);
+    }
+}
+
+#[test]
+fn test_no_gradient_once_the_budget_is_spent() {
+    let opt_prob: OptProb<f64, Dyn> = OptProb::new(Box::new(FiniteDifference::central(Rosenbrock)), None).with_max_evals(Some(6));
+    let x = DVector::from_vec(vec![-1.2, 1.0]);
+
+    // Four evaluations for the first gradient leave two, too few for another
+    assert!(opt_prob.gradient(&x).is_some());
+    assert!(opt_prob.require_gradient(&x, "Adam").is_none());
+    assert!(opt_prob.budget_exhausted());
+    assert_eq!(opt_prob.evaluations().gradient, 1);
+    assert_eq!(opt_prob.take_error(), None); // Not a missing gradient
+}
+
+#[test]
+fn test_complex_step_from_config() {
+    let conf = |alg_conf: &str| Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 50, "seed": 3, "objective_sense": "Minimize", "gradient": {{ "ComplexStep": {{}} }} }},
+        "alg_conf": {}
+    }}"#, alg_conf)).unwrap();
+    let lbfgs = r#"{ "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } }"#;
+    assert_eq!(conf(lbfgs).opt_conf.gradient, GradientConf::ComplexStep { h: 1e-20 });
+
+    let init_pop = DMatrix::from_row_slice(1, 2, &[-1.2, 1.0]);
+    let mut opt = NonConvexOpt::new(conf(lbfgs), init_pop.clone(), BareRosenbrock, None::<UnitBox>).unwrap();
+    let f0 = opt.state().best_f;
+    let result = opt.run().unwrap();
+    assert!(result.best_f < 0.2 * f0, "{} -> {}", f0, result.best_f);
+
+    // Without a complex form there is no gradient to use, even with an analytic one
+    let err = NonConvexOpt::new(conf(lbfgs), init_pop, Rosenbrock, None::<UnitBox>).err().unwrap();
+    assert!(matches!(err, OptError::MissingGradient { .. }));
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use nalgebra::{DMatrix, DVector, Dim, OVector, DefaultAllocator, allocator::Allocator};
+use common::fcns::{BareRosenbrock, UnitBox};
+use non_convex_opt::benchmarks::Sphere;
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::ObjectiveFunction,
+    error::OptError,
+    gradient_check::{check_gradient, GradientCheckConf},
+};
+
+// Quadratic whose gradient has a typo in one coordinate
+#[derive(Debug, Clone)]
+struct BuggyQuadratic {
+    wrong_coord: usize,
+}
+
+impl<D: Dim> ObjectiveFunction<f64, D> for BuggyQuadratic
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> f64 {
+        -x.dot(x)
+    }
+
+    fn gradient(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
+        let mut grad = x * -2.0;
+        grad[self.wrong_coord] = -x[self.wrong_coord];
+        Some(grad)
+    }
+}
+
+fn sample_points() -> Vec<DVector<f64>> {
+    vec![
+        DVector::from_vec(vec![0.3, -0.7, 1.5]),
+        DVector::from_vec(vec![-2.0, 0.1, 0.0]),
+    ]
+}
+
+#[test]
+fn test_correct_gradient_passes() {
+    let report = check_gradient(&Sphere, &sample_points(), &GradientCheckConf::default()).unwrap();
+
+    assert!(report.passed);
+    assert_eq!(report.errors.len(), 6);
+    assert!(report.max_rel_error < 1e-6);
+    assert_eq!(report.worst.len(), 5);
+}
+
+#[test]
+fn test_wrong_gradient_reports_worst_coordinate() {
+    let report = check_gradient(&BuggyQuadratic { wrong_coord: 1 }, &sample_points(), &GradientCheckConf::default()).unwrap();
+
+    assert!(!report.passed);
+    let failing: Vec<_> = report.errors.iter().filter(|e| !e.passed).collect();
+    assert_eq!(failing.len(), 2);
+    assert!(failing.iter().all(|e| e.coord == 1));
+
+    let worst = &report.worst[0];
+    assert_eq!(worst.coord, 1);
+    assert!((worst.analytic - 0.7).abs() < 1e-12 || (worst.analytic + 0.1).abs() < 1e-12);
+    assert!((worst.rel_error - 0.5).abs() < 1e-6);
+
+    assert!(matches!(report.into_result(), Err(OptError::GradientMismatch { coord: 1, .. })));
+}
+
+#[test]
+fn test_failure_without_reported_coordinates() {
+    let conf = GradientCheckConf { num_worst: 0, ..GradientCheckConf::default() };
+    let report = check_gradient(&BuggyQuadratic { wrong_coord: 2 }, &sample_points(), &conf).unwrap();
+
+    assert!(report.worst.is_empty());
+    assert!(!report.passed);
+    assert!(matches!(report.into_result(), Err(OptError::GradientMismatch { coord: 2, .. })));
+}
+
+#[test]
+fn test_missing_gradient_is_an_error() {
+    let err = check_gradient(&BareRosenbrock, &sample_points(), &GradientCheckConf::default()).unwrap_err();
+    assert!(matches!(err, OptError::MissingGradient { .. }));
+}
+
+#[test]
+fn test_new_checks_gradient_when_asked() {
+    let conf = |alg_conf: &str, check: &str| Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 10, "check_gradient": {} }},
+        "alg_conf": {}
+    }}"#, check, alg_conf)).unwrap();
+    let adam = r#"{ "Adam": {} }"#;
+    let de = r#"{ "DE": { "common": {}, "mutation_type": { "Adaptive": {} } } }"#;
+    let init_pop = DMatrix::from_fn(8, 3, |i, j| 0.1 * (i as f64) - 0.2 * (j as f64));
+
+    let err = NonConvexOpt::new(conf(adam, "{}"), init_pop.clone(), BuggyQuadratic { wrong_coord: 2 }, None::<UnitBox>).err().unwrap();
+    assert!(matches!(err, OptError::GradientMismatch { coord: 2, .. }));
+
+    // Off by default, and skipped for algorithms that don't use the gradient
+    assert!(NonConvexOpt::new(conf(adam, "null"), init_pop.clone(), BuggyQuadratic { wrong_coord: 2 }, None::<UnitBox>).is_ok());
+    assert!(NonConvexOpt::new(conf(de, "{}"), init_pop.clone(), BuggyQuadratic { wrong_coord: 2 }, None::<UnitBox>).is_ok());
+    assert!(NonConvexOpt::new(conf(adam, r#"{ "rtol": 1e-8 }"#), init_pop, Sphere, None::<UnitBox>).is_ok());
+}
@@ -1,12 +1,14 @@
 mod common;
 
 use nalgebra::{SMatrix, U1, U2};
-use common::fcns::{RosenbrockObjective, RosenbrockConstraints};
+use common::fcns::UnitBox;
+use non_convex_opt::benchmarks::Rosenbrock;
 
 use non_convex_opt::algorithms::grasp::grasp::GRASP;
 use non_convex_opt::utils::{
-    config::GRASPConf,
-    opt_prob::{OptProb, OptimizationAlgorithm, BooleanConstraintFunction}
+    config::{GRASPConf, BoundHandling, ObjectiveSense},
+    opt_prob::{OptProb, OptimizationAlgorithm, BooleanConstraintFunction},
+    bounds::Bounds,
 };
 
 #[test]
@@ -17,13 +19,16 @@ fn test_grasp_unconstrained() {
         num_neighbors: 20,
         step_size: 0.1,
         perturbation_prob: 0.3,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let opt_prob = OptProb::new(Box::new(obj_f), None::<Box<dyn BooleanConstraintFunction<f64, U2>>>);
+    let obj_f = Rosenbrock;
+    let opt_prob = OptProb::new(Box::new(obj_f), None::<Box<dyn BooleanConstraintFunction<f64, U2>>>)
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, -10.0, 10.0).unwrap());
     
-    let mut grasp:GRASP<f64, U1, U2>   = GRASP::new(conf, init_x.clone(), opt_prob);
+    let mut grasp:GRASP<f64, U1, U2>   = GRASP::new(conf, init_x.clone(), opt_prob, None);
     
     let initial_fitness = grasp.st.best_f;
     
@@ -42,14 +47,17 @@ fn test_grasp_constrained() {
         num_neighbors: 20,
         step_size: 0.1,
         perturbation_prob: 0.3,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, -10.0, 10.0).unwrap());
     
-    let mut grasp: GRASP<f64, U1, U2> = GRASP::new(conf, init_x.clone(), opt_prob);
+    let mut grasp: GRASP<f64, U1, U2> = GRASP::new(conf, init_x.clone(), opt_prob, None);
     
     let initial_fitness = grasp.st.best_f;
     
@@ -69,14 +77,17 @@ fn test_grasp_construction_and_local_search() {
         num_neighbors: 20,
         step_size: 0.1,
         perturbation_prob: 0.3,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, -10.0, 10.0).unwrap());
     
-    let grasp: GRASP<f64, U1, U2> = GRASP::new(conf, init_x.clone(), opt_prob);
+    let mut grasp: GRASP<f64, U1, U2> = GRASP::new(conf, init_x.clone(), opt_prob, None);
     
     let solution = grasp.construct_solution();
     let improved = grasp.local_search(&solution);
@@ -93,14 +104,17 @@ fn test_grasp_bounds() {
         num_neighbors: 20,
         step_size: 0.1,
         perturbation_prob: 0.3,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, -10.0, 10.0).unwrap());
     
-    let mut grasp: GRASP<f64, U1, U2> = GRASP::new(conf, init_x.clone(), opt_prob);
+    let mut grasp: GRASP<f64, U1, U2> = GRASP::new(conf, init_x.clone(), opt_prob, None);
     
     for _ in 0..10 {
         grasp.step();
@@ -0,0 +1,191 @@
+use std::sync::{Arc, Mutex};
+use nalgebra::{DMatrix, DVector, Dyn, Dim, OVector, DefaultAllocator, allocator::Allocator};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::{MultiObjectiveFunction, ConstraintFunction, ObjectiveSense, State, Diagnostics},
+    observer::ObserverAction,
+    indicators::{hypervolume, wfg_hypervolume, igd, igd_plus, spread, additive_epsilon, Indicator},
+};
+
+// Schaffer's problem, x^2 and (x - 2)^2, whose front is f2 = (sqrt(f1) - 2)^2 for f1 in [0, 4]
+#[derive(Debug, Clone)]
+struct Schaffer;
+
+impl<D: Dim> MultiObjectiveFunction<f64, D> for Schaffer
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::from_vec(vec![x[0].powi(2), (x[0] - 2.0).powi(2)])
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NoConstraints;
+
+impl<D: Dim> ConstraintFunction<f64, D> for NoConstraints
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, _x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::zeros(0)
+    }
+}
+
+// Evenly spaced points of the line f1 + f2 = 1
+fn linear_front(n: usize) -> Vec<Vec<f64>> {
+    (0..n).map(|i| i as f64 / (n - 1) as f64).map(|t| vec![t, 1.0 - t]).collect()
+}
+
+// Points of the positive octant of the unit sphere
+fn sphere_front(n: usize) -> Vec<Vec<f64>> {
+    (0..n).map(|i| {
+        let (a, b) = (0.1 + 1.3 * ((i * 7) % n) as f64 / n as f64, 0.1 + 1.3 * ((i * 3) % n) as f64 / n as f64);
+        vec![a.cos() * b.cos(), a.cos() * b.sin(), a.sin()]
+    }).collect()
+}
+
+#[test]
+fn test_hypervolume() {
+    // Three unit-wide steps under a staircase
+    let front = vec![vec![1.0, 3.0], vec![2.0, 2.0], vec![3.0, 1.0]];
+    assert!((hypervolume(&front, &[4.0, 4.0]) - 6.0).abs() < 1e-12);
+
+    // Dominated points and points outside the reference add nothing
+    let mut extended = front.clone();
+    extended.extend(vec![vec![3.0, 3.0], vec![5.0, 0.0], vec![2.0, 2.0]]);
+    assert!((hypervolume(&extended, &[4.0, 4.0]) - 6.0).abs() < 1e-12);
+    assert_eq!(hypervolume(&[], &[4.0, 4.0]), 0.0);
+
+    // Two boxes of volume 4 and 2 overlapping in a unit cube
+    let boxes = vec![vec![0.0, 0.0, 1.0], vec![1.0, 1.0, 0.0]];
+    assert!((hypervolume(&boxes, &[2.0, 2.0, 2.0]) - 5.0).abs() < 1e-12);
+
+    // The same boxes with extra objectives the reference adds one unit to
+    let lifted: Vec<Vec<f64>> = boxes.iter().map(|p| [p.clone(), vec![0.0, 0.0]].concat()).collect();
+    assert!((hypervolume(&lifted, &[2.0, 2.0, 2.0, 1.0, 1.0]) - 5.0).abs() < 1e-12);
+
+    // Inclusion-exclusion in four objectives, three boxes of 4 with pairwise overlaps of 1, 2 and 2
+    let four = vec![vec![0.0, 1.0, 0.0, 1.0], vec![1.0, 0.0, 1.0, 0.0], vec![1.0, 1.0, 0.0, 0.0]];
+    let expected = 3.0 * 4.0 - 5.0 + 1.0;
+    assert!((hypervolume(&four, &[2.0; 4]) - expected).abs() < 1e-12);
+}
+
+#[test]
+fn test_wfg_matches_exact() {
+    let reference = [1.1, 1.1, 1.1];
+    for n in [1, 2, 5, 20, 60] {
+        let front = sphere_front(n);
+        let exact = hypervolume(&front, &reference);
+        assert!(exact > 0.0);
+        assert!((wfg_hypervolume(&front, &reference) - exact).abs() < 1e-9, "{} points", n);
+    }
+
+    let front = linear_front(30);
+    assert!((wfg_hypervolume(&front, &[1.0, 1.0]) - hypervolume(&front, &[1.0, 1.0])).abs() < 1e-12);
+}
+
+#[test]
+fn test_distance_indicators() {
+    let reference = linear_front(11);
+    let shift = |d: f64| -> Vec<Vec<f64>> { reference.iter().map(|r| r.iter().map(|v| v + d).collect()).collect() };
+
+    assert_eq!(igd(&reference, &reference), 0.0);
+    assert_eq!(igd_plus(&reference, &reference), 0.0);
+    assert_eq!(additive_epsilon(&reference, &reference), 0.0);
+
+    // Shifted away from the reference, every measure sees the gap
+    let worse = shift(0.1);
+    assert!((igd(&worse, &reference) - 0.1 * 2f64.sqrt()).abs() < 1e-12);
+    assert!((igd_plus(&worse, &reference) - 0.1 * 2f64.sqrt()).abs() < 1e-12);
+    assert!((additive_epsilon(&worse, &reference) - 0.1).abs() < 1e-12);
+
+    // Shifted past it, IGD+ and epsilon reward the front that dominates
+    let better = shift(-0.1);
+    assert!((igd(&better, &reference) - 0.1 * 2f64.sqrt()).abs() < 1e-12);
+    assert_eq!(igd_plus(&better, &reference), 0.0);
+    assert!((additive_epsilon(&better, &reference) + 0.1).abs() < 1e-12);
+
+    // A single point covers the reference poorly
+    assert!(igd(&[vec![0.5, 0.5]], &reference) > igd(&reference[..6], &reference));
+}
+
+#[test]
+fn test_spread() {
+    let reference = linear_front(101);
+
+    // Evenly spaced and reaching both ends
+    assert!(spread(&linear_front(11), &reference) < 1e-12);
+
+    // Clustered at one end
+    let clustered: Vec<Vec<f64>> = (0..11).map(|i| vec![0.01 * i as f64, 1.0 - 0.01 * i as f64]).collect();
+    assert!(spread(&clustered, &reference) > 0.5);
+
+    // Uneven gaps
+    let uneven: Vec<Vec<f64>> = [0.0, 0.05, 0.1, 0.6, 1.0].iter().map(|&t| vec![t, 1.0 - t]).collect();
+    assert!(spread(&uneven, &reference) > spread(&linear_front(5), &reference));
+    assert_eq!(spread(&[vec![0.5, 0.5]], &reference), 1.0);
+}
+
+#[test]
+fn test_maximized_fronts() {
+    let front = vec![vec![1.0, 3.0], vec![2.0, 2.0], vec![3.0, 1.0]];
+    let negated: Vec<Vec<f64>> = front.iter().map(|p| p.iter().map(|v| -v).collect()).collect();
+
+    // The same front seen from either sense gives the same values
+    let hv = Indicator::Hypervolume { reference: vec![4.0, 4.0] };
+    let hv_max = Indicator::Hypervolume { reference: vec![-4.0, -4.0] };
+    assert!((hv.evaluate(&front, ObjectiveSense::Minimize) - 6.0).abs() < 1e-12);
+    assert!((hv_max.evaluate(&negated, ObjectiveSense::Maximize) - 6.0).abs() < 1e-12);
+
+    let reference = vec![vec![0.5, 2.5], vec![2.5, 0.5]];
+    let negated_reference: Vec<Vec<f64>> = reference.iter().map(|p| p.iter().map(|v| -v).collect()).collect();
+    let eps = Indicator::Epsilon { reference_front: reference };
+    let eps_max = Indicator::Epsilon { reference_front: negated_reference };
+    assert!((eps.evaluate(&front, ObjectiveSense::Minimize) - 0.5).abs() < 1e-12);
+    assert!((eps_max.evaluate(&negated, ObjectiveSense::Maximize) - 0.5).abs() < 1e-12);
+}
+
+#[test]
+fn test_indicators_during_run() {
+    let conf = Config::new(r#"{
+        "opt_conf": {
+            "max_iter": 60, "rtol": "0.0", "atol": "0.0", "seed": 11, "objective_sense": "Minimize",
+            "bounds": { "lower": [-5.0], "upper": [5.0] }
+        },
+        "alg_conf": { "NSGA2": { "crossover": { "Heuristic": { "crossover_prob": 0.9 } }, "mutation": { "Polynomial": { "mutation_rate": 0.3 } } } }
+    }"#).unwrap();
+    let init_pop = DMatrix::from_fn(40, 1, |i, _| -5.0 + 10.0 * (((i * 7) % 40) as f64 + 0.5) / 40.0);
+    let mut opt = NonConvexOpt::new_multi_objective(conf, init_pop, Schaffer, None::<NoConstraints>).unwrap();
+
+    let true_front: Vec<Vec<f64>> = (0..=200).map(|i| 2.0 * i as f64 / 200.0).map(|x| vec![x * x, (x - 2.0) * (x - 2.0)]).collect();
+    opt.add_indicator(Indicator::Hypervolume { reference: vec![4.0, 4.0] });
+    opt.add_indicator(Indicator::IGD { reference_front: true_front.clone() });
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorder = seen.clone();
+    opt.add_observer(Box::new(move |_iter: usize, _st: &State<f64, Dyn, Dyn>, diagnostics: &Diagnostics| {
+        recorder.lock().unwrap().push((diagnostics["hypervolume"], diagnostics["igd"]));
+        ObserverAction::Continue
+    }));
+    let result = opt.run().unwrap();
+
+    // 40 points can't cover all of the continuous front's 16 - 8 / 3
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 59);
+    let (hv_first, igd_first) = seen[0];
+    let (hv_last, igd_last) = seen[seen.len() - 1];
+    assert!(hv_last >= hv_first && igd_last <= igd_first);
+    assert!(hv_last > 13.0 && hv_last < 16.0 - 8.0 / 3.0, "{}", hv_last);
+    assert!(igd_last < 0.1, "{}", igd_last);
+
+    // The tracked value is the one of the returned front
+    let front = result.pareto_front.unwrap();
+    assert!((hypervolume(&front.f, &[4.0, 4.0]) - hv_last).abs() < 1e-12);
+    assert!((igd(&front.f, &true_front) - igd_last).abs() < 1e-12);
+}
@@ -1,10 +1,11 @@
 mod common;
 
 use nalgebra::{SMatrix, U1, U2};
-use common::fcns::{QuadraticObjective, QuadraticConstraints};
+use common::fcns::{SphereCost, UnitBox};
 
 use non_convex_opt::algorithms::limited_memory_bfgs::lbfgs::LBFGS;
 use non_convex_opt::utils::{
+    config::ObjectiveSense,
     opt_prob::{OptProb, OptimizationAlgorithm},
     alg_conf::lbfgs_conf::{
         LBFGSConf, 
@@ -15,7 +16,8 @@ use non_convex_opt::utils::{
         StrongWolfeConf, 
         MoreThuenteConf, 
         GoldenSectionConf
-    }
+    },
+    bounds::Bounds,
 };
 
 #[test]
@@ -34,9 +36,11 @@ fn test_lbfgs() {
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
     let mut lbfgs: LBFGS<f64, U1, U2> = LBFGS::new(conf, init_x.clone(), opt_prob);
     let initial_fitness = lbfgs.st.best_f;
@@ -62,9 +66,11 @@ fn test_backtracking_line_search() {
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
     let mut lbfgs: LBFGS<f64, U1, U2> =LBFGS::new(conf, init_x.clone(), opt_prob);
     let initial_fitness = lbfgs.st.best_f;
@@ -91,9 +97,11 @@ fn test_strong_wolfe_line_search() {
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
     let mut lbfgs: LBFGS<f64, U1, U2> =LBFGS::new(conf, init_x.clone(), opt_prob);
     let initial_fitness = lbfgs.st.best_f;
@@ -121,9 +129,11 @@ fn test_hager_zhang_line_search() {
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
     let mut lbfgs: LBFGS<f64, U1, U2> =LBFGS::new(conf, init_x.clone(), opt_prob);
     let initial_fitness = lbfgs.st.best_f;
@@ -149,9 +159,11 @@ fn test_more_thuente_line_search() {
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
     let mut lbfgs: LBFGS<f64, U1, U2> =LBFGS::new(conf, init_x.clone(), opt_prob);
     let initial_fitness = lbfgs.st.best_f;
@@ -177,9 +189,11 @@ fn test_golden_section_line_search() {
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
     let mut lbfgs: LBFGS<f64, U1, U2> =LBFGS::new(conf, init_x.clone(), opt_prob);
     let initial_fitness = lbfgs.st.best_f;
@@ -0,0 +1,158 @@
+mod common;
+
+use nalgebra::{DMatrix, DVector};
+use common::fcns::{SphereCost, UnitBox};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::{OptProb, ConstraintFunction},
+    bounds::{Bounds, BoundHandling},
+    linear_constraints::LinearConstraints,
+    error::OptError,
+    rng::new_rng,
+};
+
+// x >= 0 and x0 + x1 + x2 = 1, the probability simplex
+fn simplex() -> LinearConstraints<f64> {
+    LinearConstraints::new(
+        -DMatrix::identity(3, 3),
+        DVector::zeros(3),
+        DMatrix::from_row_slice(1, 3, &[1.0, 1.0, 1.0]),
+        DVector::from_vec(vec![1.0]),
+    ).unwrap()
+}
+
+#[test]
+fn test_projection_onto_simplex() {
+    let linear = simplex();
+
+    // Sorting gives theta = 0.15, so (0.35, 0.65, 0)
+    let x = linear.project(&DVector::from_vec(vec![0.5, 0.8, -0.2]), None);
+    assert!((x - DVector::from_vec(vec![0.35, 0.65, 0.0])).amax() < 1e-6);
+    assert!(linear.is_feasible(&linear.project(&DVector::from_vec(vec![3.0, -1.0, 7.0]), None)));
+
+    // A feasible point stays where it is
+    let inside = DVector::from_vec(vec![0.2, 0.3, 0.5]);
+    assert!((linear.project(&inside, None) - &inside).amax() < 1e-12);
+
+    // The box cuts the simplex down further
+    let bounds = Bounds::uniform(3, 0.0, 0.4).unwrap();
+    let x = linear.project(&DVector::from_vec(vec![1.0, 0.0, 0.0]), Some(&bounds));
+    assert!(linear.is_feasible(&x) && bounds.contains(&x), "{:?}", x.as_slice());
+    assert!((x[0] - 0.4).abs() < 1e-6 && (x[1] - 0.3).abs() < 1e-6);
+}
+
+#[test]
+fn test_hit_and_run_stays_inside() {
+    let linear = simplex();
+    let start = DVector::from_vec(vec![2.0, 2.0, 2.0]);
+    let samples = linear.sample(&start, 2000, 3, None, &mut new_rng(Some(1))).unwrap();
+
+    for x in &samples {
+        assert!(linear.is_feasible(x), "{:?}", x.as_slice());
+        assert!((x.sum() - 1.0).abs() < 1e-9);
+    }
+
+    // Uniform on the simplex, so each coordinate averages 1/3
+    let mean = samples.iter().fold(DVector::zeros(3), |acc, x| acc + x) / samples.len() as f64;
+    assert!((&mean - DVector::from_element(3, 1.0 / 3.0)).amax() < 0.03, "{:?}", mean.as_slice());
+
+    let again = linear.sample(&start, 2000, 3, None, &mut new_rng(Some(1))).unwrap();
+    assert_eq!(samples, again);
+
+    let pop: DMatrix<f64> = linear.population(&start, 10, 5, None, &mut new_rng(Some(2))).unwrap();
+    assert_eq!(pop.shape(), (10, 3));
+}
+
+#[test]
+fn test_invalid_linear_constraints() {
+    // Shapes don't line up
+    let err = LinearConstraints::inequalities(DMatrix::<f64>::zeros(2, 3), DVector::zeros(3)).unwrap_err();
+    assert!(matches!(err, OptError::InvalidLinearConstraints(_)));
+
+    // x0 = 1 and 2 x0 = 3 can't both hold
+    let err = LinearConstraints::equalities(DMatrix::from_row_slice(2, 2, &[1.0, 0.0, 2.0, 0.0]), DVector::from_vec(vec![1.0, 3.0])).unwrap_err();
+    assert!(matches!(err, OptError::InvalidLinearConstraints(_)));
+
+    // A half-plane alone is unbounded
+    let half_plane = LinearConstraints::inequalities(DMatrix::from_row_slice(1, 2, &[1.0, 1.0]), DVector::from_vec(vec![1.0])).unwrap();
+    let err = half_plane.sample(&DVector::zeros(2), 5, 1, None, &mut new_rng(Some(1))).unwrap_err();
+    assert!(matches!(err, OptError::InvalidLinearConstraints(_)));
+    let bounds = Bounds::uniform(2, -1.0, 1.0).unwrap();
+    assert!(half_plane.sample(&DVector::zeros(2), 5, 1, Some(&bounds), &mut new_rng(Some(1))).is_ok());
+}
+
+#[test]
+fn test_opt_prob_sees_linear_constraints() {
+    #[derive(Debug, Clone)]
+    struct Cap;
+    impl ConstraintFunction<f64, nalgebra::Dyn> for Cap {
+        fn g(&self, x: &DVector<f64>) -> DVector<f64> {
+            DVector::from_vec(vec![x[0] - 0.5])
+        }
+    }
+
+    let opt_prob: OptProb<f64, nalgebra::Dyn> = OptProb::new(Box::new(SphereCost { c: 0.005 }), None)
+        .with_constraints(Some(Box::new(Cap)))
+        .with_linear_constraints(simplex());
+
+    // Misses the cap by 0.1 and the sum by 0.2
+    let x = DVector::from_vec(vec![0.6, 0.3, 0.3]);
+    assert!((opt_prob.violation(&x) - 0.3).abs() < 1e-6);
+
+    // Black-box values first, then A x - b and C x - d
+    let (g, h) = opt_prob.constraint_values(&x);
+    assert_eq!(g.len(), 4);
+    assert!((g[0] - 0.1).abs() < 1e-12 && (g[1] + 0.6).abs() < 1e-12);
+    assert!((h[0] - 0.2).abs() < 1e-12);
+
+    // Repairs project onto the polytope, except under the death penalty
+    let parent = DVector::from_vec(vec![0.2, 0.3, 0.5]);
+    let repaired = opt_prob.repair(&x, &parent, BoundHandling::Clamp, &mut new_rng(Some(1)));
+    assert!(opt_prob.linear.as_ref().unwrap().is_feasible(&repaired));
+    assert_eq!(opt_prob.repair(&x, &parent, BoundHandling::DeathPenalty, &mut new_rng(Some(1))), x);
+}
+
+#[test]
+fn test_ga_and_de_keep_mixtures_feasible() {
+    let alg_confs = [
+        r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Heuristic": {} }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Gaussian": { "mutation_rate": 0.5, "sigma": 0.2 } } } }"#,
+        r#"{ "DE": { "common": {}, "mutation_type": { "Standard": {} } } }"#,
+    ];
+
+    for alg_conf in alg_confs {
+        let conf = Config::new(&format!(r#"{{
+            "opt_conf": {{
+                "max_iter": 20, "seed": 9, "objective_sense": "Minimize",
+                "linear_constraints": {{ "a": [[-1, 0, 0], [0, -1, 0], [0, 0, -1]], "b": [0, 0, 0], "c": [[1, 1, 1]], "d": [1] }}
+            }},
+            "alg_conf": {}
+        }}"#, alg_conf)).unwrap();
+
+        // Off the simplex to begin with, projected by NonConvexOpt::new
+        let init_pop = DMatrix::from_fn(10, 3, |i, j| 0.1 * (i as f64) + 0.3 * (j as f64));
+        let mut opt = NonConvexOpt::new(conf, init_pop, SphereCost { c: 0.005 }, None::<UnitBox>).unwrap();
+        let linear = simplex();
+
+        for _ in 0..10 {
+            opt.step().unwrap();
+            let st = opt.alg.state();
+            for i in 0..st.pop.nrows() {
+                let x = st.pop.row(i).transpose();
+                assert!(linear.is_feasible(&x), "{}: {:?}", alg_conf, x.as_slice());
+                assert_eq!(st.violation[i], 0.0);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_linear_constraints_dimension_is_checked() {
+    let conf = Config::new(r#"{
+        "opt_conf": { "max_iter": 5, "objective_sense": "Minimize", "linear_constraints": { "c": [[1, 1, 1]], "d": [1] } },
+        "alg_conf": { "DE": { "common": {}, "mutation_type": { "Standard": {} } } }
+    }"#).unwrap();
+    let init_pop = DMatrix::from_element(10, 2, 0.5);
+    let err = NonConvexOpt::new(conf, init_pop, SphereCost { c: 0.005 }, None::<UnitBox>).err().unwrap();
+    assert!(matches!(err, OptError::InvalidLinearConstraints(_)), "{}", err);
+}
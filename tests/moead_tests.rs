@@ -0,0 +1,102 @@
+mod common;
+
+use common::{spread, fcns::{Schaffer, AtLeastOne}};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::benchmarks::DTLZ2;
+use non_convex_opt::utils::{
+    config::Config,
+    checkpoint::AlgCheckpoint,
+    pareto::{num_weights, uniform_weights},
+    error::OptError,
+};
+
+fn conf(max_iter: usize, alg_conf: &str) -> Config {
+    common::conf(max_iter, r#""seed": 4, "objective_sense": "Minimize""#, alg_conf)
+}
+
+#[test]
+fn test_das_dennis_weights() {
+    assert_eq!(num_weights(2, 9), 10);
+    assert_eq!(num_weights(3, 12), 91);
+    assert_eq!(num_weights(5, 4), 70);
+
+    let weights = uniform_weights(3, 4);
+    assert_eq!(weights.len(), num_weights(3, 4));
+    assert_eq!(weights[0], vec![0.0, 0.0, 1.0]);
+    for (i, w) in weights.iter().enumerate() {
+        assert!((w.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(w.iter().all(|&wi| (wi * 4.0 - (wi * 4.0).round()).abs() < 1e-12));
+        assert!(weights[..i].iter().all(|other| other != w));
+    }
+}
+
+#[test]
+fn test_dtlz2_reaches_sphere() {
+    let scalarizations = [r#""Tchebycheff""#, r#"{ "PBI": {} }"#];
+
+    for scalarization in scalarizations {
+        let alg_conf = format!(r#"{{ "MOEAD": {{ "divisions": 12, "scalarization": {} }} }}"#, scalarization);
+        let init_pop = spread(91, 5, 0.0, 1.0);
+        let mut opt = NonConvexOpt::new_multi_objective(conf(250, &alg_conf), init_pop, DTLZ2 { num_objectives: 3 }, None::<AtLeastOne>).unwrap();
+        let result = opt.run().unwrap();
+
+        let front = result.pareto_front.unwrap();
+        assert!(front.len() >= 40, "{}: {}", scalarization, front.len());
+        // PBI is slower to settle the subproblems on the edges, so single points get some slack
+        let radii: Vec<f64> = front.f.iter().map(|f| f.iter().map(|fi| fi * fi).sum::<f64>().sqrt()).collect();
+        for (f, radius) in front.f.iter().zip(radii.iter()) {
+            assert!((radius - 1.0).abs() < 0.1, "{}: {:?}", scalarization, f);
+        }
+        let mean = radii.iter().sum::<f64>() / radii.len() as f64;
+        assert!((mean - 1.0).abs() < 0.02, "{}: {}", scalarization, mean);
+
+        // The weights spread the front out to every corner
+        for m in 0..3 {
+            assert!(front.f.iter().any(|f| f[m] > 0.95), "{}: no corner in objective {}", scalarization, m);
+        }
+    }
+}
+
+#[test]
+fn test_constrained_front() {
+    let alg_conf = r#"{ "MOEAD": { "divisions": 19, "neighbourhood_size": 5 } }"#;
+    let init_pop = spread(20, 1, -5.0, 5.0);
+    let mut opt = NonConvexOpt::new_multi_objective(conf(60, alg_conf), init_pop, Schaffer, Some(AtLeastOne)).unwrap();
+    let result = opt.run().unwrap();
+
+    // Only [1, 2] is both feasible and non-dominated
+    let front = result.pareto_front.unwrap();
+    assert!(front.len() >= 5);
+    for x in &front.x {
+        assert!((1.0..=2.01).contains(&x[0]), "{:?}", x);
+    }
+}
+
+#[test]
+fn test_checkpoint_resume() {
+    let alg_conf = r#"{ "MOEAD": { "divisions": 19, "neighbourhood_size": 5, "scalarization": { "PBI": { "theta": 2.0 } } } }"#;
+    let mut opt = NonConvexOpt::new_multi_objective(conf(40, alg_conf), spread(20, 1, -5.0, 5.0), Schaffer, None::<AtLeastOne>).unwrap();
+    for _ in 0..10 {
+        opt.step().unwrap();
+    }
+
+    let (checkpoint, resumed) = common::assert_resumes_exactly(&mut opt, 10, |checkpoint| {
+        NonConvexOpt::resume_multi_objective(conf(40, alg_conf), checkpoint, Schaffer, None::<AtLeastOne>).unwrap()
+    });
+    assert!(matches!(&checkpoint.alg, AlgCheckpoint::MOEAD(c) if c.ideal.len() == 2));
+    assert_eq!(resumed.alg.pareto_front(), opt.alg.pareto_front());
+}
+
+#[test]
+fn test_invalid_configs() {
+    let init_pop = spread(20, 1, -5.0, 5.0);
+
+    // 10 divisions give 11 weight vectors for two objectives
+    let divisions = r#"{ "MOEAD": { "divisions": 10 } }"#;
+    let err = NonConvexOpt::new_multi_objective(conf(10, divisions), init_pop.clone(), Schaffer, None::<AtLeastOne>).err().unwrap();
+    assert!(matches!(err, OptError::InvalidPopulation { .. }), "{}", err);
+
+    let neighbourhood = r#"{ "MOEAD": { "divisions": 19, "neighbourhood_size": 4, "strategy": "Rand2Bin" } }"#;
+    let err = NonConvexOpt::new_multi_objective(conf(10, neighbourhood), init_pop, Schaffer, None::<AtLeastOne>).err().unwrap();
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+}
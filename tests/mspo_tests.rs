@@ -1,11 +1,15 @@
 mod common;
 
 use nalgebra::{DVector, DMatrix};
-use common::fcns::{RosenbrockObjective, RosenbrockConstraints};
+use common::fcns::UnitBox;
+use non_convex_opt::benchmarks::Rosenbrock;
 
 use non_convex_opt::utils::{
-    config::{Config, AlgConf},
+    config::{Config, AlgConf, BoundHandling, ObjectiveSense},
     opt_prob::{OptProb, OptimizationAlgorithm},
+    rng::new_rng,
+    bounds::Bounds,
+    constraint_handling::{ConstraintHandler, ConstraintHandling},
 };
 use non_convex_opt::algorithms::multi_swarm::{
     mspo::MSPO,
@@ -17,12 +21,14 @@ use non_convex_opt::algorithms::multi_swarm::{
 fn test_particle_update() {
     let position = DVector::from_vec(vec![0.5f64, 0.5]);
     let velocity = DVector::from_vec(vec![0.1, 0.1]);
-    let mut particle = Particle::new(position, velocity, 0.0);
+    let mut particle = Particle::new(position, velocity, 0.0, 0.0);
     
     let global_best = DVector::from_vec(vec![1.0, 1.0]);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, -10.0, 10.0).unwrap());
     
     particle.update_velocity_and_position(
         &global_best, 
@@ -30,7 +36,10 @@ fn test_particle_update() {
         2.05, 
         2.05,
         &opt_prob,
-        (-10.0, 10.0)
+        &ConstraintHandler::new(ConstraintHandling::FeasibilityRules),
+        &Bounds::uniform(2, -10.0, 10.0).unwrap(),
+        BoundHandling::Clamp,
+        &mut new_rng(None)
     );
     
     assert!(particle.position.len() == 2);
@@ -39,9 +48,11 @@ fn test_particle_update() {
 
 #[test]
 fn test_swarm_initialization() {
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, -10.0, 10.0).unwrap());
 
     // Create initial population
     let init_pop = DMatrix::from_vec(5, 2, vec![
@@ -58,9 +69,10 @@ fn test_swarm_initialization() {
         0.729f64, 
         2.05, 
         2.05,  
-        (-10.0, 10.0),
+        &Bounds::uniform(2, -10.0, 10.0).unwrap(),
         &opt_prob,
-        init_pop
+        init_pop,
+        &mut new_rng(None)
     );
     
     assert_eq!(swarm.particles.len(), 10);
@@ -74,9 +86,11 @@ fn test_swarm_initialization() {
 
 #[test]
 fn test_swarm_update() {
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, -10.0, 10.0).unwrap());
 
     let init_pop = DMatrix::from_vec(5, 2, vec![
         0.5, 0.5,
@@ -92,13 +106,14 @@ fn test_swarm_update() {
         0.729f64, 
         2.05, 
         2.05, 
-        (-10.0, 10.0),
+        &Bounds::uniform(2, -10.0, 10.0).unwrap(),
         &opt_prob,
-        init_pop
+        init_pop,
+        &mut new_rng(None)
     );
     
     let initial_best = swarm.global_best_fitness;
-    swarm.update(&opt_prob);
+    swarm.update(&opt_prob, &ConstraintHandler::new(ConstraintHandling::FeasibilityRules), BoundHandling::Clamp, &mut new_rng(None));
     
     // After update, particles should still be within bounds
     for particle in &swarm.particles {
@@ -125,8 +140,6 @@ fn test_mspo() {
                 "w": 0.729,
                 "c1": 2.05,
                 "c2": 2.05,
-                "x_min": -10.0,
-                "x_max": 10.0,
                 "exchange_interval": 2,
                 "exchange_ratio": 0.2
             }
@@ -144,11 +157,13 @@ fn test_mspo() {
         0.5 + 0.1 * (i as f64)
     }).collect::<Vec<f64>>());
 
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, -10.0, 10.0).unwrap());
     
-    let mut mspo = MSPO::new(mspo_conf, init_pop, opt_prob);
+    let mut mspo = MSPO::new(mspo_conf, init_pop, opt_prob, None);
     let initial_fitness = mspo.st.best_f;
     
     for _ in 0..20 {
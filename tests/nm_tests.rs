@@ -1,11 +1,11 @@
 mod common;
 
 use nalgebra::{DVector, DMatrix};
-use common::fcns::{QuadraticObjective, QuadraticConstraints};
+use common::fcns::{SphereCost, UnitBox};
 
 use non_convex_opt::algorithms::nelder_mead::nm::NelderMead;
 use non_convex_opt::utils::{
-    config::NelderMeadConf,
+    config::{NelderMeadConf, BoundHandling, ObjectiveSense},
     opt_prob::OptProb,
 };
 
@@ -16,6 +16,7 @@ fn test_nm_new() {
         gamma: 2.0,
         rho: 0.5,
         sigma: 0.5,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_simplex = DMatrix::from_columns(&[
@@ -24,11 +25,12 @@ fn test_nm_new() {
         DVector::from_vec(vec![1.0, 2.0]),
     ]);
 
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
     
-    let nm = NelderMead::new(conf, init_simplex.clone(), opt_prob);
+    let nm = NelderMead::new(conf, init_simplex.clone(), opt_prob, Some(0));
     
     assert_eq!(nm.simplex.len(), 3); 
     for i in 0..3 {
@@ -43,6 +45,7 @@ fn test_nm_centroid() {
         gamma: 2.0,
         rho: 0.5,
         sigma: 0.5,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_simplex = DMatrix::from_columns(&[
@@ -51,11 +54,12 @@ fn test_nm_centroid() {
         DVector::from_vec(vec![1.0, 2.0]),
     ]);
 
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
     
-    let nm = NelderMead::new(conf, init_simplex, opt_prob);
+    let nm = NelderMead::new(conf, init_simplex, opt_prob, Some(0));
     
     // Calculate centroid manually for comparison
     let mut expected_centroid = DVector::zeros(2);
@@ -0,0 +1,134 @@
+mod common;
+
+use nalgebra::DVector;
+use common::{spread, fcns::{Schaffer, AtLeastOne, SphereCost}};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::benchmarks::ZDT1;
+use non_convex_opt::utils::{
+    config::Config,
+    checkpoint::AlgCheckpoint,
+    pareto::{dominates, constrained_dominates, non_dominated_sort, crowding_distance},
+    error::OptError,
+};
+
+const NSGA2: &str = r#"{ "NSGA2": { "crossover": { "Heuristic": { "crossover_prob": 0.9 } }, "mutation": { "Polynomial": { "mutation_rate": 0.3 } } } }"#;
+
+fn conf(max_iter: usize, alg_conf: &str) -> Config {
+    common::conf(max_iter, r#""seed": 11, "objective_sense": "Minimize""#, alg_conf)
+}
+
+#[test]
+fn test_sorting_and_crowding() {
+    let v = |a: f64, b: f64| DVector::from_vec(vec![a, b]);
+    assert!(dominates(&v(2.0, 2.0), &v(1.0, 2.0)));
+    assert!(!dominates(&v(2.0, 2.0), &v(2.0, 2.0)));
+    assert!(!dominates(&v(3.0, 0.0), &v(0.0, 3.0)));
+
+    // Feasibility comes first, then the smaller violation
+    assert!(constrained_dominates(&v(0.0, 0.0), 0.0, &v(5.0, 5.0), 0.1));
+    assert!(constrained_dominates(&v(0.0, 0.0), 0.1, &v(5.0, 5.0), 0.2));
+
+    // Maximized, so (3, 0), (2, 2) and (0, 3) form the first front
+    let objectives = vec![v(3.0, 0.0), v(1.0, 1.0), v(2.0, 2.0), v(0.0, 3.0), v(0.0, 0.0), v(1.0, 1.0)];
+    let violation = vec![0.0; 6];
+    let fronts = non_dominated_sort(&objectives, &violation);
+    assert_eq!(fronts, vec![vec![0, 2, 3], vec![1, 5], vec![4]]);
+
+    // Extremes are infinite, the middle point sums its normalized gaps
+    let crowding = crowding_distance(&objectives, &fronts[0]);
+    assert!(crowding[0].is_infinite() && crowding[2].is_infinite());
+    assert!((crowding[1] - 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_schaffer_front() {
+    let init_pop = spread(40, 1, -5.0, 5.0);
+    let mut opt = NonConvexOpt::new_multi_objective(conf(60, NSGA2), init_pop, Schaffer, None::<AtLeastOne>).unwrap();
+    let result = opt.run().unwrap();
+
+    let front = result.pareto_front.unwrap();
+    assert_eq!(front.len(), 40);
+    for (x, f) in front.x.iter().zip(front.f.iter()) {
+        assert!((-1e-3..=2.001).contains(&x[0]), "{:?}", x);
+        assert!((f[0] - x[0].powi(2)).abs() < 1e-12 && (f[1] - (x[0] - 2.0).powi(2)).abs() < 1e-12);
+    }
+
+    // Crowding keeps both ends of the front
+    let min_f1 = front.f.iter().map(|f| f[0]).fold(f64::INFINITY, f64::min);
+    let min_f2 = front.f.iter().map(|f| f[1]).fold(f64::INFINITY, f64::min);
+    assert!(min_f1 < 0.01 && min_f2 < 0.01, "{} {}", min_f1, min_f2);
+
+    // best_x minimizes the sum, at x = 1
+    assert!((result.best_x[0] - 1.0).abs() < 0.05);
+    assert_eq!(result.evals.objective, 40 * 60);
+}
+
+#[test]
+fn test_zdt1_converges() {
+    let init_pop = spread(40, 3, 0.0, 1.0);
+    let mut opt = NonConvexOpt::new_multi_objective(conf(200, NSGA2), init_pop, ZDT1, None::<AtLeastOne>).unwrap();
+    let result = opt.run().unwrap();
+
+    let front = result.pareto_front.unwrap();
+    assert!(front.len() >= 30);
+    for f in &front.f {
+        assert!((f[1] - (1.0 - f[0].sqrt())).abs() < 0.05, "{:?}", f);
+    }
+    assert!(front.f.iter().any(|f| f[0] < 0.05) && front.f.iter().any(|f| f[0] > 0.9));
+}
+
+#[test]
+fn test_constrained_front() {
+    let init_pop = spread(30, 1, -5.0, 5.0);
+    let mut opt = NonConvexOpt::new_multi_objective(conf(50, NSGA2), init_pop, Schaffer, Some(AtLeastOne)).unwrap();
+    let result = opt.run().unwrap();
+
+    // Only [1, 2] is both feasible and non-dominated
+    let front = result.pareto_front.unwrap();
+    assert!(!front.is_empty());
+    for x in &front.x {
+        assert!((1.0..=2.01).contains(&x[0]), "{:?}", x);
+    }
+}
+
+#[test]
+fn test_checkpoint_resume() {
+    let mut opt = NonConvexOpt::new_multi_objective(conf(40, NSGA2), spread(20, 1, -5.0, 5.0), Schaffer, None::<AtLeastOne>).unwrap();
+    for _ in 0..10 {
+        opt.step().unwrap();
+    }
+
+    let (checkpoint, resumed) = common::assert_resumes_exactly(&mut opt, 10, |checkpoint| {
+        NonConvexOpt::resume_multi_objective(conf(40, NSGA2), checkpoint, Schaffer, None::<AtLeastOne>).unwrap()
+    });
+    assert!(matches!(&checkpoint.alg, AlgCheckpoint::NSGA2(c) if c.objectives.len() == 20));
+    assert_eq!(resumed.alg.pareto_front(), opt.alg.pareto_front());
+}
+
+#[test]
+fn test_objectives_are_summed_for_other_algorithms() {
+    let de = r#"{ "DE": { "common": {}, "mutation_type": { "Standard": {} } } }"#;
+    let init_pop = spread(20, 1, -5.0, 5.0);
+    let mut opt = NonConvexOpt::new_multi_objective(conf(100, de), init_pop, Schaffer, None::<AtLeastOne>).unwrap();
+    let result = opt.run().unwrap();
+
+    assert!((result.best_x[0] - 1.0).abs() < 1e-3);
+    assert!((result.best_f - 2.0).abs() < 1e-5);
+    assert!(result.pareto_front.is_none());
+}
+
+#[test]
+fn test_invalid_configs() {
+    // A scalar objective has no front to sort
+    let init_pop = spread(10, 1, -5.0, 5.0);
+    let err = NonConvexOpt::new_with_constraints(conf(10, NSGA2), init_pop.clone(), SphereCost { c: 0.0 }, None::<AtLeastOne>).err().unwrap();
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+
+    let tournament = r#"{ "NSGA2": { "crossover": { "Random": {} }, "mutation": { "Gaussian": {} }, "tournament_size": 20 } }"#;
+    let err = NonConvexOpt::new_multi_objective(conf(10, tournament), init_pop.clone(), Schaffer, None::<AtLeastOne>).err().unwrap();
+    assert!(matches!(err, OptError::InvalidPopulation { .. }), "{}", err);
+
+    let nested = format!(r#"{{ "AugLag": {{ "inner": {} }} }}"#, NSGA2);
+    let err = NonConvexOpt::new_multi_objective(conf(10, &nested), init_pop, Schaffer, Some(AtLeastOne)).err().unwrap();
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+}
@@ -0,0 +1,244 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use nalgebra::{DMatrix, DVector, Dim, OVector, DefaultAllocator, allocator::Allocator};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::algorithms::continous_ga::mutation::{MutationOperator, Permutation, PermutationMove};
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::{ObjectiveFunction, MultiObjectiveFunction, BooleanConstraintFunction, ConstraintFunction},
+    permutation::{is_permutation, pmx, ox, cycle, Neighbourhood},
+    bounds::Bounds,
+    error::OptError,
+    rng::new_rng,
+};
+use rand::seq::SliceRandom;
+
+const CITIES: usize = 10;
+
+// Cities on the unit circle, visited in the order of x. The shortest tour goes round the circle, with
+// length 2 n sin(pi / n). Counts the evaluations of anything that isn't an order
+#[derive(Debug, Clone)]
+struct Tour {
+    invalid: Arc<AtomicUsize>,
+}
+
+impl Tour {
+    fn new() -> Self {
+        Self { invalid: Arc::new(AtomicUsize::new(0)) }
+    }
+}
+
+impl<D: Dim> ObjectiveFunction<f64, D> for Tour
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> f64 {
+        if !is_permutation(x.as_slice()) {
+            self.invalid.fetch_add(1, Ordering::Relaxed);
+            return f64::NEG_INFINITY;
+        }
+        // Scrambled so the identity isn't the answer
+        let city = |k: f64| {
+            let angle = 2.0 * PI * ((k as usize * 3) % CITIES) as f64 / CITIES as f64;
+            (angle.cos(), angle.sin())
+        };
+        let n = x.len();
+        -(0..n).map(|i| {
+            let (a, b) = (city(x[i]), city(x[(i + 1) % n]));
+            ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+        }).sum::<f64>()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NoConstraints;
+
+impl<D: Dim> BooleanConstraintFunction<f64, D> for NoConstraints
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, _x: &OVector<f64, D>) -> bool {
+        true
+    }
+}
+
+// Tour length against how late item 0 comes, both minimized
+#[derive(Debug, Clone)]
+struct LengthAndLateness;
+
+impl<D: Dim> MultiObjectiveFunction<f64, D> for LengthAndLateness
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> DVector<f64> {
+        let length = -ObjectiveFunction::<f64, D>::f(&Tour::new(), x);
+        let lateness = x.iter().position(|&v| v == 0.0).unwrap_or(CITIES) as f64;
+        DVector::from_vec(vec![length, lateness])
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NoMultiConstraints;
+
+impl<D: Dim> ConstraintFunction<f64, D> for NoMultiConstraints
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, _x: &OVector<f64, D>) -> DVector<f64> {
+        DVector::zeros(0)
+    }
+}
+
+fn shortest() -> f64 {
+    2.0 * CITIES as f64 * (PI / CITIES as f64).sin()
+}
+
+fn conf(max_iter: usize, alg_conf: &str) -> Config {
+    Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": {}, "rtol": "0.0", "atol": "0.0", "seed": 4 }},
+        "alg_conf": {}
+    }}"#, max_iter, alg_conf)).unwrap()
+}
+
+fn random_orders(n: usize, seed: u64) -> DMatrix<f64> {
+    let mut rng = new_rng(Some(seed));
+    let mut pop = DMatrix::zeros(n, CITIES);
+    for i in 0..n {
+        let mut order: Vec<usize> = (0..CITIES).collect();
+        order.shuffle(&mut rng);
+        pop.set_row(i, &DVector::from_iterator(CITIES, order.iter().map(|&k| k as f64)).transpose());
+    }
+    pop
+}
+
+#[test]
+fn test_crossovers() {
+    let (p1, p2) = (vec![0, 1, 2, 3, 4], vec![4, 3, 2, 1, 0]);
+    assert_eq!(pmx(&p1, &p2, 1, 2), vec![4, 1, 2, 3, 0]);
+    assert_eq!(ox(&p1, &p2, 1, 2), vec![3, 1, 2, 0, 4]);
+    assert_eq!(cycle(&[0, 1, 2, 3], &[1, 0, 3, 2]), vec![0, 1, 3, 2]);
+
+    // Children of random parents are orders that keep the parents' positions
+    let mut rng = new_rng(Some(1));
+    for k in 0..200 {
+        let mut p1: Vec<usize> = (0..12).collect();
+        let mut p2 = p1.clone();
+        p1.shuffle(&mut rng);
+        p2.shuffle(&mut rng);
+        let (i, j) = (k % 12, (k % 12 + k / 12) % 12);
+        let (i, j) = (i.min(j), i.max(j));
+
+        for child in [pmx(&p1, &p2, i, j), ox(&p1, &p2, i, j), cycle(&p1, &p2)] {
+            let as_f64: Vec<f64> = child.iter().map(|&v| v as f64).collect();
+            assert!(is_permutation(&as_f64), "{:?}", child);
+        }
+        assert_eq!(pmx(&p1, &p2, i, j)[i..=j], p1[i..=j]);
+        assert_eq!(ox(&p1, &p2, i, j)[i..=j], p1[i..=j]);
+        assert!(cycle(&p1, &p2).iter().enumerate().all(|(k, &v)| v == p1[k] || v == p2[k]));
+    }
+}
+
+#[test]
+fn test_moves_keep_orders() {
+    let mut rng = new_rng(Some(2));
+    let x = DVector::from_iterator(CITIES, (0..CITIES).map(|k| k as f64));
+    let bounds = Bounds::uniform(CITIES, 0.0, (CITIES - 1) as f64).unwrap();
+
+    for kind in [PermutationMove::Swap, PermutationMove::Insert, PermutationMove::Inversion, PermutationMove::Scramble] {
+        let mutation = Permutation::new(1.0, kind);
+        for _ in 0..100 {
+            let mutated = mutation.mutate(&x, &bounds, 1, &mut rng);
+            assert!(is_permutation(mutated.as_slice()), "{:?}: {:?}", kind, mutated);
+        }
+    }
+    assert_eq!(Permutation::new(0.0, PermutationMove::Swap).mutate(&x, &bounds, 1, &mut rng), x);
+
+    for _ in 0..100 {
+        // A swap changes two positions, 2-opt reverses one segment
+        let swapped = Neighbourhood::Swap.neighbour(&x, &mut rng);
+        assert_eq!(swapped.iter().zip(x.iter()).filter(|(a, b)| a != b).count(), 2);
+
+        let reversed = Neighbourhood::TwoOpt.neighbour(&x, &mut rng);
+        let changed: Vec<usize> = (0..CITIES).filter(|&k| reversed[k] != x[k]).collect();
+        let (i, j) = (changed[0], changed[changed.len() - 1]);
+        assert!((i..=j).all(|k| reversed[k] == x[i + j - k]), "{:?}", reversed);
+    }
+    assert_eq!(Neighbourhood::Real.neighbour(&x, &mut rng), x);
+}
+
+#[test]
+fn test_shortest_tour() {
+    for (alg_conf, pop_size) in [
+        (r#"{ "CGA": { "common": { "num_parents": 10 }, "crossover": { "OX": {} }, "selection": { "Tournament": { "tournament_size": 3 } }, "mutation": { "Inversion": { "mutation_rate": 0.3 } } } }"#, 40),
+        (r#"{ "CGA": { "common": { "num_parents": 10 }, "crossover": { "PMX": {} }, "selection": { "Tournament": { "tournament_size": 3 } }, "mutation": { "Inversion": { "mutation_rate": 0.3 } } } }"#, 40),
+        (r#"{ "TS": { "common": { "neighbourhood": "TwoOpt" }, "list_type": { "Standard": {} } } }"#, 1),
+        (r#"{ "SA": { "neighbourhood": "TwoOpt", "initial_temp": 0.05, "cooling_rate": 0.98 } }"#, 1),
+    ] {
+        let objective = Tour::new();
+        let mut opt = NonConvexOpt::new(conf(300, alg_conf), random_orders(pop_size, 5), objective.clone(), None::<NoConstraints>).unwrap();
+        let result = opt.run().unwrap();
+
+        assert_eq!(objective.invalid.load(Ordering::Relaxed), 0, "{}", alg_conf);
+        assert!(is_permutation(result.best_x.as_slice()), "{}: {:?}", alg_conf, result.best_x);
+        assert!((-result.best_f - shortest()).abs() < 1e-9, "{}: {} vs {}", alg_conf, -result.best_f, shortest());
+    }
+}
+
+#[test]
+fn test_every_operator_stays_on_orders() {
+    for crossover in ["PMX", "OX", "Cycle"] {
+        for mutation in ["Swap", "Insert", "Inversion", "Scramble"] {
+            let alg_conf = format!(r#"{{ "CGA": {{ "common": {{ "num_parents": 6 }}, "crossover": {{ "{}": {{}} }}, "selection": {{ "RouletteWheel": {{}} }}, "mutation": {{ "{}": {{ "mutation_rate": 0.5 }} }} }} }}"#, crossover, mutation);
+            let objective = Tour::new();
+            let mut opt = NonConvexOpt::new(conf(20, &alg_conf), random_orders(12, 6), objective.clone(), None::<NoConstraints>).unwrap();
+            opt.run().unwrap();
+            assert_eq!(objective.invalid.load(Ordering::Relaxed), 0, "{}", alg_conf);
+        }
+    }
+    let swap = r#"{ "TS": { "common": { "neighbourhood": "Swap" }, "list_type": { "Standard": {} } } }"#;
+    let objective = Tour::new();
+    NonConvexOpt::new(conf(20, swap), random_orders(1, 7), objective.clone(), None::<NoConstraints>).unwrap().run().unwrap();
+    assert_eq!(objective.invalid.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn test_pareto_front_of_orders() {
+    let conf = Config::new(r#"{
+        "opt_conf": { "max_iter": 80, "rtol": "0.0", "atol": "0.0", "seed": 4, "objective_sense": "Minimize" },
+        "alg_conf": { "NSGA2": { "crossover": { "OX": {} }, "mutation": { "Inversion": { "mutation_rate": 0.3 } } } }
+    }"#).unwrap();
+    let mut opt = NonConvexOpt::new_multi_objective(conf, random_orders(30, 11), LengthAndLateness, None::<NoMultiConstraints>).unwrap();
+    let front = opt.run().unwrap().pareto_front.unwrap();
+
+    // Rotating a tour keeps its length, so the shortest one starting with item 0 dominates everything
+    assert!(front.x.iter().all(|x| is_permutation(x.as_slice())));
+    assert!(front.f.iter().any(|f| f[1] == 0.0 && (f[0] - shortest()).abs() < 1e-9), "{:?}", front.f);
+}
+
+#[test]
+fn test_invalid_permutation_configs() {
+    let cga = r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "OX": {} }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Swap": {} } } }"#;
+
+    // Rows that aren't orders of 0..n
+    let mut pop = random_orders(8, 8);
+    pop[(3, 0)] = pop[(3, 1)];
+    let err = NonConvexOpt::new(conf(10, cga), pop, Tour::new(), None::<NoConstraints>).err().unwrap();
+    assert!(matches!(err, OptError::InvalidPopulation { .. }), "{}", err);
+
+    // Real-valued operators mixed in
+    let mixed = r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "OX": {} }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Gaussian": {} } } }"#;
+    let err = NonConvexOpt::new(conf(10, mixed), random_orders(8, 9), Tour::new(), None::<NoConstraints>).err().unwrap();
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+
+    let with_variables = Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 10, "variables": [{{ "Integer": {{ "lower": 0, "upper": 9 }} }}] }},
+        "alg_conf": {}
+    }}"#, cga)).unwrap();
+    let err = NonConvexOpt::new(with_variables, random_orders(8, 10), Tour::new(), None::<NoConstraints>).err().unwrap();
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+}
@@ -1,7 +1,8 @@
 mod common;
 
 use nalgebra::{DVector, DMatrix};
-use common::fcns::{RosenbrockObjective, RosenbrockConstraints};
+use common::fcns::UnitBox;
+use non_convex_opt::benchmarks::Rosenbrock;
 
 use non_convex_opt::algorithms::parallel_tempering::{
     pt::PT,
@@ -9,39 +10,42 @@ use non_convex_opt::algorithms::parallel_tempering::{
 };
 use non_convex_opt::utils::{
     opt_prob::{OptProb, OptimizationAlgorithm},
-    config::{Config, AlgConf}
+    config::{Config, AlgConf, BoundHandling, ObjectiveSense},
+    rng::new_rng,
 };
 
 
 #[test]
 fn test_metropolis_hastings_accept_reject() {
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
 
-    let x_old = DVector::from_vec(vec![0.5, 0.5]);
-    let x_new = DVector::from_vec(vec![0.6, 0.6]);
+    let x_old = DVector::from_vec(vec![0.6, 0.6]);
+    let x_new = DVector::from_vec(vec![0.5, 0.5]);
 
-    let mh: MetropolisHastings<f64, nalgebra::Dyn> = MetropolisHastings::new(opt_prob, 0.1, 0.1, 2.1, x_old.clone());
+    let mh: MetropolisHastings<f64, nalgebra::Dyn> = MetropolisHastings::new(opt_prob, 0.1, 0.1, 2.1, BoundHandling::Clamp, x_old.clone());
     let constraints_new = true;
     let t = 1.0;
     let t_swap = 2.0;
 
-    let accepted = mh.accept_reject(&x_old, &x_new, constraints_new, t, t_swap);
+    let accepted = mh.accept_reject(&x_old, &x_new, constraints_new, t, t_swap, &mut new_rng(None));
     
     assert_eq!(accepted, false);
 }
 
 #[test]
 fn test_metropolis_hastings_local_move() {
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
 
     let x_old = DVector::from_vec(vec![0.5, 0.5]);
     let step_size = DMatrix::identity(2, 2);
-    let mh = MetropolisHastings::new(opt_prob, 0.1, 0.1, 2.1, x_old.clone());
-    let x_new = mh.local_move(&x_old, &step_size, 1.0);
+    let mh = MetropolisHastings::new(opt_prob, 0.1, 0.1, 2.1, BoundHandling::Clamp, x_old.clone());
+    let x_new = mh.local_move(&x_old, &step_size, 1.0, &mut new_rng(None));
 
     assert_eq!(x_old.len(), x_new.len());
 }
@@ -51,10 +55,11 @@ fn test_metropolis_hastings_update_step_size() {
     let x_old = DVector::from_vec(vec![0.5, 0.5]);
     let x_new = DVector::from_vec(vec![0.6, 0.6]);
     let mut step_size = DMatrix::identity(2, 2);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
-    let mh = MetropolisHastings::new(opt_prob, 0.1, 0.1, 2.1, x_old.clone());
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
+    let mh = MetropolisHastings::new(opt_prob, 0.1, 0.1, 2.1, BoundHandling::Clamp, x_old.clone());
     step_size = mh.update_step_size(&mut step_size, &x_old, &x_new);
 
     assert_eq!(step_size.nrows(), 2);
@@ -70,10 +75,11 @@ fn test_pt_swap() {
     };
 
     let init_pop = DMatrix::from_vec(2, 2, vec![0.5, 0.5, 0.5, 0.5]);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
-    let mut pt = PT::new(pt_conf, init_pop, opt_prob, 5);
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
+    let mut pt = PT::new(pt_conf, init_pop, opt_prob, 5, None);
 
     pt.swap();
 
@@ -91,10 +97,11 @@ fn test_pt_step() {
     };
 
     let init_pop = DMatrix::from_vec(2, 2, vec![0.5, 0.5, 0.5, 0.5]);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
-    let mut pt = PT::new(pt_conf, init_pop, opt_prob, 5);
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
+    let mut pt = PT::new(pt_conf, init_pop, opt_prob, 5, None);
 
     for _ in 0..5 {
         pt.step();
@@ -1,12 +1,13 @@
 mod common;
 
 use nalgebra::{SMatrix, U1, U2};
-use common::fcns::{QuadraticObjective, QuadraticConstraints};
+use common::fcns::{SphereCost, UnitBox};
 
 use non_convex_opt::algorithms::simulated_annealing::sa::SimulatedAnnealing;
 use non_convex_opt::utils::{
-    config::SAConf,
+    config::{SAConf, BoundHandling, Neighbourhood, ObjectiveSense},
     opt_prob::{OptProb, OptimizationAlgorithm},
+    bounds::Bounds,
 };
 
 
@@ -18,16 +19,18 @@ fn test_sa_basic() {
         step_size: 0.1,
         num_neighbors: 10,
         reheat_after: 20,
-        x_min: -10.0,
-        x_max: 10.0,
+        bound_handling: BoundHandling::Clamp,
+        neighbourhood: Neighbourhood::Real,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
-    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob);
+    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob, None);
     let initial_fitness = sa.st.best_f;
     
     for _ in 0..10 {
@@ -46,16 +49,18 @@ fn test_sa_cooling() {
         step_size: 0.1,
         num_neighbors: 10,
         reheat_after: 20,
-        x_min: -10.0,
-        x_max: 10.0,
+        bound_handling: BoundHandling::Clamp,
+        neighbourhood: Neighbourhood::Real,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
-    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob);
+    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob, None);
     let initial_temp = sa.temperature;
     
     for _ in 0..5 {
@@ -77,16 +82,18 @@ fn test_sa_neighbor_generation() {
         step_size: 0.1,
         num_neighbors: 10,
         reheat_after: 20,
-        x_min: -10.0,
-        x_max: 10.0,
+        bound_handling: BoundHandling::Clamp,
+        neighbourhood: Neighbourhood::Real,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
-    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob);
+    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob, None);
     
     sa.step();
     assert!(sa.st.best_x.iter().all(|&x| x >= -10.0 && x <= 10.0));
@@ -100,16 +107,18 @@ fn test_sa_with_constraints() {
         step_size: 0.1,
         num_neighbors: 10,
         reheat_after: 20,
-        x_min: -10.0,
-        x_max: 10.0,
+        bound_handling: BoundHandling::Clamp,
+        neighbourhood: Neighbourhood::Real,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
-    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob);
+    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob, None);
     
     for _ in 0..10 {
         sa.step();
@@ -125,16 +134,18 @@ fn test_sa_acceptance() {
         step_size: 0.1,
         num_neighbors: 10,
         reheat_after: 20,
-        x_min: -10.0,
-        x_max: 10.0,
+        bound_handling: BoundHandling::Clamp,
+        neighbourhood: Neighbourhood::Real,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective{ a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize)
+        .with_bounds(Bounds::uniform(2, 0.0, 1.0).unwrap());
     
-    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob);
+    let mut sa: SimulatedAnnealing<f64, U1, U2> = SimulatedAnnealing::new(conf, init_x.clone(), opt_prob, None);
     let initial_x = sa.st.best_x.clone();
     
     sa.step();
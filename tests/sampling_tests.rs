@@ -0,0 +1,223 @@
+use nalgebra::{DMatrix, DVector, Dyn, Dim, OVector, SMatrix, U2, U3, DefaultAllocator, allocator::Allocator};
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::{Config, OptConf},
+    opt_prob::{ObjectiveFunction, BooleanConstraintFunction, OptProb},
+    sampling::{Sampler, SamplingConf},
+    bounds::Bounds,
+    error::OptError,
+    rng::new_rng,
+};
+
+// Best at the upper corner
+#[derive(Debug, Clone)]
+struct Corner;
+
+impl<D: Dim> ObjectiveFunction<f64, D> for Corner
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> f64 {
+        -x.iter().map(|xi| (xi - 5.0).powi(2)).sum::<f64>()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NoConstraints;
+
+impl<D: Dim> BooleanConstraintFunction<f64, D> for NoConstraints
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn g(&self, _x: &OVector<f64, D>) -> bool {
+        true
+    }
+}
+
+fn samplers() -> Vec<Sampler> {
+    vec![
+        Sampler::Uniform,
+        Sampler::LatinHypercube,
+        Sampler::MaximinLatinHypercube { candidates: 10 },
+        Sampler::Sobol { scramble: false },
+        Sampler::Sobol { scramble: true },
+        Sampler::Halton { scramble: false },
+        Sampler::Halton { scramble: true },
+    ]
+}
+
+fn bounds(dim: usize) -> Bounds<f64, Dyn> {
+    Bounds::uniform(dim, -5.0, 5.0).unwrap()
+}
+
+// Whether every one of the n slices of every dimension holds exactly one point
+fn stratified(pop: &DMatrix<f64>, bounds: &Bounds<f64, Dyn>) -> bool {
+    let n = pop.nrows();
+    (0..pop.ncols()).all(|j| {
+        let mut seen = vec![false; n];
+        pop.column(j).iter().all(|&x| {
+            let slice = (((x - bounds.lower[j]) / bounds.width(j)) * n as f64) as usize;
+            !std::mem::replace(&mut seen[slice.min(n - 1)], true)
+        })
+    })
+}
+
+fn min_distance(pop: &DMatrix<f64>) -> f64 {
+    let mut min = f64::INFINITY;
+    for i in 0..pop.nrows() {
+        for k in i + 1..pop.nrows() {
+            min = min.min((pop.row(i) - pop.row(k)).norm());
+        }
+    }
+    min
+}
+
+#[test]
+fn test_points_inside_bounds() {
+    let bounds = Bounds::<f64, Dyn>::new(DVector::from_vec(vec![-1.0, 0.0, 10.0]), DVector::from_vec(vec![1.0, 0.5, 20.0])).unwrap();
+    for sampler in samplers() {
+        let pop: DMatrix<f64> = sampler.sample(50, &bounds, &mut new_rng(Some(1))).unwrap();
+        assert_eq!(pop.shape(), (50, 3));
+        for row in pop.row_iter() {
+            assert!(bounds.contains(&row.transpose()), "{:?}: {:?}", sampler, row);
+        }
+
+        // The same seed gives the same points
+        let again: DMatrix<f64> = sampler.sample(50, &bounds, &mut new_rng(Some(1))).unwrap();
+        assert_eq!(pop, again, "{:?}", sampler);
+    }
+
+    // Static shapes too
+    let bounds = Bounds::<f64, U3>::uniform(3, 0.0, 1.0).unwrap();
+    let pop: SMatrix<f64, 8, 3> = Sampler::Sobol { scramble: true }.sample(8, &bounds, &mut new_rng(Some(2))).unwrap();
+    assert!(pop.iter().all(|&x| (0.0..=1.0).contains(&x)));
+}
+
+#[test]
+fn test_stratification() {
+    let bounds = bounds(4);
+    for sampler in [Sampler::LatinHypercube, Sampler::MaximinLatinHypercube { candidates: 5 }] {
+        let pop: DMatrix<f64> = sampler.sample(37, &bounds, &mut new_rng(Some(3))).unwrap();
+        assert!(stratified(&pop, &bounds), "{:?}", sampler);
+    }
+
+    // The first 2^k Sobol points are a Latin hypercube, scrambled or not
+    for scramble in [false, true] {
+        let pop: DMatrix<f64> = Sampler::Sobol { scramble }.sample(64, &bounds, &mut new_rng(Some(4))).unwrap();
+        assert!(stratified(&pop, &bounds), "scramble {}", scramble);
+    }
+
+    // Halton in base 2 and 3 over 2^3 and 3^2 slices, some points sit exactly on a slice edge
+    let pop: DMatrix<f64> = Sampler::Halton { scramble: true }.sample(72, &bounds, &mut new_rng(Some(5))).unwrap();
+    let count = |j: usize, slices: usize| {
+        let mut counts = vec![0; slices];
+        for &x in pop.column(j).iter() {
+            counts[((x + 5.0) / 10.0 * slices as f64 + 1e-9) as usize] += 1;
+        }
+        counts
+    };
+    assert!(count(0, 8).iter().all(|&c| c == 9));
+    assert!(count(1, 9).iter().all(|&c| c == 8));
+}
+
+#[test]
+fn test_spread() {
+    let bounds = bounds(2);
+    let mut rng = new_rng(Some(6));
+    let mean = |sampler: &Sampler, rng: &mut _| (0..20).map(|_| min_distance(&sampler.sample(20, &bounds, rng).unwrap())).sum::<f64>() / 20.0;
+
+    // Picking the best of several hypercubes keeps the points further apart, and all beat uniform draws
+    let uniform = mean(&Sampler::Uniform, &mut rng);
+    let lhs = mean(&Sampler::LatinHypercube, &mut rng);
+    let maximin = mean(&Sampler::MaximinLatinHypercube { candidates: 30 }, &mut rng);
+    let sobol = mean(&Sampler::Sobol { scramble: true }, &mut rng);
+    assert!(uniform < lhs && lhs < maximin, "{} {} {}", uniform, lhs, maximin);
+    assert!(uniform < sobol, "{} {}", uniform, sobol);
+}
+
+#[test]
+fn test_opposition_keeps_better_points() {
+    let opt_prob = OptProb::<f64, Dyn>::new(Box::new(Corner), None).with_bounds(bounds(2));
+    let base = Sampler::LatinHypercube;
+    let points: DMatrix<f64> = base.sample(10, opt_prob.bounds.as_ref().unwrap(), &mut new_rng(Some(7))).unwrap();
+    let pop: DMatrix<f64> = Sampler::Opposition { base: Box::new(base) }.population(10, &opt_prob, &mut new_rng(Some(7))).unwrap();
+
+    // Each pair gives up its worse point, so the population beats the base points it came from
+    let total = |pop: &DMatrix<f64>| pop.row_iter().map(|row| opt_prob.evaluate(&row.transpose())).sum::<f64>();
+    assert!(total(&pop) > total(&points));
+    assert_eq!(opt_prob.evaluations().objective, 40);
+
+    // Plain sampling has no objective to look at
+    let err = Sampler::Opposition { base: Box::new(Sampler::Uniform) }.sample::<f64, Dyn, Dyn>(10, &bounds(2), &mut new_rng(None)).err().unwrap();
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+}
+
+#[test]
+fn test_sampled_constructor() {
+    let conf = Config::new(r#"{
+        "opt_conf": {
+            "max_iter": 50, "rtol": "0.0", "atol": "0.0", "seed": 8,
+            "bounds": { "lower": [-5.0], "upper": [5.0] },
+            "sampling": { "sampler": { "Sobol": {} }, "pop_size": 16, "dim": 3 }
+        },
+        "alg_conf": { "DE": { "common": {}, "mutation_type": { "Standard": {} } } }
+    }"#).unwrap();
+    let mut opt = NonConvexOpt::<f64, Dyn, Dyn>::new_sampled(conf.clone(), Corner, None::<NoConstraints>).unwrap();
+    assert_eq!(opt.get_population().shape(), (16, 3));
+    let result = opt.run().unwrap();
+    assert!(result.best_x.iter().all(|&x| x > 4.0), "{:?}", result.best_x);
+
+    // The same seed draws the same population
+    let again = NonConvexOpt::<f64, Dyn, Dyn>::new_sampled(conf, Corner, None::<NoConstraints>).unwrap();
+    let first: DMatrix<f64> = Sampler::Sobol { scramble: true }.sample(16, &bounds(3), &mut new_rng(Some(8))).unwrap();
+    assert_eq!(again.get_population(), first);
+
+    // Static dimensions come from the type, integer variables are snapped
+    let conf = Config::new(r#"{
+        "opt_conf": {
+            "max_iter": 5, "seed": 9,
+            "bounds": { "lower": [-5.0], "upper": [5.0] },
+            "variables": [{ "Integer": { "lower": -3, "upper": 3 } }, "Continuous"],
+            "sampling": { "sampler": { "Opposition": { "base": { "Halton": {} } } }, "pop_size": 6 }
+        },
+        "alg_conf": { "DE": { "common": {}, "mutation_type": { "Standard": {} } } }
+    }"#).unwrap();
+    let opt = NonConvexOpt::<f64, Dyn, U2>::new_sampled(conf, Corner, None::<NoConstraints>).unwrap();
+    for row in opt.get_population().row_iter() {
+        assert!(row[0].fract() == 0.0 && (-3.0..=3.0).contains(&row[0]), "{:?}", row);
+    }
+}
+
+#[test]
+fn test_sampling_errors() {
+    let conf = |sampling: &str, bounds: &str, alg_conf: &str| Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 5, {} "sampling": {} }},
+        "alg_conf": {}
+    }}"#, bounds, sampling, alg_conf)).unwrap();
+    let de = r#"{ "DE": { "common": {}, "mutation_type": { "Standard": {} } } }"#;
+    let bounded = r#""bounds": { "lower": [0.0], "upper": [1.0] },"#;
+    let new = |conf: Config| NonConvexOpt::<f64, Dyn, Dyn>::new_sampled(conf, Corner, None::<NoConstraints>).err().unwrap();
+
+    // No bounds to sample in
+    let err = new(conf(r#"{ "pop_size": 10, "dim": 2 }"#, "", de));
+    assert!(matches!(err, OptError::InvalidBounds(_)), "{}", err);
+    // No dimension for a dynamic problem
+    let err = new(conf(r#"{ "pop_size": 10 }"#, bounded, de));
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+    // Too many dimensions for Sobol
+    let err = new(conf(r#"{ "sampler": { "Sobol": {} }, "pop_size": 10, "dim": 30 }"#, bounded, de));
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+    // Orders aren't sampled
+    let ts = r#"{ "TS": { "common": { "neighbourhood": "Swap" }, "list_type": { "Standard": {} } } }"#;
+    let err = new(conf(r#"{ "pop_size": 1, "dim": 5 }"#, bounded, ts));
+    assert!(matches!(err, OptError::InvalidConfig { .. }), "{}", err);
+
+    // No sampling configured
+    let conf = Config::new(r#"{ "opt_conf": { "max_iter": 5 }, "alg_conf": { "DE": { "common": {}, "mutation_type": { "Standard": {} } } } }"#).unwrap();
+    assert!(conf.opt_conf.sampling.is_none());
+    assert!(matches!(new(conf), OptError::InvalidConfig { .. }));
+
+    // The sampler defaults to uniform
+    let conf: OptConf = serde_json::from_str(r#"{ "sampling": { "pop_size": 4 } }"#).unwrap();
+    assert_eq!(conf.sampling, Some(SamplingConf { sampler: Sampler::Uniform, pop_size: 4, dim: None }));
+}
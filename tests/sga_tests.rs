@@ -1,11 +1,11 @@
 mod common;
 
 use nalgebra::{SMatrix, U1, U2};
-use common::fcns::{QuadraticObjective, QuadraticConstraints};
+use common::fcns::{SphereCost, UnitBox};
 
 use non_convex_opt::algorithms::sg_ascent::sga::SGAscent;
 use non_convex_opt::utils::{
-    config::SGAConf,
+    config::{SGAConf, BoundHandling, ObjectiveSense},
     opt_prob::{OptProb, OptimizationAlgorithm},
 };
 
@@ -14,14 +14,16 @@ fn test_sga() {
     let conf = SGAConf {
         learning_rate: 0.01,
         momentum: 0.9,
+        bound_handling: BoundHandling::Clamp,
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = QuadraticObjective { a: 1.0, b: 100.0 };
-    let constraints = QuadraticConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = SphereCost { c: 0.005 };
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
     
-    let mut sga: SGAscent<f64, U1, U2> = SGAscent::new(conf, init_x.clone(), opt_prob);
+    let mut sga: SGAscent<f64, U1, U2> = SGAscent::new(conf, init_x.clone(), opt_prob, None);
     let initial_fitness = sga.st.best_f;
     
     for _ in 0..10 {
@@ -1,12 +1,18 @@
 mod common;
 
-use nalgebra::DMatrix;
-use common::fcns::{RosenbrockObjective, RosenbrockConstraints};
+use nalgebra::{DMatrix, DVector};
+use common::fcns::{BareRosenbrock, SphereCost, UnitBox, NaNCliff};
 
 use non_convex_opt::NonConvexOpt;
+use non_convex_opt::benchmarks::{Michalewicz, Rosenbrock};
 use non_convex_opt::utils::{
-    config::Config,
-    opt_prob::ObjectiveFunction,
+    config::{Config, OptConf},
+    opt_prob::{ObjectiveFunction, OptProb, State, Diagnostics},
+    observer::ObserverAction,
+    checkpoint::{Checkpoint, CheckpointError, StateData, MatrixData},
+    stopping::{StoppingConf, StoppingCriterion, StopContext, TerminationReason},
+    result::{OptimizationResult, History},
+    error::OptError,
 };
 
 #[test]
@@ -20,19 +26,19 @@ fn test_cga() {
         }
     }
 
-    let mut opt = NonConvexOpt::new(conf, init_pop.clone(), RosenbrockObjective{ a: 1.0, b: 1.0}, Some(RosenbrockConstraints{}));
+    let mut opt = NonConvexOpt::new(conf, init_pop.clone(), Rosenbrock, Some(UnitBox)).unwrap();
 
     let initial_best_fitness: f64 = init_pop.row_iter()
-        .map(|row| RosenbrockObjective{ a: 1.0, b: 1.0}.f(&row.transpose()))
+        .map(|row| Rosenbrock.f(&row.transpose()))
         .fold(f64::INFINITY, |a, b| a.min(b));
 
-    let result = opt.run();
+    let result = opt.run().unwrap();
 
     println!("Initial best fitness: {}", initial_best_fitness);
     println!("Best f: {}", result.best_f);
 
     assert!(-result.best_f.exp() < 0.01);
-    assert!(result.best_f > initial_best_fitness);
+    assert!(result.best_f <= initial_best_fitness);
 }
 
 #[test]
@@ -46,18 +52,496 @@ fn test_pt() {
         }
     }
 
-    let mut opt = NonConvexOpt::new(conf, init_pop.clone(), RosenbrockObjective{ a: 1.0, b: 1.0}, Some(RosenbrockConstraints{}));
+    let mut opt = NonConvexOpt::new(conf, init_pop.clone(), BareRosenbrock, Some(UnitBox)).unwrap();
 
     let initial_best_fitness: f64 = init_pop.row_iter()
-        .map(|row| RosenbrockObjective{ a: 1.0, b: 1.0}.f(&row.transpose()))
+        .map(|row| BareRosenbrock.f(&row.transpose()))
         .fold(f64::INFINITY, |a, b| a.min(b));
 
     println!("Initial best fitness: {}", initial_best_fitness);
     
-    let result = opt.run();
+    let result = opt.run().unwrap();
 
     println!("Best f: {}", result.best_f);
 
     assert!(-result.best_f.exp() < 0.01);
-    assert!(result.best_f > initial_best_fitness);
-}   
\ No newline at end of file
+    assert!(result.best_f <= initial_best_fitness);
+}
+
+fn seeded_trajectory(alg_conf: &str, threads: usize) -> Vec<(Vec<f64>, f64)> {
+    let conf = Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 20, "rtol": "0.0", "atol": "0.0", "seed": 42, "objective_sense": "Minimize", "bounds": {{ "lower": [-10.0], "upper": [10.0] }} }},
+        "alg_conf": {}
+    }}"#, alg_conf)).unwrap();
+
+    let init_pop = DMatrix::from_fn(10, 2, |i, j| ((i * 2 + j) as f64 * 0.37).sin().abs());
+
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+    pool.install(|| {
+        let mut opt = NonConvexOpt::new(conf, init_pop, Rosenbrock, Some(UnitBox)).unwrap();
+        (0..10)
+            .map(|_| {
+                opt.step().unwrap();
+                let st = opt.alg.state();
+                (st.pop.iter().cloned().collect(), st.best_f)
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn test_seeded_runs_are_reproducible() {
+    let alg_confs = [
+        r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Heuristic": { "crossover_prob": 0.8 } }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Gaussian": { "mutation_rate": 0.2, "sigma": 0.1 } } } }"#,
+        r#"{ "PT": { "common": { "num_replicas": 4 }, "swap_conf": { "Stochastic": { "swap_probability": 0.5 } } } }"#,
+        r#"{ "DE": { "common": {}, "mutation_type": { "Standard": {} } } }"#,
+        r#"{ "CMAES": { "num_parents": 5 } }"#,
+        r#"{ "SA": {} }"#,
+        r#"{ "TS": { "common": {}, "list_type": { "Standard": {} } } }"#,
+        r#"{ "GRASP": {} }"#,
+        r#"{ "MSPO": { "num_swarms": 2, "swarm_size": 5 } }"#,
+    ];
+
+    for alg_conf in alg_confs {
+        let single = seeded_trajectory(alg_conf, 1);
+        assert_eq!(single, seeded_trajectory(alg_conf, 1), "{}", alg_conf);
+        assert_eq!(single, seeded_trajectory(alg_conf, 4), "{}", alg_conf);
+    }
+}
+
+#[test]
+fn test_minimize_reports_cost() {
+    let alg_confs = [
+        r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Heuristic": { "crossover_prob": 0.8 } }, "selection": { "RouletteWheel": {} }, "mutation": { "Gaussian": { "mutation_rate": 0.2, "sigma": 0.1 } } } }"#,
+        r#"{ "PT": { "common": { "num_replicas": 4 }, "swap_conf": { "Stochastic": { "swap_probability": 0.5 } } } }"#,
+        r#"{ "TS": { "common": {}, "list_type": { "Standard": {} } } }"#,
+        r#"{ "Adam": {} }"#,
+        r#"{ "GRASP": {} }"#,
+        r#"{ "SGA": {} }"#,
+        r#"{ "NM": {} }"#,
+        r#"{ "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } }"#,
+        r#"{ "MSPO": { "num_swarms": 2, "swarm_size": 5 } }"#,
+        r#"{ "SA": { "initial_temp": 0.01 } }"#,
+        r#"{ "DE": { "common": {}, "mutation_type": { "Standard": {} } } }"#,
+        r#"{ "CMAES": { "num_parents": 5 } }"#,
+    ];
+
+    let cost = SphereCost{ c: 0.5 };
+
+    for alg_conf in alg_confs {
+        let conf = Config::new(&format!(r#"{{
+            "opt_conf": {{ "max_iter": 50, "rtol": "0.0", "atol": "1e-8", "seed": 7, "objective_sense": "Minimize" }},
+            "alg_conf": {}
+        }}"#, alg_conf)).unwrap();
+
+        // Nelder-Mead takes a simplex of n + 1 vertices, single-point methods start at the first row
+        let n_rows = if alg_conf.contains("\"NM\"") { 3 } else { 10 };
+        let init_pop = DMatrix::from_fn(n_rows, 2, |i, j| match (i, j) {
+            (0, 0) => 0.9,
+            (0, 1) => 0.1,
+            _ => 0.3 + 0.4 * ((i * 2 + j) as f64 * 0.37).sin().abs(),
+        });
+        let initial_cost = cost.f(&init_pop.row(0).transpose());
+
+        let mut opt = NonConvexOpt::new(conf, init_pop, cost.clone(), Some(UnitBox)).unwrap();
+        let result = opt.run().unwrap();
+
+        assert!(result.best_f >= 0.0, "{}: best_f {} is not in cost units", alg_conf, result.best_f);
+        assert!(result.best_f < initial_cost, "{}: best_f {} did not decrease from {}", alg_conf, result.best_f, initial_cost);
+        assert!((cost.f(&result.best_x) - result.best_f).abs() < 1e-12, "{}", alg_conf);
+    }
+}
+
+#[test]
+fn test_atol_ignores_negative_minimum() {
+    // Michalewicz is negative over its whole box, about -1.8 at the 2-D optimum, so |f| stays above the
+    // default atol and the run uses its iterations
+    let conf = Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 20, "rtol": "0.0", "seed": 5, "objective_sense": "Minimize" }},
+        "alg_conf": {}
+    }}"#, ALL_ALG_CONFS[10])).unwrap();
+    let init_pop = DMatrix::from_fn(10, 2, |i, j| 0.2 + 2.7 * ((i * 2 + j) as f64 * 0.37).sin().abs());
+
+    let mut opt = NonConvexOpt::new(conf, init_pop, Michalewicz::default(), None::<UnitBox>).unwrap();
+    let result = opt.run().unwrap();
+
+    assert!(result.best_f < 0.0);
+    assert_eq!(result.iterations, 20);
+    assert_eq!(result.termination, TerminationReason::MaxIter);
+}
+
+#[test]
+fn test_atol_stops_minimization_near_zero() {
+    let conf = Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 500, "rtol": "0.0", "atol": "1e-6", "seed": 5, "objective_sense": "Minimize" }},
+        "alg_conf": {}
+    }}"#, ALL_ALG_CONFS[10])).unwrap();
+
+    let mut opt = NonConvexOpt::new(conf, sphere_init_pop(ALL_ALG_CONFS[10]), SphereCost{ c: 0.5 }, None::<UnitBox>).unwrap();
+    let result = opt.run().unwrap();
+
+    assert!(result.best_f <= 1e-6, "{}", result.best_f);
+    assert!(result.iterations < 500);
+    assert_eq!(result.termination, TerminationReason::Criterion("Tolerance".to_string()));
+}
+
+#[test]
+fn test_observer_can_stop_run() {
+    let conf = Config::new(r#"{
+        "opt_conf": { "max_iter": 100, "rtol": "0.0", "atol": "0.0", "seed": 3, "objective_sense": "Minimize" },
+        "alg_conf": { "SA": {} }
+    }"#).unwrap();
+
+    let init_pop = DMatrix::from_element(1, 2, 0.9);
+    let mut opt = NonConvexOpt::new(conf, init_pop, SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = seen.clone();
+    opt.add_observer(Box::new(move |iter: usize, st: &State<f64, nalgebra::Dyn, nalgebra::Dyn>, diagnostics: &Diagnostics| {
+        assert!(st.best_f >= 0.0);
+        log.lock().unwrap().push((iter, diagnostics.get("temperature").cloned()));
+        if iter >= 5 { ObserverAction::Stop } else { ObserverAction::Continue }
+    }));
+
+    let result = opt.run().unwrap();
+    let seen = seen.lock().unwrap();
+
+    assert!(opt.stopped);
+    assert_eq!(result.iterations, 5);
+    assert_eq!(result.termination, TerminationReason::Observer);
+    assert_eq!(seen.iter().map(|(iter, _)| *iter).collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+    assert!(seen.iter().all(|(_, temperature)| temperature.is_some()));
+}
+
+const ALL_ALG_CONFS: [&str; 12] = [
+    r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Heuristic": { "crossover_prob": 0.8 } }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Gaussian": { "mutation_rate": 0.2, "sigma": 0.1 } } } }"#,
+    r#"{ "PT": { "common": { "num_replicas": 4 }, "swap_conf": { "Stochastic": { "swap_probability": 0.5 } } } }"#,
+    r#"{ "TS": { "common": {}, "list_type": { "Reactive": {} } } }"#,
+    r#"{ "Adam": {} }"#,
+    r#"{ "GRASP": {} }"#,
+    r#"{ "SGA": {} }"#,
+    r#"{ "NM": {} }"#,
+    r#"{ "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } }"#,
+    r#"{ "MSPO": { "num_swarms": 2, "swarm_size": 5 } }"#,
+    r#"{ "SA": { "initial_temp": 0.01 } }"#,
+    r#"{ "DE": { "common": {}, "mutation_type": { "Adaptive": {} } } }"#,
+    r#"{ "CMAES": { "num_parents": 5 } }"#,
+];
+
+fn sphere_conf(alg_conf: &str) -> Config {
+    Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 50, "rtol": "0.0", "atol": "0.0", "seed": 11, "objective_sense": "Minimize" }},
+        "alg_conf": {}
+    }}"#, alg_conf)).unwrap()
+}
+
+fn sphere_init_pop(alg_conf: &str) -> DMatrix<f64> {
+    let n_rows = if alg_conf.contains("\"NM\"") { 3 } else { 10 };
+    DMatrix::from_fn(n_rows, 2, |i, j| 0.1 + 0.8 * ((i * 2 + j) as f64 * 0.37).sin().abs())
+}
+
+#[test]
+fn test_resume_continues_exactly() {
+    for alg_conf in ALL_ALG_CONFS {
+        let mut opt = NonConvexOpt::new(sphere_conf(alg_conf), sphere_init_pop(alg_conf), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+        common::trajectory(&mut opt, 5);
+
+        let checkpoint = opt.checkpoint();
+        let expected = common::trajectory(&mut opt, 5);
+
+        let from_json = Checkpoint::from_json(&checkpoint.to_json().unwrap()).unwrap();
+        let from_bytes = Checkpoint::from_bytes(&checkpoint.to_bytes().unwrap()).unwrap();
+
+        for restored in [from_json, from_bytes] {
+            let mut resumed = NonConvexOpt::resume(sphere_conf(alg_conf), restored, SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+            assert_eq!(common::trajectory(&mut resumed, 5), expected, "{}", alg_conf);
+        }
+    }
+}
+
+#[test]
+fn test_resume_rejects_other_algorithm() {
+    let mut opt = NonConvexOpt::new(sphere_conf(ALL_ALG_CONFS[9]), sphere_init_pop(ALL_ALG_CONFS[9]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    opt.step().unwrap();
+
+    let result = NonConvexOpt::<f64, nalgebra::Dyn, nalgebra::Dyn>::resume(sphere_conf(ALL_ALG_CONFS[3]), opt.checkpoint(), SphereCost{ c: 0.5 }, Some(UnitBox));
+    assert!(matches!(result, Err(OptError::Checkpoint(CheckpointError::AlgorithmMismatch { .. }))));
+}
+
+#[test]
+fn test_checkpoint_json_keeps_non_finite_values() {
+    let st = StateData {
+        best_x: vec![0.5, f64::NAN],
+        best_f: f64::NEG_INFINITY,
+        pop: MatrixData { nrows: 1, ncols: 2, data: vec![f64::INFINITY, 0.1] },
+        fitness: vec![f64::NEG_INFINITY],
+        constraints: vec![false],
+        violation: vec![f64::INFINITY],
+        iter: 3,
+    };
+
+    let json = serde_json::to_string(&st).unwrap();
+    let restored: StateData = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.best_f, f64::NEG_INFINITY);
+    assert!(restored.best_x[1].is_nan());
+    assert_eq!(restored.pop.data, vec![f64::INFINITY, 0.1]);
+    assert_eq!(restored.fitness, vec![f64::NEG_INFINITY]);
+    assert_eq!(restored.violation, vec![f64::INFINITY]);
+}
+
+#[test]
+fn test_run_reports_evaluation_counts() {
+    let mut opt = NonConvexOpt::new(sphere_conf(ALL_ALG_CONFS[10]), sphere_init_pop(ALL_ALG_CONFS[10]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    let before = opt.evaluations().objective;
+    assert!(before > 0); // Initial population is scored
+
+    let result = opt.run().unwrap();
+    assert!(result.evals.objective > before);
+    assert!(result.evals.constraints > 0);
+    assert_eq!(result.evals, opt.evaluations());
+}
+
+#[test]
+fn test_max_evals_is_never_exceeded() {
+    for alg_conf in ALL_ALG_CONFS {
+        let mut conf = sphere_conf(alg_conf);
+        conf.opt_conf.max_evals = Some(37);
+        conf.opt_conf.max_iter = 10_000;
+
+        let mut opt = NonConvexOpt::new(conf, sphere_init_pop(alg_conf), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+        let result = opt.run().unwrap();
+
+        assert!(result.evals.objective <= 37, "{}: {}", alg_conf, result.evals.objective);
+        assert!(opt.budget_exhausted() || opt.converged, "{}", alg_conf);
+        assert!(matches!(result.termination, TerminationReason::MaxEvals | TerminationReason::Criterion(_)), "{}", alg_conf);
+        assert!(result.iterations < 10_000, "{}", alg_conf);
+    }
+}
+
+#[test]
+fn test_evaluation_counts_survive_resume() {
+    let mut opt = NonConvexOpt::new(sphere_conf(ALL_ALG_CONFS[0]), sphere_init_pop(ALL_ALG_CONFS[0]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    common::trajectory(&mut opt, 3);
+
+    let checkpoint = Checkpoint::from_json(&opt.checkpoint().to_json().unwrap()).unwrap();
+    let resumed = NonConvexOpt::<f64, nalgebra::Dyn, nalgebra::Dyn>::resume(sphere_conf(ALL_ALG_CONFS[0]), checkpoint, SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    assert_eq!(resumed.evaluations(), opt.evaluations());
+}
+
+fn stopping_conf(alg_conf: &str, stopping: &str) -> Config {
+    Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 500, "seed": 11, "objective_sense": "Minimize", "stopping": {} }},
+        "alg_conf": {}
+    }}"#, stopping, alg_conf)).unwrap()
+}
+
+#[test]
+fn test_stopping_conf_from_json() {
+    let conf = stopping_conf(ALL_ALG_CONFS[10], r#"{ "Any": [{ "TargetValue": { "target": 1e-3 } }, { "All": [{ "Stagnation": { "iters": 20 } }, { "WallClock": { "seconds": 1.0 } }] }] }"#);
+    assert_eq!(conf.opt_conf.stopping, StoppingConf::Any(vec![
+        StoppingConf::TargetValue { target: 1e-3 },
+        StoppingConf::All(vec![StoppingConf::Stagnation { iters: 20, tol: 0.0 }, StoppingConf::WallClock { seconds: 1.0 }]),
+    ]));
+
+    // Omitted means the atol/rtol test
+    assert_eq!(sphere_conf(ALL_ALG_CONFS[10]).opt_conf.stopping, StoppingConf::Tolerance);
+}
+
+#[test]
+fn test_opt_conf_default_matches_json() {
+    let from_json: OptConf = serde_json::from_str("{}").unwrap();
+    assert_eq!(serde_json::to_value(OptConf::default()).unwrap(), serde_json::to_value(from_json).unwrap());
+}
+
+#[test]
+fn test_target_value_reports_criterion() {
+    let conf = stopping_conf(ALL_ALG_CONFS[10], r#"{ "Any": [{ "TargetValue": { "target": 1e-3 } }, { "WallClock": { "seconds": 600.0 } }] }"#);
+    let mut opt = NonConvexOpt::new(conf, sphere_init_pop(ALL_ALG_CONFS[10]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    let result = opt.run().unwrap();
+
+    assert!(result.best_f <= 1e-3);
+    assert!(result.iterations < 500);
+    assert_eq!(result.termination, TerminationReason::Criterion("TargetValue".to_string()));
+}
+
+#[test]
+fn test_algorithm_specific_criteria() {
+    let cases = [
+        (ALL_ALG_CONFS[6], r#"{ "SimplexSize": { "tol": 1e-4 } }"#, "simplex_size"),
+        (ALL_ALG_CONFS[11], r#"{ "Sigma": { "tol": 1e-3 } }"#, "sigma"),
+        (ALL_ALG_CONFS[11], r#"{ "TolX": { "tol": 1e-3 } }"#, "tol_x"),
+        (ALL_ALG_CONFS[7], r#"{ "GradientNorm": { "tol": 1e-4 } }"#, "GradientNorm"),
+        (ALL_ALG_CONFS[10], r#"{ "Diversity": { "tol": 1e-3 } }"#, "Diversity"),
+    ];
+
+    for (alg_conf, stopping, name) in cases {
+        let mut opt = NonConvexOpt::new(stopping_conf(alg_conf, stopping), sphere_init_pop(alg_conf), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+        let result = opt.run().unwrap();
+        assert_eq!(result.termination, TerminationReason::Criterion(name.to_string()), "{}", alg_conf);
+        assert!(result.iterations < 500, "{}", alg_conf);
+    }
+}
+
+#[test]
+fn test_all_waits_for_every_criterion() {
+    // Stagnation alone would stop early, All also needs the target to be reached
+    let conf = stopping_conf(ALL_ALG_CONFS[9], r#"{ "All": [{ "Stagnation": { "iters": 3 } }, { "TargetValue": { "target": -1.0 } }] }"#);
+    let mut opt = NonConvexOpt::new(conf, sphere_init_pop(ALL_ALG_CONFS[9]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    let result = opt.run().unwrap();
+
+    // A cost below -1 is impossible, so the run goes to max_iter
+    assert_eq!(result.termination, TerminationReason::MaxIter);
+    assert_eq!(result.iterations, 500);
+}
+
+// Stops after a fixed number of steps, to check user-defined criteria are picked up
+struct StopAfter(usize);
+
+impl StoppingCriterion<f64, nalgebra::Dyn, nalgebra::Dyn> for StopAfter {
+    fn name(&self) -> String {
+        "StopAfter".to_string()
+    }
+
+    fn should_stop(&mut self, ctx: &StopContext<f64, nalgebra::Dyn, nalgebra::Dyn>) -> bool {
+        ctx.st.iter >= self.0
+    }
+}
+
+#[test]
+fn test_custom_stopping_criterion() {
+    let conf = stopping_conf(ALL_ALG_CONFS[9], r#"{ "Stagnation": { "iters": 1000 } }"#);
+    let mut opt = NonConvexOpt::new(conf, sphere_init_pop(ALL_ALG_CONFS[9]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    opt.add_stopping_criterion(Box::new(StopAfter(7)));
+
+    let result = opt.run().unwrap();
+    assert_eq!(result.iterations, 7);
+    assert_eq!(result.termination, TerminationReason::Criterion("StopAfter".to_string()));
+}
+
+fn history_conf(alg_conf: &str) -> Config {
+    let mut conf = sphere_conf(alg_conf);
+    conf.opt_conf.record_history = true;
+    conf
+}
+
+#[test]
+fn test_run_records_history() {
+    let mut opt = NonConvexOpt::new(history_conf(ALL_ALG_CONFS[10]), sphere_init_pop(ALL_ALG_CONFS[10]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    let result = opt.run().unwrap();
+    let history = result.history.as_ref().unwrap();
+
+    // One entry for the initial population and one per step
+    assert_eq!(history.entries.len(), result.iterations);
+    assert_eq!(history.entries.last().unwrap().best_f, result.best_f);
+    assert!(history.entries.windows(2).all(|w| w[1].iter == w[0].iter + 1 && w[1].best_f <= w[0].best_f));
+    assert!(history.entries.iter().all(|e| e.std_fitness >= 0.0 && (0.0..=1.0).contains(&e.feasible_fraction)));
+
+    // Off by default
+    let mut opt = NonConvexOpt::new(sphere_conf(ALL_ALG_CONFS[10]), sphere_init_pop(ALL_ALG_CONFS[10]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    assert!(opt.run().unwrap().history.is_none());
+}
+
+#[test]
+fn test_result_exports() {
+    let mut opt = NonConvexOpt::new(history_conf(ALL_ALG_CONFS[9]), sphere_init_pop(ALL_ALG_CONFS[9]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    let result = opt.run().unwrap();
+
+    let restored = OptimizationResult::<f64, nalgebra::Dyn>::from_json(&result.to_json().unwrap()).unwrap();
+    assert_eq!(restored.best_x, result.best_x);
+    assert_eq!(restored.best_f, result.best_f);
+    assert_eq!(restored.termination, result.termination);
+    assert_eq!(restored.iterations, result.iterations);
+    assert_eq!(restored.evals, result.evals);
+    assert_eq!(restored.elapsed, result.elapsed);
+    assert_eq!(restored.history, result.history);
+
+    let history = result.history.unwrap();
+    assert_eq!(History::from_json(&history.to_json().unwrap()).unwrap(), history);
+
+    let csv = history.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("iter,best_f,mean_fitness,std_fitness,feasible_fraction"));
+    assert_eq!(lines.count(), history.entries.len());
+}
+
+#[test]
+fn test_history_survives_resume() {
+    let mut opt = NonConvexOpt::new(history_conf(ALL_ALG_CONFS[0]), sphere_init_pop(ALL_ALG_CONFS[0]), SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    common::trajectory(&mut opt, 3);
+
+    let checkpoint = Checkpoint::from_json(&opt.checkpoint().to_json().unwrap()).unwrap();
+    let mut resumed = NonConvexOpt::<f64, nalgebra::Dyn, nalgebra::Dyn>::resume(history_conf(ALL_ALG_CONFS[0]), checkpoint, SphereCost{ c: 0.5 }, Some(UnitBox)).unwrap();
+    assert_eq!(resumed.history(), opt.history());
+
+    resumed.step().unwrap();
+    assert_eq!(resumed.history().unwrap().entries.len(), opt.history().unwrap().entries.len() + 1);
+}
+
+fn new_error(alg_conf: &str, init_pop: DMatrix<f64>) -> OptError {
+    NonConvexOpt::new(sphere_conf(alg_conf), init_pop, BareRosenbrock, None::<UnitBox>).err().unwrap()
+}
+
+#[test]
+fn test_new_rejects_missing_gradient() {
+    for alg_conf in [ALL_ALG_CONFS[3], ALL_ALG_CONFS[5], ALL_ALG_CONFS[7]] {
+        assert!(matches!(new_error(alg_conf, sphere_init_pop(alg_conf)), OptError::MissingGradient { .. }), "{}", alg_conf);
+    }
+}
+
+#[test]
+fn test_new_rejects_bad_population() {
+    // Nelder-Mead needs n + 1 vertices
+    assert!(matches!(new_error(ALL_ALG_CONFS[6], DMatrix::from_element(5, 2, 0.5)), OptError::InvalidPopulation { .. }));
+
+    // Rand2Bin draws five donors besides the target
+    let de_conf = r#"{ "DE": { "common": {}, "mutation_type": { "Standard": { "strategy": "Rand2Bin" } } } }"#;
+    assert!(matches!(new_error(de_conf, DMatrix::from_element(5, 2, 0.5)), OptError::InvalidPopulation { .. }));
+    assert!(NonConvexOpt::new(sphere_conf(de_conf), DMatrix::from_element(6, 2, 0.5), BareRosenbrock, None::<UnitBox>).is_ok());
+
+    let mut init_pop = sphere_init_pop(ALL_ALG_CONFS[10]);
+    init_pop[(3, 1)] = f64::NAN;
+    assert!(matches!(new_error(ALL_ALG_CONFS[10], init_pop), OptError::InvalidPopulation { .. }));
+}
+
+#[test]
+fn test_new_rejects_bad_config() {
+    let grasp_conf = r#"{ "GRASP": { "alpha": 0.5 } }"#;
+    assert!(matches!(new_error(grasp_conf, sphere_init_pop(grasp_conf)), OptError::InvalidConfig { .. }));
+
+    let cga_conf = r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Heuristic": { "crossover_prob": 1.5 } }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Gaussian": { "mutation_rate": 0.2, "sigma": 0.1 } } } }"#;
+    let err = new_error(cga_conf, sphere_init_pop(cga_conf));
+    assert_eq!(err, OptError::InvalidConfig { alg: "CGA".to_string(), reason: "crossover_prob must be in [0, 1], got 1.5".to_string() });
+}
+
+#[test]
+fn test_nan_objective_is_an_error() {
+    let conf = Config::new(&format!(r#"{{
+        "opt_conf": {{ "max_iter": 200, "seed": 5, "stopping": {{ "Stagnation": {{ "iters": 1000 }} }} }},
+        "alg_conf": {}
+    }}"#, ALL_ALG_CONFS[10])).unwrap();
+
+    // Every step pushes x[0] up until a trial point lands past the cliff
+    let init_pop = DMatrix::from_fn(10, 2, |i, j| 0.02 * (i + j) as f64);
+    let mut opt = NonConvexOpt::new(conf.clone(), init_pop.clone(), NaNCliff{ cliff: 0.22 }, None::<UnitBox>).unwrap();
+    match opt.run() {
+        Err(OptError::NonFiniteObjective { x, value }) => {
+            assert!(value.is_nan());
+            assert!(x[0] > 0.22);
+        },
+        other => panic!("expected a NonFiniteObjective error, got {:?}", other.map(|r| (r.best_f, r.iterations, r.termination))),
+    }
+
+    // Already caught when scoring the initial population
+    let err = NonConvexOpt::new(conf, init_pop, NaNCliff{ cliff: 0.1 }, None::<UnitBox>).err().unwrap();
+    assert!(matches!(err, OptError::NonFiniteObjective { .. }));
+}
+
+#[test]
+fn test_evaluate_multi_without_objectives_is_an_error() {
+    let opt_prob = OptProb::new(Box::new(SphereCost{ c: 0.5 }), Some(Box::new(UnitBox)));
+    let f = opt_prob.evaluate_multi(&DVector::from_element(2, 0.5));
+
+    assert_eq!(f.as_slice(), &[f64::NEG_INFINITY]);
+    assert!(matches!(opt_prob.take_error(), Some(OptError::InvalidConfig { .. })));
+}
@@ -1,11 +1,12 @@
 mod common;
 
 use nalgebra::{SMatrix, U1, U2};
-use common::fcns::{RosenbrockObjective, RosenbrockConstraints};
+use common::fcns::UnitBox;
+use non_convex_opt::benchmarks::Rosenbrock;
 
 use non_convex_opt::algorithms::tabu_search::tabu::TabuSearch;
 use non_convex_opt::utils::{
-    config::{Config, AlgConf},
+    config::{Config, AlgConf, ObjectiveSense},
     opt_prob::{OptProb, OptimizationAlgorithm},
 };
 
@@ -41,11 +42,12 @@ fn test_standard_tabu() {
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
     
-    let mut tabu: TabuSearch<f64, U1, U2> = TabuSearch::new(tabu_conf, init_x.clone(), opt_prob);
+    let mut tabu: TabuSearch<f64, U1, U2> = TabuSearch::new(tabu_conf, init_x.clone(), opt_prob, None);
     let initial_fitness = tabu.st.best_f;
     
     for _ in 0..10 {
@@ -66,11 +68,12 @@ fn test_reactive_tabu() {
     };
 
     let init_x = SMatrix::<f64, 1, 2>::from_row_slice(&[0.5, 0.5]);
-    let obj_f = RosenbrockObjective{ a: 1.0, b: 1.0};
-    let constraints = RosenbrockConstraints{};
-    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)));
+    let obj_f = Rosenbrock;
+    let constraints = UnitBox;
+    let opt_prob = OptProb::new(Box::new(obj_f), Some(Box::new(constraints)))
+        .with_sense(ObjectiveSense::Minimize);
     
-    let mut tabu: TabuSearch<f64, U1, U2> = TabuSearch::new(tabu_conf, init_x.clone(), opt_prob);
+    let mut tabu: TabuSearch<f64, U1, U2> = TabuSearch::new(tabu_conf, init_x.clone(), opt_prob, None);
     let initial_fitness = tabu.st.best_f;
     
     for _ in 0..10 {
@@ -0,0 +1,200 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use nalgebra::{DMatrix, DVector, Dyn, Dim, OVector, DefaultAllocator, allocator::Allocator};
+use common::fcns::NoConstraints;
+use non_convex_opt::NonConvexOpt;
+use non_convex_opt::utils::{
+    config::Config,
+    opt_prob::ObjectiveFunction,
+    variables::{Variables, VarType},
+    bounds::Bounds,
+    error::OptError,
+    rng::new_rng,
+};
+
+const LEVELS: [f64; 4] = [0.001, 0.01, 0.1, 1.0];
+
+// A layer count, a continuous weight and a step size, best at (3, 0.37, 0.01). Counts every
+// evaluation that isn't on the integer and discrete levels
+#[derive(Debug, Clone)]
+struct Tuning {
+    off_level: Arc<AtomicUsize>,
+}
+
+impl Tuning {
+    fn new() -> Self {
+        Self { off_level: Arc::new(AtomicUsize::new(0)) }
+    }
+}
+
+impl<D: Dim> ObjectiveFunction<f64, D> for Tuning
+where
+    DefaultAllocator: Allocator<D>
+{
+    fn f(&self, x: &OVector<f64, D>) -> f64 {
+        if x[0].fract() != 0.0 || !LEVELS.contains(&x[2]) {
+            self.off_level.fetch_add(1, Ordering::Relaxed);
+        }
+        -((x[0] - 3.0).powi(2) + (x[1] - 0.37).powi(2) + (x[2].log10() + 2.0).powi(2))
+    }
+
+    fn gradient(&self, x: &OVector<f64, D>) -> Option<OVector<f64, D>> {
+        let mut grad = x.clone();
+        grad[0] = -2.0 * (x[0] - 3.0);
+        grad[1] = -2.0 * (x[1] - 0.37);
+        grad[2] = -2.0 * (x[2].log10() + 2.0) / (x[2] * std::f64::consts::LN_10);
+        Some(grad)
+    }
+}
+
+const VARIABLES: &str = r#"[{ "Integer": { "lower": 0, "upper": 8 } }, "Continuous", { "Discrete": { "values": [1.0, 0.1, 0.01, 0.001] } }]"#;
+
+fn conf(max_iter: usize, alg_conf: &str) -> Config {
+    let opt_conf = format!(r#""seed": 5, "bounds": {{ "lower": [-1.0], "upper": [1.0] }}, "variables": {}"#, VARIABLES);
+    common::conf(max_iter, &opt_conf, alg_conf)
+}
+
+// Off the levels on purpose, the optimizer snaps it
+fn init_pop(n: usize) -> DMatrix<f64> {
+    DMatrix::from_fn(n, 3, |i, j| match j {
+        0 => ((i * 5) % 9) as f64 + 0.3,
+        1 => -0.9 + 1.8 * i as f64 / n as f64,
+        _ => [0.6, 0.004, 0.2][i % 3],
+    })
+}
+
+// Every algorithm that can snap its candidates
+const ALG_CONFS: [&str; 9] = [
+    r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Heuristic": {} }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Polynomial": { "mutation_rate": 0.5 } } } }"#,
+    r#"{ "PT": { "common": { "num_replicas": 3 }, "swap_conf": { "Always": {} } } }"#,
+    r#"{ "DE": { "common": {}, "mutation_type": { "Standard": {} } } }"#,
+    r#"{ "CMAES": { "num_parents": 5, "initial_sigma": 2.0 } }"#,
+    r#"{ "MSPO": { "num_swarms": 2, "swarm_size": 5 } }"#,
+    r#"{ "TS": { "common": { "step_size": 0.5 }, "list_type": { "Standard": {} } } }"#,
+    r#"{ "GRASP": { "step_size": 0.5 } }"#,
+    r#"{ "SA": { "step_size": 0.5 } }"#,
+    r#"{ "AugLag": { "inner": { "DE": { "common": {}, "mutation_type": { "Standard": {} } } } } }"#,
+];
+
+#[test]
+fn test_variables_spec() {
+    let types = vec![VarType::Integer { lower: 0, upper: 8 }, VarType::Continuous, VarType::Discrete { values: LEVELS.iter().rev().cloned().collect() }];
+    let variables = Variables::from_conf(&types, 3).unwrap();
+    assert!(!variables.is_continuous());
+    assert_eq!(variables.types()[2], VarType::Discrete { values: LEVELS.to_vec() });
+
+    // Rounded to the integer and the closest listed value, continuous dimensions untouched
+    let x = DVector::from_vec(vec![2.6, 0.123, 0.04]);
+    assert_eq!(variables.snap(&x), DVector::from_vec(vec![3.0, 0.123, 0.01]));
+    assert!(variables.contains(&variables.snap(&x)));
+    assert!(!variables.contains(&x));
+
+    // A small move goes one level, a large one keeps its own rounding
+    let mut rng = new_rng(Some(1));
+    let parent = DVector::from_vec(vec![3.0, 0.0, 0.01]);
+    let nudged = DVector::from_vec(vec![3.2, 0.05, 0.009]);
+    assert_eq!(variables.snap(&variables.step_away(&nudged, &parent, &mut rng)), DVector::from_vec(vec![4.0, 0.05, 0.001]));
+    let jumped = DVector::from_vec(vec![5.8, 0.0, 0.01]);
+    assert_eq!(variables.snap(&variables.step_away(&jumped, &parent, &mut rng)), DVector::from_vec(vec![6.0, 0.0, 0.01]));
+
+    // The ranges replace the box in their dimensions
+    let bounds: Bounds<f64, Dyn> = variables.restrict(Some(Bounds::uniform(3, -1.0, 1.0).unwrap())).unwrap();
+    assert_eq!(bounds.lower.as_slice(), &[0.0, -1.0, 0.001]);
+    assert_eq!(bounds.upper.as_slice(), &[8.0, 1.0, 1.0]);
+
+    // A single type applies to every dimension
+    assert_eq!(Variables::from_conf(&[VarType::Integer { lower: -2, upper: 2 }], 4).unwrap().dim(), 4);
+    assert!(matches!(Variables::from_conf(&types, 2), Err(OptError::InvalidVariables(_))));
+    assert!(matches!(Variables::new(vec![VarType::Integer { lower: 3, upper: 1 }]), Err(OptError::InvalidVariables(_))));
+    assert!(matches!(Variables::new(vec![VarType::Discrete { values: vec![] }]), Err(OptError::InvalidVariables(_))));
+}
+
+#[test]
+fn test_objective_only_sees_levels() {
+    for alg_conf in ALG_CONFS {
+        let objective = Tuning::new();
+        let mut opt = NonConvexOpt::new(conf(60, alg_conf), init_pop(10), objective.clone(), None::<NoConstraints>).unwrap();
+        let result = opt.run().unwrap();
+
+        assert_eq!(objective.off_level.load(Ordering::Relaxed), 0, "{}", alg_conf);
+        assert!(result.best_x[0].fract() == 0.0 && (0.0..=8.0).contains(&result.best_x[0]), "{}: {:?}", alg_conf, result.best_x);
+        assert!(LEVELS.contains(&result.best_x[2]), "{}: {:?}", alg_conf, result.best_x);
+        assert!((-1.0..=1.0).contains(&result.best_x[1]), "{}: {:?}", alg_conf, result.best_x);
+        for row in opt.alg.state().pop.row_iter() {
+            assert!(row[0].fract() == 0.0 && LEVELS.contains(&row[2]), "{}: {:?}", alg_conf, row);
+        }
+    }
+}
+
+#[test]
+fn test_integer_optimum() {
+    // The population-based ones, a single SA or tabu walk can settle on a neighbouring level
+    for alg_conf in [ALG_CONFS[0], ALG_CONFS[1], ALG_CONFS[2], ALG_CONFS[3], ALG_CONFS[4], ALG_CONFS[8]] {
+        let mut opt = NonConvexOpt::new(conf(150, alg_conf), init_pop(10), Tuning::new(), None::<NoConstraints>).unwrap();
+        let result = opt.run().unwrap();
+        assert_eq!((result.best_x[0], result.best_x[2]), (3.0, 0.01), "{}: {:?}", alg_conf, result.best_x);
+    }
+}
+
+#[test]
+fn test_small_mutations_leave_their_level() {
+    // Gaussian noise of 0.05 would always round back, so every gene starts on the wrong level
+    let gaussian = r#"{ "CGA": { "common": { "num_parents": 4 }, "crossover": { "Random": {} }, "selection": { "Tournament": { "tournament_size": 2 } }, "mutation": { "Gaussian": { "mutation_rate": 0.3, "sigma": 0.05 } } } }"#;
+    let init_pop = DMatrix::from_fn(10, 3, |_, j| [8.0, 0.0, 1.0][j]);
+    let mut opt = NonConvexOpt::new(conf(200, gaussian), init_pop, Tuning::new(), None::<NoConstraints>).unwrap();
+    let result = opt.run().unwrap();
+    assert_eq!((result.best_x[0], result.best_x[2]), (3.0, 0.01), "{:?}", result.best_x);
+}
+
+#[test]
+fn test_gradient_and_simplex_methods_snap() {
+    for alg_conf in [
+        r#"{ "Adam": { "learning_rate": 0.6 } }"#,
+        r#"{ "SGA": { "learning_rate": 0.2 } }"#,
+        r#"{ "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } }"#,
+        r#"{ "NM": {} }"#,
+        r#"{ "AugLag": { "inner": { "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } } } }"#,
+    ] {
+        let objective = Tuning::new();
+        let mut opt = NonConvexOpt::new(conf(60, alg_conf), init_pop(4), objective.clone(), None::<NoConstraints>).unwrap();
+        let start = opt.alg.state().best_x.clone();
+        let result = opt.run().unwrap();
+
+        assert_eq!(objective.off_level.load(Ordering::Relaxed), 0, "{}", alg_conf);
+        assert!(result.best_x[0].fract() == 0.0 && LEVELS.contains(&result.best_x[2]), "{}: {:?}", alg_conf, result.best_x);
+        assert!(result.best_x != start, "{}: stuck at {:?}", alg_conf, start);
+    }
+}
+
+#[test]
+fn test_variable_dimensions_must_match() {
+    let all_continuous = Config::new(r#"{
+        "opt_conf": { "max_iter": 10, "variables": ["Continuous"] },
+        "alg_conf": { "LBFGS": { "common": {}, "line_search": { "Backtracking": {} } } }
+    }"#).unwrap();
+    assert!(NonConvexOpt::new(all_continuous, init_pop(1), Tuning::new(), None::<NoConstraints>).is_ok());
+
+    let mismatch = Config::new(r#"{
+        "opt_conf": { "max_iter": 10, "variables": ["Continuous", "Continuous"] },
+        "alg_conf": { "DE": { "common": {}, "mutation_type": { "Standard": {} } } }
+    }"#).unwrap();
+    let err = NonConvexOpt::new(mismatch, init_pop(10), Tuning::new(), None::<NoConstraints>).err().unwrap();
+    assert!(matches!(err, OptError::InvalidVariables(_)), "{}", err);
+}
+
+#[test]
+fn test_variables_survive_resume() {
+    let de = ALG_CONFS[2];
+    let objective = Tuning::new();
+    let mut opt = NonConvexOpt::new(conf(40, de), init_pop(10), objective.clone(), None::<NoConstraints>).unwrap();
+    for _ in 0..5 {
+        opt.step().unwrap();
+    }
+
+    common::assert_resumes_exactly(&mut opt, 10, |checkpoint| {
+        NonConvexOpt::resume(conf(40, de), checkpoint, objective.clone(), None::<NoConstraints>).unwrap()
+    });
+    assert_eq!(objective.off_level.load(Ordering::Relaxed), 0);
+}